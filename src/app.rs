@@ -1,80 +1,544 @@
-use crate::contexts::{
-    Context, boot::BootContext, dns::DnsContext, host::HostContext, logs::LogsContext,
-    network::NetworkContext, units::UnitsContext,
-};
-use crate::systemd::client::SystemdClient;
+use crate::commands::{self, Command, Run};
+use crate::config::Config;
+#[cfg(feature = "boot")]
+use crate::contexts::boot::BootContext;
+#[cfg(feature = "coredumps")]
+use crate::contexts::coredumps::CoredumpsContext;
+#[cfg(feature = "dns")]
+use crate::contexts::dns::DnsContext;
+#[cfg(feature = "host")]
+use crate::contexts::host::HostContext;
+#[cfg(feature = "kernel")]
+use crate::contexts::kernel::KernelContext;
+#[cfg(feature = "logs")]
+use crate::contexts::logs::LogsContext;
+#[cfg(feature = "machines")]
+use crate::contexts::machines::MachinesContext;
+#[cfg(feature = "network")]
+use crate::contexts::network::NetworkContext;
+#[cfg(feature = "portable")]
+use crate::contexts::portable::PortableContext;
+#[cfg(feature = "storage")]
+use crate::contexts::storage::StorageContext;
+#[cfg(feature = "units")]
+use crate::contexts::units::UnitsContext;
+use crate::contexts::{Context, Pending, RefreshTimer};
+use crate::messages::Messages;
+use crate::power::BatteryAlarm;
+#[cfg(any(feature = "units", feature = "logs"))]
+use crate::presets::Presets;
+use crate::pressure::Pressure;
+use crate::systemd::client::{BusPreference, ManagerStatus, SystemdClient};
+use crate::watchlist::Watchlist;
+use crate::widgets::picker::{Picker, PickerEvent};
 use anyhow::Result;
 use crossterm::event::KeyEvent;
+use std::time::{Duration, Instant};
+
+/// Tabs compiled into this build as (id, title), in header order. Ids don't
+/// depend on the features, so `--tab`, palette commands and `current_context`
+/// name the same tab in every build.
+pub const TABS: &[(usize, &str)] = &[
+    #[cfg(feature = "units")]
+    (0, "Units"),
+    #[cfg(feature = "network")]
+    (1, "Network"),
+    #[cfg(feature = "dns")]
+    (2, "DNS"),
+    #[cfg(feature = "host")]
+    (3, "Host"),
+    #[cfg(feature = "boot")]
+    (4, "Boot"),
+    #[cfg(feature = "logs")]
+    (5, "Logs"),
+    #[cfg(feature = "machines")]
+    (6, "Machines"),
+    #[cfg(feature = "coredumps")]
+    (7, "Coredumps"),
+    #[cfg(feature = "storage")]
+    (8, "Storage"),
+    #[cfg(feature = "portable")]
+    (9, "Portable"),
+    #[cfg(feature = "kernel")]
+    (10, "Kernel"),
+];
+
+/// Config section and default auto-refresh interval of each tab, by id.
+/// Logs poll the journal on every tick; Boot and Machines only on `r`.
+const REFRESH_DEFAULTS: [(&str, Option<Duration>); 11] = [
+    ("units", Some(Duration::from_secs(5))),
+    ("network", Some(Duration::from_secs(2))),
+    ("dns", Some(Duration::from_secs(30))),
+    ("host", Some(Duration::from_secs(60))),
+    ("boot", None),
+    ("logs", Some(Duration::ZERO)),
+    ("machines", None),
+    ("coredumps", Some(Duration::from_secs(30))),
+    ("storage", Some(Duration::from_secs(10))),
+    ("portable", Some(Duration::from_secs(30))),
+    ("kernel", Some(Duration::from_secs(30))),
+];
+
+/// How often the header re-reads the manager's version and state
+const MANAGER_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many times longer hidden tabs wait between refreshes
+const DEFAULT_BACKGROUND_SLOWDOWN: u32 = 4;
+
+/// Tabs that keep refreshing while hidden: top-level `background_refresh`,
+/// "all", "none" (the default) or a comma-separated list of tab sections
+/// such as "units,network"
+fn background_tabs_from_config(config: &Config) -> Vec<usize> {
+    let Some(value) = config.get("", "background_refresh") else {
+        return Vec::new();
+    };
+    match value.trim() {
+        "none" | "" => Vec::new(),
+        "all" => TABS.iter().map(|(id, _)| *id).collect(),
+        list => list
+            .split(',')
+            .map(str::trim)
+            .filter_map(|name| {
+                let id = REFRESH_DEFAULTS
+                    .iter()
+                    .position(|(section, _)| *section == name);
+                if id.is_none() {
+                    tracing::warn!("Unknown tab {:?} in background_refresh", name);
+                }
+                id
+            })
+            .collect(),
+    }
+}
+
+/// Top-level `background_slowdown`, at least 1
+fn background_slowdown_from_config(config: &Config) -> u32 {
+    match config.get("", "background_slowdown") {
+        Some(value) => value.parse::<u32>().map(|n| n.max(1)).unwrap_or_else(|_| {
+            tracing::warn!("Invalid background_slowdown {:?}", value);
+            DEFAULT_BACKGROUND_SLOWDOWN
+        }),
+        None => DEFAULT_BACKGROUND_SLOWDOWN,
+    }
+}
+
+/// Tabs that read this machine's files, /proc, /sys or journal, which
+/// --host can't reach; the others go through the managed machine's buses
+const LOCAL_TABS: [usize; 6] = [1, 4, 5, 7, 8, 10];
+
+/// Whether the tab with this id is compiled in
+pub fn has_tab(id: usize) -> bool {
+    TABS.iter().any(|(tab, _)| *tab == id)
+}
+
+/// The second pane of the split view. The focused pane is always
+/// `current_context`, so keys, help and input capture need no special case.
+struct Split {
+    other: usize,
+    other_on_right: bool,
+}
+
+/// How many matches the go-to-unit picker lists
+#[cfg(feature = "units")]
+pub const UNIT_SEARCH_LIMIT: usize = 200;
 
 pub struct App {
     current_context: usize,
     show_help: bool,
+    /// Ctrl+P "go to unit" picker, open over any tab
+    unit_search: Option<Picker>,
+    /// `:` command palette
+    palette: Option<Picker>,
+    report: Pending<std::io::Result<std::path::PathBuf>>,
+    split: Option<Split>,
+    /// Version and health of the manager for the header
+    manager_status: Option<ManagerStatus>,
+    manager_status_pending: Pending<Result<ManagerStatus>>,
+    manager_status_read: Instant,
+    /// CPU, memory and IO stalls for the header
+    pressure: Pressure,
+    /// Low-battery warning for the status bar
+    battery: BatteryAlarm,
+    /// Auto-refresh timer of each tab, indexed by tab id
+    refresh: Vec<RefreshTimer>,
+    /// Tabs refreshed while hidden, every `background_slowdown` intervals
+    background: Vec<usize>,
+    background_slowdown: u32,
     systemd: SystemdClient,
+    #[cfg(feature = "units")]
     units: UnitsContext,
+    #[cfg(feature = "network")]
     network: NetworkContext,
+    #[cfg(feature = "dns")]
     dns: DnsContext,
+    #[cfg(feature = "host")]
     host: HostContext,
+    #[cfg(feature = "boot")]
     boot: BootContext,
+    #[cfg(feature = "logs")]
     logs: LogsContext,
-    error_message: Option<String>,
+    #[cfg(feature = "machines")]
+    machines: MachinesContext,
+    #[cfg(feature = "coredumps")]
+    coredumps: CoredumpsContext,
+    #[cfg(feature = "storage")]
+    storage: StorageContext,
+    #[cfg(feature = "portable")]
+    portable: PortableContext,
+    #[cfg(feature = "kernel")]
+    kernel: KernelContext,
+    messages: Messages,
+    watchlist: Watchlist,
 }
 
 impl App {
-    pub async fn new() -> Result<Self> {
-        let systemd = SystemdClient::new().await?;
+    pub async fn new(host: Option<&str>, bus: BusPreference, config: &Config) -> Result<Self> {
+        let systemd = SystemdClient::new(host, bus).await?;
 
-        let units = UnitsContext::new(&systemd).await?;
-        let network = NetworkContext::new();
-        let dns = DnsContext::new();
-        let host = HostContext::new();
-        let boot = BootContext::new();
-        let logs = LogsContext::new();
+        let messages = Messages::new();
+        let watchlist = Watchlist::load();
+        #[cfg(any(feature = "units", feature = "logs"))]
+        let presets = Presets::from_config(config);
+
+        #[cfg(feature = "units")]
+        let units = UnitsContext::new(&systemd, config, &messages, &watchlist, &presets).await?;
+        #[cfg(feature = "network")]
+        let network = NetworkContext::new(&systemd, config, &messages);
+        #[cfg(feature = "dns")]
+        let dns = DnsContext::new(&systemd, &messages);
+        #[cfg(feature = "host")]
+        let host = HostContext::new(&systemd, &messages);
+        #[cfg(feature = "boot")]
+        let boot = BootContext::new(&messages);
+        #[cfg(feature = "logs")]
+        let logs = LogsContext::new(config, &messages, &presets);
+        #[cfg(feature = "machines")]
+        let machines = MachinesContext::new(&systemd, &messages);
+        #[cfg(feature = "coredumps")]
+        let coredumps = CoredumpsContext::new(&messages);
+        #[cfg(feature = "storage")]
+        let storage = StorageContext::new(&systemd, &messages);
+        #[cfg(feature = "portable")]
+        let portable = PortableContext::new(&systemd, &messages);
+        #[cfg(feature = "kernel")]
+        let kernel = KernelContext::new(&systemd, &messages);
 
         Ok(Self {
-            current_context: 0,
+            current_context: TABS[0].0,
             show_help: false,
+            unit_search: None,
+            palette: None,
+            report: Pending::idle(),
+            split: None,
+            manager_status: None,
+            manager_status_pending: Pending::idle(),
+            // Read on the first tick
+            manager_status_read: Instant::now()
+                .checked_sub(MANAGER_STATUS_INTERVAL)
+                .unwrap_or_else(Instant::now),
+            pressure: Pressure::from_config(config),
+            battery: BatteryAlarm::from_config(config),
+            refresh: REFRESH_DEFAULTS
+                .iter()
+                .map(|(section, default)| RefreshTimer::from_config(config, section, *default))
+                .collect(),
+            background: background_tabs_from_config(config),
+            background_slowdown: background_slowdown_from_config(config),
             systemd,
+            #[cfg(feature = "units")]
             units,
+            #[cfg(feature = "network")]
             network,
+            #[cfg(feature = "dns")]
             dns,
+            #[cfg(feature = "host")]
             host,
+            #[cfg(feature = "boot")]
             boot,
+            #[cfg(feature = "logs")]
             logs,
-            error_message: None,
+            #[cfg(feature = "machines")]
+            machines,
+            #[cfg(feature = "coredumps")]
+            coredumps,
+            #[cfg(feature = "storage")]
+            storage,
+            #[cfg(feature = "portable")]
+            portable,
+            #[cfg(feature = "kernel")]
+            kernel,
+            messages,
+            watchlist,
         })
     }
 
+    pub fn manager_status(&self) -> Option<&ManagerStatus> {
+        self.manager_status.as_ref()
+    }
+
+    pub fn pressure(&self) -> &Pressure {
+        &self.pressure
+    }
+
+    /// Charge of the battery running low, if one is
+    pub fn low_battery(&self) -> Option<u8> {
+        self.battery.low()
+    }
+
     pub fn current_context(&self) -> usize {
         self.current_context
     }
 
     pub fn context_name(&self) -> &'static str {
-        match self.current_context {
-            0 => "Units",
-            1 => "Network",
-            2 => "DNS",
-            3 => "Host",
-            4 => "Boot",
-            5 => "Logs",
-            _ => "Unknown",
+        TABS.iter()
+            .find(|(tab, _)| *tab == self.current_context)
+            .map_or("Unknown", |(_, name)| name)
+    }
+
+    /// Position of the current tab in the header
+    pub fn tab_position(&self) -> usize {
+        TABS.iter()
+            .position(|(tab, _)| *tab == self.current_context)
+            .unwrap_or(0)
+    }
+
+    /// Put `ctx` in the focused pane; a tab already shown in the other
+    /// pane just takes the focus
+    fn show(&mut self, ctx: usize) {
+        if self.split.as_ref().is_some_and(|split| split.other == ctx) {
+            self.cycle_focus();
+        } else {
+            self.current_context = ctx;
         }
     }
 
+    /// Show a second tab beside the current one: Logs, or Units when the
+    /// current tab is Logs. Closes the split when open.
+    pub fn toggle_split(&mut self) {
+        if self.split.take().is_some() {
+            #[cfg(feature = "logs")]
+            self.logs.set_unit(None);
+            return;
+        }
+        let preferred = if self.current_context == 5 { 0 } else { 5 };
+        let other = if has_tab(preferred) && !self.is_local_only(preferred) {
+            Some(preferred)
+        } else {
+            TABS.iter()
+                .map(|(tab, _)| *tab)
+                .find(|tab| *tab != self.current_context && !self.is_local_only(*tab))
+        };
+        match other {
+            Some(other) => {
+                self.split = Some(Split {
+                    other,
+                    other_on_right: true,
+                })
+            }
+            None => self.messages.warn("Only one tab in this build"),
+        }
+    }
+
+    /// Move the focus to the other pane of the split
+    pub fn cycle_focus(&mut self) {
+        if let Some(split) = &mut self.split {
+            std::mem::swap(&mut split.other, &mut self.current_context);
+            split.other_on_right = !split.other_on_right;
+        }
+    }
+
+    /// Tabs shown (left, right) when split
+    pub fn panes(&self) -> Option<(usize, usize)> {
+        self.split.as_ref().map(|split| {
+            if split.other_on_right {
+                (self.current_context, split.other)
+            } else {
+                (split.other, self.current_context)
+            }
+        })
+    }
+
+    /// The tab in the unfocused pane
+    pub fn other_pane(&self) -> Option<usize> {
+        self.split.as_ref().map(|split| split.other)
+    }
+
     pub fn next_context(&mut self) {
-        self.current_context = (self.current_context + 1) % 6;
+        self.show(TABS[(self.tab_position() + 1) % TABS.len()].0);
     }
 
     pub fn prev_context(&mut self) {
-        if self.current_context == 0 {
-            self.current_context = 5;
+        let position = self.tab_position().checked_sub(1).unwrap_or(TABS.len() - 1);
+        self.show(TABS[position].0);
+    }
+
+    /// Whether `tab` is off because it would show this machine while
+    /// `--host` manages another
+    pub fn is_local_only(&self, tab: usize) -> bool {
+        self.systemd.host().is_some() && LOCAL_TABS.contains(&tab)
+    }
+
+    pub fn set_context(&mut self, ctx: usize) {
+        if has_tab(ctx) {
+            self.show(ctx);
         } else {
-            self.current_context -= 1;
+            self.messages
+                .warn("That tab isn't compiled into this build of rootwork");
         }
     }
 
-    pub fn set_context(&mut self, ctx: usize) {
-        if ctx < 6 {
-            self.current_context = ctx;
+    /// Switch to the tab at `position` in the header, as the number keys do
+    pub fn select_tab(&mut self, position: usize) {
+        if let Some((tab, _)) = TABS.get(position) {
+            self.show(*tab);
+        }
+    }
+
+    /// Pre-fill the units filter, e.g. from the command line
+    #[cfg_attr(not(feature = "units"), allow(unused_variables))]
+    pub fn set_unit_filter(&mut self, filter: &str) {
+        #[cfg(feature = "units")]
+        self.units.set_filter(filter);
+    }
+
+    /// Switch to the Units tab with the detail popup open for `name`
+    #[cfg_attr(not(feature = "units"), allow(unused_variables))]
+    pub fn open_unit(&mut self, name: &str) -> bool {
+        #[cfg(feature = "units")]
+        if self.units.open_unit(name) {
+            self.show(0);
+            return true;
+        }
+        false
+    }
+
+    pub fn open_unit_search(&mut self) {
+        if has_tab(0) {
+            self.unit_search = Some(Picker::new());
+        } else {
+            self.messages
+                .warn("Go to unit needs the units tab, which isn't compiled in");
+        }
+    }
+
+    pub fn unit_search(&self) -> Option<&Picker> {
+        self.unit_search.as_ref()
+    }
+
+    #[cfg(feature = "units")]
+    fn handle_unit_search_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.unit_search.as_mut() else {
+            return;
+        };
+        let matches = self.units.search(&picker.query, UNIT_SEARCH_LIMIT);
+        match picker.handle_key(key, matches.len()) {
+            PickerEvent::Accept => {
+                let name = matches.get(picker.list.selected).map(|u| u.name.clone());
+                self.unit_search = None;
+                if let Some(name) = name {
+                    self.open_unit(&name);
+                }
+            }
+            PickerEvent::Close => self.unit_search = None,
+            PickerEvent::Changed | PickerEvent::None => {}
+        }
+    }
+
+    /// Write a markdown snapshot of every tab to the current directory
+    pub fn export_report(&mut self) {
+        if self.report.is_pending() {
+            return;
+        }
+        self.messages.info("Writing report...");
+        let systemd = self.systemd.clone();
+        self.report = Pending::spawn_async(async move {
+            let text = crate::report::generate(&systemd).await;
+            crate::report::write(&text)
+        });
+    }
+
+    pub fn open_palette(&mut self) {
+        self.palette = Some(Picker::new());
+    }
+
+    pub fn palette(&self) -> Option<&Picker> {
+        self.palette.as_ref()
+    }
+
+    /// Feed a key to the open palette; returns the command picked with Enter
+    pub fn handle_palette_key(&mut self, key: KeyEvent) -> Option<&'static Command> {
+        let picker = self.palette.as_mut()?;
+        let matches = commands::search(&picker.query);
+        match picker.handle_key(key, matches.len()) {
+            PickerEvent::Accept => {
+                let command = matches.get(picker.list.selected).copied();
+                self.palette = None;
+                command
+            }
+            PickerEvent::Close => {
+                self.palette = None;
+                None
+            }
+            PickerEvent::Changed | PickerEvent::None => None,
+        }
+    }
+
+    /// Run a palette command that acts on a tab. Quitting, suspending and
+    /// the other global commands are the main loop's business.
+    pub fn run_command(&mut self, command: &Command) {
+        match command.run {
+            Run::Key(ctx, code) => {
+                self.set_context(ctx);
+                self.handle_key(KeyEvent::from(code));
+            }
+            #[cfg(feature = "units")]
+            Run::UnitsList(code) => {
+                self.show(0);
+                self.units.close_detail();
+                self.units.handle_key(KeyEvent::from(code));
+            }
+            #[cfg(feature = "units")]
+            Run::UnitDetail(code) => {
+                self.show(0);
+                if self.units.ensure_detail() {
+                    self.units.handle_key(KeyEvent::from(code));
+                } else {
+                    self.messages.warn("Select a unit first");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether keys should go to a text field rather than global shortcuts
+    pub fn captures_input(&self) -> bool {
+        if self.unit_search.is_some() || self.palette.is_some() {
+            return true;
+        }
+        match self.current_context {
+            #[cfg(feature = "units")]
+            0 => self.units.captures_input(),
+            #[cfg(feature = "network")]
+            1 => self.network.captures_input(),
+            #[cfg(feature = "dns")]
+            2 => self.dns.captures_input(),
+            #[cfg(feature = "host")]
+            3 => self.host.captures_input(),
+            #[cfg(feature = "boot")]
+            4 => self.boot.captures_input(),
+            #[cfg(feature = "logs")]
+            5 => self.logs.captures_input(),
+            #[cfg(feature = "machines")]
+            6 => self.machines.captures_input(),
+            #[cfg(feature = "coredumps")]
+            7 => self.coredumps.captures_input(),
+            #[cfg(feature = "storage")]
+            8 => self.storage.captures_input(),
+            #[cfg(feature = "portable")]
+            9 => self.portable.captures_input(),
+            #[cfg(feature = "kernel")]
+            10 => self.kernel.captures_input(),
+            _ => false,
         }
     }
 
@@ -93,69 +557,279 @@ impl App {
             return;
         }
 
+        #[cfg(feature = "units")]
+        if self.unit_search.is_some() {
+            self.handle_unit_search_key(key);
+            return;
+        }
+
+        if self.is_local_only(self.current_context) {
+            return;
+        }
+
         // Route to current context
         match self.current_context {
+            #[cfg(feature = "units")]
             0 => self.units.handle_key(key),
-            1 => self.network.handle_key(key),
+            #[cfg(feature = "network")]
+            1 => {
+                self.network.handle_key(key);
+                if let Some(unit) = self.network.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
+            #[cfg(feature = "dns")]
             2 => self.dns.handle_key(key),
+            #[cfg(feature = "host")]
             3 => self.host.handle_key(key),
+            #[cfg(feature = "boot")]
             4 => self.boot.handle_key(key),
+            #[cfg(feature = "logs")]
             5 => self.logs.handle_key(key),
+            #[cfg(feature = "machines")]
+            6 => {
+                self.machines.handle_key(key);
+                if let Some(unit) = self.machines.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
+            #[cfg(feature = "coredumps")]
+            7 => {
+                self.coredumps.handle_key(key);
+                if let Some(unit) = self.coredumps.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
+            #[cfg(feature = "storage")]
+            8 => {
+                self.storage.handle_key(key);
+                if let Some(unit) = self.storage.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
+            #[cfg(feature = "portable")]
+            9 => {
+                self.portable.handle_key(key);
+                if let Some(unit) = self.portable.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
+            #[cfg(feature = "kernel")]
+            10 => {
+                self.kernel.handle_key(key);
+                if let Some(unit) = self.kernel.take_jump() {
+                    self.open_unit(&unit);
+                }
+            }
             _ => {}
         }
     }
 
     pub async fn tick(&mut self) {
-        // Update current context
-        match self.current_context {
+        // Watched units are followed from every tab
+        self.watchlist.tick(&self.systemd, &self.messages);
+
+        if let Some(result) = self.report.poll() {
+            match result {
+                Ok(path) => self
+                    .messages
+                    .info(format!("Report written to {}", path.display())),
+                Err(e) => self.messages.error(format!("Report: {}", e)),
+            }
+        }
+
+        match self.manager_status_pending.poll() {
+            Some(Ok(status)) => self.manager_status = Some(status),
+            // The header just shows nothing; the tabs report bus trouble
+            Some(Err(e)) => {
+                tracing::debug!("Manager status: {}", e);
+                self.manager_status = None;
+            }
+            None if !self.manager_status_pending.is_pending()
+                && self.manager_status_read.elapsed() >= MANAGER_STATUS_INTERVAL =>
+            {
+                let systemd = self.systemd.clone();
+                self.manager_status_read = Instant::now();
+                self.manager_status_pending =
+                    Pending::spawn_async(async move { systemd.manager_status().await });
+            }
+            None => {}
+        }
+
+        // /proc is this machine's, not the remote host's
+        if self.systemd.host().is_none() {
+            self.pressure.tick();
+            self.battery.tick();
+        }
+
+        // Every tab's timers and pending work run on each tick, shown or
+        // not, so pollers such as the OOM banner keep going; what is on
+        // screen decides only how often a tab re-reads its data
+        for &(ctx, _) in TABS {
+            if self.is_local_only(ctx) {
+                continue;
+            }
+            let slowdown = if ctx == self.current_context || Some(ctx) == self.other_pane() {
+                Some(1)
+            } else if self.background.contains(&ctx) {
+                Some(self.background_slowdown)
+            } else {
+                None
+            };
+            if let Some(slowdown) = slowdown
+                && self.refresh[ctx].due(slowdown)
+            {
+                self.auto_refresh(ctx);
+            }
+            self.tick_context(ctx).await;
+        }
+
+        if self.other_pane().is_some() {
+            self.follow_split_unit();
+        }
+    }
+
+    /// With Units and Logs side by side, Logs show the selected unit
+    fn follow_split_unit(&mut self) {
+        #[cfg(all(feature = "units", feature = "logs"))]
+        if let Some((left, right)) = self.panes()
+            && [left, right].contains(&0)
+            && [left, right].contains(&5)
+        {
+            let unit = self.units.selected_unit().map(|unit| unit.name.clone());
+            self.logs.set_unit(unit.as_deref());
+        }
+    }
+
+    async fn tick_context(&mut self, ctx: usize) {
+        match ctx {
+            #[cfg(feature = "units")]
             0 => self.units.tick().await,
+            #[cfg(feature = "network")]
             1 => self.network.tick().await,
+            #[cfg(feature = "dns")]
             2 => self.dns.tick().await,
+            #[cfg(feature = "host")]
             3 => self.host.tick().await,
+            #[cfg(feature = "boot")]
             4 => self.boot.tick().await,
+            #[cfg(feature = "logs")]
             5 => self.logs.tick().await,
+            #[cfg(feature = "machines")]
+            6 => self.machines.tick().await,
+            #[cfg(feature = "coredumps")]
+            7 => self.coredumps.tick().await,
+            #[cfg(feature = "storage")]
+            8 => self.storage.tick().await,
+            #[cfg(feature = "portable")]
+            9 => self.portable.tick().await,
+            #[cfg(feature = "kernel")]
+            10 => self.kernel.tick().await,
             _ => {}
         }
     }
 
+    fn auto_refresh(&mut self, ctx: usize) {
+        match ctx {
+            #[cfg(feature = "units")]
+            0 => self.units.auto_refresh(),
+            #[cfg(feature = "network")]
+            1 => self.network.auto_refresh(),
+            #[cfg(feature = "dns")]
+            2 => self.dns.auto_refresh(),
+            #[cfg(feature = "host")]
+            3 => self.host.auto_refresh(),
+            #[cfg(feature = "boot")]
+            4 => self.boot.auto_refresh(),
+            #[cfg(feature = "logs")]
+            5 => self.logs.auto_refresh(),
+            #[cfg(feature = "machines")]
+            6 => self.machines.auto_refresh(),
+            #[cfg(feature = "coredumps")]
+            7 => self.coredumps.auto_refresh(),
+            #[cfg(feature = "storage")]
+            8 => self.storage.auto_refresh(),
+            #[cfg(feature = "portable")]
+            9 => self.portable.auto_refresh(),
+            #[cfg(feature = "kernel")]
+            10 => self.kernel.auto_refresh(),
+            _ => {}
+        }
+    }
+
+    /// Command a context wants run with the terminal handed over
+    pub fn take_external_command(&mut self) -> Option<std::process::Command> {
+        #[cfg(feature = "units")]
+        return self.units.take_external_command();
+        #[cfg(not(feature = "units"))]
+        None
+    }
+
     // Getters for contexts
+    #[cfg(feature = "units")]
     pub fn units(&self) -> &UnitsContext {
         &self.units
     }
 
+    #[cfg(feature = "network")]
     pub fn network(&self) -> &NetworkContext {
         &self.network
     }
 
+    #[cfg(feature = "dns")]
     pub fn dns(&self) -> &DnsContext {
         &self.dns
     }
 
+    #[cfg(feature = "host")]
     pub fn host(&self) -> &HostContext {
         &self.host
     }
 
+    #[cfg(feature = "boot")]
     pub fn boot(&self) -> &BootContext {
         &self.boot
     }
 
+    #[cfg(feature = "logs")]
     pub fn logs(&self) -> &LogsContext {
         &self.logs
     }
 
-    pub fn systemd(&self) -> &SystemdClient {
-        &self.systemd
+    #[cfg(feature = "machines")]
+    pub fn machines(&self) -> &MachinesContext {
+        &self.machines
+    }
+
+    #[cfg(feature = "coredumps")]
+    pub fn coredumps(&self) -> &CoredumpsContext {
+        &self.coredumps
     }
 
-    pub fn error_message(&self) -> Option<&str> {
-        self.error_message.as_deref()
+    #[cfg(feature = "storage")]
+    pub fn storage(&self) -> &StorageContext {
+        &self.storage
+    }
+
+    #[cfg(feature = "portable")]
+    pub fn portable(&self) -> &PortableContext {
+        &self.portable
+    }
+
+    #[cfg(feature = "kernel")]
+    pub fn kernel(&self) -> &KernelContext {
+        &self.kernel
+    }
+
+    pub fn systemd(&self) -> &SystemdClient {
+        &self.systemd
     }
 
-    pub fn set_error(&mut self, msg: String) {
-        self.error_message = Some(msg);
+    pub fn messages(&self) -> &Messages {
+        &self.messages
     }
 
-    pub fn clear_error(&mut self) {
-        self.error_message = None;
+    pub fn watchlist(&self) -> &Watchlist {
+        &self.watchlist
     }
 }
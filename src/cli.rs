@@ -0,0 +1,356 @@
+use crate::systemd::client::{SystemdClient, UnitInfo};
+use crate::systemd::logs::{UnitLogEntry, display_time, read_recent_unit_logs};
+use clap::{Subcommand, ValueEnum};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Exit code when the unit ended up failed/inactive, same as `systemctl is-active`
+const EXIT_NOT_ACTIVE: u8 = 3;
+/// Exit code when `wait-active` ran out of time, same as timeout(1)
+const EXIT_TIMEOUT: u8 = 124;
+/// Exit code for a unit that isn't loaded, same as `systemctl status`
+const EXIT_NO_SUCH_UNIT: u8 = 4;
+
+/// Non-interactive commands; the TUI starts when none is given
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Run an action on a unit. Returns once the job is queued.
+    Unit { action: UnitVerb, unit: String },
+    /// Block until a unit is active. Exits 3 if it fails, 124 on timeout.
+    WaitActive {
+        unit: String,
+        /// Give up after this many seconds
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+    /// Print all loaded units
+    ListUnits {
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Print failed units. Exits 3 if there are any.
+    Failed {
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+    /// Write a markdown report of failed units, recent errors, network,
+    /// DNS, host and boot state. Prints to stdout unless --file is given.
+    Report {
+        #[arg(long, value_name = "PATH")]
+        file: Option<std::path::PathBuf>,
+    },
+    /// Print a unit's state and recent log lines
+    Status {
+        unit: String,
+        /// Number of log lines to include
+        #[arg(short = 'n', long, default_value_t = 10)]
+        lines: usize,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum UnitVerb {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+impl UnitVerb {
+    fn label(&self) -> &'static str {
+        match self {
+            UnitVerb::Start => "start",
+            UnitVerb::Stop => "stop",
+            UnitVerb::Restart => "restart",
+            UnitVerb::Enable => "enable",
+            UnitVerb::Disable => "disable",
+        }
+    }
+}
+
+/// Run a subcommand and map the outcome to an exit code. Diagnostics go to
+/// stderr unless `quiet` is set.
+pub async fn run(cmd: CliCommand, systemd: &SystemdClient, quiet: bool) -> ExitCode {
+    match cmd {
+        CliCommand::Unit { action, unit } => {
+            let result = match action {
+                UnitVerb::Start => systemd.start_unit(&unit).await,
+                UnitVerb::Stop => systemd.stop_unit(&unit).await,
+                UnitVerb::Restart => systemd.restart_unit(&unit).await,
+                UnitVerb::Enable => systemd.enable_unit(&unit).await,
+                UnitVerb::Disable => systemd.disable_unit(&unit).await,
+            };
+            match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    if !quiet {
+                        eprintln!("{} {}: {}", action.label(), unit, e);
+                    }
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        CliCommand::WaitActive { unit, timeout } => {
+            wait_active(systemd, &unit, timeout.map(Duration::from_secs), quiet).await
+        }
+        CliCommand::ListUnits { output } => match list_units(systemd, quiet).await {
+            Some(units) => {
+                print_units(&units, output);
+                ExitCode::SUCCESS
+            }
+            None => ExitCode::FAILURE,
+        },
+        CliCommand::Failed { output } => match list_units(systemd, quiet).await {
+            Some(units) => {
+                let failed: Vec<UnitInfo> = units.into_iter().filter(|u| u.is_failed()).collect();
+                print_units(&failed, output);
+                if failed.is_empty() {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::from(EXIT_NOT_ACTIVE)
+                }
+            }
+            None => ExitCode::FAILURE,
+        },
+        CliCommand::Report { file } => {
+            let report = crate::report::generate(systemd).await;
+            let Some(path) = file else {
+                print!("{}", report);
+                return ExitCode::SUCCESS;
+            };
+            match std::fs::write(&path, report) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    if !quiet {
+                        eprintln!("Failed to write {}: {}", path.display(), e);
+                    }
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        CliCommand::Status {
+            unit,
+            lines,
+            output,
+        } => {
+            let Some(units) = list_units(systemd, quiet).await else {
+                return ExitCode::FAILURE;
+            };
+            let Some(info) = units.into_iter().find(|u| u.name == unit) else {
+                if !quiet {
+                    eprintln!("Unit {} could not be found.", unit);
+                }
+                return ExitCode::from(EXIT_NO_SUCH_UNIT);
+            };
+            // The journal is only readable locally
+            let logs = systemd
+                .host()
+                .is_none()
+                .then(|| read_recent_unit_logs(&info.name, systemd.is_user_mode(), lines));
+            print_status(&info, logs.as_deref(), output);
+            if info.is_active() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(EXIT_NOT_ACTIVE)
+            }
+        }
+    }
+}
+
+async fn list_units(systemd: &SystemdClient, quiet: bool) -> Option<Vec<UnitInfo>> {
+    match systemd.list_units().await {
+        Ok(mut units) => {
+            units.sort_by(|a, b| a.name.cmp(&b.name));
+            Some(units)
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("Failed to list units: {}", e);
+            }
+            None
+        }
+    }
+}
+
+fn print_units(units: &[UnitInfo], output: OutputFormat) {
+    match output {
+        OutputFormat::Json => {
+            let items: Vec<String> = units.iter().map(unit_json).collect();
+            println!("[{}]", items.join(","));
+        }
+        OutputFormat::Table => {
+            let name_width = units.iter().map(|u| u.name.len()).max().unwrap_or(0).max(4);
+            println!(
+                "{:name_width$} {:9} {:12} {:12} DESCRIPTION",
+                "UNIT", "LOAD", "ACTIVE", "SUB"
+            );
+            for u in units {
+                println!(
+                    "{:name_width$} {:9} {:12} {:12} {}",
+                    u.name, u.load_state, u.active_state, u.sub_state, u.description
+                );
+            }
+            println!("\n{} units listed.", units.len());
+        }
+    }
+}
+
+/// `logs` is None when the journal can't be read, e.g. over --host
+fn print_status(unit: &UnitInfo, logs: Option<&[UnitLogEntry]>, output: OutputFormat) {
+    match output {
+        OutputFormat::Json => {
+            let unit = unit_json(unit);
+            // Splice the logs into the unit object
+            println!("{},\"logs\":{}}}", &unit[..unit.len() - 1], logs_json(logs));
+        }
+        OutputFormat::Table => {
+            println!(
+                "{} {} - {}",
+                unit.state_indicator(),
+                unit.name,
+                unit.description
+            );
+            println!("     Loaded: {}", unit.load_state);
+            println!("     Active: {} ({})", unit.active_state, unit.sub_state);
+            match logs {
+                None => println!("       Logs: journal not available over --host"),
+                Some([]) => {}
+                Some(logs) => {
+                    println!();
+                    for e in logs {
+                        println!("{} {}", display_time(e.timestamp_micros), e.message);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn unit_json(u: &UnitInfo) -> String {
+    format!(
+        "{{\"name\":{},\"description\":{},\"load_state\":{},\"active_state\":{},\"sub_state\":{}}}",
+        json_string(&u.name),
+        json_string(&u.description),
+        json_string(&u.load_state),
+        json_string(&u.active_state),
+        json_string(&u.sub_state)
+    )
+}
+
+/// The log lines as a JSON array, or null without a journal
+fn logs_json(logs: Option<&[UnitLogEntry]>) -> String {
+    let Some(logs) = logs else {
+        return "null".to_string();
+    };
+    let entries: Vec<String> = logs
+        .iter()
+        .map(|e| {
+            format!(
+                "{{\"timestamp_usec\":{},\"message\":{}}}",
+                e.timestamp_micros,
+                json_string(&e.message)
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+async fn wait_active(
+    systemd: &SystemdClient,
+    unit: &str,
+    timeout: Option<Duration>,
+    quiet: bool,
+) -> ExitCode {
+    let started = Instant::now();
+    loop {
+        match systemd.active_state(unit).await {
+            Ok(state) if state == "active" => return ExitCode::SUCCESS,
+            Ok(state) if state == "failed" => {
+                if !quiet {
+                    eprintln!("{}: failed", unit);
+                }
+                return ExitCode::from(EXIT_NOT_ACTIVE);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if !quiet {
+                    eprintln!("{}: {}", unit, e);
+                }
+                return ExitCode::FAILURE;
+            }
+        }
+
+        if let Some(timeout) = timeout
+            && started.elapsed() >= timeout
+        {
+            if !quiet {
+                eprintln!("{}: timed out waiting for active state", unit);
+            }
+            return ExitCode::from(EXIT_TIMEOUT);
+        }
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_string_escapes_quotes_and_control_characters() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a \"b\" \\c"), r#""a \"b\" \\c""#);
+        assert_eq!(json_string("x\ny\tz\r"), r#""x\ny\tz\r""#);
+        assert_eq!(json_string("\u{1b}[1m"), r#""\u001b[1m""#);
+        assert_eq!(json_string("ünïcode ✓"), "\"ünïcode ✓\"");
+    }
+
+    #[test]
+    fn json_string_round_trips_through_a_json_parser() {
+        let nasty = "quote \" backslash \\ nul \0 bell \u{7} del \u{7f} emoji 🦀 \u{2028}";
+        let parsed: String = serde_json::from_str(&json_string(nasty)).unwrap();
+        assert_eq!(parsed, nasty);
+    }
+
+    #[test]
+    fn logs_json_is_null_without_a_journal() {
+        assert_eq!(logs_json(None), "null");
+        assert_eq!(logs_json(Some(&[])), "[]");
+        let logs = [UnitLogEntry {
+            timestamp_micros: 42,
+            monotonic_usec: None,
+            message: "said \"hi\"".to_string(),
+        }];
+        let value: serde_json::Value = serde_json::from_str(&logs_json(Some(&logs))).unwrap();
+        assert_eq!(value[0]["timestamp_usec"], 42);
+        assert_eq!(value[0]["message"], "said \"hi\"");
+    }
+}
@@ -0,0 +1,705 @@
+use crate::app;
+use crate::widgets::picker::fuzzy_match_score;
+use crossterm::event::KeyCode;
+
+/// What running a palette entry does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Run {
+    Quit,
+    Shell,
+    Help,
+    GoToUnit,
+    Report,
+    /// Open or close the split view
+    Split,
+    /// Focus the other pane of the split
+    SplitFocus,
+    Tab(usize),
+    Theme(&'static str),
+    Monochrome,
+    /// Replay a key on a tab, as if it was pressed there
+    Key(usize, KeyCode),
+    /// Replay a key on the units list, closing the detail popup first
+    UnitsList(KeyCode),
+    /// Replay a key in the detail popup, opening it on the selected unit first
+    UnitDetail(KeyCode),
+}
+
+/// One palette entry. `keys` is the shortcut that does the same thing, so
+/// the palette doubles as a way to learn them.
+#[derive(Debug)]
+pub struct Command {
+    pub title: &'static str,
+    pub keys: &'static str,
+    pub run: Run,
+}
+
+impl Command {
+    /// False when the tab it acts on isn't compiled in
+    pub fn available(&self) -> bool {
+        match self.run {
+            Run::Tab(tab) | Run::Key(tab, _) => app::has_tab(tab),
+            Run::GoToUnit | Run::UnitsList(_) | Run::UnitDetail(_) => app::has_tab(0),
+            _ => true,
+        }
+    }
+}
+
+const fn cmd(title: &'static str, keys: &'static str, run: Run) -> Command {
+    Command { title, keys, run }
+}
+
+pub const COMMANDS: &[Command] = &[
+    cmd("Go to unit", "Ctrl+P", Run::GoToUnit),
+    cmd("Show help", "?", Run::Help),
+    cmd("Export a state report (markdown)", "Ctrl+E", Run::Report),
+    cmd("Split: show a second tab beside this one", "|", Run::Split),
+    cmd("Split: focus the other pane", "Ctrl+W", Run::SplitFocus),
+    cmd("Suspend to shell", "!", Run::Shell),
+    cmd("Quit", "q", Run::Quit),
+    cmd("Switch to Units", "1", Run::Tab(0)),
+    cmd("Switch to Network", "2", Run::Tab(1)),
+    cmd("Switch to DNS", "3", Run::Tab(2)),
+    cmd("Switch to Host", "4", Run::Tab(3)),
+    cmd("Switch to Boot", "5", Run::Tab(4)),
+    cmd("Switch to Logs", "6", Run::Tab(5)),
+    cmd("Switch to Machines", "7", Run::Tab(6)),
+    cmd("Switch to Coredumps", "8", Run::Tab(7)),
+    cmd("Switch to Storage", "9", Run::Tab(8)),
+    cmd("Switch to Portable", "0", Run::Tab(9)),
+    cmd("Switch to Kernel", "", Run::Tab(10)),
+    cmd("Theme: dark", "", Run::Theme("dark")),
+    cmd("Theme: light", "", Run::Theme("light")),
+    cmd("Theme: solarized", "", Run::Theme("solarized")),
+    cmd("Theme: high contrast", "", Run::Theme("high-contrast")),
+    cmd("Toggle monochrome", "", Run::Monochrome),
+    // Units list
+    cmd("Units: filter", "/", Run::UnitsList(KeyCode::Char('/'))),
+    cmd(
+        "Units: toggle tree/list view",
+        "t",
+        Run::UnitsList(KeyCode::Char('t')),
+    ),
+    cmd(
+        "Units: nest path/automount units under what they trigger",
+        "N",
+        Run::UnitsList(KeyCode::Char('N')),
+    ),
+    cmd("Units: cycle sort", "s", Run::UnitsList(KeyCode::Char('s'))),
+    cmd(
+        "Units: reverse sort direction",
+        "S",
+        Run::UnitsList(KeyCode::Char('S')),
+    ),
+    cmd(
+        "Units: show all/failed/active/running/inactive units",
+        "f",
+        Run::UnitsList(KeyCode::Char('f')),
+    ),
+    cmd(
+        "Units: hide/show systemd-internal units",
+        "I",
+        Run::UnitsList(KeyCode::Char('I')),
+    ),
+    cmd(
+        "Units: show/hide unit types in list view",
+        "y",
+        Run::UnitsList(KeyCode::Char('y')),
+    ),
+    cmd(
+        "Units: choose list columns",
+        "C",
+        Run::UnitsList(KeyCode::Char('C')),
+    ),
+    cmd(
+        "Units: apply or save a filter preset",
+        "P",
+        Run::UnitsList(KeyCode::Char('P')),
+    ),
+    cmd(
+        "Units: expand all groups",
+        "e",
+        Run::UnitsList(KeyCode::Char('e')),
+    ),
+    cmd(
+        "Units: collapse all groups",
+        "c",
+        Run::UnitsList(KeyCode::Char('c')),
+    ),
+    cmd(
+        "Units: batch action on the selected group",
+        "B",
+        Run::UnitsList(KeyCode::Char('B')),
+    ),
+    cmd(
+        "Units: pin/unpin the selected unit on the watchlist",
+        "w",
+        Run::UnitsList(KeyCode::Char('w')),
+    ),
+    cmd(
+        "Units: switch target (isolate, set default)",
+        "T",
+        Run::UnitsList(KeyCode::Char('T')),
+    ),
+    cmd(
+        "Units: user managers of all users (failed units per user)",
+        "U",
+        Run::UnitsList(KeyCode::Char('U')),
+    ),
+    // Unit detail
+    cmd(
+        "Unit: start",
+        "Enter, s",
+        Run::UnitDetail(KeyCode::Char('s')),
+    ),
+    cmd(
+        "Unit: stop",
+        "Enter, x",
+        Run::UnitDetail(KeyCode::Char('x')),
+    ),
+    cmd(
+        "Unit: enable",
+        "Enter, e",
+        Run::UnitDetail(KeyCode::Char('e')),
+    ),
+    cmd(
+        "Unit: disable",
+        "Enter, d",
+        Run::UnitDetail(KeyCode::Char('d')),
+    ),
+    cmd(
+        "Unit: open a shell inside it",
+        "Enter, o",
+        Run::UnitDetail(KeyCode::Char('o')),
+    ),
+    cmd(
+        "Unit: read the man page",
+        "Enter, m",
+        Run::UnitDetail(KeyCode::Char('m')),
+    ),
+    cmd(
+        "Unit: open the man page in man(1)",
+        "Enter, M",
+        Run::UnitDetail(KeyCode::Char('M')),
+    ),
+    cmd(
+        "Unit: copy documentation links",
+        "Enter, y",
+        Run::UnitDetail(KeyCode::Char('y')),
+    ),
+    cmd(
+        "Unit: inspect properties",
+        "Enter, p",
+        Run::UnitDetail(KeyCode::Char('p')),
+    ),
+    cmd(
+        "Unit: check sandboxing",
+        "Enter, a",
+        Run::UnitDetail(KeyCode::Char('a')),
+    ),
+    cmd(
+        "Unit: state change history",
+        "Enter, h",
+        Run::UnitDetail(KeyCode::Char('h')),
+    ),
+    cmd(
+        "Unit: environment variables and overrides",
+        "Enter, v",
+        Run::UnitDetail(KeyCode::Char('v')),
+    ),
+    cmd(
+        "Unit: resource limits and IP traffic (CPUQuota, MemoryMax, IPAccounting, ...)",
+        "Enter, l",
+        Run::UnitDetail(KeyCode::Char('l')),
+    ),
+    cmd(
+        "Unit: conditions and asserts (why it didn't start)",
+        "Enter, c",
+        Run::UnitDetail(KeyCode::Char('c')),
+    ),
+    cmd(
+        "Unit: unmount (mount units)",
+        "Enter, U",
+        Run::UnitDetail(KeyCode::Char('U')),
+    ),
+    cmd(
+        "Unit: remount with its options (mount units)",
+        "Enter, R",
+        Run::UnitDetail(KeyCode::Char('R')),
+    ),
+    cmd(
+        "Unit: only show logs of the current run",
+        "Enter, i",
+        Run::UnitDetail(KeyCode::Char('i')),
+    ),
+    cmd(
+        "Unit: follow logs",
+        "Enter, f",
+        Run::UnitDetail(KeyCode::Char('f')),
+    ),
+    cmd(
+        "Unit: cycle log timestamps",
+        "Enter, t",
+        Run::UnitDetail(KeyCode::Char('t')),
+    ),
+    // Other tabs
+    cmd(
+        "Network: filter interfaces",
+        "/",
+        Run::Key(1, KeyCode::Char('/')),
+    ),
+    cmd(
+        "Network: hide/show virtual interfaces (veth, docker, bridges)",
+        "v",
+        Run::Key(1, KeyCode::Char('v')),
+    ),
+    cmd(
+        "Network: sockets on the selected interface",
+        "s",
+        Run::Key(1, KeyCode::Char('s')),
+    ),
+    cmd(
+        "Network: show or fold the routing table",
+        "R",
+        Run::Key(1, KeyCode::Char('R')),
+    ),
+    cmd(
+        "Network: show another network namespace",
+        "n",
+        Run::Key(1, KeyCode::Char('n')),
+    ),
+    cmd(
+        "Network: firewall (nftables ruleset with hit counters)",
+        "F",
+        Run::Key(1, KeyCode::Char('F')),
+    ),
+    cmd(
+        "Network: bandwidth top (TCP connections by throughput)",
+        "B",
+        Run::Key(1, KeyCode::Char('B')),
+    ),
+    cmd(
+        "Network: connectivity checks (gateway, DNS, HTTP)",
+        "c",
+        Run::Key(1, KeyCode::Char('c')),
+    ),
+    cmd(
+        "Network: traceroute to a host (mtr-style)",
+        "t",
+        Run::Key(1, KeyCode::Char('t')),
+    ),
+    cmd("Network: refresh", "r", Run::Key(1, KeyCode::Char('r'))),
+    cmd("DNS: refresh", "r", Run::Key(2, KeyCode::Char('r'))),
+    cmd("Host: refresh", "r", Run::Key(3, KeyCode::Char('r'))),
+    cmd(
+        "Host: sync the clock now (restart systemd-timesyncd)",
+        "s",
+        Run::Key(3, KeyCode::Char('s')),
+    ),
+    cmd("Boot: refresh", "r", Run::Key(4, KeyCode::Char('r'))),
+    cmd(
+        "Boot: update systemd-boot on the ESP (bootctl update, root)",
+        "U",
+        Run::Key(4, KeyCode::Char('U')),
+    ),
+    cmd(
+        "Boot: scroll the journal of the selected entry's last boot",
+        "l",
+        Run::Key(4, KeyCode::Char('l')),
+    ),
+    cmd(
+        "Logs: pause/unpause streaming",
+        "p",
+        Run::Key(5, KeyCode::Char('p')),
+    ),
+    cmd("Logs: toggle follow", "f", Run::Key(5, KeyCode::Char('f'))),
+    cmd(
+        "Logs: follow again and show held new entries",
+        "G",
+        Run::Key(5, KeyCode::Char('G')),
+    ),
+    cmd("Logs: clear", "c", Run::Key(5, KeyCode::Char('c'))),
+    cmd("Logs: reload", "r", Run::Key(5, KeyCode::Char('r'))),
+    cmd(
+        "Logs: cycle journal source (all, system + user, system, user)",
+        "J",
+        Run::Key(5, KeyCode::Char('J')),
+    ),
+    cmd(
+        "Logs: jump to next error",
+        "n",
+        Run::Key(5, KeyCode::Char('n')),
+    ),
+    cmd(
+        "Logs: jump to previous error",
+        "N",
+        Run::Key(5, KeyCode::Char('N')),
+    ),
+    cmd(
+        "Logs: cycle timestamps",
+        "t",
+        Run::Key(5, KeyCode::Char('t')),
+    ),
+    cmd(
+        "Logs: show every field of the entry",
+        "Enter",
+        Run::Key(5, KeyCode::Enter),
+    ),
+    cmd(
+        "Logs: filter by journal fields (FIELD=value, OR) and ~ message regex",
+        "/",
+        Run::Key(5, KeyCode::Char('/')),
+    ),
+    cmd(
+        "Logs: collapse repeated messages (×N) on/off",
+        "d",
+        Run::Key(5, KeyCode::Char('d')),
+    ),
+    cmd(
+        "Logs: expand the selected ×N row",
+        "e",
+        Run::Key(5, KeyCode::Char('e')),
+    ),
+    cmd(
+        "Logs: apply or save a filter preset",
+        "P",
+        Run::Key(5, KeyCode::Char('P')),
+    ),
+    cmd(
+        "Logs: jump to the next bar of the volume strip",
+        "]",
+        Run::Key(5, KeyCode::Char(']')),
+    ),
+    cmd(
+        "Logs: jump to the previous bar of the volume strip",
+        "[",
+        Run::Key(5, KeyCode::Char('[')),
+    ),
+    cmd(
+        "Logs: show/hide the volume strip",
+        "H",
+        Run::Key(5, KeyCode::Char('H')),
+    ),
+    cmd(
+        "Logs: SELinux/AppArmor denials per unit with suggested rules",
+        "A",
+        Run::Key(5, KeyCode::Char('A')),
+    ),
+    cmd("Machines: terminate", "x", Run::Key(6, KeyCode::Char('x'))),
+    cmd("Machines: power off", "p", Run::Key(6, KeyCode::Char('p'))),
+    cmd(
+        "Machines: jump to the machine's unit",
+        "Enter",
+        Run::Key(6, KeyCode::Enter),
+    ),
+    cmd(
+        "Machines: select images (s: start, x: remove)",
+        "i",
+        Run::Key(6, KeyCode::Char('i')),
+    ),
+    cmd("Machines: refresh", "r", Run::Key(6, KeyCode::Char('r'))),
+    cmd(
+        "Coredumps: show the backtrace",
+        "Enter",
+        Run::Key(7, KeyCode::Enter),
+    ),
+    cmd(
+        "Coredumps: jump to the crashed unit",
+        "u",
+        Run::Key(7, KeyCode::Char('u')),
+    ),
+    cmd(
+        "Coredumps: delete the dump file",
+        "x",
+        Run::Key(7, KeyCode::Char('x')),
+    ),
+    cmd("Coredumps: refresh", "r", Run::Key(7, KeyCode::Char('r'))),
+    cmd(
+        "Storage: jump to the mount unit",
+        "Enter",
+        Run::Key(8, KeyCode::Enter),
+    ),
+    cmd(
+        "Storage: select swap areas (a: swapon, x: swapoff)",
+        "s",
+        Run::Key(8, KeyCode::Char('s')),
+    ),
+    cmd("Storage: refresh", "r", Run::Key(8, KeyCode::Char('r'))),
+    cmd(
+        "Portable: attach the image",
+        "a",
+        Run::Key(9, KeyCode::Char('a')),
+    ),
+    cmd(
+        "Portable: detach the image",
+        "x",
+        Run::Key(9, KeyCode::Char('x')),
+    ),
+    cmd(
+        "Portable: select the image's units",
+        "u",
+        Run::Key(9, KeyCode::Char('u')),
+    ),
+    cmd("Portable: refresh", "r", Run::Key(9, KeyCode::Char('r'))),
+    cmd(
+        "Kernel: filter sysctl parameters",
+        "/",
+        Run::Key(10, KeyCode::Char('/')),
+    ),
+    cmd(
+        "Kernel: only parameters that differ from sysctl.d",
+        "d",
+        Run::Key(10, KeyCode::Char('d')),
+    ),
+    cmd(
+        "Kernel: change the parameter's value",
+        "e",
+        Run::Key(10, KeyCode::Char('e')),
+    ),
+    cmd(
+        "Kernel: restore the configured value",
+        "c",
+        Run::Key(10, KeyCode::Char('c')),
+    ),
+    cmd(
+        "Kernel: switch between sysctl parameters and modules",
+        "m",
+        Run::Key(10, KeyCode::Char('m')),
+    ),
+    cmd("Kernel: refresh", "r", Run::Key(10, KeyCode::Char('r'))),
+];
+
+/// Commands matching `query`, best first; table order breaks ties
+pub fn search(query: &str) -> Vec<&'static Command> {
+    let needle = query.to_lowercase();
+    let mut scored: Vec<(usize, &'static Command)> = COMMANDS
+        .iter()
+        .filter(|c| c.available())
+        .filter_map(|c| Some((fuzzy_match_score(&c.title.to_lowercase(), &needle)?, c)))
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+/// A task-oriented walkthrough for the help overlay. Each `{}` in `keys` is
+/// filled with the shortcut of the matching entry of `runs`, looked up in
+/// `COMMANDS` so the recipes follow the keymap.
+struct Recipe {
+    tab: usize,
+    task: &'static str,
+    keys: &'static str,
+    runs: &'static [Run],
+}
+
+const fn recipe(
+    tab: usize,
+    task: &'static str,
+    keys: &'static str,
+    runs: &'static [Run],
+) -> Recipe {
+    Recipe {
+        tab,
+        task,
+        keys,
+        runs,
+    }
+}
+
+const fn ch(c: char) -> KeyCode {
+    KeyCode::Char(c)
+}
+
+const RECIPES: &[Recipe] = &[
+    recipe(
+        0,
+        "Restart a failed service",
+        "{} name Enter, {} y, {} y",
+        &[
+            Run::UnitsList(ch('/')),
+            Run::UnitDetail(ch('x')),
+            Run::UnitDetail(ch('s')),
+        ],
+    ),
+    recipe(
+        0,
+        "Restart every failed service",
+        "tree view: on the service group, {} Enter",
+        &[Run::UnitsList(ch('B'))],
+    ),
+    recipe(
+        0,
+        "Watch logs for one unit",
+        "{} name Enter, {}",
+        &[Run::UnitsList(ch('/')), Run::UnitDetail(ch('f'))],
+    ),
+    recipe(
+        0,
+        "Jump to a unit from any tab",
+        "{}, name, Enter",
+        &[Run::GoToUnit],
+    ),
+    recipe(
+        0,
+        "See what changed recently",
+        "{} until [recent], then j/k",
+        &[Run::UnitsList(ch('s'))],
+    ),
+    recipe(
+        0,
+        "Open a shell inside a service",
+        "on the unit: {}",
+        &[Run::UnitDetail(ch('o'))],
+    ),
+    recipe(
+        0,
+        "Read a unit's man page",
+        "on the unit: {}",
+        &[Run::UnitDetail(ch('m'))],
+    ),
+    recipe(
+        0,
+        "Find a unit property",
+        "on the unit: {}, / name",
+        &[Run::UnitDetail(ch('p'))],
+    ),
+    recipe(
+        0,
+        "Check how well a service is sandboxed",
+        "on the unit: {}",
+        &[Run::UnitDetail(ch('a'))],
+    ),
+    recipe(
+        0,
+        "See why a service keeps restarting",
+        "on the unit: {}",
+        &[Run::UnitDetail(ch('h'))],
+    ),
+    recipe(
+        0,
+        "Read only the logs of a service's latest start",
+        "on the unit: {}",
+        &[Run::UnitDetail(ch('i'))],
+    ),
+    recipe(
+        0,
+        "Throttle a runaway service",
+        "on the unit: {}, j/k, Enter 50% Enter",
+        &[Run::UnitDetail(ch('l'))],
+    ),
+    recipe(
+        0,
+        "Try a service with an extra env var",
+        "on the unit: {}, n NAME=value Enter, {} y, {} y",
+        &[
+            Run::UnitDetail(ch('v')),
+            Run::UnitDetail(ch('x')),
+            Run::UnitDetail(ch('s')),
+        ],
+    ),
+    recipe(
+        1,
+        "Inspect one interface",
+        "j/k to select, {} to refresh counters",
+        &[Run::Key(1, ch('r'))],
+    ),
+    recipe(
+        1,
+        "Find the service listening on a port",
+        "j/k to the interface, {}, j/k, Enter",
+        &[Run::Key(1, ch('s'))],
+    ),
+    recipe(
+        2,
+        "Check which DNS an interface uses",
+        "j/k in Per-Interface DNS",
+        &[],
+    ),
+    recipe(
+        3,
+        "Check the clock is in sync",
+        "Time panel: NTP Synchronized, NTP Offset",
+        &[],
+    ),
+    recipe(
+        4,
+        "Find the default boot entry",
+        "j/k through Boot Entries",
+        &[],
+    ),
+    recipe(
+        5,
+        "Read a burst of messages",
+        "{} to pause, j/k, {} to resume",
+        &[Run::Key(5, ch('p')), Run::Key(5, ch('p'))],
+    ),
+    recipe(5, "Go back to the live tail", "{}", &[Run::Key(5, ch('G'))]),
+    recipe(
+        5,
+        "See which PID or binary logged a line",
+        "j/k to it, {}",
+        &[Run::Key(5, KeyCode::Enter)],
+    ),
+    recipe(
+        5,
+        "Tail your user units next to system messages",
+        "{} until [system + user]",
+        &[Run::Key(5, ch('J'))],
+    ),
+    recipe(
+        6,
+        "Open the unit behind a container",
+        "j/k to select, {}",
+        &[Run::Key(6, KeyCode::Enter)],
+    ),
+    recipe(
+        6,
+        "Shut down a container cleanly",
+        "j/k to select, {} y",
+        &[Run::Key(6, ch('p'))],
+    ),
+    recipe(
+        7,
+        "Find why a service crashed",
+        "j/k to the dump, {}",
+        &[Run::Key(7, KeyCode::Enter)],
+    ),
+    recipe(
+        7,
+        "Free space taken by old dumps",
+        "j/k to the dump, {} y",
+        &[Run::Key(7, ch('x'))],
+    ),
+];
+
+impl Recipe {
+    /// The keys with every `{}` filled in, or None if a step has no command
+    /// in this build. Once the detail popup is open, later detail steps
+    /// leave out the Enter that opens it.
+    fn keys(&self) -> Option<String> {
+        let mut parts = self.keys.split("{}");
+        let mut out = parts.next().unwrap_or_default().to_string();
+        let mut in_detail = false;
+        for (run, rest) in self.runs.iter().zip(parts) {
+            let command = COMMANDS.iter().find(|c| c.run == *run && c.available())?;
+            let keys = match run {
+                Run::UnitDetail(_) if in_detail => {
+                    command.keys.strip_prefix("Enter, ").unwrap_or(command.keys)
+                }
+                _ => command.keys,
+            };
+            in_detail = matches!(run, Run::UnitDetail(_));
+            out.push_str(keys);
+            out.push_str(rest);
+        }
+        Some(out)
+    }
+}
+
+/// The recipes for `tab`, as (task, keys)
+pub fn recipes(tab: usize) -> Vec<(&'static str, String)> {
+    RECIPES
+        .iter()
+        .filter(|r| r.tab == tab)
+        .filter_map(|r| Some((r.task, r.keys()?)))
+        .collect()
+}
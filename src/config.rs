@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// User configuration, read from `$XDG_CONFIG_HOME/rootwork/config.toml`.
+///
+/// Only the TOML subset we need is understood: `[section]` headers and
+/// `key = value` pairs with string, number or boolean values. Keys before
+/// the first header live in the "" section.
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to read {}: {}", path.display(), e);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(base.join("rootwork").join("config.toml"))
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut current = String::new();
+
+        for line in content.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                current = name.trim().to_string();
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                sections
+                    .entry(current.clone())
+                    .or_default()
+                    .insert(key.trim().to_string(), value.to_string());
+            }
+        }
+
+        Self { sections }
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)
+            .and_then(|s| s.get(key))
+            .map(|v| v.as_str())
+    }
+
+    /// Every section with its keys, in no particular order
+    #[cfg(any(feature = "units", feature = "logs"))]
+    pub fn sections(&self) -> impl Iterator<Item = (&str, &HashMap<String, String>)> {
+        self.sections
+            .iter()
+            .map(|(name, keys)| (name.as_str(), keys))
+    }
+
+    /// All keys of a section, e.g. color overrides
+    pub fn section(&self, section: &str) -> Option<&HashMap<String, String>> {
+        self.sections.get(section)
+    }
+}
+
+/// Drop a trailing `# comment`, leaving `#` inside quoted strings alone
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
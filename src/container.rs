@@ -0,0 +1,160 @@
+//! Telling whether rootwork runs inside a container, and the container facts
+//! worth showing instead of firmware and boot loader state, which a container
+//! can't see.
+
+#[cfg(feature = "host")]
+use crate::contexts::format_bytes;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(not(any(feature = "host", feature = "boot")), allow(dead_code))]
+pub struct Container {
+    /// "docker", "podman", "systemd-nspawn", "lxc", ... as systemd names them
+    pub kind: String,
+}
+
+static DETECTED: OnceLock<Option<Container>> = OnceLock::new();
+
+/// The container rootwork runs in, if any. Checked once: it can't change
+/// while running.
+pub fn detect() -> Option<&'static Container> {
+    DETECTED.get_or_init(detect_uncached).as_ref()
+}
+
+#[cfg(any(feature = "network", feature = "boot"))]
+pub fn in_container() -> bool {
+    detect().is_some()
+}
+
+/// Same sources as systemd-detect-virt --container, most reliable first
+fn detect_uncached() -> Option<Container> {
+    let kind = read_trimmed("/run/systemd/container")
+        .or_else(pid1_container_env)
+        .or_else(|| {
+            Path::new("/run/.containerenv")
+                .exists()
+                .then(|| "podman".into())
+        })
+        .or_else(|| Path::new("/.dockerenv").exists().then(|| "docker".into()))?;
+    Some(Container { kind })
+}
+
+/// `container=` from PID 1's environment; only readable as root
+fn pid1_container_env() -> Option<String> {
+    let environ = fs::read("/proc/1/environ").ok()?;
+    environ
+        .split(|b| *b == 0)
+        .filter_map(|var| var.strip_prefix(b"container="))
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+        .find(|value| !value.is_empty())
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Limits and isolation of the container, as far as it can see them
+#[derive(Debug, Clone)]
+#[cfg(feature = "host")]
+pub struct ContainerInfo {
+    pub kind: String,
+    /// What runs as PID 1, e.g. "systemd" or "bash"
+    pub init: String,
+    pub memory_max: String,
+    pub cpu_max: String,
+    pub pids_max: String,
+    /// How root inside maps to the host, e.g. "0 → 100000 (65536 ids)"
+    pub user_namespace: String,
+}
+
+#[cfg(feature = "host")]
+impl ContainerInfo {
+    pub fn gather(container: &Container) -> Self {
+        let cgroup = own_cgroup_dir();
+        let limit = |file: &str| read_trimmed(&format!("{}/{}", cgroup, file));
+        Self {
+            kind: container.kind.clone(),
+            init: read_trimmed("/proc/1/comm").unwrap_or_else(|| "unknown".to_string()),
+            // cgroup v1 hosts keep it under the memory controller
+            memory_max: limit("memory.max")
+                .or_else(|| read_trimmed("/sys/fs/cgroup/memory/memory.limit_in_bytes"))
+                .map(|max| match max.parse() {
+                    // v1 spells "no limit" as a page-rounded i64::MAX
+                    Ok(bytes) if bytes >= 1 << 62 => "max".to_string(),
+                    Ok(bytes) => format_bytes(bytes),
+                    Err(_) => max,
+                })
+                .unwrap_or_else(|| "unknown".to_string()),
+            cpu_max: limit("cpu.max")
+                .map(|max| format_cpu_max(&max))
+                .unwrap_or_else(|| "unknown".to_string()),
+            pids_max: limit("pids.max").unwrap_or_else(|| "unknown".to_string()),
+            user_namespace: user_namespace(),
+        }
+    }
+
+    pub fn rows(&self) -> [(&'static str, &str); 6] {
+        [
+            ("Container", &self.kind),
+            ("Init (PID 1)", &self.init),
+            ("Memory limit", &self.memory_max),
+            ("CPU limit", &self.cpu_max),
+            ("Task limit", &self.pids_max),
+            ("User namespace", &self.user_namespace),
+        ]
+    }
+}
+
+/// The unified-hierarchy cgroup rootwork is in. With a cgroup namespace
+/// that's the root of /sys/fs/cgroup.
+#[cfg(feature = "host")]
+fn own_cgroup_dir() -> String {
+    let path = fs::read_to_string("/proc/self/cgroup")
+        .ok()
+        .and_then(|text| {
+            text.lines()
+                .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+        })
+        .unwrap_or_default();
+    format!("/sys/fs/cgroup{}", path.trim_end_matches('/'))
+}
+
+/// cgroup v2 `cpu.max` is "<quota> <period>" in µs, or "max <period>"
+#[cfg(feature = "host")]
+fn format_cpu_max(text: &str) -> String {
+    let mut parts = text.split_whitespace();
+    match (
+        parts.next(),
+        parts.next().and_then(|p| p.parse::<u64>().ok()),
+    ) {
+        (Some("max"), _) => "max".to_string(),
+        (Some(quota), Some(period)) if period > 0 => match quota.parse::<u64>() {
+            Ok(quota) => format!("{:.2} CPUs", quota as f64 / period as f64),
+            Err(_) => text.to_string(),
+        },
+        _ => text.to_string(),
+    }
+}
+
+/// First line of uid_map; the identity map means no user namespace
+#[cfg(feature = "host")]
+fn user_namespace() -> String {
+    let Some(map) = read_trimmed("/proc/self/uid_map") else {
+        return "unknown".to_string();
+    };
+    let fields: Vec<&str> = map
+        .lines()
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .collect();
+    match fields.as_slice() {
+        ["0", "0", "4294967295"] => "none (shares the host's users)".to_string(),
+        [inside, outside, count] => format!("{} → {} ({} ids)", inside, outside, count),
+        _ => map,
+    }
+}
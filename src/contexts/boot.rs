@@ -1,14 +1,23 @@
-use crate::contexts::Context;
-use anyhow::Result;
-use crossterm::event::KeyEvent;
+use crate::container;
+use crate::contexts::{Context, Pending, format_bytes};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::{DiskUsage, is_root};
+use crate::systemd::logs::{self, BootLogEntry, JournalBoot};
+use crate::widgets::selectable::{ListState, SelectableTable};
+use anyhow::{Context as _, Result, bail};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    text::Span,
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Row, Table},
 };
-use std::path::Path;
+use std::cmp::Ordering;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub struct BootInfo {
     systemd_boot: bool,
@@ -17,6 +26,160 @@ pub struct BootInfo {
     secure_boot: String,
     setup_mode: String,
     entries: Vec<BootEntry>,
+    kernel_release: String,
+    cmdline: String,
+    /// /proc/sys/kernel/tainted, a bitmask of TAINT_FLAGS
+    tainted: u64,
+    esp: Option<Esp>,
+}
+
+/// The EFI system partition and the systemd-boot on it
+pub struct Esp {
+    path: PathBuf,
+    usage: Option<DiskUsage>,
+    /// systemd-boot version installed on the ESP
+    installed: Option<String>,
+    /// Version the systemd package ships for `bootctl update` to copy
+    shipped: Option<String>,
+}
+
+/// Where bootctl looks for the ESP, in its order
+const ESP_PATHS: [&str; 3] = ["/efi", "/boot/efi", "/boot"];
+/// systemd-boot images the systemd package ships
+const SHIPPED_BOOT_DIR: &str = "/usr/lib/systemd/boot/efi";
+
+impl Esp {
+    fn find() -> Option<Self> {
+        let path = ESP_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .find(|p| p.join("EFI").is_dir())?;
+        let path_str = path.to_string_lossy().into_owned();
+        Some(Self {
+            usage: DiskUsage::of(&path_str).ok(),
+            installed: boot_loader_version(&path.join("EFI/systemd")),
+            shipped: boot_loader_version(Path::new(SHIPPED_BOOT_DIR)),
+            path,
+        })
+    }
+
+    /// Whether `bootctl update` would install a newer systemd-boot
+    fn update_available(&self) -> bool {
+        match (&self.installed, &self.shipped) {
+            (Some(installed), Some(shipped)) => {
+                compare_versions(installed, shipped) == Ordering::Less
+            }
+            _ => false,
+        }
+    }
+
+    /// "/efi: 410 MiB free of 512 MiB"
+    fn describe(&self) -> String {
+        match self.usage {
+            Some(usage) => format!(
+                "{}: {} free of {}",
+                self.path.display(),
+                format_bytes(usage.available),
+                format_bytes(usage.total)
+            ),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+/// Version of the first systemd-boot*.efi in `dir`, from the
+/// "#### LoaderInfo: systemd-boot 255.4-1 ####" marker bootctl reads too
+fn boot_loader_version(dir: &Path) -> Option<String> {
+    const MARKER: &[u8] = b"#### LoaderInfo: systemd-boot ";
+    let image = std::fs::read_dir(dir).ok()?.flatten().find(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        name.starts_with("systemd-boot") && name.ends_with(".efi")
+    })?;
+    let data = std::fs::read(image.path()).ok()?;
+    let start = data.windows(MARKER.len()).position(|w| w == MARKER)? + MARKER.len();
+    let rest = &data[start..];
+    let end = rest.windows(5).position(|w| w == b" ####")?;
+    Some(String::from_utf8_lossy(&rest[..end]).into_owned())
+}
+
+/// Orders versions like "254.5-1.fc39" numerically by their digit runs,
+/// the way strverscmp does for bootctl
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let segments = |v: &str| -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        let mut last_digit = None;
+        for c in v.chars() {
+            let digit = c.is_ascii_digit();
+            match out.last_mut() {
+                Some(seg) if last_digit == Some(digit) => seg.push(c),
+                _ => out.push(c.to_string()),
+            }
+            last_digit = Some(digit);
+        }
+        out
+    };
+    for (x, y) in segments(a).iter().zip(segments(b).iter()) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    segments(a).len().cmp(&segments(b).len())
+}
+
+/// Taint bits as the kernel's admin-guide/tainted-kernels lists them:
+/// the letter Oops reports show and what it means
+const TAINT_FLAGS: [(char, &str); 19] = [
+    ('P', "proprietary module loaded"),
+    ('F', "module force-loaded"),
+    ('S', "CPU out of specification"),
+    ('R', "module force-unloaded"),
+    ('M', "machine check exception"),
+    ('B', "bad page referenced"),
+    ('U', "tainted by user request"),
+    ('D', "kernel died (oops or BUG)"),
+    ('A', "ACPI table overridden"),
+    ('W', "kernel issued a warning"),
+    ('C', "staging driver loaded"),
+    ('I', "platform firmware bug worked around"),
+    ('O', "out-of-tree module loaded"),
+    ('E', "unsigned module loaded"),
+    ('L', "soft lockup occurred"),
+    ('K', "kernel live patched"),
+    ('X', "auxiliary taint (distribution-defined)"),
+    ('T', "built with struct randomization"),
+    ('N', "in-kernel test run"),
+];
+
+/// "OE: out-of-tree module loaded, unsigned module loaded", or "not
+/// tainted"
+fn describe_taint(tainted: u64) -> String {
+    if tainted == 0 {
+        return "not tainted".to_string();
+    }
+    let set: Vec<_> = TAINT_FLAGS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| tainted & (1 << bit) != 0)
+        .map(|(_, flag)| flag)
+        .collect();
+    let letters: String = set.iter().map(|(letter, _)| letter).collect();
+    let reasons: Vec<_> = set.iter().map(|(_, reason)| *reason).collect();
+    let unknown = tainted >> TAINT_FLAGS.len() << TAINT_FLAGS.len();
+    match (set.is_empty(), unknown) {
+        (true, _) => format!("bits {:#x}", unknown),
+        (false, 0) => format!("{}: {}", letters, reasons.join(", ")),
+        (false, _) => format!(
+            "{}: {} (and bits {:#x})",
+            letters,
+            reasons.join(", "),
+            unknown
+        ),
+    }
 }
 
 pub struct BootEntry {
@@ -24,29 +187,224 @@ pub struct BootEntry {
     title: String,
     version: Option<String>,
     machine_id: Option<String>,
+    /// Kernel image, relative to the partition root
+    linux: Option<String>,
+    /// Kernel command line: `options` of a BLS entry, .cmdline of a UKI
+    cmdline: Option<String>,
+    /// A Type #2 entry: a unified kernel image under EFI/Linux
+    uki: bool,
     is_default: bool,
 }
 
+/// Most of a section `pe_sections` reads; .osrel, .cmdline and .uname are
+/// a few KiB at most
+const PE_SECTION_MAX: usize = 64 * 1024;
+
+fn read_at<R: Read + Seek>(file: &mut R, offset: u64, buf: &mut [u8]) -> std::io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buf)
+}
+
+/// Reads the PE section table of a UKI and returns the named sections
+/// it has, with their contents as (lossy) text. Only the headers and those
+/// sections are read, not the kernel and initrd around them.
+fn pe_sections<R: Read + Seek>(
+    file: &mut R,
+    wanted: &[&str],
+) -> std::io::Result<Vec<(String, String)>> {
+    let mut sections = Vec::new();
+    let mut word = [0u8; 4];
+    read_at(file, 0x3c, &mut word)?;
+    let pe = u64::from(u32::from_le_bytes(word));
+    let mut coff = [0u8; 24];
+    read_at(file, pe, &mut coff)?;
+    if &coff[..4] != b"PE\0\0" {
+        return Ok(sections);
+    }
+    let count = u16::from_le_bytes([coff[6], coff[7]]);
+    let optional_size = u16::from_le_bytes([coff[20], coff[21]]);
+    let mut table = vec![0u8; usize::from(count) * 40];
+    read_at(file, pe + 24 + u64::from(optional_size), &mut table)?;
+
+    for header in table.chunks_exact(40) {
+        let name = String::from_utf8_lossy(&header[..8])
+            .trim_end_matches('\0')
+            .to_string();
+        if !wanted.contains(&name.as_str()) {
+            continue;
+        }
+        let u32_at =
+            |o: usize| u32::from_le_bytes([header[o], header[o + 1], header[o + 2], header[o + 3]]);
+        let (virtual_size, raw_size, offset) = (u32_at(8), u32_at(16), u32_at(20));
+        // The raw size is padded to the file alignment
+        let size = if virtual_size == 0 {
+            raw_size
+        } else {
+            virtual_size.min(raw_size)
+        };
+        let mut content = vec![0u8; (size as usize).min(PE_SECTION_MAX)];
+        if read_at(file, u64::from(offset), &mut content).is_ok() {
+            let text = String::from_utf8_lossy(&content);
+            sections.push((name, text.trim_end_matches('\0').trim().to_string()));
+        }
+    }
+    Ok(sections)
+}
+
+impl BootEntry {
+    /// Type #1 entry from `path`; keys it doesn't have stay None
+    fn parse(path: &Path, id: &str) -> Self {
+        let mut entry = BootEntry {
+            id: id.to_string(),
+            title: id.to_string(),
+            version: None,
+            machine_id: None,
+            linux: None,
+            cmdline: None,
+            uki: false,
+            is_default: false,
+        };
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        for line in text.lines() {
+            let Some((key, value)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key {
+                "title" => entry.title = value,
+                "version" => entry.version = Some(value),
+                "machine-id" => entry.machine_id = Some(value),
+                "linux" => entry.linux = Some(value),
+                "options" => {
+                    // May repeat; systemd-boot joins them
+                    entry.cmdline = Some(match entry.cmdline.take() {
+                        Some(options) => format!("{} {}", options, value),
+                        None => value,
+                    })
+                }
+                _ => {}
+            }
+        }
+        entry
+    }
+
+    /// Type #2 entry from the UKI at `path`. Title and version come from
+    /// the embedded os-release, as systemd-boot shows them; .uname, where
+    /// ukify added it, names the kernel release.
+    fn parse_uki(path: &Path, id: &str) -> Self {
+        let mut entry = BootEntry {
+            id: id.to_string(),
+            title: id.to_string(),
+            version: None,
+            machine_id: None,
+            linux: Some(format!("/EFI/Linux/{}.efi", id)),
+            cmdline: None,
+            uki: true,
+            is_default: false,
+        };
+        let sections = std::fs::File::open(path)
+            .and_then(|mut file| pe_sections(&mut file, &[".osrel", ".cmdline", ".uname"]))
+            .unwrap_or_default();
+        let mut version_id = None;
+        for (name, text) in sections {
+            match name.as_str() {
+                ".osrel" => {
+                    for line in text.lines() {
+                        let Some((key, value)) = line.split_once('=') else {
+                            continue;
+                        };
+                        let value = value.trim().trim_matches('"').to_string();
+                        match key.trim() {
+                            "PRETTY_NAME" => entry.title = value,
+                            "VERSION_ID" => version_id = Some(value),
+                            _ => {}
+                        }
+                    }
+                }
+                ".cmdline" => entry.cmdline = Some(text),
+                ".uname" => entry.version = Some(text),
+                _ => {}
+            }
+        }
+        if entry.version.is_none() {
+            entry.version = version_id;
+        }
+        entry
+    }
+
+    /// Whether this entry boots kernel release `kernel`. kernel-install
+    /// puts the release in `version`, the image path and the entry ID.
+    fn boots_kernel(&self, kernel: &str) -> bool {
+        self.version.as_deref() == Some(kernel)
+            || self.linux.as_deref().is_some_and(|l| l.contains(kernel))
+            || self.id.contains(kernel)
+    }
+}
+
 impl BootInfo {
-    fn gather() -> Result<Self> {
+    pub fn gather() -> Result<Self> {
         Self::from_fallback()
     }
 
+    /// The report's Boot section: kernel and firmware facts, the ESP, and
+    /// a table of loader entries with the default marked
+    pub fn write_report(&self, out: &mut String) {
+        out.push_str("## Boot\n\n");
+        out.push_str(&format!("- Kernel: {}\n", self.kernel_release));
+        out.push_str(&format!("- Command line: `{}`\n", self.cmdline));
+        out.push_str(&format!("- Taint: {}\n", describe_taint(self.tainted)));
+        out.push_str(&format!("- Firmware: {}\n", self.firmware));
+        out.push_str(&format!("- Secure Boot: {}\n", self.secure_boot));
+        out.push_str(&format!("- Setup mode: {}\n", self.setup_mode));
+        if self.systemd_boot {
+            out.push_str(&format!("- systemd-boot: {}\n", self.loader_version));
+        }
+        if let Some(esp) = &self.esp {
+            out.push_str(&format!("- ESP: {}\n", esp.describe()));
+            if esp.update_available() {
+                out.push_str(&format!(
+                    "- systemd-boot update available: {}\n",
+                    esp.shipped.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        if !self.entries.is_empty() {
+            out.push_str("\n| Entry | Type | Title | Version | Default |\n|---|---|---|---|---|\n");
+            for entry in &self.entries {
+                out.push_str(&crate::report::table_row(&[
+                    &entry.id,
+                    if entry.uki { "UKI" } else { "BLS" },
+                    &entry.title,
+                    entry.version.as_deref().unwrap_or("-"),
+                    if entry.is_default { "yes" } else { "" },
+                ]));
+            }
+        }
+        out.push('\n');
+    }
+
     fn from_fallback() -> Result<Self> {
         // Fallback: check /boot or /efi for entries
         let entries = Self::scan_boot_entries()?;
 
         // Check for secure boot via efivars if available
         let secure_boot = Self::check_secure_boot();
+        let esp = Esp::find();
+        let installed = esp.as_ref().and_then(|esp| esp.installed.clone());
 
         Ok(Self {
-            systemd_boot: Path::new("/boot/EFI/systemd").exists()
+            systemd_boot: installed.is_some()
+                || Path::new("/boot/EFI/systemd").exists()
                 || Path::new("/efi/EFI/systemd").exists(),
             firmware: "unknown".to_string(),
-            loader_version: "unknown".to_string(),
+            loader_version: installed.unwrap_or_else(|| "unknown".to_string()),
             secure_boot,
             setup_mode: "unknown".to_string(),
             entries,
+            kernel_release: read_proc("/proc/sys/kernel/osrelease"),
+            cmdline: read_proc("/proc/cmdline"),
+            tainted: read_proc("/proc/sys/kernel/tainted").parse().unwrap_or(0),
+            esp,
         })
     }
 
@@ -62,19 +420,36 @@ impl BootInfo {
                     let path = entry.path();
                     if path.extension().map(|e| e == "conf").unwrap_or(false) {
                         if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                            entries.push(BootEntry {
-                                id: name.to_string(),
-                                title: name.to_string(),
-                                version: None,
-                                machine_id: None,
-                                is_default: false,
-                            });
+                            entries.push(BootEntry::parse(&path, name));
                         }
                     }
                 }
             }
         }
 
+        // Unified kernel images need no entry file; systemd-boot picks up
+        // every EFI/Linux/*.efi on the ESP and XBOOTLDR partition
+        for root in &ESP_PATHS {
+            let Ok(dir) = std::fs::read_dir(Path::new(root).join("EFI/Linux")) else {
+                continue;
+            };
+            for entry in dir.flatten() {
+                let path = entry.path();
+                if !path
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("efi"))
+                {
+                    continue;
+                }
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    // /boot and /boot/efi can be the same mount
+                    if !entries.iter().any(|e| e.uki && e.id == name) {
+                        entries.push(BootEntry::parse_uki(&path, name));
+                    }
+                }
+            }
+        }
+
         Ok(entries)
     }
 
@@ -107,50 +482,153 @@ impl BootInfo {
     }
 }
 
+/// A one-line /proc file, trimmed; empty if unreadable
+fn read_proc(path: &str) -> String {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
 pub struct BootContext {
     info: Option<BootInfo>,
-    error: Option<String>,
-    selected_entry: usize,
+    error: Option<RootworkError>,
+    pending: Pending<Result<BootInfo>>,
+    messages: Messages,
+    entries: ListState,
+    /// Boots in the journal, newest first
+    boots: Vec<JournalBoot>,
+    boots_pending: Pending<Result<Vec<JournalBoot>>>,
+    /// Warnings and errors of the boot matching the selected entry
+    boot_log: Option<(String, Vec<BootLogEntry>)>,
+    boot_log_pending: Pending<(String, Vec<BootLogEntry>)>,
+    /// Boot ID of the last log load, so moving the cursor between entries
+    /// of the same kernel doesn't reload it
+    log_requested: Option<String>,
+    /// j/k scroll the boot log instead of the entries
+    log_focused: bool,
+    log_list: ListState,
+    confirm_update: bool,
+    update_pending: Pending<Result<String>>,
 }
 
+/// Boot log lines shown per boot; the interesting part of a boot is its start
+const BOOT_LOG_MAX: usize = 2000;
+/// warning
+const BOOT_LOG_PRIORITY: u8 = 4;
+
 impl BootContext {
-    pub fn new() -> Self {
-        let (info, error) = match BootInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather boot info: {}", e))),
+    pub fn new(messages: &Messages) -> Self {
+        let mut ctx = Self {
+            info: None,
+            error: None,
+            pending: Pending::idle(),
+            messages: messages.clone(),
+            entries: ListState::new(),
+            boots: Vec::new(),
+            boots_pending: Pending::idle(),
+            boot_log: None,
+            boot_log_pending: Pending::idle(),
+            log_requested: None,
+            log_focused: false,
+            log_list: ListState::new(),
+            confirm_update: false,
+            update_pending: Pending::idle(),
         };
+        ctx.refresh();
+        ctx
+    }
 
-        Self {
-            info,
-            error,
-            selected_entry: 0,
+    fn refresh(&mut self) {
+        // Firmware and the ESP belong to the host; reading them from a
+        // container only yields "not available" everywhere
+        if container::in_container() {
+            return;
+        }
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn(BootInfo::gather);
+        }
+        if !self.boots_pending.is_pending() {
+            self.boots_pending = Pending::spawn(logs::list_boots);
         }
     }
 
-    fn refresh(&mut self) {
-        let (info, error) = match BootInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather boot info: {}", e))),
-        };
-        self.info = info;
-        self.error = error;
-        self.selected_entry = 0;
+    fn esp(&self) -> Option<&Esp> {
+        self.info.as_ref()?.esp.as_ref()
     }
 
-    fn move_up(&mut self) {
-        if let Some(ref info) = self.info {
-            if !info.entries.is_empty() && self.selected_entry > 0 {
-                self.selected_entry -= 1;
-            }
+    /// `U`: ask before copying the shipped systemd-boot onto the ESP
+    fn request_update(&mut self) {
+        if self.update_pending.is_pending() {
+            return;
+        }
+        if !is_root() {
+            self.messages.warn("Updating systemd-boot needs root");
+            return;
+        }
+        match self.esp() {
+            Some(esp) if esp.update_available() => self.confirm_update = true,
+            Some(esp) if esp.installed.is_none() => self
+                .messages
+                .warn("systemd-boot isn't installed on the ESP (see bootctl install)"),
+            Some(_) => self.messages.info("systemd-boot on the ESP is up to date"),
+            None => self.messages.warn("No EFI system partition found"),
         }
     }
 
-    fn move_down(&mut self) {
-        if let Some(ref info) = self.info {
-            if !info.entries.is_empty() && self.selected_entry + 1 < info.entries.len() {
-                self.selected_entry += 1;
+    fn run_update(&mut self) {
+        self.update_pending = Pending::spawn(|| {
+            let output = Command::new("bootctl")
+                .arg("update")
+                .output()
+                .context("Can't run bootctl")?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !output.status.success() {
+                bail!("bootctl update: {}", stderr.trim());
             }
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Ok(format!("{}{}", stdout, stderr).trim().to_string())
+        });
+    }
+
+    fn selected_entry(&self) -> Option<&BootEntry> {
+        self.info.as_ref()?.entries.get(self.entries.selected)
+    }
+
+    /// The latest boot of the selected entry's kernel and how many boots
+    /// ago it was, as `journalctl -b -N` counts
+    fn selected_boot(&self) -> Option<(usize, &JournalBoot)> {
+        let entry = self.selected_entry()?;
+        self.boots.iter().enumerate().find(|(_, boot)| {
+            boot.kernel
+                .as_deref()
+                .is_some_and(|kernel| entry.boots_kernel(kernel))
+        })
+    }
+
+    /// Start reading the log of the selected entry's boot unless it's
+    /// already shown or on its way
+    fn load_boot_log(&mut self) {
+        let Some(id) = self.selected_boot().map(|(_, boot)| boot.id.clone()) else {
+            return;
+        };
+        if self.log_requested.as_deref() == Some(id.as_str()) {
+            return;
         }
+        self.log_requested = Some(id.clone());
+        self.boot_log_pending = Pending::spawn(move || {
+            let entries = logs::read_boot_logs(&id, BOOT_LOG_PRIORITY, BOOT_LOG_MAX);
+            (id, entries)
+        });
+    }
+
+    fn boot_log_len(&self) -> usize {
+        self.boot_log
+            .as_ref()
+            .map_or(0, |(_, entries)| entries.len())
+    }
+
+    fn entry_count(&self) -> usize {
+        self.info.as_ref().map_or(0, |info| info.entries.len())
     }
 }
 
@@ -159,10 +637,35 @@ impl Context for BootContext {
         "Boot"
     }
 
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
     fn draw(&self, f: &mut Frame, area: Rect) {
+        if let Some(container) = container::detect() {
+            let text = format!(
+                "Not applicable: rootwork is running inside a container ({}).\n\n\
+                 Firmware, Secure Boot and boot entries belong to the host; \
+                 run rootwork there to see them.",
+                container.kind
+            );
+            let block = Block::default().title(" Boot ").borders(Borders::ALL);
+            f.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(crate::palette::gray()))
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(7), Constraint::Min(0)])
+            .constraints([
+                Constraint::Length(11),
+                Constraint::Percentage(35),
+                Constraint::Min(0),
+            ])
             .split(area);
 
         // Boot firmware info
@@ -170,43 +673,162 @@ impl Context for BootContext {
 
         // Boot entries
         draw_boot_entries(self, f, chunks[1]);
+
+        // Journal of the selected entry's last boot
+        draw_boot_log(self, f, chunks[2]);
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
+        if self.confirm_update {
+            self.confirm_update = false;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.run_update();
+            }
+            return;
+        }
         match key.code {
-            crossterm::event::KeyCode::Char('r') => self.refresh(),
-            crossterm::event::KeyCode::Char('j') | crossterm::event::KeyCode::Down => {
-                self.move_down()
+            KeyCode::Char('U') => {
+                self.request_update();
+                return;
+            }
+            KeyCode::Char('r') => {
+                self.refresh();
+                return;
+            }
+            KeyCode::Char('l') | KeyCode::Tab => {
+                self.log_focused = !self.log_focused;
+                return;
             }
-            crossterm::event::KeyCode::Char('k') | crossterm::event::KeyCode::Up => self.move_up(),
+            KeyCode::Esc => {
+                self.log_focused = false;
+                return;
+            }
+            _ => {}
+        }
+        // j/k and friends move whichever pane has focus
+        let (list, len) = if self.log_focused {
+            let len = self.boot_log_len();
+            (&mut self.log_list, len)
+        } else {
+            let len = self.entry_count();
+            (&mut self.entries, len)
+        };
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => list.page_up(len),
+            KeyCode::Char('g') => list.top(len),
+            KeyCode::Char('G') => list.bottom(len),
             _ => {}
         }
+        if !self.log_focused {
+            self.load_boot_log();
+        }
     }
 
-    async fn tick(&mut self) {}
+    async fn tick(&mut self) {
+        if let Some(result) = self.update_pending.poll() {
+            match result {
+                Ok(output) => {
+                    let last = output.lines().last().unwrap_or("systemd-boot updated");
+                    self.messages.info(last.to_string());
+                }
+                Err(e) => self.messages.error(e.to_string()),
+            }
+            self.refresh();
+        }
+        if let Some((id, entries)) = self.boot_log_pending.poll() {
+            self.boot_log = Some((id, entries));
+            self.log_list.reset();
+        }
+        if let Some(result) = self.boots_pending.poll() {
+            match result {
+                Ok(boots) => self.boots = boots,
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to list boots", &e);
+                    self.messages.error(err.to_string());
+                }
+            }
+            self.load_boot_log();
+        }
+
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        match result {
+            Ok(info) => {
+                self.info = Some(info);
+                self.error = None;
+            }
+            Err(e) => {
+                let err = RootworkError::from_anyhow("Failed to gather boot info", &e);
+                self.messages.error(err.to_string());
+                self.info = None;
+                self.error = Some(err);
+            }
+        }
+        self.entries.clamp(self.entry_count());
+        self.load_boot_log();
+    }
 }
 
 fn draw_firmware_info(ctx: &BootContext, f: &mut Frame, area: Rect) {
-    let block = Block::default()
-        .title(" Firmware / Bootloader ")
-        .borders(Borders::ALL);
+    let title = match ctx.esp() {
+        Some(esp) if ctx.confirm_update => format!(
+            " Update systemd-boot on {} from {} to {}? [y/n] ",
+            esp.path.display(),
+            esp.installed.as_deref().unwrap_or("?"),
+            esp.shipped.as_deref().unwrap_or("?")
+        ),
+        _ if ctx.update_pending.is_pending() => ctx.update_pending.title("Running bootctl update"),
+        _ => ctx.pending.title("Kernel / Firmware / Bootloader"),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
 
     if let Some(ref error) = ctx.error {
-        let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+        let error_text = Paragraph::new(error.lines()).block(block);
         f.render_widget(error_text, area);
         return;
     }
 
     if let Some(ref info) = ctx.info {
-        let bootloader_status = if info.systemd_boot {
-            format!("systemd-boot ({}) ✓", info.loader_version)
-        } else {
-            "other".to_string()
+        let bootloader_status = match &info.esp {
+            Some(esp) if esp.update_available() => format!(
+                "systemd-boot ({}), {} available: U to update",
+                info.loader_version,
+                esp.shipped.as_deref().unwrap_or_default()
+            ),
+            _ if info.systemd_boot => format!("systemd-boot ({}) ✓", info.loader_version),
+            _ => "other".to_string(),
+        };
+        let bootloader_style = match &info.esp {
+            Some(esp) if esp.update_available() => Style::default().fg(crate::palette::yellow()),
+            _ => Style::default(),
         };
+        let esp = info
+            .esp
+            .as_ref()
+            .map_or_else(|| "not found".to_string(), Esp::describe);
 
+        let taint_style = if info.tainted == 0 {
+            Style::default()
+        } else {
+            Style::default().fg(crate::palette::yellow())
+        };
         let rows = vec![
+            Row::new(vec!["Kernel", &info.kernel_release]),
+            Row::new(vec!["Command Line", &info.cmdline]),
+            Row::new(vec![
+                Span::raw("Taint"),
+                Span::styled(describe_taint(info.tainted), taint_style),
+            ]),
             Row::new(vec!["Firmware", &info.firmware]),
-            Row::new(vec!["Bootloader", &bootloader_status]),
+            Row::new(vec![
+                Span::raw("Bootloader"),
+                Span::styled(bootloader_status, bootloader_style),
+            ]),
+            Row::new(vec!["ESP", &esp]),
             Row::new(vec!["Secure Boot", &info.secure_boot]),
             Row::new(vec!["Setup Mode", &info.setup_mode]),
         ];
@@ -222,9 +844,12 @@ fn draw_firmware_info(ctx: &BootContext, f: &mut Frame, area: Rect) {
 }
 
 fn draw_boot_entries(ctx: &BootContext, f: &mut Frame, area: Rect) {
-    let block = Block::default()
+    let mut block = Block::default()
         .title(" Boot Entries ")
         .borders(Borders::ALL);
+    if let Some(cmdline) = ctx.selected_entry().and_then(|e| e.cmdline.as_deref()) {
+        block = block.title_bottom(format!(" {} ", cmdline));
+    }
 
     if let Some(ref info) = ctx.info {
         if info.entries.is_empty() {
@@ -233,22 +858,21 @@ fn draw_boot_entries(ctx: &BootContext, f: &mut Frame, area: Rect) {
             return;
         }
 
-        let header = Row::new(vec!["Default", "Title", "Version", "ID"])
+        let header = Row::new(vec!["Default", "Type", "Title", "Version", "ID"])
             .style(Style::default().add_modifier(Modifier::BOLD));
 
-        let rows: Vec<Row> = info
-            .entries
-            .iter()
-            .enumerate()
-            .map(|(i, entry)| {
-                let style = if i == ctx.selected_entry {
-                    Style::default()
-                        .bg(crate::palette::dark_gray())
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-
+        let table = SelectableTable::new(
+            &ctx.entries,
+            info.entries.len(),
+            [
+                Constraint::Length(8),
+                Constraint::Length(5),
+                Constraint::Length(30),
+                Constraint::Length(15),
+                Constraint::Min(20),
+            ],
+            |i| {
+                let entry = &info.entries[i];
                 let default_indicator = if entry.is_default {
                     Span::styled("★", Style::default().fg(crate::palette::yellow()))
                 } else {
@@ -257,6 +881,7 @@ fn draw_boot_entries(ctx: &BootContext, f: &mut Frame, area: Rect) {
 
                 Row::new(vec![
                     default_indicator,
+                    Span::raw(if entry.uki { "UKI" } else { "BLS" }),
                     Span::raw(entry.title.clone()),
                     Span::raw(entry.version.clone().unwrap_or_else(|| "-".to_string())),
                     Span::styled(
@@ -264,18 +889,7 @@ fn draw_boot_entries(ctx: &BootContext, f: &mut Frame, area: Rect) {
                         Style::default().fg(crate::palette::gray()),
                     ),
                 ])
-                .style(style)
-            })
-            .collect();
-
-        let table = Table::new(
-            rows,
-            vec![
-                Constraint::Length(8),
-                Constraint::Length(30),
-                Constraint::Length(15),
-                Constraint::Min(20),
-            ],
+            },
         )
         .header(header)
         .block(block);
@@ -286,3 +900,124 @@ fn draw_boot_entries(ctx: &BootContext, f: &mut Frame, area: Rect) {
         f.render_widget(loading, area);
     }
 }
+
+fn draw_boot_log(ctx: &BootContext, f: &mut Frame, area: Rect) {
+    let selected = ctx.selected_boot();
+    let title = match selected {
+        Some((n, boot)) => {
+            let started = chrono::DateTime::from_timestamp_micros(boot.first_usec as i64)
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            format!(
+                "Journal of boot -{} ({}, {}): warnings and errors",
+                n,
+                boot.kernel.as_deref().unwrap_or("?"),
+                started
+            )
+        }
+        None => "Journal of last boot".to_string(),
+    };
+    let mut block = Block::default()
+        .title(ctx.boot_log_pending.title(&title))
+        .borders(Borders::ALL);
+    if ctx.log_focused {
+        block = block.border_style(Style::default().fg(crate::palette::cyan()));
+    }
+
+    let message = if ctx.info.is_none() || ctx.boots_pending.is_pending() {
+        Some("Loading...".to_string())
+    } else if ctx.selected_entry().is_none() {
+        Some("Select a boot entry".to_string())
+    } else if selected.is_none() {
+        Some(
+            "The journal has no boot of this entry's kernel \
+             (never booted, or rotated out of the journal)"
+                .to_string(),
+        )
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        f.render_widget(
+            Paragraph::new(message)
+                .style(Style::default().fg(crate::palette::gray()))
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let entries = match &ctx.boot_log {
+        Some((id, entries)) if selected.is_some_and(|(_, boot)| &boot.id == id) => entries,
+        _ => {
+            f.render_widget(Paragraph::new("Loading...").block(block), area);
+            return;
+        }
+    };
+    if entries.is_empty() {
+        f.render_widget(
+            Paragraph::new("No warnings or errors logged during this boot").block(block),
+            area,
+        );
+        return;
+    }
+
+    let table = SelectableTable::new(
+        &ctx.log_list,
+        entries.len(),
+        [
+            Constraint::Length(15),
+            Constraint::Length(20),
+            Constraint::Min(20),
+        ],
+        |i| {
+            let entry = &entries[i];
+            Row::new(vec![
+                Line::from(Span::styled(
+                    entry.display_time.clone(),
+                    Style::default().fg(crate::palette::gray()),
+                )),
+                Line::from(entry.identifier.clone()),
+                Line::from(Span::styled(
+                    entry.message.clone(),
+                    crate::palette::log_priority(entry.priority),
+                )),
+            ])
+        },
+    )
+    .block(block);
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untainted_kernel() {
+        assert_eq!(describe_taint(0), "not tainted");
+    }
+
+    #[test]
+    fn taint_bits_map_to_letters_in_bit_order() {
+        assert_eq!(describe_taint(1), "P: proprietary module loaded");
+        assert_eq!(
+            describe_taint((1 << 12) | (1 << 13)),
+            "OE: out-of-tree module loaded, unsigned module loaded"
+        );
+        assert_eq!(describe_taint(1 << 18), "N: in-kernel test run");
+    }
+
+    #[test]
+    fn unknown_taint_bits_are_kept() {
+        assert_eq!(
+            describe_taint((1 << 9) | (1 << 20)),
+            "W: kernel issued a warning (and bits 0x100000)"
+        );
+        assert_eq!(describe_taint(1 << 19), "bits 0x80000");
+    }
+}
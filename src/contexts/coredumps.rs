@@ -0,0 +1,397 @@
+use crate::contexts::{Context, Pending, centered_rect, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::widgets::selectable::{ListState, SelectableList, SelectableTable};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Row},
+};
+use std::os::raw::c_void;
+use std::path::PathBuf;
+
+use crate::systemd::journal_ffi::{
+    SD_JOURNAL_LOCAL_ONLY, sd_journal_add_match, sd_journal_close, sd_journal_get_realtime_usec,
+    sd_journal_open, sd_journal_previous, sd_journal_seek_tail,
+};
+use crate::systemd::logs::get_journal_field;
+
+/// systemd-coredump's "Process N dumped core" message
+const COREDUMP_MESSAGE_ID: &str = "fc2e22bc6ee647b6b90729ab34a250b1";
+
+/// How far back to look
+const MAX_COREDUMPS: usize = 200;
+
+/// Where the dump itself ended up
+#[derive(Debug, Clone, PartialEq)]
+enum Storage {
+    /// External file that's still there
+    Present(PathBuf),
+    /// External file that was vacuumed or deleted
+    Missing(PathBuf),
+    /// Not stored (Storage=none, too big, or journal storage)
+    None,
+}
+
+impl Storage {
+    fn label(&self) -> &'static str {
+        match self {
+            Storage::Present(_) => "present",
+            Storage::Missing(_) => "missing",
+            Storage::None => "none",
+        }
+    }
+}
+
+pub struct Coredump {
+    timestamp_micros: u64,
+    pid: Option<u32>,
+    uid: Option<u32>,
+    /// Signal name when systemd recorded it, else the number
+    signal: String,
+    exe: String,
+    unit: Option<String>,
+    storage: Storage,
+    /// The journal message, which carries the stack trace
+    backtrace: String,
+}
+
+impl Coredump {
+    fn list() -> Result<Vec<Coredump>, RootworkError> {
+        let mut out = Vec::new();
+        unsafe {
+            let mut j: *mut c_void = std::ptr::null_mut();
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY);
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
+            }
+
+            let m = format!("MESSAGE_ID={COREDUMP_MESSAGE_ID}");
+            let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+
+            let _ = sd_journal_seek_tail(j);
+            while out.len() < MAX_COREDUMPS && sd_journal_previous(j) > 0 {
+                if let Some(dump) = read_coredump(j) {
+                    out.push(dump);
+                }
+            }
+            sd_journal_close(j);
+        }
+        Ok(out)
+    }
+
+    /// What the backtrace popup shows: where the dump is, then the message
+    fn backtrace_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Storage::Present(path) | Storage::Missing(path) = &self.storage {
+            lines.push(format!(
+                "Dump: {} ({})",
+                path.display(),
+                self.storage.label()
+            ));
+        }
+        lines.extend(self.backtrace.lines().map(str::to_string));
+        lines
+    }
+
+    fn time(&self) -> String {
+        chrono::DateTime::from_timestamp_micros(self.timestamp_micros as i64)
+            .map(|dt| {
+                let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
+                local.format("%Y-%m-%d %H:%M:%S").to_string()
+            })
+            .unwrap_or_else(|| "?".to_string())
+    }
+}
+
+fn read_coredump(j: *mut c_void) -> Option<Coredump> {
+    let mut timestamp_micros = 0u64;
+    if unsafe { sd_journal_get_realtime_usec(j, &mut timestamp_micros) } < 0 {
+        return None;
+    }
+
+    let storage = match get_journal_field(j, "COREDUMP_FILENAME").map(PathBuf::from) {
+        Some(path) if path.exists() => Storage::Present(path),
+        Some(path) => Storage::Missing(path),
+        None => Storage::None,
+    };
+
+    Some(Coredump {
+        timestamp_micros,
+        pid: get_journal_field(j, "COREDUMP_PID").and_then(|p| p.parse().ok()),
+        uid: get_journal_field(j, "COREDUMP_UID").and_then(|u| u.parse().ok()),
+        signal: get_journal_field(j, "COREDUMP_SIGNAL_NAME")
+            .or_else(|| get_journal_field(j, "COREDUMP_SIGNAL"))
+            .unwrap_or_else(|| "?".to_string()),
+        exe: get_journal_field(j, "COREDUMP_EXE")
+            .or_else(|| get_journal_field(j, "COREDUMP_COMM"))
+            .unwrap_or_else(|| "?".to_string()),
+        unit: get_journal_field(j, "COREDUMP_UNIT"),
+        storage,
+        backtrace: get_journal_field(j, "MESSAGE").unwrap_or_default(),
+    })
+}
+
+pub struct CoredumpsContext {
+    dumps: Vec<Coredump>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<Vec<Coredump>, RootworkError>>,
+    list: ListState,
+    /// Cursor in the open backtrace popup
+    backtrace: Option<ListState>,
+    confirm_delete: bool,
+    jump_to_unit: Option<String>,
+    messages: Messages,
+}
+
+impl CoredumpsContext {
+    pub fn new(messages: &Messages) -> Self {
+        let mut ctx = Self {
+            dumps: Vec::new(),
+            error: None,
+            pending: Pending::idle(),
+            list: ListState::new(),
+            backtrace: None,
+            confirm_delete: false,
+            jump_to_unit: None,
+            messages: messages.clone(),
+        };
+        ctx.refresh();
+        ctx
+    }
+
+    fn refresh(&mut self) {
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn(Coredump::list);
+        }
+    }
+
+    fn selected(&self) -> Option<&Coredump> {
+        self.dumps.get(self.list.selected)
+    }
+
+    fn delete_selected(&mut self) {
+        let Some(dump) = self.selected() else {
+            return;
+        };
+        let Storage::Present(path) = dump.storage.clone() else {
+            self.messages.warn("No dump file to delete");
+            return;
+        };
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                self.messages.info(format!("Deleted {}", path.display()));
+                self.refresh();
+            }
+            Err(e) => self
+                .messages
+                .error(format!("Failed to delete {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
+    }
+
+    fn handle_backtrace_key(&mut self, key: KeyEvent) {
+        let len = self.selected().map_or(0, |d| d.backtrace_lines().len());
+        let Some(list) = self.backtrace.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => self.backtrace = None,
+            KeyCode::Char('j') | KeyCode::Down => list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => list.page_up(len),
+            KeyCode::Char('g') => list.top(len),
+            KeyCode::Char('G') => list.bottom(len),
+            _ => {}
+        }
+    }
+}
+
+impl Context for CoredumpsContext {
+    fn name(&self) -> &'static str {
+        "Coredumps"
+    }
+
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(area);
+
+        draw_coredumps(self, f, chunks[0]);
+
+        let status = match self.selected() {
+            Some(dump) if self.confirm_delete => format!(
+                "Delete the core dump of {} (PID {}) ? [y/n]",
+                dump.exe,
+                dump.pid
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "?".into())
+            ),
+            _ => "Enter:backtrace u:unit x:delete dump r:refresh".to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
+            chunks[1],
+        );
+
+        if let (Some(list), Some(dump)) = (&self.backtrace, self.selected()) {
+            draw_backtrace(dump, list, f, area);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.backtrace.is_some() {
+            self.handle_backtrace_key(key);
+            return;
+        }
+
+        if self.confirm_delete {
+            self.confirm_delete = false;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.delete_selected();
+            }
+            return;
+        }
+
+        let len = self.dumps.len();
+        match key.code {
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('j') | KeyCode::Down => self.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => self.list.page_up(len),
+            KeyCode::Char('g') => self.list.top(len),
+            KeyCode::Char('G') => self.list.bottom(len),
+            KeyCode::Enter if self.selected().is_some() => self.backtrace = Some(ListState::new()),
+            KeyCode::Char('u') => match self.selected().map(|d| d.unit.clone()) {
+                Some(Some(unit)) => self.jump_to_unit = Some(unit),
+                Some(None) => self
+                    .messages
+                    .warn("The crashed process didn't belong to a unit"),
+                None => {}
+            },
+            KeyCode::Char('x') => match self.selected().map(|d| &d.storage) {
+                Some(Storage::Present(_)) => self.confirm_delete = true,
+                Some(_) => self.messages.warn("No dump file to delete"),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self) {
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        self.dumps =
+            note_refresh_error(&mut self.error, &self.messages, result).unwrap_or_default();
+        self.list.clamp(self.dumps.len());
+    }
+}
+
+fn draw_coredumps(ctx: &CoredumpsContext, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(
+            ctx.pending
+                .title(&format!("Coredumps ({})", ctx.dumps.len())),
+        )
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+
+    if ctx.dumps.is_empty() {
+        let text = if ctx.pending.is_pending() {
+            "Loading..."
+        } else {
+            "No coredumps in the journal"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec![
+        "Time",
+        "PID",
+        "UID",
+        "Signal",
+        "Dump",
+        "Executable",
+        "Unit",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let table = SelectableTable::new(
+        &ctx.list,
+        ctx.dumps.len(),
+        [
+            Constraint::Length(19),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(24),
+            Constraint::Length(28),
+        ],
+        |i| {
+            let dump = &ctx.dumps[i];
+            let storage_color = match dump.storage {
+                Storage::Present(_) => crate::palette::green(),
+                Storage::Missing(_) => crate::palette::yellow(),
+                Storage::None => crate::palette::gray(),
+            };
+            let or_dash = |v: Option<u32>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+
+            Row::new(vec![
+                Span::styled(dump.time(), Style::default().fg(crate::palette::gray())),
+                Span::raw(or_dash(dump.pid)),
+                Span::raw(or_dash(dump.uid)),
+                Span::styled(
+                    dump.signal.clone(),
+                    Style::default().fg(crate::palette::red()),
+                ),
+                Span::styled(dump.storage.label(), Style::default().fg(storage_color)),
+                Span::styled(
+                    dump.exe.clone(),
+                    Style::default().fg(crate::palette::cyan()),
+                ),
+                Span::raw(dump.unit.clone().unwrap_or_else(|| "-".to_string())),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_backtrace(dump: &Coredump, list: &ListState, f: &mut Frame, area: Rect) {
+    let popup = centered_rect(90, 85, area);
+    f.render_widget(Clear, popup);
+
+    let lines = dump.backtrace_lines();
+    let widget = SelectableList::new(list, lines.len(), |i| Line::from(lines[i].as_str())).block(
+        Block::default()
+            .title(format!(" Backtrace: {} ", dump.exe))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(widget, popup);
+}
@@ -1,4 +1,8 @@
-use crate::contexts::Context;
+use crate::contexts::{Context, Pending, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::SystemdClient;
+use crate::widgets::selectable::{ListState, SelectableTable};
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 use ratatui::{
@@ -9,7 +13,12 @@ use ratatui::{
 };
 use std::collections::{BTreeMap, BTreeSet};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use zbus::blocking::{Connection, Proxy};
+
+const RESOLVED: (&str, &str, &str) = (
+    "org.freedesktop.resolve1",
+    "/org/freedesktop/resolve1",
+    "org.freedesktop.resolve1.Manager",
+);
 
 pub struct DnsInfo {
     current_dns: Vec<String>,
@@ -28,27 +37,64 @@ pub struct InterfaceDns {
 }
 
 impl DnsInfo {
-    fn gather() -> Result<Self> {
-        Self::from_resolved_dbus().or_else(|_| Self::from_resolv_conf())
+    pub async fn gather(systemd: &SystemdClient) -> Result<Self> {
+        match Self::from_resolved_dbus(systemd).await {
+            Ok(info) => Ok(info),
+            // resolv.conf is this machine's, not the remote host's
+            Err(e) if systemd.host().is_some() => Err(e),
+            Err(_) => Self::from_resolv_conf(),
+        }
+    }
+
+    /// The report's DNS section: global resolver settings, then the
+    /// servers and domains each link brought
+    pub fn write_report(&self, out: &mut String) {
+        out.push_str("## DNS\n\n");
+        out.push_str(&format!(
+            "- Current DNS: {}\n",
+            list_or_none(&self.current_dns)
+        ));
+        out.push_str(&format!(
+            "- Fallback DNS: {}\n",
+            list_or_none(&self.fallback_dns)
+        ));
+        out.push_str(&format!(
+            "- Search domains: {}\n",
+            list_or_none(&self.search_domains)
+        ));
+        out.push_str(&format!("- DNSSEC: {}\n", self.dnssec));
+        out.push_str(&format!("- DNSOverTLS: {}\n", self.dnsovertls));
+        if !self.interface_dns.is_empty() {
+            out.push_str("\n| Interface | Servers | Search domains |\n|---|---|---|\n");
+            for iface in &self.interface_dns {
+                out.push_str(&crate::report::table_row(&[
+                    &iface.name,
+                    &list_or_none(&iface.dns_servers),
+                    &list_or_none(&iface.search_domains),
+                ]));
+            }
+        }
+        out.push('\n');
     }
 
-    fn from_resolved_dbus() -> Result<Self> {
-        let conn = Connection::system()?;
-        let proxy = Proxy::new(
-            &conn,
-            "org.freedesktop.resolve1",
-            "/org/freedesktop/resolve1",
-            "org.freedesktop.resolve1.Manager",
-        )?;
-
-        let dns: Vec<(i32, i32, Vec<u8>)> = proxy.get_property("DNS")?;
-        let fallback_dns_raw: Vec<(i32, i32, Vec<u8>)> = proxy.get_property("FallbackDNS")?;
-        let domains: Vec<(i32, String, bool)> = proxy.get_property("Domains")?;
-        let dnssec: String = proxy
-            .get_property("DNSSEC")
+    async fn from_resolved_dbus(systemd: &SystemdClient) -> Result<Self> {
+        let (service, path, interface) = RESOLVED;
+        let dns: Vec<(i32, i32, Vec<u8>)> = systemd
+            .system_property(service, path, interface, "DNS")
+            .await?;
+        let fallback_dns_raw: Vec<(i32, i32, Vec<u8>)> = systemd
+            .system_property(service, path, interface, "FallbackDNS")
+            .await?;
+        let domains: Vec<(i32, String, bool)> = systemd
+            .system_property(service, path, interface, "Domains")
+            .await?;
+        let dnssec: String = systemd
+            .system_property(service, path, interface, "DNSSEC")
+            .await
             .unwrap_or_else(|_| "unknown".to_string());
-        let dnsovertls: String = proxy
-            .get_property("DNSOverTLS")
+        let dnsovertls: String = systemd
+            .system_property(service, path, interface, "DNSOverTLS")
+            .await
             .unwrap_or_else(|_| "unknown".to_string());
 
         let mut global_dns = BTreeSet::new();
@@ -181,50 +227,38 @@ fn ifindex_to_name(ifindex: i32) -> Option<String> {
 
 pub struct DnsContext {
     info: Option<DnsInfo>,
-    error: Option<String>,
-    selected_interface: usize,
+    error: Option<RootworkError>,
+    pending: Pending<Result<DnsInfo>>,
+    systemd: SystemdClient,
+    messages: Messages,
+    interfaces: ListState,
 }
 
 impl DnsContext {
-    pub fn new() -> Self {
-        let (info, error) = match DnsInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather DNS info: {}", e))),
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            info: None,
+            error: None,
+            pending: Pending::idle(),
+            systemd: systemd.clone(),
+            messages: messages.clone(),
+            interfaces: ListState::new(),
         };
-
-        Self {
-            info,
-            error,
-            selected_interface: 0,
-        }
+        ctx.refresh();
+        ctx
     }
 
     fn refresh(&mut self) {
-        let (info, error) = match DnsInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather DNS info: {}", e))),
-        };
-        self.info = info;
-        self.error = error;
-        self.selected_interface = 0;
-    }
-
-    fn move_up(&mut self) {
-        if let Some(ref info) = self.info
-            && !info.interface_dns.is_empty()
-            && self.selected_interface > 0
-        {
-            self.selected_interface -= 1;
+        if !self.pending.is_pending() {
+            let systemd = self.systemd.clone();
+            self.pending = Pending::spawn_async(async move { DnsInfo::gather(&systemd).await });
         }
     }
 
-    fn move_down(&mut self) {
-        if let Some(ref info) = self.info
-            && !info.interface_dns.is_empty()
-            && self.selected_interface + 1 < info.interface_dns.len()
-        {
-            self.selected_interface += 1;
-        }
+    fn interface_count(&self) -> usize {
+        self.info
+            .as_ref()
+            .map_or(0, |info| info.interface_dns.len())
     }
 }
 
@@ -233,6 +267,10 @@ impl Context for DnsContext {
         "DNS"
     }
 
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
     fn draw(&self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -247,23 +285,41 @@ impl Context for DnsContext {
         match key.code {
             crossterm::event::KeyCode::Char('r') => self.refresh(),
             crossterm::event::KeyCode::Char('j') | crossterm::event::KeyCode::Down => {
-                self.move_down()
+                self.interfaces.down(self.interface_count())
+            }
+            crossterm::event::KeyCode::Char('k') | crossterm::event::KeyCode::Up => {
+                self.interfaces.up(self.interface_count())
             }
-            crossterm::event::KeyCode::Char('k') | crossterm::event::KeyCode::Up => self.move_up(),
+            crossterm::event::KeyCode::Char(' ') | crossterm::event::KeyCode::PageDown => {
+                self.interfaces.page_down(self.interface_count())
+            }
+            crossterm::event::KeyCode::Char('b') | crossterm::event::KeyCode::PageUp => {
+                self.interfaces.page_up(self.interface_count())
+            }
+            crossterm::event::KeyCode::Char('g') => self.interfaces.top(self.interface_count()),
+            crossterm::event::KeyCode::Char('G') => self.interfaces.bottom(self.interface_count()),
             _ => {}
         }
     }
 
-    async fn tick(&mut self) {}
+    async fn tick(&mut self) {
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        let result =
+            result.map_err(|e| RootworkError::from_anyhow("Failed to gather DNS info", &e));
+        self.info = note_refresh_error(&mut self.error, &self.messages, result);
+        self.interfaces.clamp(self.interface_count());
+    }
 }
 
 fn draw_global_dns(ctx: &DnsContext, f: &mut Frame, area: Rect) {
     let block = Block::default()
-        .title(" Global DNS Settings ")
+        .title(ctx.pending.title("Global DNS Settings"))
         .borders(Borders::ALL);
 
     if let Some(ref error) = ctx.error {
-        let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+        let error_text = Paragraph::new(error.lines()).block(block);
         f.render_widget(error_text, area);
         return;
     }
@@ -320,35 +376,23 @@ fn draw_interface_dns(ctx: &DnsContext, f: &mut Frame, area: Rect) {
         let header = Row::new(vec!["Interface", "DNS Servers", "Search Domains"])
             .style(Style::default().add_modifier(Modifier::BOLD));
 
-        let rows: Vec<Row> = info
-            .interface_dns
-            .iter()
-            .enumerate()
-            .map(|(i, iface)| {
-                let name_style = if i == ctx.selected_interface {
-                    Style::default()
-                        .fg(crate::palette::black())
-                        .bg(crate::palette::cyan())
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(crate::palette::cyan())
-                };
-
-                Row::new(vec![
-                    Cell::from(iface.name.clone()).style(name_style),
-                    Cell::from(iface.dns_servers.join(", ")),
-                    Cell::from(iface.search_domains.join(", ")),
-                ])
-            })
-            .collect();
-
-        let table = Table::new(
-            rows,
-            vec![
+        let table = SelectableTable::new(
+            &ctx.interfaces,
+            info.interface_dns.len(),
+            [
                 Constraint::Length(16),
                 Constraint::Length(30),
                 Constraint::Min(20),
             ],
+            |i| {
+                let iface = &info.interface_dns[i];
+                Row::new(vec![
+                    Cell::from(iface.name.clone())
+                        .style(Style::default().fg(crate::palette::cyan())),
+                    Cell::from(iface.dns_servers.join(", ")),
+                    Cell::from(iface.search_domains.join(", ")),
+                ])
+            },
         )
         .header(header)
         .block(block);
@@ -359,3 +403,11 @@ fn draw_interface_dns(ctx: &DnsContext, f: &mut Frame, area: Rect) {
         f.render_widget(loading, area);
     }
 }
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "(none)".to_string()
+    } else {
+        items.join(", ")
+    }
+}
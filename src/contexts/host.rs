@@ -1,13 +1,19 @@
-use crate::contexts::Context;
-use crossterm::event::KeyEvent;
+use crate::container::{self, ContainerInfo};
+use crate::contexts::{Context, Pending, format_duration_usec, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::power::PowerSupplies;
+use crate::systemd::client::SystemdClient;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Row, Table},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
 };
 use std::fs;
-use zbus::blocking::{Connection, Proxy};
+use std::path::Path;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
 
 pub struct HostInfo {
     hostname: String,
@@ -19,66 +25,513 @@ pub struct HostInfo {
     uptime: String,
     ntp_enabled: String,
     ntp_sync: String,
+    rtc_time: String,
+    local_rtc: Option<bool>,
+    ntp_offset: String,
+    /// Only when systemd-timesyncd is the NTP client
+    timesync: Option<Timesync>,
+    /// logind sessions; None without logind
+    sessions: Option<Vec<LoginSession>>,
+    /// UIDs the service manager allocated for DynamicUser= services
+    dynamic_users: Vec<(u32, String)>,
+    sensors: Vec<Sensor>,
+    power: PowerSupplies,
+    dual_boot: bool,
+    /// Read over --host: only what the bus tells, none of the local files
+    remote: bool,
+    /// Limits and namespaces when running inside a container
+    container: Option<ContainerInfo>,
+}
+
+/// systemd-timesyncd's state, from org.freedesktop.timesync1
+pub struct Timesync {
+    /// Server currently used, empty before the first lookup
+    server_name: String,
+    server_address: Option<std::net::IpAddr>,
+    poll_interval_usec: u64,
+    /// From the last NTP exchange
+    offset_usec: Option<i64>,
+    jitter_usec: Option<u64>,
+    /// Servers by where they were configured, in the order timesyncd
+    /// tries them: per-link (networkd/DHCP), timesyncd.conf, fallback
+    link_servers: Vec<String>,
+    system_servers: Vec<String>,
+    fallback_servers: Vec<String>,
+}
+
+const TIMESYNCD_UNIT: &str = "systemd-timesyncd.service";
+
+impl Timesync {
+    async fn gather(conn: Option<&SystemdClient>) -> Option<Self> {
+        async fn get<T>(conn: Option<&SystemdClient>, property: &str) -> Option<T>
+        where
+            T: TryFrom<OwnedValue>,
+            T::Error: Into<zbus::Error>,
+        {
+            dbus_get(
+                conn,
+                "org.freedesktop.timesync1",
+                "/org/freedesktop/timesync1",
+                "org.freedesktop.timesync1.Manager",
+                property,
+            )
+            .await
+        }
+
+        // Fails when timesyncd isn't running or another client is in use
+        let server_name = get::<String>(conn, "ServerName").await?;
+        let server_address =
+            get::<(i32, Vec<u8>)>(conn, "ServerAddress")
+                .await
+                .and_then(|(_, bytes)| match bytes.len() {
+                    4 => Some(<[u8; 4]>::try_from(bytes).ok()?.into()),
+                    16 => Some(<[u8; 16]>::try_from(bytes).ok()?.into()),
+                    _ => None,
+                });
+        let message = get::<NtpMessage>(conn, "NTPMessage").await;
+        Some(Self {
+            server_name,
+            server_address,
+            poll_interval_usec: get(conn, "PollIntervalUSec").await.unwrap_or(0),
+            offset_usec: message.as_ref().and_then(ntp_offset),
+            // Zero until timesyncd has a few samples
+            jitter_usec: message.map(|m| m.14).filter(|&j| j > 0),
+            link_servers: get(conn, "LinkNTPServers").await.unwrap_or_default(),
+            system_servers: get(conn, "SystemNTPServers").await.unwrap_or_default(),
+            fallback_servers: get(conn, "FallbackNTPServers").await.unwrap_or_default(),
+        })
+    }
+
+    /// "ntp.example.org (192.0.2.1)"
+    fn server(&self) -> String {
+        match (self.server_name.is_empty(), self.server_address) {
+            (true, _) => "none yet".to_string(),
+            (false, Some(address)) => format!("{} ({})", self.server_name, address),
+            (false, None) => self.server_name.clone(),
+        }
+    }
+}
+
+/// A temperature or fan reading from /sys/class/hwmon
+pub struct Sensor {
+    /// Driver name of the chip, e.g. "coretemp", "nvme", "k10temp"
+    chip: String,
+    /// tempN_label, or the channel name when the driver has none
+    label: String,
+    reading: Reading,
+}
+
+pub enum Reading {
+    /// Degrees Celsius with the chip's own limits, when it reports them
+    Temperature {
+        celsius: f64,
+        max: Option<f64>,
+        crit: Option<f64>,
+    },
+    Fan {
+        rpm: u64,
+    },
+}
+
+/// Warning level for temperatures whose chip reports no tempN_max
+const DEFAULT_TEMP_WARN: f64 = 80.0;
+/// Critical level for temperatures whose chip reports no tempN_crit
+const DEFAULT_TEMP_CRIT: f64 = 95.0;
+
+impl Sensor {
+    /// Every temperature and fan channel of every hwmon chip
+    fn scan() -> Vec<Self> {
+        let mut sensors = Vec::new();
+        let Ok(dir) = fs::read_dir("/sys/class/hwmon") else {
+            return sensors;
+        };
+        let mut chips: Vec<_> = dir.flatten().map(|entry| entry.path()).collect();
+        chips.sort();
+        for chip_dir in chips {
+            let read = |name: &str| {
+                fs::read_to_string(chip_dir.join(name))
+                    .ok()
+                    .map(|s| s.trim().to_string())
+            };
+            let number = |name: &str| read(name).and_then(|v| v.parse::<i64>().ok());
+            let chip = read("name").unwrap_or_else(|| "?".to_string());
+            let Ok(files) = fs::read_dir(&chip_dir) else {
+                continue;
+            };
+            let mut channels: Vec<String> = files
+                .flatten()
+                .filter_map(|f| f.file_name().into_string().ok())
+                .filter_map(|f| f.strip_suffix("_input").map(str::to_string))
+                .filter(|c| c.starts_with("temp") || c.starts_with("fan"))
+                .collect();
+            channels.sort_by_key(|c| {
+                let digits = c.trim_start_matches(|ch: char| ch.is_ascii_alphabetic());
+                (c.starts_with("fan"), digits.parse::<u32>().unwrap_or(0))
+            });
+            for channel in channels {
+                let Some(value) = number(&format!("{}_input", channel)) else {
+                    // Unplugged fan headers and sleeping drives fail to read
+                    continue;
+                };
+                let label = read(&format!("{}_label", channel)).unwrap_or_else(|| channel.clone());
+                let reading = if channel.starts_with("fan") {
+                    Reading::Fan {
+                        rpm: value.max(0) as u64,
+                    }
+                } else {
+                    // Millidegrees; a limit of 0 means none is set
+                    let limit = |suffix: &str| {
+                        number(&format!("{}_{}", channel, suffix))
+                            .filter(|&v| v > 0)
+                            .map(|v| v as f64 / 1000.0)
+                    };
+                    Reading::Temperature {
+                        celsius: value as f64 / 1000.0,
+                        max: limit("max"),
+                        crit: limit("crit"),
+                    }
+                };
+                sensors.push(Sensor {
+                    chip: chip.clone(),
+                    label,
+                    reading,
+                });
+            }
+        }
+        sensors
+    }
+
+    fn style(&self) -> Style {
+        match self.reading {
+            Reading::Temperature { celsius, max, crit } => {
+                if celsius >= crit.unwrap_or(DEFAULT_TEMP_CRIT) {
+                    Style::default()
+                        .fg(crate::palette::red())
+                        .add_modifier(Modifier::BOLD)
+                } else if celsius >= max.unwrap_or(DEFAULT_TEMP_WARN) {
+                    Style::default().fg(crate::palette::yellow())
+                } else {
+                    Style::default()
+                }
+            }
+            // A stopped fan that's meant to spin shows up as 0 RPM
+            Reading::Fan { rpm: 0 } => Style::default().fg(crate::palette::gray()),
+            Reading::Fan { .. } => Style::default(),
+        }
+    }
+
+    /// "54.0°C (high 80, crit 100)", "1200 RPM"
+    fn value(&self) -> String {
+        match self.reading {
+            Reading::Temperature { celsius, max, crit } => {
+                let limits: Vec<String> = [("high", max), ("crit", crit)]
+                    .iter()
+                    .filter_map(|(name, limit)| limit.map(|l| format!("{} {:.0}", name, l)))
+                    .collect();
+                if limits.is_empty() {
+                    format!("{:.1}°C", celsius)
+                } else {
+                    format!("{:.1}°C ({})", celsius, limits.join(", "))
+                }
+            }
+            Reading::Fan { rpm } => format!("{} RPM", rpm),
+        }
+    }
+}
+
+/// A logind session, as `loginctl list-sessions` shows it
+pub struct LoginSession {
+    id: String,
+    user: String,
+    uid: u32,
+    seat: String,
+    tty: String,
+    remote_host: String,
+    /// "user", "greeter", "manager", ...
+    class: String,
+    /// "active", "online" or "closing"
+    state: String,
+    since_usec: u64,
+}
+
+/// (id, uid, user, seat, object path) from ListSessions
+type SessionRow = (String, u32, String, String, OwnedObjectPath);
+
+impl LoginSession {
+    async fn list(conn: Option<&SystemdClient>) -> Option<Vec<Self>> {
+        let bus = conn?.system_bus().await.ok()?;
+        let manager = zbus::Proxy::new(
+            bus,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        )
+        .await
+        .ok()?;
+        let rows: Vec<SessionRow> = manager.call("ListSessions", &()).await.ok()?;
+        let mut sessions = Vec::new();
+        for (id, uid, user, seat, path) in rows {
+            let Ok(session) = zbus::Proxy::new(
+                bus,
+                "org.freedesktop.login1",
+                path,
+                "org.freedesktop.login1.Session",
+            )
+            .await
+            else {
+                continue;
+            };
+            let text = |value: zbus::Result<String>| value.unwrap_or_default();
+            sessions.push(Self {
+                id,
+                user,
+                uid,
+                seat,
+                tty: text(session.get_property("TTY").await),
+                remote_host: text(session.get_property("RemoteHost").await),
+                class: text(session.get_property("Class").await),
+                state: text(session.get_property("State").await),
+                since_usec: session.get_property("Timestamp").await.unwrap_or(0),
+            });
+        }
+        sessions.sort_by(|a, b| a.user.cmp(&b.user).then(a.since_usec.cmp(&b.since_usec)));
+        Some(sessions)
+    }
+
+    /// "seat0/tty2", "pts/1 from 192.0.2.7", ...
+    fn location(&self) -> String {
+        let place = match (self.seat.is_empty(), self.tty.is_empty()) {
+            (false, false) => format!("{}/{}", self.seat, self.tty),
+            (false, true) => self.seat.clone(),
+            (true, false) => self.tty.clone(),
+            (true, true) => "-".to_string(),
+        };
+        if self.remote_host.is_empty() {
+            place
+        } else {
+            format!("{} from {}", place, self.remote_host)
+        }
+    }
+}
+
+/// Users the service manager allocated, like `userdbctl --service=
+/// io.systemd.DynamicUser` lists them
+async fn dynamic_users(conn: Option<&SystemdClient>) -> Vec<(u32, String)> {
+    let Some(bus) = conn else {
+        return Vec::new();
+    };
+    let Ok(bus) = bus.system_bus().await else {
+        return Vec::new();
+    };
+    let Ok(manager) = zbus::Proxy::new(
+        bus,
+        "org.freedesktop.systemd1",
+        "/org/freedesktop/systemd1",
+        "org.freedesktop.systemd1.Manager",
+    )
+    .await
+    else {
+        return Vec::new();
+    };
+    let mut users: Vec<(u32, String)> = manager
+        .call("GetDynamicUsers", &())
+        .await
+        .unwrap_or_default();
+    users.sort();
+    users
 }
 
 impl HostInfo {
-    fn gather() -> anyhow::Result<Self> {
-        let conn = Connection::system()?;
+    /// The report's Host section as a flat list: names, OS, clock and NTP,
+    /// sessions, power and sensors
+    pub fn write_report(&self, out: &mut String) {
+        out.push_str("## Host\n\n");
+        for (label, value) in [
+            ("Hostname", &self.hostname),
+            ("Static hostname", &self.static_hostname),
+            ("OS", &self.os_name),
+            ("OS version", &self.os_version),
+            ("Uptime", &self.uptime),
+            ("Timezone", &self.timezone),
+            ("Locale", &self.locale),
+            ("NTP enabled", &self.ntp_enabled),
+            ("NTP synchronized", &self.ntp_sync),
+            ("NTP offset", &self.ntp_offset),
+            ("RTC time", &self.rtc_time),
+        ] {
+            out.push_str(&format!("- {}: {}\n", label, value));
+        }
+        if let Some(timesync) = &self.timesync {
+            out.push_str(&format!("- NTP server: {}\n", timesync.server()));
+            out.push_str(&format!(
+                "- NTP fallback servers: {}\n",
+                timesync.fallback_servers.join(" ")
+            ));
+        }
+        if let Some(sessions) = &self.sessions {
+            for session in sessions {
+                out.push_str(&format!(
+                    "- Session {}: {} ({}) on {}, {}\n",
+                    session.id,
+                    session.user,
+                    session.uid,
+                    session.location(),
+                    session.state
+                ));
+            }
+        }
+        for (uid, name) in &self.dynamic_users {
+            out.push_str(&format!("- Dynamic user: {} ({})\n", name, uid));
+        }
+        if let Some(online) = self.power.ac_online {
+            out.push_str(&format!(
+                "- AC power: {}\n",
+                if online { "online" } else { "offline" }
+            ));
+        }
+        for battery in &self.power.batteries {
+            out.push_str(&format!("- {}: {}\n", battery.name, battery.describe()));
+        }
+        for sensor in &self.sensors {
+            out.push_str(&format!(
+                "- Sensor {} {}: {}\n",
+                sensor.chip,
+                sensor.label,
+                sensor.value()
+            ));
+        }
+        if let Some(container) = &self.container {
+            for (label, value) in container.rows() {
+                out.push_str(&format!("- {}: {}\n", label, value));
+            }
+        }
+        out.push('\n');
+    }
+
+    pub async fn gather(systemd: &SystemdClient) -> anyhow::Result<Self> {
+        let remote = systemd.host().is_some();
+        let container = container::detect().filter(|_| !remote);
+        // Containers often have no system bus; what /proc and /etc tell is
+        // still worth showing there
+        let conn = match systemd.system_bus().await {
+            Ok(_) => Some(systemd),
+            Err(_) if container.is_some() => None,
+            Err(e) => return Err(e),
+        };
 
         // hostname1
-        let hostname = dbus_get_string(
-            &conn,
+        let hostname = dbus_get::<String>(
+            conn,
             "org.freedesktop.hostname1",
             "/org/freedesktop/hostname1",
             "org.freedesktop.hostname1",
             "Hostname",
         )
+        .await
         .unwrap_or_else(|| "unknown".to_string());
 
-        let static_hostname = dbus_get_string(
-            &conn,
+        let static_hostname = dbus_get::<String>(
+            conn,
             "org.freedesktop.hostname1",
             "/org/freedesktop/hostname1",
             "org.freedesktop.hostname1",
             "StaticHostname",
         )
+        .await
         .unwrap_or_else(|| hostname.clone());
 
         // timedate1
-        let timezone = dbus_get_string(
-            &conn,
+        let timezone = dbus_get::<String>(
+            conn,
             "org.freedesktop.timedate1",
             "/org/freedesktop/timedate1",
             "org.freedesktop.timedate1",
             "Timezone",
         )
+        .await
         .unwrap_or_else(|| "unknown".to_string());
 
-        let ntp_enabled = dbus_get_bool(
-            &conn,
+        let ntp_enabled = dbus_get::<bool>(
+            conn,
             "org.freedesktop.timedate1",
             "/org/freedesktop/timedate1",
             "org.freedesktop.timedate1",
             "NTP",
         )
+        .await
         .map(|v| if v { "enabled" } else { "disabled" }.to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
-        let ntp_sync = dbus_get_bool(
-            &conn,
+        let ntp_sync = dbus_get::<bool>(
+            conn,
             "org.freedesktop.timedate1",
             "/org/freedesktop/timedate1",
             "org.freedesktop.timedate1",
             "NTPSynchronized",
         )
+        .await
         .map(|v| if v { "yes" } else { "no" }.to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
+        let rtc_time = dbus_get::<u64>(
+            conn,
+            "org.freedesktop.timedate1",
+            "/org/freedesktop/timedate1",
+            "org.freedesktop.timedate1",
+            "RTCTimeUSec",
+        )
+        .await
+        .map(format_rtc_time)
+        .unwrap_or_else(|| "unknown".to_string());
+
+        let local_rtc = dbus_get::<bool>(
+            conn,
+            "org.freedesktop.timedate1",
+            "/org/freedesktop/timedate1",
+            "org.freedesktop.timedate1",
+            "LocalRTC",
+        )
+        .await;
+
+        // timesync1 only exists when systemd-timesyncd is the NTP client
+        let timesync = Timesync::gather(conn).await;
+        let ntp_offset = timesync
+            .as_ref()
+            .and_then(|t| t.offset_usec)
+            .map(format_offset)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        // login1 and the manager's DynamicUser= allocations
+        let sessions = LoginSession::list(conn).await;
+        let dynamic_users = dynamic_users(conn).await;
+        // A container sees the host's sensors; they're still this machine's
+        let (sensors, power) = if remote {
+            (Vec::new(), PowerSupplies::default())
+        } else {
+            (Sensor::scan(), PowerSupplies::read())
+        };
+
         // locale1
-        let locale = dbus_get_locale(&conn).unwrap_or_else(|| "unknown".to_string());
+        let locale = dbus_get_locale(conn)
+            .await
+            .unwrap_or_else(|| "unknown".to_string());
 
-        let (os_name, os_version) = Self::get_os_info();
-        let uptime = Self::get_uptime();
+        let (os_name, os_version, uptime) = if remote {
+            let os_name = dbus_get::<String>(
+                conn,
+                "org.freedesktop.hostname1",
+                "/org/freedesktop/hostname1",
+                "org.freedesktop.hostname1",
+                "OperatingSystemPrettyName",
+            )
+            .await
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+            (os_name, String::new(), "unknown".to_string())
+        } else {
+            let (os_name, os_version) = Self::get_os_info();
+            (os_name, os_version, Self::get_uptime())
+        };
 
         Ok(Self {
             hostname,
@@ -90,9 +543,32 @@ impl HostInfo {
             uptime,
             ntp_enabled,
             ntp_sync,
+            rtc_time,
+            local_rtc,
+            ntp_offset,
+            timesync,
+            sessions,
+            dynamic_users,
+            sensors,
+            power,
+            dual_boot: !remote && Self::has_foreign_os(),
+            remote,
+            container: container.map(ContainerInfo::gather),
         })
     }
 
+    /// Look for another OS's loader on the ESP; RTC-in-local-time is only
+    /// defensible when sharing the clock with Windows.
+    fn has_foreign_os() -> bool {
+        [
+            "/boot/EFI/Microsoft",
+            "/efi/EFI/Microsoft",
+            "/boot/efi/EFI/Microsoft",
+        ]
+        .iter()
+        .any(|p| Path::new(p).exists())
+    }
+
     fn get_os_info() -> (String, String) {
         if let Ok(content) = fs::read_to_string("/etc/os-release") {
             let mut name = "unknown".to_string();
@@ -137,38 +613,82 @@ impl HostInfo {
     }
 }
 
-fn dbus_get_string(
-    conn: &Connection,
-    service: &str,
-    path: &str,
-    interface: &str,
+async fn dbus_get<T>(
+    systemd: Option<&SystemdClient>,
+    service: &'static str,
+    path: &'static str,
+    interface: &'static str,
     property: &str,
-) -> Option<String> {
-    let proxy = Proxy::new(conn, service, path, interface).ok()?;
-    proxy.get_property::<String>(property).ok()
+) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+    T::Error: Into<zbus::Error>,
+{
+    systemd?
+        .system_property(service, path, interface, property)
+        .await
+        .ok()
 }
 
-fn dbus_get_bool(
-    conn: &Connection,
-    service: &str,
-    path: &str,
-    interface: &str,
-    property: &str,
-) -> Option<bool> {
-    let proxy = Proxy::new(conn, service, path, interface).ok()?;
-    proxy.get_property::<bool>(property).ok()
+/// Leap, version, mode, stratum, precision, root delay, root dispersion,
+/// reference, originate, receive, transmit, destination, ignored, packet
+/// count, jitter.
+type NtpMessage = (
+    u32,
+    u32,
+    u32,
+    u32,
+    i32,
+    u64,
+    u64,
+    Vec<u8>,
+    u64,
+    u64,
+    u64,
+    u64,
+    bool,
+    u64,
+    u64,
+);
+
+/// Offset between the system clock and the NTP server in microseconds,
+/// computed from the last NTP exchange the same way timedatectl does.
+fn ntp_offset(msg: &NtpMessage) -> Option<i64> {
+    let (origin, recv, trans, dest) = (msg.8 as i64, msg.9 as i64, msg.10 as i64, msg.11 as i64);
+    if origin == 0 || dest == 0 {
+        return None;
+    }
+    Some(((recv - origin) + (trans - dest)) / 2)
+}
+
+fn format_rtc_time(usec: u64) -> String {
+    // The RTC value is reported as-is, without applying any timezone
+    chrono::DateTime::from_timestamp((usec / 1_000_000) as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
 }
 
-fn dbus_get_locale(conn: &Connection) -> Option<String> {
-    let proxy = Proxy::new(
+fn format_offset(usec: i64) -> String {
+    let abs = usec.unsigned_abs();
+    let sign = if usec < 0 { "-" } else { "+" };
+    if abs >= 1_000_000 {
+        format!("{}{:.3}s", sign, abs as f64 / 1_000_000.0)
+    } else if abs >= 1_000 {
+        format!("{}{:.3}ms", sign, abs as f64 / 1_000.0)
+    } else {
+        format!("{}{}us", sign, abs)
+    }
+}
+
+async fn dbus_get_locale(conn: Option<&SystemdClient>) -> Option<String> {
+    let values: Vec<String> = dbus_get(
         conn,
         "org.freedesktop.locale1",
         "/org/freedesktop/locale1",
         "org.freedesktop.locale1",
+        "Locale",
     )
-    .ok()?;
-
-    let values = proxy.get_property::<Vec<String>>("Locale").ok()?;
+    .await?;
     values
         .iter()
         .find(|s| s.starts_with("LANG="))
@@ -178,26 +698,48 @@ fn dbus_get_locale(conn: &Connection) -> Option<String> {
 
 pub struct HostContext {
     info: Option<HostInfo>,
-    error: Option<String>,
+    error: Option<RootworkError>,
+    pending: Pending<anyhow::Result<HostInfo>>,
+    systemd: SystemdClient,
+    messages: Messages,
+    sync_pending: Pending<anyhow::Result<()>>,
 }
 
 impl HostContext {
-    pub fn new() -> Self {
-        let (info, error) = match HostInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather host info: {}", e))),
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            info: None,
+            error: None,
+            pending: Pending::idle(),
+            systemd: systemd.clone(),
+            messages: messages.clone(),
+            sync_pending: Pending::idle(),
         };
-
-        Self { info, error }
+        ctx.refresh();
+        ctx
     }
 
     fn refresh(&mut self) {
-        let (info, error) = match HostInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather host info: {}", e))),
-        };
-        self.info = info;
-        self.error = error;
+        if !self.pending.is_pending() {
+            let systemd = self.systemd.clone();
+            self.pending = Pending::spawn_async(async move { HostInfo::gather(&systemd).await });
+        }
+    }
+
+    /// `s`: timesyncd queries its server right after starting, so a
+    /// restart is the way to force a sync
+    fn sync_now(&mut self) {
+        if self.sync_pending.is_pending() {
+            return;
+        }
+        if self.info.as_ref().is_some_and(|i| i.timesync.is_none()) {
+            self.messages
+                .warn("systemd-timesyncd isn't running; another NTP client may be in use");
+            return;
+        }
+        let systemd = self.systemd.clone();
+        self.sync_pending =
+            Pending::spawn_async(async move { systemd.restart_unit(TIMESYNCD_UNIT).await });
     }
 }
 
@@ -206,29 +748,43 @@ impl Context for HostContext {
         "Host"
     }
 
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
     fn draw(&self, f: &mut Frame, area: Rect) {
         let block = Block::default()
-            .title(" Host Information ")
+            .title(self.pending.title("Host Information"))
             .borders(Borders::ALL);
 
         if let Some(ref error) = self.error {
-            let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+            let error_text = Paragraph::new(error.lines()).block(block);
             f.render_widget(error_text, area);
             return;
         }
 
         if let Some(ref info) = self.info {
-            let os_str = format!("{} {}", info.os_name, info.os_version);
+            let container_height = if info.container.is_some() { 8 } else { 0 };
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(8),
+                    Constraint::Length(container_height),
+                    Constraint::Length(10),
+                    Constraint::Min(0),
+                ])
+                .split(area);
+
+            let os_str = format!("{} {}", info.os_name, info.os_version)
+                .trim_end()
+                .to_string();
 
             let rows = vec![
                 Row::new(vec!["Hostname", &info.hostname]),
                 Row::new(vec!["Static Hostname", &info.static_hostname]),
                 Row::new(vec!["Operating System", &os_str]),
-                Row::new(vec!["Timezone", &info.timezone]),
                 Row::new(vec!["Locale", &info.locale]),
                 Row::new(vec!["Uptime", &info.uptime]),
-                Row::new(vec!["NTP Enabled", &info.ntp_enabled]),
-                Row::new(vec!["NTP Synchronized", &info.ntp_sync]),
             ];
 
             let table = Table::new(rows, vec![Constraint::Length(20), Constraint::Min(30)])
@@ -237,9 +793,36 @@ impl Context for HostContext {
                         .style(Style::default().add_modifier(Modifier::BOLD)),
                 )
                 .block(block)
-                .row_highlight_style(Style::default().bg(crate::palette::dark_gray()));
+                .row_highlight_style(crate::palette::selected());
+
+            f.render_widget(table, chunks[0]);
 
-            f.render_widget(table, area);
+            if let Some(container) = &info.container {
+                draw_container(container, f, chunks[1]);
+            }
+            let time_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[2]);
+            draw_time(info, f, time_chunks[0]);
+            draw_timesync(self, info, f, time_chunks[1]);
+            let bottom = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[3]);
+            draw_users(info, f, bottom[0]);
+            // Laptops only; desktops report no battery
+            let power_rows = if info.power.batteries.is_empty() {
+                0
+            } else {
+                info.power.batteries.len() as u16 + 3
+            };
+            let right = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(power_rows), Constraint::Min(0)])
+                .split(bottom[1]);
+            draw_power(&info.power, f, right[0]);
+            draw_sensors(info, f, right[1]);
         } else {
             let loading = Paragraph::new("Loading...").block(block);
             f.render_widget(loading, area);
@@ -247,10 +830,288 @@ impl Context for HostContext {
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
-        if let crossterm::event::KeyCode::Char('r') = key.code {
-            self.refresh();
+        match key.code {
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('s') => self.sync_now(),
+            _ => {}
         }
     }
 
-    async fn tick(&mut self) {}
+    async fn tick(&mut self) {
+        if let Some(result) = self.sync_pending.poll() {
+            match result {
+                Ok(()) => {
+                    self.messages
+                        .info(format!("Restarted {} to sync now", TIMESYNCD_UNIT));
+                    self.refresh();
+                }
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to restart timesyncd", &e);
+                    self.messages.error(err.to_string());
+                }
+            }
+        }
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        let result =
+            result.map_err(|e| RootworkError::from_anyhow("Failed to gather host info", &e));
+        self.info = note_refresh_error(&mut self.error, &self.messages, result);
+    }
+}
+
+fn draw_container(container: &ContainerInfo, f: &mut Frame, area: Rect) {
+    let rows = container
+        .rows()
+        .map(|(label, value)| Row::new(vec![Cell::from(label), Cell::from(value)]));
+    let table = Table::new(rows, vec![Constraint::Length(20), Constraint::Min(30)])
+        .block(Block::default().title(" Container ").borders(Borders::ALL));
+    f.render_widget(table, area);
+}
+
+fn draw_time(info: &HostInfo, f: &mut Frame, area: Rect) {
+    let block = Block::default().title(" Time ").borders(Borders::ALL);
+
+    let local_rtc = match info.local_rtc {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "unknown",
+    };
+
+    let mut rows = vec![
+        Row::new(vec![
+            Cell::from("Timezone"),
+            Cell::from(info.timezone.as_str()),
+        ]),
+        Row::new(vec![
+            Cell::from("RTC Time"),
+            Cell::from(info.rtc_time.as_str()),
+        ]),
+        Row::new(vec![Cell::from("RTC in Local TZ"), Cell::from(local_rtc)]),
+        Row::new(vec![
+            Cell::from("NTP Enabled"),
+            Cell::from(info.ntp_enabled.as_str()),
+        ]),
+        Row::new(vec![
+            Cell::from("NTP Synchronized"),
+            Cell::from(info.ntp_sync.as_str()),
+        ]),
+        Row::new(vec![
+            Cell::from("NTP Offset"),
+            Cell::from(info.ntp_offset.as_str()),
+        ]),
+    ];
+
+    if info.local_rtc == Some(true) && !info.dual_boot {
+        rows.push(Row::new(vec![
+            Cell::from("Warning").style(Style::default().fg(crate::palette::yellow())),
+            Cell::from(
+                "RTC is in local time but no other OS was found; \
+                 use 'timedatectl set-local-rtc 0' to avoid DST problems",
+            )
+            .style(Style::default().fg(crate::palette::yellow())),
+        ]));
+    }
+
+    let table = Table::new(rows, vec![Constraint::Length(20), Constraint::Min(30)]).block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_timesync(ctx: &HostContext, info: &HostInfo, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(ctx.sync_pending.title("systemd-timesyncd"))
+        .borders(Borders::ALL);
+    let Some(timesync) = &info.timesync else {
+        f.render_widget(
+            Paragraph::new("Not running; the clock is synced by another NTP client, if any")
+                .style(Style::default().fg(crate::palette::gray()))
+                .block(block),
+            area,
+        );
+        return;
+    };
+
+    let servers = |list: &[String]| {
+        if list.is_empty() {
+            "-".to_string()
+        } else {
+            list.join(" ")
+        }
+    };
+    let poll = if timesync.poll_interval_usec == 0 {
+        "-".to_string()
+    } else {
+        format_duration_usec(timesync.poll_interval_usec)
+    };
+    let rows = vec![
+        Row::new(vec![Cell::from("Server"), Cell::from(timesync.server())]),
+        Row::new(vec![Cell::from("Poll Interval"), Cell::from(poll)]),
+        Row::new(vec![
+            Cell::from("Offset"),
+            Cell::from(info.ntp_offset.as_str()),
+        ]),
+        Row::new(vec![
+            Cell::from("Jitter"),
+            Cell::from(
+                timesync
+                    .jitter_usec
+                    .map(|j| format_offset(j as i64).trim_start_matches('+').to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ]),
+        Row::new(vec![
+            Cell::from("Link Servers"),
+            Cell::from(servers(&timesync.link_servers)),
+        ]),
+        Row::new(vec![
+            Cell::from("System Servers"),
+            Cell::from(servers(&timesync.system_servers)),
+        ]),
+        Row::new(vec![
+            Cell::from("Fallback Servers"),
+            Cell::from(servers(&timesync.fallback_servers)),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("s: sync now (restarts timesyncd)")
+                .style(Style::default().fg(crate::palette::gray())),
+        ]),
+    ];
+    let table = Table::new(rows, vec![Constraint::Length(18), Constraint::Min(20)]).block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_users(info: &HostInfo, f: &mut Frame, area: Rect) {
+    let dynamic = if info.dynamic_users.is_empty() {
+        "none".to_string()
+    } else {
+        info.dynamic_users
+            .iter()
+            .map(|(uid, name)| format!("{} ({})", name, uid))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let block = Block::default()
+        .title(" Users ")
+        .title_bottom(format!(" Dynamic users: {} ", dynamic))
+        .borders(Borders::ALL);
+
+    let Some(sessions) = &info.sessions else {
+        f.render_widget(
+            Paragraph::new("No logind on this system")
+                .style(Style::default().fg(crate::palette::gray()))
+                .block(block),
+            area,
+        );
+        return;
+    };
+    if sessions.is_empty() {
+        f.render_widget(Paragraph::new("Nobody is logged in").block(block), area);
+        return;
+    }
+
+    let rows = sessions.iter().map(|session| {
+        let since = chrono::DateTime::from_timestamp_micros(session.since_usec as i64)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let state_style = match session.state.as_str() {
+            "active" => Style::default().fg(crate::palette::green()),
+            "closing" => Style::default().fg(crate::palette::gray()),
+            _ => Style::default(),
+        };
+        Row::new(vec![
+            Cell::from(format!("{} ({})", session.user, session.uid)),
+            Cell::from(session.id.as_str()),
+            Cell::from(session.location()),
+            Cell::from(session.class.as_str()),
+            Cell::from(session.state.as_str()).style(state_style),
+            Cell::from(since),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(20),
+            Constraint::Length(8),
+            Constraint::Min(20),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(16),
+        ],
+    )
+    .header(
+        Row::new(vec!["User", "Session", "Where", "Class", "State", "Since"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_sensors(info: &HostInfo, f: &mut Frame, area: Rect) {
+    let block = Block::default().title(" Sensors ").borders(Borders::ALL);
+    if info.sensors.is_empty() {
+        let text = if info.remote {
+            "Sensors are only read on this machine, not over --host"
+        } else {
+            "No hwmon sensors (common in VMs)"
+        };
+        f.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(crate::palette::gray()))
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let rows = info.sensors.iter().map(|sensor| {
+        Row::new(vec![
+            Cell::from(sensor.chip.as_str()).style(Style::default().fg(crate::palette::gray())),
+            Cell::from(sensor.label.as_str()),
+            Cell::from(sensor.value()).style(sensor.style()),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(12),
+            Constraint::Length(16),
+            Constraint::Min(10),
+        ],
+    )
+    .block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_power(power: &PowerSupplies, f: &mut Frame, area: Rect) {
+    if power.batteries.is_empty() {
+        return;
+    }
+    let ac = match power.ac_online {
+        Some(true) => "online",
+        Some(false) => "offline",
+        None => "unknown",
+    };
+    let mut rows = vec![Row::new(vec![Cell::from("AC"), Cell::from(ac)])];
+    for battery in &power.batteries {
+        let style = match battery.capacity {
+            Some(c) if battery.is_discharging() && c <= 10 => Style::default()
+                .fg(crate::palette::red())
+                .add_modifier(Modifier::BOLD),
+            Some(c) if battery.is_discharging() && c <= 25 => {
+                Style::default().fg(crate::palette::yellow())
+            }
+            _ => Style::default(),
+        };
+        rows.push(Row::new(vec![
+            Cell::from(battery.name.as_str()),
+            Cell::from(battery.describe()).style(style),
+        ]));
+    }
+    let table = Table::new(rows, vec![Constraint::Length(8), Constraint::Min(20)])
+        .block(Block::default().title(" Power ").borders(Borders::ALL));
+    f.render_widget(table, area);
 }
@@ -0,0 +1,1004 @@
+use crate::contexts::format_bytes;
+use crate::contexts::{Context, Pending, glob_match};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::{SystemdClient, is_root};
+use crate::widgets::picker::fuzzy_match_score;
+use crate::widgets::selectable::{ListState, SelectableTable};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROC_SYS: &str = "/proc/sys";
+
+/// Where sysctl.d(5) drop-ins live, in priority order: a file name in an
+/// earlier directory masks the same name in later ones
+const SYSCTL_DIRS: [&str; 5] = [
+    "/etc/sysctl.d",
+    "/run/sysctl.d",
+    "/usr/local/lib/sysctl.d",
+    "/usr/lib/sysctl.d",
+    "/lib/sysctl.d",
+];
+
+/// Read after the drop-ins, so it wins over them
+const SYSCTL_CONF: &str = "/etc/sysctl.conf";
+
+/// modules-load.d(5) directories, masking like the sysctl.d ones
+const MODULES_LOAD_DIRS: [&str; 5] = [
+    "/etc/modules-load.d",
+    "/run/modules-load.d",
+    "/usr/local/lib/modules-load.d",
+    "/usr/lib/modules-load.d",
+    "/lib/modules-load.d",
+];
+
+/// Loads what the modules-load.d files list at boot
+const MODULES_LOAD_UNIT: &str = "systemd-modules-load.service";
+
+/// A kernel parameter under /proc/sys
+pub struct Sysctl {
+    /// Dotted name as sysctl(8) prints it, e.g. "net.ipv4.ip_forward"
+    name: String,
+    /// None when we may not read it
+    value: Option<String>,
+    writable: bool,
+    /// What systemd-sysctl applies at boot, if any file sets it
+    configured: Option<Configured>,
+}
+
+/// An assignment from a sysctl.d file
+#[derive(Clone)]
+struct Configured {
+    value: String,
+    /// "file:line"
+    source: String,
+}
+
+impl Sysctl {
+    /// Whether the running value differs from the configured one.
+    /// Whitespace doesn't count: the kernel prints tabs between fields.
+    fn deviates(&self) -> bool {
+        match (&self.value, &self.configured) {
+            (Some(value), Some(configured)) => !value
+                .split_whitespace()
+                .eq(configured.value.split_whitespace()),
+            _ => false,
+        }
+    }
+
+    /// Every parameter under /proc/sys with what the sysctl.d files say
+    fn gather() -> Result<Vec<Sysctl>> {
+        let config = SysctlConfig::read();
+        let mut params = Vec::new();
+        walk(Path::new(PROC_SYS), &mut Vec::new(), &mut params)?;
+        for param in &mut params {
+            param.configured = config.lookup(&param.name).cloned();
+        }
+        params.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(params)
+    }
+
+    /// Write a value at runtime, like `sysctl -w`
+    fn write(name: &str, value: &str) -> Result<()> {
+        fs::write(proc_path(name), format!("{}\n", value))?;
+        Ok(())
+    }
+}
+
+/// Collect the files below `dir`. Dots inside a path component become
+/// slashes in the name (e.g. the VLAN "eth0.100"), as in sysctl(8).
+fn walk(dir: &Path, components: &mut Vec<String>, params: &mut Vec<Sysctl>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        components.push(entry.file_name().to_string_lossy().replace('.', "/"));
+        if file_type.is_dir() {
+            // Unreadable subtrees (e.g. other namespaces' bits) are skipped
+            let _ = walk(&entry.path(), components, params);
+        } else if let Ok(metadata) = entry.metadata() {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = metadata.permissions().mode();
+            // Write-only triggers such as vm.drop_caches have nothing to show,
+            // and reading some of them has side effects
+            if mode & 0o444 != 0 {
+                let value = fs::read_to_string(entry.path())
+                    .ok()
+                    .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+                params.push(Sysctl {
+                    name: components.join("."),
+                    value,
+                    writable: mode & 0o222 != 0,
+                    configured: None,
+                });
+            }
+        }
+        components.pop();
+    }
+    Ok(())
+}
+
+/// /proc/sys path of a dotted name
+fn proc_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(PROC_SYS);
+    for component in name.split('.') {
+        path.push(component.replace('/', "."));
+    }
+    path
+}
+
+/// The assignments systemd-sysctl would apply, by name
+#[derive(Default)]
+struct SysctlConfig {
+    exact: HashMap<String, Configured>,
+    /// Glob assignments such as "net.ipv4.conf.*.rp_filter", in file order
+    globs: Vec<(String, Configured)>,
+}
+
+impl SysctlConfig {
+    fn read() -> Self {
+        let mut config = SysctlConfig::default();
+        for path in drop_in_files(&SYSCTL_DIRS)
+            .into_iter()
+            .chain(std::iter::once(PathBuf::from(SYSCTL_CONF)))
+        {
+            if let Ok(text) = fs::read_to_string(&path) {
+                config.parse(&path, &text);
+            }
+        }
+        config
+    }
+
+    /// Later assignments override earlier ones
+    fn parse(&mut self, path: &Path, text: &str) {
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            // "-" only means "ignore failures"
+            let key = normalize_key(key.trim().trim_start_matches('-'));
+            let configured = Configured {
+                value: value.trim().to_string(),
+                source: format!("{}:{}", path.display(), number + 1),
+            };
+            if key.contains(['*', '?']) {
+                self.globs.retain(|(pattern, _)| *pattern != key);
+                self.globs.push((key, configured));
+            } else {
+                self.exact.insert(key, configured);
+            }
+        }
+    }
+
+    /// Explicit assignments take precedence over globs, as in systemd-sysctl
+    fn lookup(&self, name: &str) -> Option<&Configured> {
+        self.exact.get(name).or_else(|| {
+            self.globs
+                .iter()
+                .rev()
+                .find(|(pattern, _)| glob_match(pattern, name))
+                .map(|(_, configured)| configured)
+        })
+    }
+}
+
+/// `*.conf` files of drop-in directories listed in priority order, sorted by
+/// file name; a name in an earlier directory masks it in later ones
+fn drop_in_files(dirs: &[&str]) -> Vec<PathBuf> {
+    let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".conf") {
+                files.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+    files.into_values().collect()
+}
+
+/// Keys may use slashes as separators, in which case dots are literal
+fn normalize_key(key: &str) -> String {
+    match key.find(['.', '/']) {
+        Some(i) if key.as_bytes()[i] == b'/' => key
+            .chars()
+            .map(|c| match c {
+                '/' => '.',
+                '.' => '/',
+                c => c,
+            })
+            .collect(),
+        _ => key.to_string(),
+    }
+}
+
+/// A kernel module that is loaded, or that something asks to load: a
+/// modules-load.d entry or a `modprobe@` unit
+pub struct Module {
+    /// With underscores; modprobe treats "-" the same
+    name: String,
+    loaded: Option<LoadedModule>,
+    /// Compiled into the kernel, so there's nothing to load
+    builtin: bool,
+    /// "file:line" of the modules-load.d entry
+    configured: Option<String>,
+    /// The `modprobe@NAME.service` instance and its active state
+    unit: Option<(String, String)>,
+}
+
+/// A line of /proc/modules
+struct LoadedModule {
+    size: u64,
+    /// None when the module can't be unloaded
+    refcount: Option<u32>,
+    /// Modules that depend on this one
+    used_by: Vec<String>,
+    /// "Live", "Loading" or "Unloading"
+    state: String,
+}
+
+impl Module {
+    /// Asked for but missing, or its modprobe@ unit failed
+    fn deviates(&self) -> bool {
+        let wanted = self.configured.is_some() || self.unit.is_some();
+        let missing = self.loaded.is_none() && !self.builtin;
+        wanted && missing
+            || self
+                .unit
+                .as_ref()
+                .is_some_and(|(_, state)| state == "failed")
+    }
+
+    /// Loaded modules, then wanted ones that aren't
+    async fn gather(systemd: SystemdClient) -> Result<Vec<Module>> {
+        let text = fs::read_to_string("/proc/modules")?;
+        let mut modules: BTreeMap<String, Module> = BTreeMap::new();
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, size, refcount, used_by, state, ..] = fields[..] else {
+                continue;
+            };
+            modules.insert(
+                name.to_string(),
+                Module {
+                    name: name.to_string(),
+                    loaded: Some(LoadedModule {
+                        size: size.parse().unwrap_or(0),
+                        refcount: refcount.parse().ok(),
+                        used_by: used_by
+                            .split(',')
+                            .filter(|m| !m.is_empty() && *m != "-")
+                            .map(str::to_string)
+                            .collect(),
+                        state: state.to_string(),
+                    }),
+                    builtin: false,
+                    configured: None,
+                    unit: None,
+                },
+            );
+        }
+
+        for path in drop_in_files(&MODULES_LOAD_DIRS) {
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue;
+            };
+            for (number, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                    continue;
+                }
+                wanted(&mut modules, line).configured =
+                    Some(format!("{}:{}", path.display(), number + 1));
+            }
+        }
+        // The list stands on its own when the bus is away
+        let units = systemd
+            .list_units_matching(&["modprobe@*.service"])
+            .await
+            .unwrap_or_default();
+        for unit in units {
+            let Some(instance) = unit
+                .name
+                .strip_prefix("modprobe@")
+                .and_then(|rest| rest.strip_suffix(".service"))
+            else {
+                continue;
+            };
+            wanted(&mut modules, instance).unit =
+                Some((unit.name.clone(), unit.active_state.clone()));
+        }
+
+        Ok(modules.into_values().collect())
+    }
+}
+
+/// The entry of a module something asks for, added when it isn't loaded
+fn wanted<'a>(modules: &'a mut BTreeMap<String, Module>, name: &str) -> &'a mut Module {
+    let name = name.replace('-', "_");
+    modules.entry(name.clone()).or_insert_with(|| Module {
+        builtin: Path::new("/sys/module").join(&name).exists(),
+        name,
+        loaded: None,
+        configured: None,
+        unit: None,
+    })
+}
+
+/// Which list the Kernel tab shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KernelView {
+    Params,
+    Modules,
+}
+
+pub struct KernelContext {
+    systemd: SystemdClient,
+    view: KernelView,
+    params: Vec<Sysctl>,
+    /// Indices into `params` matching the filter, best match first
+    visible: Vec<usize>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<Vec<Sysctl>>>,
+    list: ListState,
+    modules: Vec<Module>,
+    /// Indices into `modules` matching the filter
+    visible_modules: Vec<usize>,
+    modules_error: Option<RootworkError>,
+    modules_pending: Pending<Result<Vec<Module>>>,
+    module_list: ListState,
+    /// Applies to both lists
+    filter: String,
+    show_filter: bool,
+    /// Only what differs from the configuration: parameters with another
+    /// value than the sysctl.d files set, modules that failed to load
+    only_deviating: bool,
+    /// New value being typed for the selected parameter
+    edit_input: Option<String>,
+    write: Pending<Result<String>>,
+    jump_to_unit: Option<String>,
+    messages: Messages,
+}
+
+impl KernelContext {
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            systemd: systemd.clone(),
+            view: KernelView::Params,
+            params: Vec::new(),
+            visible: Vec::new(),
+            error: None,
+            pending: Pending::idle(),
+            list: ListState::new(),
+            modules: Vec::new(),
+            visible_modules: Vec::new(),
+            modules_error: None,
+            modules_pending: Pending::idle(),
+            module_list: ListState::new(),
+            filter: String::new(),
+            show_filter: false,
+            only_deviating: false,
+            edit_input: None,
+            write: Pending::idle(),
+            jump_to_unit: None,
+            messages: messages.clone(),
+        };
+        ctx.refresh();
+        ctx
+    }
+
+    fn refresh(&mut self) {
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn(Sysctl::gather);
+        }
+        if !self.modules_pending.is_pending() {
+            self.modules_pending = Pending::spawn_async(Module::gather(self.systemd.clone()));
+        }
+    }
+
+    fn selected(&self) -> Option<&Sysctl> {
+        self.visible
+            .get(self.list.selected)
+            .map(|&i| &self.params[i])
+    }
+
+    fn selected_module(&self) -> Option<&Module> {
+        self.visible_modules
+            .get(self.module_list.selected)
+            .map(|&i| &self.modules[i])
+    }
+
+    /// Rank both lists by the filter, keeping the selections when they
+    /// still match
+    fn apply_filter(&mut self) {
+        let needle = self.filter.trim().to_lowercase();
+
+        let anchor = self.selected().map(|p| p.name.clone());
+        self.visible = rank(&self.params, &needle, |p| {
+            (!self.only_deviating || p.deviates()).then_some(p.name.as_str())
+        });
+        let position = anchor.and_then(|name| {
+            self.visible
+                .iter()
+                .position(|&i| self.params[i].name == name)
+        });
+        reselect(&mut self.list, position, self.visible.len());
+
+        let anchor = self.selected_module().map(|m| m.name.clone());
+        self.visible_modules = rank(&self.modules, &needle, |m| {
+            (!self.only_deviating || m.deviates()).then_some(m.name.as_str())
+        });
+        let position = anchor.and_then(|name| {
+            self.visible_modules
+                .iter()
+                .position(|&i| self.modules[i].name == name)
+        });
+        reselect(&mut self.module_list, position, self.visible_modules.len());
+    }
+
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            KernelView::Params => KernelView::Modules,
+            KernelView::Modules => KernelView::Params,
+        };
+    }
+
+    fn toggle_deviating(&mut self) {
+        self.only_deviating = !self.only_deviating;
+        self.apply_filter();
+    }
+
+    /// Start typing a new value for the selected parameter, pre-filled with
+    /// the running one, or the configured one with `configured`
+    fn start_edit(&mut self, configured: bool) {
+        let Some(param) = self.selected() else {
+            return;
+        };
+        let text = if configured {
+            match &param.configured {
+                Some(c) => c.value.clone(),
+                None => {
+                    self.messages
+                        .warn(format!("No sysctl.d file sets {}", param.name));
+                    return;
+                }
+            }
+        } else {
+            param.value.clone().unwrap_or_default()
+        };
+        if !param.writable {
+            self.messages.warn(format!("{} is read-only", param.name));
+        } else if !is_root() {
+            self.messages.warn("Changing kernel parameters needs root");
+        } else {
+            self.edit_input = Some(text);
+        }
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.edit_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let value = input.trim().to_string();
+                self.edit_input = None;
+                let Some(name) = self.selected().map(|p| p.name.clone()) else {
+                    return;
+                };
+                self.write = Pending::spawn(move || {
+                    Sysctl::write(&name, &value)
+                        .map(|()| format!("{} = {} (until reboot)", name, value))
+                        .map_err(|e| anyhow::anyhow!("{}: {}", name, e))
+                });
+            }
+            KeyCode::Esc => self.edit_input = None,
+            _ => {}
+        }
+    }
+
+    /// The selected module's modprobe@ unit, or the unit that reads
+    /// modules-load.d when that's where the module comes from
+    fn jump_to_module_unit(&mut self) {
+        let Some(module) = self.selected_module() else {
+            return;
+        };
+        match (&module.unit, &module.configured) {
+            (Some((unit, _)), _) => self.jump_to_unit = Some(unit.clone()),
+            (None, Some(_)) => self.jump_to_unit = Some(MODULES_LOAD_UNIT.to_string()),
+            (None, None) => self.messages.warn(format!("No unit loads {}", module.name)),
+        }
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
+    }
+}
+
+/// Indices of the items whose name (None to drop the item) matches
+/// `needle`, best first; ties keep the list order
+fn rank<T>(items: &[T], needle: &str, name: impl Fn(&T) -> Option<&str>) -> Vec<usize> {
+    let mut ranked: Vec<(usize, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| Some((fuzzy_match_score(&name(item)?.to_lowercase(), needle)?, i)))
+        .collect();
+    ranked.sort_by_key(|(score, _)| *score);
+    ranked.into_iter().map(|(_, i)| i).collect()
+}
+
+fn reselect(list: &mut ListState, position: Option<usize>, len: usize) {
+    match position {
+        Some(position) => list.select(position, len),
+        None => list.clamp(len),
+    }
+}
+
+impl Context for KernelContext {
+    fn name(&self) -> &'static str {
+        "Kernel"
+    }
+
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
+    fn captures_input(&self) -> bool {
+        self.show_filter || self.edit_input.is_some()
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(6),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        match self.view {
+            KernelView::Params => {
+                draw_params(self, f, chunks[0]);
+                draw_param_detail(self, f, chunks[1]);
+            }
+            KernelView::Modules => {
+                draw_modules(self, f, chunks[0]);
+                draw_module_detail(self, f, chunks[1]);
+            }
+        }
+
+        let status = match (&self.edit_input, self.selected()) {
+            (Some(input), Some(param)) => Line::from(vec![
+                Span::styled(
+                    format!("{} = ", param.name),
+                    Style::default().fg(crate::palette::yellow()),
+                ),
+                Span::raw(input.as_str()),
+                Span::raw("_  (Enter: write until reboot, Esc: cancel)"),
+            ]),
+            _ if self.show_filter => Line::from("Type to filter, Enter: keep, Esc: undo"),
+            _ if self.view == KernelView::Modules => {
+                Line::from("/:filter d:failed only Enter:open loading unit m:sysctl r:refresh")
+            }
+            _ => Line::from(
+                "/:filter d:deviating only e:edit c:set configured value m:modules r:refresh",
+            ),
+        };
+        f.render_widget(
+            Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
+            chunks[2],
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.edit_input.is_some() {
+            self.handle_edit_key(key);
+            return;
+        }
+
+        if self.show_filter {
+            match key.code {
+                KeyCode::Esc => {
+                    self.show_filter = false;
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Enter => self.show_filter = false,
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('/') => self.show_filter = true,
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Char('d') => self.toggle_deviating(),
+            KeyCode::Char('m') => self.toggle_view(),
+            KeyCode::Char('r') => self.refresh(),
+            _ => {}
+        }
+
+        let (list, len) = match self.view {
+            KernelView::Params => (&mut self.list, self.visible.len()),
+            KernelView::Modules => (&mut self.module_list, self.visible_modules.len()),
+        };
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => list.up(len),
+            KeyCode::Char('g') => list.top(len),
+            KeyCode::Char('G') => list.bottom(len),
+            KeyCode::PageDown => list.page_down(len),
+            KeyCode::PageUp => list.page_up(len),
+            _ => {}
+        }
+
+        match (self.view, key.code) {
+            (KernelView::Params, KeyCode::Char('e') | KeyCode::Enter) => self.start_edit(false),
+            (KernelView::Params, KeyCode::Char('c')) => self.start_edit(true),
+            (KernelView::Modules, KeyCode::Enter) => self.jump_to_module_unit(),
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self) {
+        match self.write.poll() {
+            Some(Ok(done)) => {
+                self.messages.info(done);
+                self.refresh();
+            }
+            Some(Err(e)) => self.messages.error(format!("sysctl: {}", e)),
+            None => {}
+        }
+
+        if let Some(result) = self.pending.poll() {
+            // Keep the selection by name across the new listing
+            let anchor = self.selected().map(|p| p.name.clone());
+            self.visible.clear();
+            match result {
+                Ok(params) => {
+                    self.params = params;
+                    self.error = None;
+                }
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to read /proc/sys", &e);
+                    if self.error.is_none() {
+                        self.messages.error(err.to_string());
+                    }
+                    self.params.clear();
+                    self.error = Some(err);
+                }
+            }
+            self.apply_filter();
+            let position = anchor.and_then(|name| {
+                self.visible
+                    .iter()
+                    .position(|&i| self.params[i].name == name)
+            });
+            reselect(&mut self.list, position, self.visible.len());
+        }
+
+        if let Some(result) = self.modules_pending.poll() {
+            let anchor = self.selected_module().map(|m| m.name.clone());
+            self.visible_modules.clear();
+            match result {
+                Ok(modules) => {
+                    self.modules = modules;
+                    self.modules_error = None;
+                }
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to read /proc/modules", &e);
+                    if self.modules_error.is_none() {
+                        self.messages.error(err.to_string());
+                    }
+                    self.modules.clear();
+                    self.modules_error = Some(err);
+                }
+            }
+            self.apply_filter();
+            let position = anchor.and_then(|name| {
+                self.visible_modules
+                    .iter()
+                    .position(|&i| self.modules[i].name == name)
+            });
+            reselect(&mut self.module_list, position, self.visible_modules.len());
+        }
+    }
+}
+
+/// List title with the filter and how many items it lets through
+fn list_title(ctx: &KernelContext, what: &str, shown: usize, total: usize) -> String {
+    if ctx.show_filter || !ctx.filter.is_empty() {
+        format!("{} [filter: {}] ({}/{})", what, ctx.filter, shown, total)
+    } else {
+        format!("{} ({})", what, shown)
+    }
+}
+
+fn draw_params(ctx: &KernelContext, f: &mut Frame, area: Rect) {
+    let deviating = ctx.params.iter().filter(|p| p.deviates()).count();
+    let mut title = list_title(
+        ctx,
+        "Kernel parameters",
+        ctx.visible.len(),
+        ctx.params.len(),
+    );
+    if ctx.only_deviating {
+        title.push_str(" [deviating only]");
+    } else if deviating > 0 {
+        title.push_str(&format!(" [{} deviate from sysctl.d]", deviating));
+    }
+    let block = Block::default()
+        .title(ctx.pending.title(&title))
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    if ctx.visible.is_empty() {
+        let text = if ctx.pending.is_pending() {
+            "Loading..."
+        } else if ctx.only_deviating && ctx.filter.is_empty() {
+            "Every configured parameter has its configured value"
+        } else {
+            "No matching parameters"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Value", "Configured"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.list,
+        ctx.visible.len(),
+        [
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+        ],
+        |i| {
+            let param = &ctx.params[ctx.visible[i]];
+            let value = match &param.value {
+                Some(value) => Span::raw(value.clone()),
+                None => Span::styled("(unreadable)", Style::default().fg(crate::palette::gray())),
+            };
+            let configured = match &param.configured {
+                Some(c) if param.deviates() => Span::styled(
+                    c.value.clone(),
+                    Style::default()
+                        .fg(crate::palette::yellow())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Some(c) => Span::styled(
+                    c.value.clone(),
+                    Style::default().fg(crate::palette::green()),
+                ),
+                None => Span::raw(""),
+            };
+            Row::new(vec![
+                Span::styled(
+                    param.name.clone(),
+                    Style::default().fg(crate::palette::cyan()),
+                ),
+                value,
+                configured,
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_param_detail(ctx: &KernelContext, f: &mut Frame, area: Rect) {
+    let block = Block::default().title(" Parameter ").borders(Borders::ALL);
+    let Some(param) = ctx.selected() else {
+        f.render_widget(Paragraph::new("").block(block), area);
+        return;
+    };
+
+    let label =
+        |text: &'static str| Span::styled(text, Style::default().fg(crate::palette::gray()));
+    let mut lines = vec![Line::from(vec![
+        label("Path: "),
+        Span::raw(proc_path(&param.name).display().to_string()),
+        Span::raw(if param.writable { "" } else { "  (read-only)" }),
+    ])];
+    lines.push(match &param.configured {
+        Some(c) => Line::from(vec![
+            label("Set in: "),
+            Span::raw(c.source.clone()),
+            if param.deviates() {
+                Span::styled(
+                    "  differs from the running value",
+                    Style::default().fg(crate::palette::yellow()),
+                )
+            } else {
+                Span::raw("")
+            },
+        ]),
+        None => Line::from(vec![label("Set in: "), Span::raw("no sysctl.d file")]),
+    });
+    lines.push(Line::from(vec![
+        label("Value: "),
+        Span::raw(param.value.clone().unwrap_or_else(|| "-".to_string())),
+    ]));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_modules(ctx: &KernelContext, f: &mut Frame, area: Rect) {
+    let failed = ctx.modules.iter().filter(|m| m.deviates()).count();
+    let loaded = ctx.modules.iter().filter(|m| m.loaded.is_some()).count();
+    let mut title = list_title(
+        ctx,
+        "Kernel modules",
+        ctx.visible_modules.len(),
+        ctx.modules.len(),
+    );
+    title.push_str(&format!(" [{} loaded]", loaded));
+    if ctx.only_deviating {
+        title.push_str(" [failed only]");
+    } else if failed > 0 {
+        title.push_str(&format!(" [{} failed to load]", failed));
+    }
+    let block = Block::default()
+        .title(ctx.modules_pending.title(&title))
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.modules_error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    if ctx.visible_modules.is_empty() {
+        let text = if ctx.modules_pending.is_pending() {
+            "Loading..."
+        } else if ctx.only_deviating && ctx.filter.is_empty() {
+            "Every module asked for is loaded"
+        } else {
+            "No matching modules"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Size", "Refs", "Used by", "Loaded by"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.module_list,
+        ctx.visible_modules.len(),
+        [
+            Constraint::Length(24),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Min(20),
+            Constraint::Length(22),
+        ],
+        |i| {
+            let module = &ctx.modules[ctx.visible_modules[i]];
+            let name_color = if module.deviates() {
+                crate::palette::red()
+            } else if module.loaded.is_none() {
+                crate::palette::gray()
+            } else {
+                crate::palette::cyan()
+            };
+            let (size, refs, used_by) = match &module.loaded {
+                Some(loaded) => (
+                    format_bytes(loaded.size),
+                    loaded
+                        .refcount
+                        .map_or_else(|| "-".to_string(), |n| n.to_string()),
+                    loaded.used_by.join(","),
+                ),
+                None if module.builtin => ("built in".to_string(), String::new(), String::new()),
+                None => ("not loaded".to_string(), String::new(), String::new()),
+            };
+            let loaded_by = match (&module.unit, &module.configured) {
+                (Some((unit, state)), _) => Span::styled(
+                    unit.clone(),
+                    Style::default().fg(if state == "failed" {
+                        crate::palette::red()
+                    } else {
+                        crate::palette::green()
+                    }),
+                ),
+                (None, Some(_)) => Span::raw("modules-load.d"),
+                (None, None) => Span::raw(""),
+            };
+            Row::new(vec![
+                Span::styled(module.name.clone(), Style::default().fg(name_color)),
+                Span::raw(size),
+                Span::raw(refs),
+                Span::raw(used_by),
+                loaded_by,
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_module_detail(ctx: &KernelContext, f: &mut Frame, area: Rect) {
+    let block = Block::default().title(" Module ").borders(Borders::ALL);
+    let Some(module) = ctx.selected_module() else {
+        f.render_widget(Paragraph::new("").block(block), area);
+        return;
+    };
+
+    let label =
+        |text: &'static str| Span::styled(text, Style::default().fg(crate::palette::gray()));
+    let state = match &module.loaded {
+        Some(loaded) => format!(
+            "{}, {} used by: {}",
+            loaded.state,
+            format_bytes(loaded.size),
+            if loaded.used_by.is_empty() {
+                "-".to_string()
+            } else {
+                loaded.used_by.join(", ")
+            }
+        ),
+        None if module.builtin => "built into the kernel".to_string(),
+        None => "not loaded".to_string(),
+    };
+    let mut lines = vec![Line::from(vec![label("State: "), Span::raw(state)])];
+    if let Some(source) = &module.configured {
+        lines.push(Line::from(vec![
+            label("Listed in: "),
+            Span::raw(source.clone()),
+            Span::raw(format!("  (loaded by {})", MODULES_LOAD_UNIT)),
+        ]));
+    }
+    if let Some((unit, state)) = &module.unit {
+        lines.push(Line::from(vec![
+            label("Unit: "),
+            Span::raw(format!("{} ({})", unit, state)),
+        ]));
+    }
+    if module.deviates() {
+        lines.push(Line::styled(
+            "Asked for but not loaded: Enter opens the unit, its logs say why",
+            Style::default().fg(crate::palette::red()),
+        ));
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -1,62 +1,428 @@
-use crate::contexts::Context;
+use crate::config::Config;
+use crate::contexts::{Context, Pending, centered_rect, copy_to_clipboard, glob_match};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::presets::{Preset, PresetAction, PresetMenu, Presets};
+use crate::systemd::denials::{DenialSource, read_denials};
+use crate::systemd::logs::JournalSource;
+use crate::timestamp::{self, TimestampFormat};
+use crate::widgets::selectable::{ListState, SelectableList, SelectableTable};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::Style,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Row},
 };
+use regex::Regex;
+use std::cell::Cell;
 use std::collections::VecDeque;
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_int, c_void};
-
-#[link(name = "systemd")]
-unsafe extern "C" {
-    fn sd_journal_open(ret: *mut *mut c_void, flags: c_int) -> c_int;
-    fn sd_journal_close(j: *mut c_void);
-    fn sd_journal_add_match(j: *mut c_void, data: *const c_void, size: usize) -> c_int;
-    fn sd_journal_seek_tail(j: *mut c_void) -> c_int;
-    fn sd_journal_seek_realtime_usec(j: *mut c_void, usec: u64) -> c_int;
-    fn sd_journal_previous(j: *mut c_void) -> c_int;
-    fn sd_journal_next(j: *mut c_void) -> c_int;
-    fn sd_journal_get_realtime_usec(j: *mut c_void, ret: *mut u64) -> c_int;
-    fn sd_journal_get_data(
-        j: *mut c_void,
-        field: *const c_char,
-        data: *mut *const u8,
-        length: *mut usize,
-    ) -> c_int;
-}
-
-const SD_JOURNAL_LOCAL_ONLY: c_int = 1;
+use std::os::raw::{c_char, c_void};
+
+use crate::systemd::journal_ffi::{
+    sd_journal_add_disjunction, sd_journal_add_match, sd_journal_close, sd_journal_enumerate_data,
+    sd_journal_get_cursor, sd_journal_get_monotonic_usec, sd_journal_get_realtime_usec,
+    sd_journal_next, sd_journal_open, sd_journal_previous, sd_journal_restart_data,
+    sd_journal_seek_cursor, sd_journal_seek_realtime_usec, sd_journal_seek_tail,
+    sd_journal_test_cursor,
+};
+use crate::systemd::logs::get_journal_field;
+
+/// Realtime and monotonic clocks drifting apart by more than this between two
+/// entries of the same boot means the wall clock was stepped
+const CLOCK_JUMP_USEC: i64 = 2_000_000;
+
+/// Entries kept in memory unless `[logs] max_entries` says otherwise
+const DEFAULT_MAX_ENTRIES: usize = 20_000;
+
+/// Entries read at a time, at startup and per backfill when scrolling up
+/// past the oldest, unless `[logs] page_size` says otherwise
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Columns of the message shown in the list; the popup has the rest
+const MESSAGE_COLUMN_MAX: usize = 200;
+
+/// `[logs] max_entries`, at least a screenful
+fn max_entries_from_config(config: &Config) -> usize {
+    match config.get("logs", "max_entries") {
+        Some(value) => value
+            .parse::<usize>()
+            .map(|n| n.max(100))
+            .unwrap_or_else(|_| {
+                tracing::warn!("Invalid logs max_entries {:?}", value);
+                DEFAULT_MAX_ENTRIES
+            }),
+        None => DEFAULT_MAX_ENTRIES,
+    }
+}
+
+/// `[logs] page_size`
+fn page_size_from_config(config: &Config) -> usize {
+    match config.get("logs", "page_size") {
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .unwrap_or_else(|| {
+                tracing::warn!("Invalid logs page_size {:?}", value);
+                DEFAULT_PAGE_SIZE
+            }),
+        None => DEFAULT_PAGE_SIZE,
+    }
+}
 
 pub struct LogEntry {
     timestamp_micros: u64,
-    display_time: String,
+    /// Monotonic timestamp and the boot it belongs to
+    monotonic: Option<(u64, [u8; 16])>,
+    /// Wall clock step right before this entry, in µs
+    clock_jump: Option<i64>,
     unit: String,
     message: String,
     priority: u8,
+    /// The unit padded and cut to its column, built once rather than per draw
+    unit_column: String,
+    /// Length of the message prefix shown in the list, on a char boundary
+    message_shown: usize,
+    /// Where to find the record again for the field popup
+    cursor: Option<String>,
+    /// Identical entries that followed this one, folded into its row
+    duplicates: Vec<LogEntry>,
+    /// Expanded by hand with `e`; left alone by folding from then on
+    unfolded: bool,
+}
+
+impl LogEntry {
+    /// The latest record of the row, itself or its last duplicate
+    fn last(&self) -> &LogEntry {
+        self.duplicates.last().unwrap_or(self)
+    }
+
+    /// Whether `other` repeats this entry's message
+    fn is_repeated_by(&self, other: &LogEntry) -> bool {
+        !self.unfolded
+            && !other.unfolded
+            && self.priority == other.priority
+            && self.unit == other.unit
+            && self.message == other.message
+    }
+}
+
+/// Entries per time bucket over the loaded window, for the volume strip
+/// above the list
+struct Histogram {
+    start_usec: u64,
+    bucket_usec: u64,
+    /// Entry count and most severe priority of each bucket
+    buckets: Vec<(usize, u8)>,
+}
+
+const MINUTE_USEC: u64 = 60_000_000;
+
+impl Histogram {
+    /// Minute buckets, widened to several minutes each when the window
+    /// wouldn't fit in `width` columns otherwise
+    fn new<'a>(records: impl Iterator<Item = &'a LogEntry> + Clone, width: usize) -> Option<Self> {
+        let (first, last) = records.clone().fold((u64::MAX, 0), |(first, last), e| {
+            (first.min(e.timestamp_micros), last.max(e.timestamp_micros))
+        });
+        if first > last || width == 0 {
+            return None;
+        }
+        let start_usec = first - first % MINUTE_USEC;
+        let minutes = (last - start_usec) / MINUTE_USEC + 1;
+        let bucket_usec = minutes.div_ceil(width as u64) * MINUTE_USEC;
+        let mut histogram = Self {
+            start_usec,
+            bucket_usec,
+            buckets: vec![(0, 7); ((last - start_usec) / bucket_usec + 1) as usize],
+        };
+        for e in records {
+            let i = histogram.bucket_of(e.timestamp_micros);
+            let bucket = &mut histogram.buckets[i];
+            bucket.0 += 1;
+            bucket.1 = bucket.1.min(e.priority);
+        }
+        Some(histogram)
+    }
+
+    fn bucket_of(&self, timestamp_usec: u64) -> usize {
+        let bucket = timestamp_usec.saturating_sub(self.start_usec) / self.bucket_usec;
+        (bucket as usize).min(self.buckets.len() - 1)
+    }
+}
+
+/// `[logs] collapse_repeats`: fold consecutive identical messages, on by
+/// default
+fn collapse_from_config(config: &Config) -> bool {
+    match config.get("logs", "collapse_repeats") {
+        None | Some("true") => true,
+        Some("false") => false,
+        Some(other) => {
+            tracing::warn!("Invalid logs collapse_repeats {:?}", other);
+            true
+        }
+    }
+}
+
+/// Entries a regex-filtered read looks through for matches before giving up
+const REGEX_SCAN_MAX: usize = 100_000;
+
+/// The `/` filter: journal field matches, evaluated by sd-journal, and a
+/// regex on MESSAGE applied here.
+///
+/// `_PID=1234 SYSLOG_IDENTIFIER=sshd OR _COMM=sudo ~ fail(ed|ure)` reads
+/// as (_PID=1234 AND SYSLOG_IDENTIFIER=sshd) OR _COMM=sudo, keeping the
+/// messages the regex after `~` finds. As in journalctl, two matches on
+/// the same field within a group are alternatives.
+///
+/// A filter preset (`P`) adds unit globs and a case-insensitive search
+/// of the message, both checked here, and a priority, matched by
+/// sd-journal.
+#[derive(Clone)]
+pub struct LogFilter {
+    text: String,
+    /// Alternatives, each a conjunction of FIELD=value matches
+    groups: Vec<Vec<String>>,
+    regex: Option<Regex>,
+    units: Vec<String>,
+    search: Option<Regex>,
+    preset: Option<Preset>,
+}
+
+impl LogFilter {
+    fn parse(text: &str) -> Result<Self, String> {
+        let (matches, regex) = match text.split_once('~') {
+            Some((matches, regex)) => (matches, Some(regex.trim())),
+            None => (text, None),
+        };
+        let regex = match regex {
+            Some("") | None => None,
+            Some(pattern) => {
+                Some(Regex::new(pattern).map_err(|e| format!("Invalid regex: {}", e))?)
+            }
+        };
+        let mut groups = vec![Vec::new()];
+        for term in matches.split_whitespace() {
+            match term {
+                "OR" | "or" | "+" | "||" => groups.push(Vec::new()),
+                "AND" | "and" | "&&" => {}
+                _ => {
+                    let valid = term.split_once('=').is_some_and(|(field, _)| {
+                        !field.is_empty()
+                            && field
+                                .chars()
+                                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                    });
+                    if !valid {
+                        return Err(format!(
+                            "Expected FIELD=value, OR or ~regex, not {:?}",
+                            term
+                        ));
+                    }
+                    groups.last_mut().unwrap().push(term.to_string());
+                }
+            }
+        }
+        if groups.iter().any(Vec::is_empty) && groups.len() > 1 {
+            return Err("OR needs matches on both sides".to_string());
+        }
+        groups.retain(|group| !group.is_empty());
+        if groups.is_empty() && regex.is_none() {
+            return Err("Empty filter".to_string());
+        }
+        Ok(Self {
+            text: text.trim().to_string(),
+            groups,
+            regex,
+            units: Vec::new(),
+            search: None,
+            preset: None,
+        })
+    }
+
+    fn from_preset(preset: Preset) -> Result<Self, String> {
+        let mut filter = match preset.journal.as_deref() {
+            Some(journal) => Self::parse(journal)?,
+            None => Self {
+                text: String::new(),
+                groups: Vec::new(),
+                regex: None,
+                units: Vec::new(),
+                search: None,
+                preset: None,
+            },
+        };
+        if let Some(priority) = preset.priority {
+            // Matches on one field are alternatives within a group
+            let matches = (0..=priority).map(|p| format!("PRIORITY={}", p));
+            if filter.groups.is_empty() {
+                filter.groups.push(matches.collect());
+            } else {
+                let matches: Vec<String> = matches.collect();
+                for group in &mut filter.groups {
+                    group.extend(matches.iter().cloned());
+                }
+            }
+        }
+        if let Some(text) = &preset.text {
+            let pattern = format!("(?i){}", regex::escape(text));
+            filter.search = Some(Regex::new(&pattern).map_err(|e| e.to_string())?);
+        }
+        filter.units = preset.units.clone();
+        filter.text = format!("preset {}", preset.name);
+        filter.preset = Some(preset);
+        Ok(filter)
+    }
+
+    /// Whether entries are dropped after sd-journal returns them, so a
+    /// read has to look further back for enough of them
+    fn filters_here(&self) -> bool {
+        self.regex.is_some() || self.search.is_some() || !self.units.is_empty()
+    }
+
+    fn keeps(&self, entry: &LogEntry) -> bool {
+        self.regex
+            .as_ref()
+            .is_none_or(|r| r.is_match(&entry.message))
+            && self
+                .search
+                .as_ref()
+                .is_none_or(|r| r.is_match(&entry.message))
+            && (self.units.is_empty() || self.units.iter().any(|p| glob_match(p, &entry.unit)))
+    }
+}
+
+/// How far back the denials panel looks
+const DENIAL_WINDOW_USEC: u64 = 24 * 3_600_000_000;
+
+/// `A`: SELinux and AppArmor denials of the last day per source, with the
+/// rules that would allow them
+struct DenialsPanel {
+    sources: Vec<DenialSource>,
+    loaded: bool,
+    list: ListState,
+    pending: Pending<Vec<DenialSource>>,
+}
+
+impl DenialsPanel {
+    fn new() -> Self {
+        let mut panel = Self {
+            sources: Vec::new(),
+            loaded: false,
+            list: ListState::new(),
+            pending: Pending::idle(),
+        };
+        panel.reload();
+        panel
+    }
+
+    fn reload(&mut self) {
+        let since = timestamp::now_usec().saturating_sub(DENIAL_WINDOW_USEC);
+        self.pending = Pending::spawn(move || read_denials(since));
+    }
+
+    /// The selected source's rules, one per line, for a policy module
+    fn rules_text(&self) -> Option<String> {
+        let source = self.sources.get(self.list.selected)?;
+        Some(
+            source
+                .rules
+                .iter()
+                .map(|r| format!("# {}\n{}\n", r.context, r.rule))
+                .collect(),
+        )
+    }
+}
+
+/// Every field of one journal record, like `journalctl -o verbose`
+struct EntryDetail {
+    fields: Vec<(String, String)>,
+    message: String,
+    list: ListState,
 }
 
 pub struct LogsContext {
+    /// Rows of the list; folded repeats hang off their first entry
     entries: VecDeque<LogEntry>,
+    /// Entries folded into rows, counted against `max_entries`
+    folded: usize,
     max_entries: usize,
-    filter_unit: Option<String>,
+    page_size: usize,
+    /// No older entries to backfill before the first row
+    at_head: bool,
+    /// Oldest record a read looked at, kept or not, where backfill picks
+    /// up so a regex filter doesn't scan the same records again
+    head_cursor: Option<String>,
+    /// Newest record looked at, where streaming picks up
+    tail_cursor: Option<String>,
+    /// Backfill read, tagged with the cursor it started from
+    older: Pending<(String, Result<Scan, RootworkError>)>,
+    newer: Pending<Result<Scan, RootworkError>>,
+    /// Backfilling dropped the newest rows to stay under `max_entries`,
+    /// so streaming waits until `G` reloads the tail
+    detached: bool,
+    /// Fold consecutive identical messages into one "×N" row (`d`)
+    collapse: bool,
+    /// Volume strip above the list (`H`)
+    histogram: bool,
+    /// Bars that fit in the strip at the last render
+    histogram_width: Cell<usize>,
+    /// Unit the split view follows, on top of the `/` filter and preset,
+    /// which it leaves alone
+    split_unit: Option<String>,
     paused: bool,
     follow_mode: bool,
-    selected: usize,
+    /// Entries read while paused or scrolled up, shown by `G`
+    held: VecDeque<LogEntry>,
+    list: ListState,
+    timestamps: TimestampFormat,
+    /// Journal files read, cycled with `J`
+    source: JournalSource,
+    detail: Option<EntryDetail>,
+    denials: Option<DenialsPanel>,
+    /// Field matches and MESSAGE regex, typed after `/`
+    filter: Option<LogFilter>,
+    filter_input: Option<String>,
+    preset_menu: Option<PresetMenu>,
+    presets: Presets,
+    error: Option<RootworkError>,
+    messages: Messages,
 }
 
 impl LogsContext {
-    pub fn new() -> Self {
+    pub fn new(config: &Config, messages: &Messages, presets: &Presets) -> Self {
         let mut ctx = Self {
             entries: VecDeque::new(),
-            max_entries: 1000,
-            filter_unit: None,
+            folded: 0,
+            max_entries: max_entries_from_config(config),
+            page_size: page_size_from_config(config),
+            at_head: false,
+            head_cursor: None,
+            tail_cursor: None,
+            older: Pending::idle(),
+            newer: Pending::idle(),
+            detached: false,
+            collapse: collapse_from_config(config),
+            histogram: true,
+            histogram_width: Cell::new(60),
+            split_unit: None,
             paused: false,
             follow_mode: true,
-            selected: 0,
+            held: VecDeque::new(),
+            list: ListState::new(),
+            timestamps: TimestampFormat::from_config(config),
+            source: JournalSource::from_config(config),
+            detail: None,
+            denials: None,
+            filter: None,
+            filter_input: None,
+            preset_menu: None,
+            presets: presets.clone(),
+            error: None,
+            messages: messages.clone(),
         };
         ctx.load_entries();
         ctx
@@ -64,326 +430,1574 @@ impl LogsContext {
 
     fn load_entries(&mut self) {
         self.entries.clear();
-        self.selected = 0;
+        self.folded = 0;
+        self.at_head = false;
+        self.detached = false;
+        self.held.clear();
+        self.list.reset();
+        // Reads still running were for the old filter or rows
+        self.older = Pending::idle();
+        self.newer = Pending::idle();
 
-        let fresh = JournalReader::read_recent(self.filter_unit.as_deref(), 100);
-        for e in fresh {
+        let (scan, tail_cursor) = match JournalReader::read_recent(
+            self.source,
+            self.unit_scope(),
+            self.filter.as_ref(),
+            self.page_size,
+        ) {
+            Ok(read) => read,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        self.error = None;
+        self.tail_cursor = tail_cursor;
+        for e in scan.entries {
             self.add_entry(e);
         }
+        self.head_cursor = scan.cursor;
+        self.at_head = scan.ended;
 
         if self.follow_mode {
             self.scroll_to_bottom();
         }
     }
 
+    /// Follow `unit` for the split view, or show every unit's entries
+    /// again with None
+    pub fn set_unit(&mut self, unit: Option<&str>) {
+        if self.split_unit.as_deref() != unit {
+            self.split_unit = unit.map(str::to_string);
+            self.load_entries();
+        }
+    }
+
+    /// The unit whose entries are read
+    fn unit_scope(&self) -> Option<&str> {
+        self.split_unit.as_deref()
+    }
+
+    /// Start reading what came after the newest record looked at
     pub fn refresh(&mut self) {
-        if self.paused {
+        if self.detached || self.newer.is_pending() {
             return;
         }
 
-        let last_seen = self.entries.back().map(|e| e.timestamp_micros).unwrap_or(0);
-        let old_len = self.entries.len();
+        let last_seen = self
+            .held
+            .back()
+            .or_else(|| self.entries.back().map(LogEntry::last))
+            .map(|e| e.timestamp_micros)
+            .unwrap_or(0);
+        let source = self.source;
+        let unit = self.split_unit.clone();
+        let filter = self.filter.clone();
+        let cursor = self.tail_cursor.clone();
+        self.newer = Pending::spawn(move || {
+            JournalReader::read_since(
+                source,
+                unit.as_deref(),
+                filter.as_ref(),
+                cursor.as_deref(),
+                last_seen,
+            )
+        });
+    }
 
-        let fresh = JournalReader::read_since(self.filter_unit.as_deref(), last_seen);
+    /// Show, or hold back, what `refresh` read
+    fn take_newer(&mut self, result: Result<Scan, RootworkError>) {
+        if self.detached {
+            return;
+        }
+        let old_len = self.entries.len();
+        let scan = match result {
+            Ok(scan) => scan,
+            Err(e) => {
+                // Polled every tick, so only announce the transition
+                if self.error.is_none() {
+                    self.messages.error(e.to_string());
+                }
+                self.error = Some(e);
+                return;
+            }
+        };
+        self.error = None;
+        if scan.cursor.is_some() {
+            self.tail_cursor = scan.cursor;
+        }
+        let fresh = scan.entries;
+        if self.paused || !self.follow_mode {
+            // Hold them rather than move the rows being read
+            self.held.extend(fresh);
+            while self.held.len() > self.max_entries {
+                self.held.pop_front();
+            }
+            return;
+        }
         for e in fresh {
             self.add_entry(e);
         }
 
-        if self.follow_mode && !self.paused && self.entries.len() > old_len {
+        if self.entries.len() > old_len {
             self.scroll_to_bottom();
         }
     }
 
+    /// Follow the tail, showing what was held meanwhile unless paused, or
+    /// stop following and hold new entries back
+    fn set_follow(&mut self, on: bool) {
+        self.follow_mode = on;
+        if on {
+            self.flush_held();
+            self.scroll_to_bottom();
+        }
+    }
+
+    fn flush_held(&mut self) {
+        if self.paused {
+            return;
+        }
+        for e in std::mem::take(&mut self.held) {
+            self.add_entry(e);
+        }
+    }
+
+    /// Where backfill reads back from: the oldest record looked at, or
+    /// the first row
+    fn backfill_cursor(&self) -> Option<String> {
+        self.head_cursor
+            .clone()
+            .or_else(|| self.entries.front().and_then(|e| e.cursor.clone()))
+    }
+
+    /// Start reading a page of entries older than the first row
+    fn backfill(&mut self) {
+        if self.at_head || self.older.is_pending() {
+            return;
+        }
+        let Some(cursor) = self.backfill_cursor() else {
+            return;
+        };
+        let source = self.source;
+        let unit = self.split_unit.clone();
+        let filter = self.filter.clone();
+        let max = self.page_size;
+        self.older = Pending::spawn(move || {
+            let read =
+                JournalReader::read_before(source, unit.as_deref(), filter.as_ref(), &cursor, max);
+            (cursor, read)
+        });
+    }
+
+    /// Put what `backfill` read in front, keeping the selected row selected
+    fn take_older(&mut self, result: Result<Scan, RootworkError>) {
+        let scan = match result {
+            Ok(scan) => scan,
+            Err(e) => {
+                self.messages.error(e.to_string());
+                return;
+            }
+        };
+        self.at_head = scan.ended;
+        if scan.cursor.is_some() {
+            self.head_cursor = scan.cursor;
+        }
+        let older = scan.entries;
+        if older.is_empty() && scan.ended {
+            self.messages.info("Beginning of the journal");
+            return;
+        }
+        if let Some(scanned) = scan.scanned
+            && older.len() < self.page_size
+            && !scan.ended
+        {
+            self.messages.info(format!(
+                "Looked through {} entries, more may exist, press again",
+                scanned
+            ));
+        }
+        if older.is_empty() {
+            return;
+        }
+
+        let newer = std::mem::take(&mut self.entries);
+        self.folded = 0;
+        for e in older {
+            self.push_entry(e);
+        }
+        let added = self.entries.len();
+        // Rows already on screen stay as they were, only the seam's clock
+        // jump is worked out again
+        for (i, mut row) in newer.into_iter().enumerate() {
+            if i == 0 {
+                row.clock_jump = self
+                    .entries
+                    .back()
+                    .and_then(|prev| clock_jump(prev.last(), &row));
+            }
+            self.folded += row.duplicates.len();
+            self.entries.push_back(row);
+        }
+        self.follow_mode = false;
+        self.list
+            .select(self.list.selected + added, self.entries.len());
+        while self.entries.len() + self.folded > self.max_entries && self.entries.len() > 1 {
+            if let Some(dropped) = self.entries.pop_back() {
+                self.folded -= dropped.duplicates.len();
+                self.detached = true;
+            }
+        }
+        self.list.select(self.list.selected, self.entries.len());
+    }
+
+    /// Backfill once the cursor reaches the first row
+    fn backfill_at_top(&mut self) {
+        if self.list.selected == 0 {
+            self.backfill();
+        }
+    }
+
     fn add_entry(&mut self, entry: LogEntry) {
-        self.entries.push_back(entry);
-        if self.entries.len() > self.max_entries {
-            self.entries.pop_front();
-            if self.selected > 0 {
-                self.selected -= 1;
+        self.push_entry(entry);
+        while self.entries.len() + self.folded > self.max_entries && self.entries.len() > 1 {
+            if let Some(dropped) = self.entries.pop_front() {
+                self.folded -= dropped.duplicates.len();
+            }
+            let selected = self.list.selected.saturating_sub(1);
+            self.list.select(selected, self.entries.len());
+            self.at_head = false;
+            self.head_cursor = None;
+        }
+    }
+
+    /// Append `entry` as a row, or fold it into the last one
+    fn push_entry(&mut self, mut entry: LogEntry) {
+        if let Some(prev) = self.entries.back() {
+            entry.clock_jump = clock_jump(prev.last(), &entry);
+        }
+        match self.entries.back_mut() {
+            // A clock step is worth its own row
+            Some(prev)
+                if self.collapse && entry.clock_jump.is_none() && prev.is_repeated_by(&entry) =>
+            {
+                prev.duplicates.push(entry);
+                self.folded += 1;
+            }
+            _ => self.entries.push_back(entry),
+        }
+    }
+
+    /// `d`: fold repeats into "×N" rows, or show every entry
+    fn toggle_collapse(&mut self) {
+        self.collapse = !self.collapse;
+        let selected = self
+            .entries
+            .get(self.list.selected)
+            .map(|e| e.timestamp_micros);
+        let entries = std::mem::take(&mut self.entries);
+        self.folded = 0;
+        for mut entry in entries {
+            let duplicates = std::mem::take(&mut entry.duplicates);
+            for mut e in std::iter::once(entry).chain(duplicates) {
+                e.unfolded = false;
+                self.add_entry(e);
             }
         }
+        match selected {
+            Some(ts) if !self.follow_mode => {
+                let index = self
+                    .entries
+                    .iter()
+                    .position(|e| e.timestamp_micros >= ts)
+                    .unwrap_or(0);
+                self.list.select(index, self.entries.len());
+            }
+            _ => self.scroll_to_bottom(),
+        }
+        self.messages.info(if self.collapse {
+            "Collapsing repeated messages"
+        } else {
+            "Showing every repeated message"
+        });
+    }
+
+    /// `e`: spread the selected row's repeats back into the list
+    fn expand_selected(&mut self) {
+        let index = self.list.selected;
+        let Some(entry) = self.entries.get_mut(index) else {
+            return;
+        };
+        if entry.duplicates.is_empty() {
+            return;
+        }
+        entry.unfolded = true;
+        let duplicates = std::mem::take(&mut entry.duplicates);
+        self.folded -= duplicates.len();
+        for (offset, mut duplicate) in duplicates.into_iter().enumerate() {
+            duplicate.unfolded = true;
+            self.entries.insert(index + 1 + offset, duplicate);
+        }
     }
 
     fn move_up(&mut self) {
-        if self.selected > 0 {
-            self.selected -= 1;
+        if self.list.selected > 0 {
+            self.list.up(self.entries.len());
             self.follow_mode = false;
         }
+        self.backfill_at_top();
     }
 
     fn move_down(&mut self) {
-        if self.selected + 1 < self.entries.len() {
-            self.selected += 1;
-            if self.selected == self.entries.len() - 1 {
-                self.follow_mode = true;
-            }
+        self.list.down(self.entries.len());
+        if self.list.is_at_bottom(self.entries.len()) && self.held.is_empty() {
+            self.follow_mode = true;
         }
     }
 
     fn page_up(&mut self) {
-        self.selected = self.selected.saturating_sub(10);
+        self.list.page_up(self.entries.len());
         self.follow_mode = false;
+        self.backfill_at_top();
     }
 
     fn page_down(&mut self) {
-        self.selected = (self.selected + 10).min(self.entries.len().saturating_sub(1));
-        if self.selected == self.entries.len().saturating_sub(1) {
+        self.list.page_down(self.entries.len());
+        if self.list.is_at_bottom(self.entries.len()) && self.held.is_empty() {
             self.follow_mode = true;
         }
     }
 
     fn scroll_to_bottom(&mut self) {
-        if !self.entries.is_empty() {
-            self.selected = self.entries.len() - 1;
+        self.list.bottom(self.entries.len());
+    }
+
+    /// Move to the next (or previous) entry at priority err or worse
+    fn jump_to_error(&mut self, forward: bool) {
+        let selected = self.list.selected;
+        let is_error = |i: &usize| self.entries[*i].priority <= 3;
+        let found = if forward {
+            (selected + 1..self.entries.len()).find(is_error)
+        } else {
+            (0..selected).rev().find(is_error)
+        };
+        match found {
+            Some(i) => {
+                self.list.select(i, self.entries.len());
+                self.follow_mode =
+                    self.list.is_at_bottom(self.entries.len()) && self.held.is_empty();
+            }
+            None => self.messages.info(if forward {
+                "No more errors below"
+            } else {
+                "No more errors above"
+            }),
         }
     }
 
+    /// Every loaded record, folded repeats included
+    fn records(&self) -> impl Iterator<Item = &LogEntry> + Clone {
+        self.entries
+            .iter()
+            .flat_map(|e| std::iter::once(e).chain(&e.duplicates))
+    }
+
+    fn histogram(&self) -> Option<Histogram> {
+        Histogram::new(self.records(), self.histogram_width.get())
+    }
+
+    /// `]` / `[`: move to the first entry of the next (or previous) bar of
+    /// the volume strip that has any
+    fn jump_to_bucket(&mut self, forward: bool) {
+        let Some(histogram) = self.histogram() else {
+            return;
+        };
+        let selected = self.list.selected.min(self.entries.len().saturating_sub(1));
+        let bucket = |i: usize| histogram.bucket_of(self.entries[i].timestamp_micros);
+        let current = bucket(selected);
+        let found = if forward {
+            (selected + 1..self.entries.len()).find(|&i| bucket(i) > current)
+        } else {
+            (0..selected)
+                .rev()
+                .map(bucket)
+                .find(|&b| b < current)
+                .and_then(|target| (0..selected).find(|&i| bucket(i) == target))
+        };
+        match found {
+            Some(i) => {
+                self.list.select(i, self.entries.len());
+                self.follow_mode =
+                    self.list.is_at_bottom(self.entries.len()) && self.held.is_empty();
+            }
+            None => self.messages.info(if forward {
+                "Already at the last bar"
+            } else {
+                "Already at the first bar"
+            }),
+        }
+    }
+
+    /// Errors (priority 0-3) and warnings among the rows on screen
+    fn counts_in_view(&self) -> (usize, usize) {
+        let visible = self.list.visible(self.entries.len());
+        self.entries
+            .range(visible)
+            .fold((0, 0), |(err, warn), e| match e.priority {
+                0..=3 => (err + 1, warn),
+                4 => (err, warn + 1),
+                _ => (err, warn),
+            })
+    }
+
     fn toggle_pause(&mut self) {
         self.paused = !self.paused;
+        if !self.paused && self.follow_mode {
+            self.set_follow(true);
+        }
     }
 
     fn toggle_follow(&mut self) {
-        self.follow_mode = !self.follow_mode;
-        if self.follow_mode {
-            self.scroll_to_bottom();
-        }
+        self.set_follow(!self.follow_mode);
+    }
+
+    fn cycle_source(&mut self) {
+        self.source = self.source.next();
+        self.messages
+            .info(format!("Journal: {}", self.source.label()));
+        self.load_entries();
+    }
+
+    fn cycle_timestamps(&mut self) {
+        self.timestamps = self.timestamps.next();
+        self.messages
+            .info(format!("Timestamps: {}", self.timestamps.label()));
     }
 
     fn clear(&mut self) {
         self.entries.clear();
-        self.selected = 0;
+        self.folded = 0;
+        self.held.clear();
+        self.detached = false;
+        self.list.reset();
+        // A page still being read back would refill what was just cleared
+        self.older = Pending::idle();
+    }
+
+    fn open_detail(&mut self) {
+        let Some(entry) = self.entries.get(self.list.selected) else {
+            return;
+        };
+        let Some(cursor) = entry.cursor.as_deref() else {
+            self.messages.warn("This entry has no journal cursor");
+            return;
+        };
+        match JournalReader::read_fields(self.source, cursor) {
+            Ok(fields) => {
+                self.detail = Some(EntryDetail {
+                    fields,
+                    message: entry.message.clone(),
+                    list: ListState::new(),
+                });
+            }
+            Err(e) => self.messages.error(e.to_string()),
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.filter_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.filter_input = None,
+            KeyCode::Enter if input.trim().is_empty() => {
+                self.filter_input = None;
+                if self.filter.take().is_some() {
+                    self.load_entries();
+                }
+            }
+            KeyCode::Enter => match LogFilter::parse(input) {
+                Ok(filter) => {
+                    self.filter_input = None;
+                    self.filter = Some(filter);
+                    self.load_entries();
+                }
+                // Leave the input open to fix it
+                Err(e) => self.messages.error(e),
+            },
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_preset_menu_key(&mut self, key: KeyEvent) {
+        let Some(menu) = self.preset_menu.as_mut() else {
+            return;
+        };
+        match menu.handle_key(key) {
+            PresetAction::None => {}
+            PresetAction::Close => self.preset_menu = None,
+            PresetAction::Apply(preset) => {
+                self.preset_menu = None;
+                let name = preset.name.clone();
+                match LogFilter::from_preset(preset) {
+                    Ok(filter) => {
+                        self.filter = Some(filter);
+                        self.messages.info(format!("Preset: {}", name));
+                        self.load_entries();
+                    }
+                    Err(e) => self.messages.error(format!("Preset {}: {}", name, e)),
+                }
+            }
+            PresetAction::Save(name) => {
+                self.preset_menu = None;
+                self.save_preset(name);
+            }
+        }
+    }
+
+    /// Save the applied preset under a new name, or the unit and `/`
+    /// filter shown
+    fn save_preset(&mut self, name: String) {
+        let mut preset = match self.filter.as_ref() {
+            Some(LogFilter {
+                preset: Some(preset),
+                ..
+            }) => preset.clone(),
+            filter => Preset {
+                journal: filter.map(|f| f.text.clone()),
+                ..Default::default()
+            },
+        };
+        if let Some(unit) = &self.split_unit {
+            preset.units = vec![unit.clone()];
+        }
+        preset.name = name;
+        if preset.units.is_empty()
+            && preset.priority.is_none()
+            && preset.text.is_none()
+            && preset.journal.is_none()
+        {
+            self.messages
+                .warn("Nothing to save: filter with / or pick a unit first");
+            return;
+        }
+        match self.presets.save(preset.clone()) {
+            Ok(()) => {
+                self.messages.info(format!("Saved preset {}", preset.name));
+                // With the split's unit in it, the preset would outlive the
+                // split as this tab's filter
+                if self.split_unit.is_none()
+                    && let Ok(filter) = LogFilter::from_preset(preset)
+                {
+                    self.filter = Some(filter);
+                }
+            }
+            Err(e) => self.messages.error(format!("Failed to save preset: {}", e)),
+        }
+    }
+
+    fn handle_denials_key(&mut self, key: KeyEvent) {
+        let Some(panel) = self.denials.as_mut() else {
+            return;
+        };
+        let len = panel.sources.len();
+        match key.code {
+            KeyCode::Esc => self.denials = None,
+            KeyCode::Char('j') | KeyCode::Down => panel.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => panel.list.up(len),
+            KeyCode::Char('g') => panel.list.top(len),
+            KeyCode::Char('G') => panel.list.bottom(len),
+            KeyCode::Char('r') => panel.reload(),
+            KeyCode::Char('y') => {
+                if let Some(text) = panel.rules_text() {
+                    match copy_to_clipboard(&text) {
+                        Ok(()) => self.messages.info("Copied the rules to clipboard"),
+                        Err(e) => self.messages.error(format!("Copy failed: {}", e)),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_detail_key(&mut self, key: KeyEvent) {
+        let Some(detail) = self.detail.as_mut() else {
+            return;
+        };
+        let len = detail.fields.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => self.detail = None,
+            KeyCode::Char('j') | KeyCode::Down => detail.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => detail.list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => detail.list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => detail.list.page_up(len),
+            KeyCode::Char('g') => detail.list.top(len),
+            KeyCode::Char('G') => detail.list.bottom(len),
+            KeyCode::Char('y') => match copy_to_clipboard(&detail.message) {
+                Ok(()) => self.messages.info("Copied message to clipboard"),
+                Err(e) => self.messages.error(format!("Copy failed: {}", e)),
+            },
+            _ => {}
+        }
     }
 }
 
+/// One bar per bucket, its height the entry count and its color the
+/// most severe priority in it; the selected entry's bar is reversed
+fn draw_histogram(ctx: &LogsContext, f: &mut Frame, area: Rect) {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    ctx.histogram_width
+        .set(usize::from(area.width.saturating_sub(2)));
+    let Some(histogram) = ctx.histogram() else {
+        return;
+    };
+    let selected = ctx
+        .entries
+        .get(ctx.list.selected)
+        .map(|e| histogram.bucket_of(e.timestamp_micros));
+    let max = histogram
+        .buckets
+        .iter()
+        .map(|(count, _)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bars: Vec<Span> = histogram
+        .buckets
+        .iter()
+        .enumerate()
+        .map(|(i, &(count, priority))| {
+            let mut style = if count == 0 {
+                Style::default().fg(crate::palette::dark_gray())
+            } else {
+                crate::palette::log_priority(priority)
+            };
+            if selected == Some(i) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let bar = if count == 0 {
+                ' '
+            } else {
+                BARS[(count * BARS.len()).div_ceil(max) - 1]
+            };
+            Span::styled(bar.to_string(), style)
+        })
+        .collect();
+
+    let end_usec = histogram.start_usec + histogram.bucket_usec * histogram.buckets.len() as u64;
+    let minutes = histogram.bucket_usec / MINUTE_USEC;
+    let block = Block::default()
+        .title(format!(
+            " {}–{}, {} per bar, max {}  [ / ] jump ",
+            clock_minute(histogram.start_usec),
+            clock_minute(end_usec),
+            if minutes == 1 {
+                "1 minute".to_string()
+            } else {
+                format!("{} minutes", minutes)
+            },
+            max
+        ))
+        .borders(Borders::ALL);
+    f.render_widget(Paragraph::new(Line::from(bars)).block(block), area);
+}
+
+/// "14:05" in local time
+fn clock_minute(usec: u64) -> String {
+    local_time(usec, "%H:%M")
+}
+
+/// "Oct 18 14:05:09" in local time
+fn clock_time(usec: u64) -> String {
+    local_time(usec, "%b %d %H:%M:%S")
+}
+
+fn local_time(usec: u64, format: &str) -> String {
+    chrono::DateTime::from_timestamp_micros(usec as i64)
+        .map(|dt| {
+            let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
+            local.format(format).to_string()
+        })
+        .unwrap_or_default()
+}
+
 impl Context for LogsContext {
     fn name(&self) -> &'static str {
         "Logs"
     }
 
-    fn draw(&self, f: &mut Frame, area: Rect) {
+    fn draw(&self, f: &mut Frame, full: Rect) {
+        let area = if self.histogram && !self.entries.is_empty() && self.error.is_none() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(full);
+            draw_histogram(self, f, chunks[0]);
+            chunks[1]
+        } else {
+            full
+        };
+
         let block = Block::default()
             .title(format!(
-                " Journal Logs {}{}{} ",
+                " Journal Logs {}{}{}{}{}{}{}{}{}",
+                match self.source {
+                    JournalSource::All => String::new(),
+                    source => format!("[{}] ", source.label()),
+                },
                 if self.paused { "[PAUSED] " } else { "" },
+                if self.detached {
+                    "[history, G for live] "
+                } else {
+                    ""
+                },
                 if self.follow_mode { "[follow] " } else { "" },
-                self.filter_unit
-                    .as_ref()
+                match self.held.len() {
+                    0 => String::new(),
+                    1 => "[1 new entry, G] ".to_string(),
+                    n => format!("[{} new entries, G] ", n),
+                },
+                self.unit_scope()
                     .map(|u| format!("[{}] ", u))
-                    .unwrap_or_default()
+                    .unwrap_or_default(),
+                match (&self.filter_input, &self.filter) {
+                    (Some(input), _) => format!("filter: {}_ ", input),
+                    (None, Some(filter)) => format!("[{}] ", filter.text),
+                    (None, None) => String::new(),
+                },
+                match self.counts_in_view() {
+                    (0, 0) => String::new(),
+                    (err, warn) => format!("─ {} err / {} warn in view ", err, warn),
+                },
+                match self.older.spinner() {
+                    "" => String::new(),
+                    frame => format!("[older {}] ", frame),
+                }
             ))
             .borders(Borders::ALL);
 
-        let visible_lines = area.height.saturating_sub(2) as usize;
-        if visible_lines == 0 {
-            f.render_widget(Paragraph::new("").block(block), area);
+        if let Some(ref error) = self.error {
+            f.render_widget(Paragraph::new(error.lines()).block(block), area);
             return;
         }
 
-        let scroll_offset = if self.entries.len() <= visible_lines {
-            0
-        } else if self.selected >= self.entries.len().saturating_sub(visible_lines) {
-            self.entries.len().saturating_sub(visible_lines)
-        } else {
-            self.selected
-        };
+        if self.entries.is_empty() {
+            f.render_widget(Paragraph::new("No log entries").block(block), area);
+            return;
+        }
 
-        let lines: Vec<Line> = self
-            .entries
-            .iter()
-            .skip(scroll_offset)
-            .take(visible_lines)
-            .enumerate()
-            .map(|(i, entry)| {
-                let actual_idx = scroll_offset + i;
-                let is_selected = actual_idx == self.selected;
-                let bg_style = if is_selected {
-                    Style::default().bg(crate::palette::dark_gray())
-                } else {
+        let now_usec = timestamp::now_usec();
+        let time_width = self.timestamps.width();
+        let list = SelectableList::new(&self.list, self.entries.len(), |i| {
+            let entry = &self.entries[i];
+            let priority_style = crate::palette::log_priority(entry.priority);
+
+            let jump = entry.clock_jump.map(|delta| {
+                Span::styled(
+                    format!("[clock {}] ", format_jump(delta)),
                     Style::default()
-                };
+                        .fg(crate::palette::black())
+                        .bg(crate::palette::yellow())
+                        .add_modifier(Modifier::BOLD),
+                )
+            });
 
-                let priority_color = match entry.priority {
-                    0..=2 => crate::palette::red(),
-                    3 => crate::palette::light_red(),
-                    4 => crate::palette::yellow(),
-                    5 => crate::palette::green(),
-                    6 => crate::palette::blue(),
-                    _ => crate::palette::gray(),
-                };
+            let mut spans = vec![
+                Span::styled(
+                    format!(
+                        "{:width$} ",
+                        self.timestamps.format(
+                            entry.timestamp_micros,
+                            entry.monotonic.map(|(usec, _)| usec),
+                            now_usec
+                        ),
+                        width = time_width
+                    ),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                Span::styled(
+                    entry.unit_column.as_str(),
+                    Style::default().fg(crate::palette::cyan()),
+                ),
+            ];
+            spans.extend(jump);
+            if !entry.duplicates.is_empty() {
+                spans.push(Span::styled(
+                    format!("×{} ", entry.duplicates.len() + 1),
+                    Style::default()
+                        .fg(crate::palette::yellow())
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            spans.push(Span::styled(
+                &entry.message[..entry.message_shown],
+                priority_style,
+            ));
+            if entry.message_shown < entry.message.len() {
+                spans.push(Span::styled("...", priority_style));
+            }
+            Line::from(spans)
+        })
+        .block(block);
 
-                let msg = if entry.message.len() > 200 {
-                    format!("{}...", &entry.message[..200])
-                } else {
-                    entry.message.clone()
-                };
+        f.render_widget(list, area);
 
-                Line::from(vec![
-                    Span::styled(
-                        format!("{:15} ", entry.display_time),
-                        Style::default().fg(crate::palette::gray()),
-                    ),
-                    Span::styled(
-                        format!("{:20} ", &entry.unit[..entry.unit.len().min(20)]),
-                        Style::default().fg(crate::palette::cyan()),
-                    ),
-                    Span::styled(msg, Style::default().fg(priority_color)),
-                ])
-                .style(bg_style)
-            })
-            .collect();
+        if let Some(panel) = &self.denials {
+            draw_denials(panel, f, full);
+        }
 
-        if lines.is_empty() {
-            f.render_widget(Paragraph::new("No log entries").block(block), area);
-        } else {
-            f.render_widget(Paragraph::new(lines).block(block), area);
+        if let Some(detail) = &self.detail {
+            draw_detail(detail, f, full);
+        }
+
+        if let Some(menu) = &self.preset_menu {
+            menu.draw(f, full);
         }
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
+        if self.detail.is_some() {
+            self.handle_detail_key(key);
+            return;
+        }
+        if self.denials.is_some() {
+            self.handle_denials_key(key);
+            return;
+        }
+        if self.filter_input.is_some() {
+            self.handle_filter_key(key);
+            return;
+        }
+        if self.preset_menu.is_some() {
+            self.handle_preset_menu_key(key);
+            return;
+        }
+
         match key.code {
+            KeyCode::Enter => self.open_detail(),
             KeyCode::Char('j') | KeyCode::Down => self.move_down(),
             KeyCode::Char('k') | KeyCode::Up => self.move_up(),
             KeyCode::Char(' ') | KeyCode::PageDown => self.page_down(),
             KeyCode::Char('b') | KeyCode::PageUp => self.page_up(),
             KeyCode::Char('G') => {
-                self.scroll_to_bottom();
-                self.follow_mode = true;
+                self.paused = false;
+                if self.detached {
+                    self.follow_mode = true;
+                    self.load_entries();
+                } else {
+                    self.set_follow(true);
+                }
             }
             KeyCode::Char('g') => {
-                self.selected = 0;
+                self.list.top(self.entries.len());
                 self.follow_mode = false;
+                self.backfill_at_top();
             }
             KeyCode::Char('p') => self.toggle_pause(),
             KeyCode::Char('f') => self.toggle_follow(),
             KeyCode::Char('c') => self.clear(),
+            KeyCode::Char('t') => self.cycle_timestamps(),
+            KeyCode::Char('J') => self.cycle_source(),
+            KeyCode::Char('n') => self.jump_to_error(true),
+            KeyCode::Char('N') => self.jump_to_error(false),
             KeyCode::Char('r') => self.load_entries(),
+            KeyCode::Char('d') => self.toggle_collapse(),
+            KeyCode::Char('/') => {
+                let text = self.filter.as_ref().map(|f| match &f.preset {
+                    Some(preset) => preset.journal.clone().unwrap_or_default(),
+                    None => f.text.clone(),
+                });
+                self.filter_input = Some(text.unwrap_or_default());
+            }
+            KeyCode::Char('P') => self.preset_menu = Some(PresetMenu::new(&self.presets)),
+            KeyCode::Esc if self.filter.is_some() => {
+                self.filter = None;
+                self.load_entries();
+            }
+            KeyCode::Char('e') => self.expand_selected(),
+            KeyCode::Char(']') => self.jump_to_bucket(true),
+            KeyCode::Char('[') => self.jump_to_bucket(false),
+            KeyCode::Char('H') => self.histogram = !self.histogram,
+            KeyCode::Char('A') => self.denials = Some(DenialsPanel::new()),
             _ => {}
         }
     }
 
     async fn tick(&mut self) {
+        if let Some(result) = self.newer.poll() {
+            self.take_newer(result);
+        }
+        // Rows dropped from the front meanwhile leave a gap before them
+        if let Some((from, result)) = self.older.poll()
+            && self.backfill_cursor().as_deref() == Some(from.as_str())
+        {
+            self.take_older(result);
+        }
+        if let Some(panel) = self.denials.as_mut()
+            && let Some(sources) = panel.pending.poll()
+        {
+            panel.sources = sources;
+            panel.loaded = true;
+            panel.list.clamp(panel.sources.len());
+        }
+    }
+
+    fn captures_input(&self) -> bool {
+        self.filter_input.is_some() || self.preset_menu.is_some()
+    }
+
+    fn auto_refresh(&mut self) {
         self.refresh();
     }
 }
 
+fn draw_denials(panel: &DenialsPanel, f: &mut Frame, area: Rect) {
+    let popup = centered_rect(90, 80, area);
+    f.render_widget(Clear, popup);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Min(0)])
+        .split(popup);
+
+    let block = Block::default()
+        .title(
+            panel
+                .pending
+                .title("SELinux / AppArmor denials, last 24h  r=reload  Esc=close"),
+        )
+        .borders(Borders::ALL);
+    if panel.sources.is_empty() {
+        let text = if panel.loaded {
+            "No denials in the journal"
+        } else {
+            "Reading the journal..."
+        };
+        f.render_widget(Paragraph::new(text).block(block), chunks[0]);
+    } else {
+        let table = SelectableTable::new(
+            &panel.list,
+            panel.sources.len(),
+            [
+                Constraint::Min(30),
+                Constraint::Length(9),
+                Constraint::Length(15),
+            ],
+            |i| {
+                let source = &panel.sources[i];
+                Row::new(vec![
+                    Span::styled(
+                        source.name.as_str(),
+                        Style::default().fg(crate::palette::cyan()),
+                    ),
+                    Span::raw(format!("{:>8}", source.count)),
+                    Span::styled(
+                        clock_time(source.last_usec),
+                        Style::default().fg(crate::palette::gray()),
+                    ),
+                ])
+            },
+        )
+        .header(Row::new(vec!["Source", "  Denials", "Last"]))
+        .block(block);
+        f.render_widget(table, chunks[0]);
+    }
+
+    let mut lines = Vec::new();
+    if let Some(source) = panel.sources.get(panel.list.selected) {
+        for rule in &source.rules {
+            let mut head = vec![
+                Span::raw(format!("×{:<5} ", rule.count)),
+                Span::styled(
+                    rule.rule.as_str(),
+                    Style::default()
+                        .fg(crate::palette::yellow())
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ];
+            if rule.permissive {
+                head.push(Span::styled(
+                    "  (permissive, not enforced)",
+                    Style::default().fg(crate::palette::gray()),
+                ));
+            }
+            lines.push(Line::from(head));
+            lines.push(Line::styled(
+                format!("       {}", rule.context),
+                Style::default().fg(crate::palette::gray()),
+            ));
+        }
+    }
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(" Rules that would allow them  y=copy ")
+                .borders(Borders::ALL),
+        ),
+        chunks[1],
+    );
+}
+
+fn draw_detail(detail: &EntryDetail, f: &mut Frame, area: Rect) {
+    let popup = centered_rect(90, 80, area);
+    f.render_widget(Clear, popup);
+
+    let field_style = Style::default().fg(crate::palette::cyan());
+    let trusted_style = Style::default().fg(crate::palette::gray());
+    let table = SelectableTable::new(
+        &detail.list,
+        detail.fields.len(),
+        [Constraint::Length(28), Constraint::Min(20)],
+        |i| {
+            let (name, value) = &detail.fields[i];
+            // Fields starting with '_' are added by journald, not the sender
+            let style = if name.starts_with('_') {
+                trusted_style
+            } else {
+                field_style
+            };
+            Row::new(vec![
+                Span::styled(name.as_str(), style),
+                Span::raw(value.as_str()),
+            ])
+        },
+    )
+    .block(
+        Block::default()
+            .title(format!(
+                " Journal Entry [{} fields]  y=copy message  q=close ",
+                detail.fields.len()
+            ))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(table, popup);
+}
+
+/// Wall clock step between two consecutive entries: how much further the
+/// realtime clock moved than the monotonic one. Only meaningful within a boot.
+fn clock_jump(prev: &LogEntry, next: &LogEntry) -> Option<i64> {
+    let (prev_mono, prev_boot) = prev.monotonic?;
+    let (next_mono, next_boot) = next.monotonic?;
+    if prev_boot != next_boot {
+        return None;
+    }
+    let realtime = next.timestamp_micros as i64 - prev.timestamp_micros as i64;
+    let monotonic = next_mono as i64 - prev_mono as i64;
+    let delta = realtime - monotonic;
+    (delta.abs() > CLOCK_JUMP_USEC).then_some(delta)
+}
+
+/// e.g. "+37s" or "-1h02m"
+fn format_jump(delta_usec: i64) -> String {
+    let sign = if delta_usec < 0 { '-' } else { '+' };
+    let secs = delta_usec.unsigned_abs() / 1_000_000;
+    if secs >= 3600 {
+        format!("{}{}h{:02}m", sign, secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}{}m{:02}s", sign, secs / 60, secs % 60)
+    } else {
+        format!("{}{}s", sign, secs)
+    }
+}
+
+/// The report's Recent errors section: the last `max` entries at err or
+/// worse in a code block, one line each
+pub fn write_error_report(out: &mut String, max: usize) {
+    out.push_str("## Recent errors\n\n");
+    match JournalReader::read_errors(JournalSource::default(), max) {
+        Ok(entries) if entries.is_empty() => out.push_str("No errors in the journal.\n"),
+        Ok(entries) => {
+            out.push_str("```\n");
+            for entry in entries {
+                let time = chrono::DateTime::from_timestamp_micros(entry.timestamp_micros as i64)
+                    .map(|dt| {
+                        dt.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "?".to_string());
+                // A multi-line message would run on unprefixed, or close
+                // the block with a line of backticks
+                let message = entry.message.trim_end().replace('\n', " ⏎ ");
+                out.push_str(&format!("{} {}: {}\n", time, entry.unit, message));
+            }
+            out.push_str("```\n");
+        }
+        Err(e) => out.push_str(&format!("Unavailable: {}\n", e)),
+    }
+    out.push('\n');
+}
+
+/// Entries a read kept, and how far it looked
+struct Scan {
+    entries: Vec<LogEntry>,
+    /// The last record looked at, kept or not, to carry on from
+    cursor: Option<String>,
+    /// Ran out of journal, rather than stopping at the page size or at
+    /// `REGEX_SCAN_MAX`
+    ended: bool,
+    /// Records looked through, set only when the filter drops entries here
+    /// and so the read could give up short of a page
+    scanned: Option<usize>,
+}
+
 struct JournalReader;
 
 impl JournalReader {
-    fn read_recent(unit: Option<&str>, max: usize) -> Vec<LogEntry> {
+    /// Entries at priority err (3) or worse, oldest first
+    fn read_errors(source: JournalSource, max: usize) -> Result<Vec<LogEntry>, RootworkError> {
         let mut out = Vec::new();
         unsafe {
             let mut j: *mut c_void = std::ptr::null_mut();
-            if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null()
-            {
-                return out;
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, source.flags());
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
             }
 
-            if let Some(u) = unit {
-                let m = format!("_SYSTEMD_UNIT={u}");
+            for priority in 0..=3 {
+                let m = format!("PRIORITY={priority}");
                 let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
             }
 
             let _ = sd_journal_seek_tail(j);
-            for _ in 0..max {
+            while out.len() < max && sd_journal_previous(j) > 0 {
+                if let Some(e) = read_current_entry(j) {
+                    out.push(e);
+                }
+            }
+            sd_journal_close(j);
+        }
+        out.reverse();
+        Ok(out)
+    }
+
+    /// Up to `max` entries right before the one at `cursor`, oldest first
+    fn read_before(
+        source: JournalSource,
+        unit: Option<&str>,
+        filter: Option<&LogFilter>,
+        cursor: &str,
+        max: usize,
+    ) -> Result<Scan, RootworkError> {
+        let cursor_c = CString::new(cursor)
+            .map_err(|_| RootworkError::Journal("Invalid journal cursor".to_string()))?;
+        let filtered = filter.is_some_and(LogFilter::filters_here);
+        let mut out = Vec::new();
+        let mut ended = false;
+        let mut scanned = 0;
+        let last;
+        unsafe {
+            let mut j: *mut c_void = std::ptr::null_mut();
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, source.flags());
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
+            }
+
+            add_matches(j, unit, filter);
+
+            let rc = sd_journal_seek_cursor(j, cursor_c.as_ptr());
+            if rc < 0 {
+                sd_journal_close(j);
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to seek the journal",
+                    rc,
+                ));
+            }
+            let scan = if filtered { REGEX_SCAN_MAX } else { max + 1 };
+            for _ in 0..scan {
+                if out.len() >= max {
+                    break;
+                }
                 if sd_journal_previous(j) <= 0 {
+                    ended = true;
                     break;
                 }
-                if let Some(e) = read_current_entry(j) {
+                // The first step back lands on the cursor's own entry
+                if sd_journal_test_cursor(j, cursor_c.as_ptr()) > 0 {
+                    continue;
+                }
+                scanned += 1;
+                if let Some(e) = read_current_entry(j)
+                    && filter.is_none_or(|f| f.keeps(&e))
+                {
                     out.push(e);
                 }
             }
+            // Stepping past either end leaves the journal where it was
+            last = get_cursor(j);
             sd_journal_close(j);
         }
         out.reverse();
-        out
+        Ok(Scan {
+            entries: out,
+            cursor: last,
+            ended,
+            scanned: filtered.then_some(scanned),
+        })
     }
 
-    fn read_since(unit: Option<&str>, since_micros: u64) -> Vec<LogEntry> {
+    /// The last `max` entries, oldest first, and the cursor of the newest
+    /// record looked at
+    fn read_recent(
+        source: JournalSource,
+        unit: Option<&str>,
+        filter: Option<&LogFilter>,
+        max: usize,
+    ) -> Result<(Scan, Option<String>), RootworkError> {
+        let filtered = filter.is_some_and(LogFilter::filters_here);
         let mut out = Vec::new();
+        let mut ended = false;
+        let mut scanned = 0;
+        let mut tail = None;
+        let last;
         unsafe {
             let mut j: *mut c_void = std::ptr::null_mut();
-            if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null()
-            {
-                return out;
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, source.flags());
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
             }
 
-            if let Some(u) = unit {
-                let m = format!("_SYSTEMD_UNIT={u}");
-                let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
-            }
+            add_matches(j, unit, filter);
 
-            let _ = sd_journal_seek_realtime_usec(j, since_micros.saturating_add(1));
-            loop {
-                if sd_journal_next(j) <= 0 {
+            let _ = sd_journal_seek_tail(j);
+            let scan = if filtered { REGEX_SCAN_MAX } else { max };
+            for _ in 0..scan {
+                if out.len() >= max {
+                    break;
+                }
+                if sd_journal_previous(j) <= 0 {
+                    ended = true;
                     break;
                 }
+                scanned += 1;
+                if tail.is_none() {
+                    tail = get_cursor(j);
+                }
                 if let Some(e) = read_current_entry(j)
-                    && e.timestamp_micros > since_micros
+                    && filter.is_none_or(|f| f.keeps(&e))
                 {
                     out.push(e);
                 }
+            }
+            last = get_cursor(j);
+            sd_journal_close(j);
+        }
+        out.reverse();
+        let scan = Scan {
+            entries: out,
+            cursor: last,
+            ended,
+            scanned: filtered.then_some(scanned),
+        };
+        Ok((scan, tail))
+    }
+
+    /// Entries after the record at `cursor`, or after `since_micros`
+    /// without one, looking through at most `REGEX_SCAN_MAX` records so a
+    /// regex filter matching nothing can't stall; the next call carries
+    /// on from the returned cursor
+    fn read_since(
+        source: JournalSource,
+        unit: Option<&str>,
+        filter: Option<&LogFilter>,
+        cursor: Option<&str>,
+        since_micros: u64,
+    ) -> Result<Scan, RootworkError> {
+        let cursor_c = cursor
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| RootworkError::Journal("Invalid journal cursor".to_string()))?;
+        let filtered = filter.is_some_and(LogFilter::filters_here);
+        let mut out = Vec::new();
+        let mut ended = false;
+        let mut scanned = 0;
+        let mut moved = false;
+        let mut last = None;
+        unsafe {
+            let mut j: *mut c_void = std::ptr::null_mut();
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, source.flags());
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
+            }
+
+            add_matches(j, unit, filter);
+
+            match &cursor_c {
+                Some(cursor) => {
+                    let _ = sd_journal_seek_cursor(j, cursor.as_ptr());
+                }
+                None => {
+                    let _ = sd_journal_seek_realtime_usec(j, since_micros.saturating_add(1));
+                }
+            }
+            for _ in 0..REGEX_SCAN_MAX {
                 if out.len() >= 500 {
                     break;
                 }
+                if sd_journal_next(j) <= 0 {
+                    ended = true;
+                    break;
+                }
+                moved = true;
+                // The first step lands on the cursor's own entry
+                if let Some(cursor) = &cursor_c
+                    && sd_journal_test_cursor(j, cursor.as_ptr()) > 0
+                {
+                    continue;
+                }
+                scanned += 1;
+                if let Some(e) = read_current_entry(j)
+                    && (cursor_c.is_some() || e.timestamp_micros > since_micros)
+                    && filter.is_none_or(|f| f.keeps(&e))
+                {
+                    out.push(e);
+                }
+            }
+            if moved {
+                last = get_cursor(j);
             }
 
             sd_journal_close(j);
         }
-        out
+        Ok(Scan {
+            entries: out,
+            cursor: last,
+            ended,
+            scanned: filtered.then_some(scanned),
+        })
+    }
+
+    /// All fields of the record at `cursor`, the sender's own first and
+    /// journald's trusted `_` fields after, each group sorted by name
+    fn read_fields(
+        source: JournalSource,
+        cursor: &str,
+    ) -> Result<Vec<(String, String)>, RootworkError> {
+        let cursor_c = CString::new(cursor)
+            .map_err(|_| RootworkError::Journal("Invalid journal cursor".to_string()))?;
+        let mut fields = Vec::new();
+        unsafe {
+            let mut j: *mut c_void = std::ptr::null_mut();
+            let rc = sd_journal_open(&mut j as *mut *mut c_void, source.flags());
+            if rc < 0 || j.is_null() {
+                return Err(RootworkError::from_journal_errno(
+                    "Failed to open journal",
+                    rc,
+                ));
+            }
+
+            let found = sd_journal_seek_cursor(j, cursor_c.as_ptr()) >= 0
+                && sd_journal_next(j) > 0
+                && sd_journal_test_cursor(j, cursor_c.as_ptr()) > 0;
+            if !found {
+                sd_journal_close(j);
+                return Err(RootworkError::Journal(
+                    "Entry is no longer in the journal".to_string(),
+                ));
+            }
+
+            sd_journal_restart_data(j);
+            let mut data: *const u8 = std::ptr::null();
+            let mut len: usize = 0;
+            while sd_journal_enumerate_data(j, &mut data, &mut len) > 0 {
+                let bytes = std::slice::from_raw_parts(data, len);
+                let Some(eq) = bytes.iter().position(|b| *b == b'=') else {
+                    continue;
+                };
+                let name = String::from_utf8_lossy(&bytes[..eq]).into_owned();
+                let value = &bytes[eq + 1..];
+                let value = match std::str::from_utf8(value) {
+                    Ok(text) if !text.chars().any(|c| c.is_control() && c != '\t') => {
+                        text.to_string()
+                    }
+                    _ => format!("[{} bytes of binary data]", value.len()),
+                };
+                fields.push((name, value));
+            }
+            sd_journal_close(j);
+        }
+        fields.sort_by(|a, b| (a.0.starts_with('_'), &a.0).cmp(&(b.0.starts_with('_'), &b.0)));
+        Ok(fields)
+    }
+}
+
+/// The unit filter ANDed with each alternative of the field filter
+fn add_matches(j: *mut c_void, unit: Option<&str>, filter: Option<&LogFilter>) {
+    let unit = unit.map(|u| format!("_SYSTEMD_UNIT={u}"));
+    let groups: &[Vec<String>] = filter.map_or(&[], |f| &f.groups);
+    let add = |m: &str| unsafe {
+        let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+    };
+    if groups.is_empty() {
+        if let Some(unit) = &unit {
+            add(unit);
+        }
+        return;
+    }
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            unsafe {
+                let _ = sd_journal_add_disjunction(j);
+            }
+        }
+        if let Some(unit) = &unit {
+            add(unit);
+        }
+        for m in group {
+            add(m);
+        }
     }
 }
 
 fn read_current_entry(j: *mut c_void) -> Option<LogEntry> {
     let timestamp_micros = get_realtime_usec(j)?;
-    let message = get_field(j, "MESSAGE")?;
-    let unit = get_field(j, "_SYSTEMD_UNIT")
-        .or_else(|| get_field(j, "SYSLOG_IDENTIFIER"))
+    let message = get_journal_field(j, "MESSAGE")?;
+    // A user unit's _SYSTEMD_UNIT is just its manager, user@UID.service
+    let unit = get_journal_field(j, "_SYSTEMD_USER_UNIT")
+        .or_else(|| get_journal_field(j, "_SYSTEMD_UNIT"))
+        .or_else(|| get_journal_field(j, "SYSLOG_IDENTIFIER"))
         .unwrap_or_else(|| "system".to_string());
-    let priority = get_field(j, "PRIORITY")
+    let priority = get_journal_field(j, "PRIORITY")
         .and_then(|p| p.parse().ok())
         .unwrap_or(6);
 
-    let ts_secs = (timestamp_micros / 1_000_000) as i64;
-    let display_time = chrono::DateTime::from_timestamp(ts_secs, 0)
-        .map(|dt| {
-            let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
-            local.format("%y%m%d %H:%M:%S").to_string()
-        })
-        .unwrap_or_else(|| "?".to_string());
-
     Some(LogEntry {
         timestamp_micros,
-        display_time,
+        monotonic: get_monotonic_usec(j),
+        clock_jump: None,
+        unit_column: format!("{:20} ", &unit[..char_boundary(&unit, 20)]),
+        message_shown: char_boundary(&message, MESSAGE_COLUMN_MAX),
         unit,
         message,
         priority,
+        cursor: get_cursor(j),
+        duplicates: Vec::new(),
+        unfolded: false,
     })
 }
 
+/// The largest char boundary of `text` at or below `max` bytes
+fn char_boundary(text: &str, max: usize) -> usize {
+    if text.len() <= max {
+        return text.len();
+    }
+    (0..=max)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+fn get_cursor(j: *mut c_void) -> Option<String> {
+    let mut cursor: *mut c_char = std::ptr::null_mut();
+    let rc = unsafe { sd_journal_get_cursor(j, &mut cursor) };
+    if rc < 0 || cursor.is_null() {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(cursor) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { libc::free(cursor as *mut c_void) };
+    Some(text)
+}
+
 fn get_realtime_usec(j: *mut c_void) -> Option<u64> {
     let mut ts = 0u64;
     let rc = unsafe { sd_journal_get_realtime_usec(j, &mut ts as *mut u64) };
     if rc >= 0 { Some(ts) } else { None }
 }
 
-fn get_field(j: *mut c_void, field: &str) -> Option<String> {
-    let field_c = CString::new(field).ok()?;
-    let mut data_ptr: *const u8 = std::ptr::null();
-    let mut len: usize = 0;
-    let rc = unsafe {
-        sd_journal_get_data(
-            j,
-            field_c.as_ptr(),
-            &mut data_ptr as *mut *const u8,
-            &mut len as *mut usize,
-        )
-    };
-    if rc < 0 || data_ptr.is_null() || len == 0 {
-        return None;
+fn get_monotonic_usec(j: *mut c_void) -> Option<(u64, [u8; 16])> {
+    let mut ts = 0u64;
+    let mut boot_id = [0u8; 16];
+    let rc = unsafe { sd_journal_get_monotonic_usec(j, &mut ts, &mut boot_id) };
+    if rc >= 0 { Some((ts, boot_id)) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(text: &str) -> Vec<Vec<String>> {
+        LogFilter::parse(text).unwrap().groups
+    }
+
+    #[test]
+    fn matches_without_or_are_one_group() {
+        assert_eq!(
+            groups("_PID=1234 SYSLOG_IDENTIFIER=sshd"),
+            [["_PID=1234", "SYSLOG_IDENTIFIER=sshd"]]
+        );
+        assert_eq!(groups("_PID=1 AND _UID=0 && _GID=0").len(), 1);
+    }
+
+    #[test]
+    fn or_splits_groups() {
+        assert_eq!(
+            groups("_PID=1234 SYSLOG_IDENTIFIER=sshd OR _COMM=sudo"),
+            [
+                vec!["_PID=1234", "SYSLOG_IDENTIFIER=sshd"],
+                vec!["_COMM=sudo"]
+            ]
+        );
+        for or in ["OR", "or", "+", "||"] {
+            assert_eq!(groups(&format!("_COMM=a {} _COMM=b", or)).len(), 2);
+        }
+    }
+
+    #[test]
+    fn regex_follows_the_tilde() {
+        let filter = LogFilter::parse("_COMM=sudo OR _COMM=su ~ fail(ed|ure)").unwrap();
+        assert_eq!(filter.groups.len(), 2);
+        assert_eq!(filter.regex.unwrap().as_str(), "fail(ed|ure)");
+        assert_eq!(filter.text, "_COMM=sudo OR _COMM=su ~ fail(ed|ure)");
+
+        // Only the first ~ splits; the rest belongs to the regex
+        let filter = LogFilter::parse("~a~b").unwrap();
+        assert!(filter.groups.is_empty());
+        assert_eq!(filter.regex.unwrap().as_str(), "a~b");
+        assert!(LogFilter::parse("~ x").unwrap().filters_here());
+        assert!(!LogFilter::parse("_PID=1").unwrap().filters_here());
     }
 
-    let bytes = unsafe { std::slice::from_raw_parts(data_ptr, len) };
-    let text = String::from_utf8_lossy(bytes);
-    let prefix = format!("{}=", field);
-    text.strip_prefix(&prefix).map(|s| s.to_string())
+    #[test]
+    fn empty_regex_is_no_regex() {
+        let filter = LogFilter::parse("_PID=1 ~ ").unwrap();
+        assert!(filter.regex.is_none());
+        assert_eq!(filter.groups, [["_PID=1"]]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |text: &str| LogFilter::parse(text).err().unwrap();
+        assert_eq!(error(""), "Empty filter");
+        assert_eq!(error("  ~  "), "Empty filter");
+        assert_eq!(error("_PID=1 OR"), "OR needs matches on both sides");
+        assert_eq!(error("OR _PID=1"), "OR needs matches on both sides");
+        assert_eq!(
+            error("_PID=1 OR OR _PID=2"),
+            "OR needs matches on both sides"
+        );
+        assert_eq!(
+            error("sshd"),
+            "Expected FIELD=value, OR or ~regex, not \"sshd\""
+        );
+        assert!(error("_pid=1").starts_with("Expected FIELD=value"));
+        assert!(error("=1").starts_with("Expected FIELD=value"));
+        assert!(error("_PID=1 ~ (").starts_with("Invalid regex"));
+    }
 }
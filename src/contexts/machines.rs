@@ -0,0 +1,665 @@
+use crate::contexts::{Context, Pending, format_bytes, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::SystemdClient;
+use crate::widgets::selectable::{ListState, SelectableTable};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+use zbus::Proxy;
+use zbus::zvariant::OwnedObjectPath;
+
+pub struct Machine {
+    name: String,
+    class: String,
+    service: String,
+    state: String,
+    leader: Option<u32>,
+    unit: Option<String>,
+}
+
+impl Machine {
+    async fn list(systemd: SystemdClient) -> Result<Vec<Machine>> {
+        let conn = systemd.system_bus().await?;
+        let manager = machine_manager(conn).await?;
+
+        let raw: Vec<(String, String, String, OwnedObjectPath)> =
+            manager.call("ListMachines", &()).await?;
+
+        let mut machines = Vec::with_capacity(raw.len());
+        for (name, class, service, path) in raw {
+            let props = Proxy::new(
+                conn,
+                "org.freedesktop.machine1",
+                path,
+                "org.freedesktop.machine1.Machine",
+            )
+            .await
+            .ok();
+            let (mut state, mut leader, mut unit) = (None, None, None);
+            if let Some(ref p) = props {
+                state = p.get_property::<String>("State").await.ok();
+                leader = p
+                    .get_property::<u32>("Leader")
+                    .await
+                    .ok()
+                    .filter(|pid| *pid != 0);
+                unit = p
+                    .get_property::<String>("Unit")
+                    .await
+                    .ok()
+                    .filter(|u| !u.is_empty());
+            }
+
+            machines.push(Machine {
+                name,
+                class,
+                service,
+                state: state.unwrap_or_else(|| "unknown".to_string()),
+                leader,
+                unit,
+            });
+        }
+
+        machines.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(machines)
+    }
+}
+
+async fn machine_manager(conn: &zbus::Connection) -> Result<Proxy<'static>> {
+    Ok(Proxy::new(
+        conn,
+        "org.freedesktop.machine1",
+        "/org/freedesktop/machine1",
+        "org.freedesktop.machine1.Manager",
+    )
+    .await?)
+}
+
+/// An image machined can start a container from, as `machinectl
+/// list-images` shows it
+pub struct Image {
+    name: String,
+    /// "directory", "subvolume", "raw" or "block"
+    kind: String,
+    read_only: bool,
+    /// Realtime µs of the last modification, 0 if unknown
+    mtime_usec: u64,
+    /// Disk usage, None when machined couldn't tell
+    usage: Option<u64>,
+}
+
+impl Image {
+    async fn list(systemd: SystemdClient) -> Result<Vec<Image>> {
+        let manager = machine_manager(systemd.system_bus().await?).await?;
+
+        let raw: Vec<(String, String, bool, u64, u64, u64, OwnedObjectPath)> =
+            manager.call("ListImages", &()).await?;
+        let mut images: Vec<Image> = raw
+            .into_iter()
+            // The host's own root shows up as ".host"
+            .filter(|(name, ..)| !name.starts_with('.'))
+            .map(|(name, kind, read_only, _crtime, mtime, usage, _)| Image {
+                name,
+                kind,
+                read_only,
+                mtime_usec: mtime,
+                usage: Some(usage).filter(|u| *u != u64::MAX),
+            })
+            .collect();
+        images.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(images)
+    }
+
+    fn modified(&self) -> String {
+        if self.mtime_usec == 0 {
+            return "-".to_string();
+        }
+        chrono::DateTime::from_timestamp_micros(self.mtime_usec as i64)
+            .map(|dt| {
+                let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
+                local.format("%Y-%m-%d %H:%M").to_string()
+            })
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ImageAction {
+    /// `machinectl start`: boot a container from the image
+    Start,
+    Remove,
+}
+
+impl ImageAction {
+    fn label(&self) -> &'static str {
+        match self {
+            ImageAction::Start => "start",
+            ImageAction::Remove => "remove",
+        }
+    }
+
+    async fn run(&self, systemd: &SystemdClient, name: &str) -> Result<()> {
+        let conn = systemd.system_bus().await?;
+        match self {
+            ImageAction::Start => {
+                let manager = Proxy::new(
+                    conn,
+                    "org.freedesktop.systemd1",
+                    "/org/freedesktop/systemd1",
+                    "org.freedesktop.systemd1.Manager",
+                )
+                .await?;
+                let unit = format!("systemd-nspawn@{}.service", name);
+                let _: OwnedObjectPath = manager
+                    .call("StartUnit", &(unit.as_str(), "replace"))
+                    .await?;
+            }
+            ImageAction::Remove => {
+                let manager = machine_manager(conn).await?;
+                let _: () = manager.call("RemoveImage", &(name,)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MachineAction {
+    Terminate,
+    Poweroff,
+}
+
+impl MachineAction {
+    fn label(&self) -> &'static str {
+        match self {
+            MachineAction::Terminate => "terminate",
+            MachineAction::Poweroff => "poweroff",
+        }
+    }
+
+    async fn run(&self, systemd: &SystemdClient, name: &str) -> Result<()> {
+        let manager = machine_manager(systemd.system_bus().await?).await?;
+
+        match self {
+            MachineAction::Terminate => {
+                let _: () = manager.call("TerminateMachine", &(name,)).await?;
+            }
+            MachineAction::Poweroff => {
+                // Same as machinectl poweroff: ask the container's init to shut down
+                let _: () = manager
+                    .call("KillMachine", &(name, "leader", libc::SIGRTMIN() + 4))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct MachinesContext {
+    systemd: SystemdClient,
+    machines: Vec<Machine>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<Vec<Machine>>>,
+    /// The first listing has come back
+    loaded: bool,
+    /// Terminate or poweroff of a machine, reporting what happened
+    machine_action: Pending<Result<String>>,
+    selected: usize,
+    confirm_action: Option<MachineAction>,
+    images: Vec<Image>,
+    images_error: Option<RootworkError>,
+    images_pending: Pending<Result<Vec<Image>>>,
+    /// The first listing has come back
+    images_loaded: bool,
+    /// Start or remove of an image, reporting what happened
+    image_action: Pending<Result<String>>,
+    image_list: ListState,
+    /// j/k move through the images instead of the machines
+    images_focused: bool,
+    confirm_remove: bool,
+    jump_to_unit: Option<String>,
+    messages: Messages,
+}
+
+impl MachinesContext {
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            systemd: systemd.clone(),
+            machines: Vec::new(),
+            error: None,
+            pending: Pending::idle(),
+            loaded: false,
+            machine_action: Pending::idle(),
+            selected: 0,
+            confirm_action: None,
+            images: Vec::new(),
+            images_error: None,
+            images_pending: Pending::idle(),
+            images_loaded: false,
+            image_action: Pending::idle(),
+            image_list: ListState::new(),
+            images_focused: false,
+            confirm_remove: false,
+            jump_to_unit: None,
+            messages: messages.clone(),
+        };
+        ctx.refresh();
+        ctx
+    }
+
+    fn refresh(&mut self) {
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn_async(Machine::list(self.systemd.clone()));
+        }
+        if !self.images_pending.is_pending() {
+            self.images_pending = Pending::spawn_async(Image::list(self.systemd.clone()));
+        }
+    }
+
+    fn selected_image(&self) -> Option<&Image> {
+        self.images.get(self.image_list.selected)
+    }
+
+    fn run_image_action(&mut self, action: ImageAction) {
+        let Some(name) = self.selected_image().map(|i| i.name.clone()) else {
+            return;
+        };
+        if self.image_action.is_pending() {
+            self.messages.warn("An image action is still running");
+            return;
+        }
+        let systemd = self.systemd.clone();
+        self.image_action = Pending::spawn_async(async move {
+            match action.run(&systemd, &name).await {
+                Ok(()) => Ok(format!("{} {}: OK", action.label(), name)),
+                Err(e) => Err(anyhow::anyhow!("{} {}: {}", action.label(), name, e)),
+            }
+        });
+    }
+
+    fn handle_image_key(&mut self, key: KeyEvent) {
+        if self.confirm_remove {
+            self.confirm_remove = false;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.run_image_action(ImageAction::Remove);
+            }
+            return;
+        }
+        let len = self.images.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.image_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.image_list.up(len),
+            KeyCode::Char('g') => self.image_list.top(len),
+            KeyCode::Char('G') => self.image_list.bottom(len),
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('s') => match self.selected_image() {
+                Some(image) if self.machines.iter().any(|m| m.name == image.name) => self
+                    .messages
+                    .warn(format!("{} is already running", image.name)),
+                Some(_) => self.run_image_action(ImageAction::Start),
+                None => {}
+            },
+            KeyCode::Char('x') => match self.selected_image() {
+                Some(image) if image.read_only => {
+                    self.messages.warn(format!("{} is read-only", image.name))
+                }
+                Some(_) => self.confirm_remove = true,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn run_machine_action(&mut self, action: MachineAction) {
+        let Some(name) = self.machines.get(self.selected).map(|m| m.name.clone()) else {
+            return;
+        };
+        if self.machine_action.is_pending() {
+            self.messages.warn("A machine action is still running");
+            return;
+        }
+        let systemd = self.systemd.clone();
+        self.machine_action = Pending::spawn_async(async move {
+            match action.run(&systemd, &name).await {
+                Ok(()) => Ok(format!("{} {}: OK", action.label(), name)),
+                Err(e) => Err(anyhow::anyhow!("{} {}: {}", action.label(), name, e)),
+            }
+        });
+    }
+
+    fn move_up(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.machines.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
+    }
+}
+
+impl Context for MachinesContext {
+    fn name(&self) -> &'static str {
+        "Machines"
+    }
+
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect) {
+        let images_height = (self.images.len().max(1) as u16 + 3).min(10);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(images_height),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        draw_machines(self, f, chunks[0]);
+        draw_images(self, f, chunks[1]);
+
+        let status = if self.images_focused {
+            match self.selected_image() {
+                Some(image) if self.confirm_remove => {
+                    format!("Remove image {} and its files? [y/n]", image.name)
+                }
+                _ => "s:start x:remove i/Esc:machines r:refresh".to_string(),
+            }
+        } else if let Some(action) = self.confirm_action {
+            let name = self
+                .machines
+                .get(self.selected)
+                .map(|m| m.name.as_str())
+                .unwrap_or("?");
+            format!("Confirm {} on {} ? [y/n]", action.label(), name)
+        } else {
+            "x:terminate p:poweroff Enter:unit i:images r:refresh".to_string()
+        };
+        f.render_widget(
+            Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
+            chunks[2],
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.confirm_action.is_none() && !self.confirm_remove {
+            match key.code {
+                KeyCode::Char('i') => {
+                    self.images_focused = !self.images_focused;
+                    return;
+                }
+                KeyCode::Esc if self.images_focused => {
+                    self.images_focused = false;
+                    return;
+                }
+                _ => {}
+            }
+        }
+        if self.images_focused {
+            self.handle_image_key(key);
+            return;
+        }
+
+        if let Some(action) = self.confirm_action {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.confirm_action = None;
+                    self.run_machine_action(action);
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.confirm_action = None;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('j') | KeyCode::Down => self.move_down(),
+            KeyCode::Char('k') | KeyCode::Up => self.move_up(),
+            KeyCode::Char('x') if !self.machines.is_empty() => {
+                self.confirm_action = Some(MachineAction::Terminate)
+            }
+            KeyCode::Char('p') if !self.machines.is_empty() => {
+                self.confirm_action = Some(MachineAction::Poweroff)
+            }
+            KeyCode::Enter => {
+                if let Some(machine) = self.machines.get(self.selected) {
+                    self.jump_to_unit = Some(
+                        machine
+                            .unit
+                            .clone()
+                            .unwrap_or_else(|| format!("systemd-nspawn@{}.service", machine.name)),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self) {
+        match self.machine_action.poll() {
+            Some(Ok(done)) => {
+                self.messages.info(done);
+                self.refresh();
+            }
+            Some(Err(e)) => {
+                self.messages.error(e.to_string());
+                self.refresh();
+            }
+            None => {}
+        }
+
+        if let Some(result) = self.pending.poll() {
+            let result =
+                result.map_err(|e| RootworkError::from_anyhow("Failed to list machines", &e));
+            self.machines =
+                note_refresh_error(&mut self.error, &self.messages, result).unwrap_or_default();
+            self.loaded = true;
+            if self.selected >= self.machines.len() {
+                self.selected = self.machines.len().saturating_sub(1);
+            }
+        }
+
+        match self.image_action.poll() {
+            Some(Ok(done)) => {
+                self.messages.info(done);
+                self.refresh();
+            }
+            Some(Err(e)) => {
+                self.messages.error(e.to_string());
+                self.refresh();
+            }
+            None => {}
+        }
+
+        match self.images_pending.poll() {
+            Some(Ok(images)) => {
+                self.images = images;
+                self.images_error = None;
+            }
+            Some(Err(e)) => {
+                self.images.clear();
+                self.images_error = Some(RootworkError::from_anyhow("Failed to list images", &e));
+            }
+            None => return,
+        }
+        self.images_loaded = true;
+        self.image_list.clamp(self.images.len());
+    }
+}
+
+fn draw_machines(ctx: &MachinesContext, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(
+            ctx.machine_action
+                .title(&format!("Machines ({})", ctx.machines.len())),
+        )
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+
+    if ctx.machines.is_empty() {
+        let text = if !ctx.loaded {
+            "Reading machines..."
+        } else {
+            "No machines registered with systemd-machined"
+        };
+        let empty = Paragraph::new(text).block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Class", "Service", "State", "Leader", "Unit"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = ctx
+        .machines
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let style = if i == ctx.selected {
+                crate::palette::selected()
+            } else {
+                Style::default()
+            };
+
+            let state_color = match m.state.as_str() {
+                "running" => crate::palette::green(),
+                "opening" | "closing" => crate::palette::yellow(),
+                _ => crate::palette::gray(),
+            };
+
+            Row::new(vec![
+                Span::styled(m.name.clone(), Style::default().fg(crate::palette::cyan())),
+                Span::raw(m.class.clone()),
+                Span::raw(m.service.clone()),
+                Span::styled(m.state.clone(), Style::default().fg(state_color)),
+                Span::raw(
+                    m.leader
+                        .map(|p| p.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Span::styled(
+                    m.unit.clone().unwrap_or_else(|| "-".to_string()),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(20),
+            Constraint::Length(10),
+            Constraint::Length(16),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_images(ctx: &MachinesContext, f: &mut Frame, area: Rect) {
+    let title = ctx.image_action.title(&format!(
+        "Images ({}){}",
+        ctx.images.len(),
+        if ctx.images_focused {
+            " [s: start, x: remove, i: back]"
+        } else {
+            " [i: select]"
+        }
+    ));
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    if ctx.images_focused {
+        block = block.border_style(Style::default().fg(crate::palette::cyan()));
+    }
+
+    if let Some(ref error) = ctx.images_error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    if ctx.images.is_empty() {
+        let text = if !ctx.images_loaded {
+            "Reading images..."
+        } else {
+            "No images found by systemd-machined"
+        };
+        let empty = Paragraph::new(text).block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Type", "RO", "Usage", "Modified", "State"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.image_list,
+        ctx.images.len(),
+        [
+            Constraint::Length(20),
+            Constraint::Length(10),
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Length(17),
+            Constraint::Min(8),
+        ],
+        |i| {
+            let image = &ctx.images[i];
+            let running = ctx.machines.iter().any(|m| m.name == image.name);
+            Row::new(vec![
+                Span::styled(
+                    image.name.clone(),
+                    Style::default().fg(crate::palette::cyan()),
+                ),
+                Span::raw(image.kind.clone()),
+                Span::raw(if image.read_only { "ro" } else { "" }),
+                Span::raw(
+                    image
+                        .usage
+                        .map(format_bytes)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Span::styled(
+                    image.modified(),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                if running {
+                    Span::styled("running", Style::default().fg(crate::palette::green()))
+                } else {
+                    Span::styled("-", Style::default().fg(crate::palette::gray()))
+                },
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
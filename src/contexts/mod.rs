@@ -1,12 +1,55 @@
+#[cfg(feature = "boot")]
 pub mod boot;
+#[cfg(feature = "coredumps")]
+pub mod coredumps;
+#[cfg(feature = "dns")]
 pub mod dns;
+#[cfg(feature = "host")]
 pub mod host;
+#[cfg(feature = "kernel")]
+pub mod kernel;
+#[cfg(feature = "logs")]
 pub mod logs;
+#[cfg(feature = "machines")]
+pub mod machines;
+#[cfg(feature = "network")]
 pub mod network;
+#[cfg(feature = "portable")]
+pub mod portable;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(feature = "units")]
 pub mod units;
 
+use crate::config::Config;
+#[cfg(any(
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable"
+))]
+use crate::{error::RootworkError, messages::Messages};
 use crossterm::event::KeyEvent;
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "logs",
+    feature = "coredumps"
+))]
+use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::{Frame, layout::Rect};
+use std::time::{Duration, Instant};
+
+/// Areas narrower than this get the compact layout: fewer columns,
+/// numbered tabs, short hints. 80-column terminals fall below it.
+pub const NARROW_WIDTH: u16 = 100;
+
+/// Content areas shorter than this fold secondary panes away
+#[cfg(any(feature = "units", feature = "network"))]
+pub const SHORT_HEIGHT: u16 = 26;
 
 /// Trait for all context views
 pub trait Context {
@@ -14,4 +57,325 @@ pub trait Context {
     fn draw(&self, f: &mut Frame, area: Rect);
     fn handle_key(&mut self, key: KeyEvent);
     async fn tick(&mut self);
+
+    /// Re-gather without blocking; `App::tick` calls it when the tab's
+    /// refresh timer fires
+    fn auto_refresh(&mut self) {}
+
+    /// True while the context is taking typed text (a filter, a name to
+    /// confirm), so global single-key shortcuts must not fire
+    fn captures_input(&self) -> bool {
+        false
+    }
+}
+
+/// When `App::tick` refreshes a tab on its own: every
+/// `[<tab>] refresh_interval` seconds (fractions allowed), 0 for never
+pub struct RefreshTimer {
+    interval: Option<Duration>,
+    last: Instant,
+}
+
+impl RefreshTimer {
+    pub fn from_config(config: &Config, section: &str, default: Option<Duration>) -> Self {
+        let interval = match config.get(section, "refresh_interval") {
+            Some(value) => match value.parse::<f64>() {
+                Ok(secs) if secs > 0.0 => Some(Duration::from_secs_f64(secs)),
+                Ok(_) => None,
+                Err(_) => {
+                    tracing::warn!("Invalid {} refresh_interval {:?}", section, value);
+                    default
+                }
+            },
+            None => default,
+        };
+        Self {
+            interval,
+            last: Instant::now(),
+        }
+    }
+
+    /// True once per interval stretched `slowdown` times, restarting it
+    pub fn due(&mut self, slowdown: u32) -> bool {
+        match self.interval {
+            Some(interval) if self.last.elapsed() >= interval * slowdown => {
+                self.last = Instant::now();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Human-readable size with binary units, e.g. "1.5 GiB"
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "host",
+    feature = "boot",
+    feature = "machines",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit_idx = 0;
+
+    while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_idx += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_idx])
+}
+
+/// Shell-style match with `*` and `?`
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "logs",
+    feature = "kernel"
+))]
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut pi, mut ni) = (0, 0);
+    // Where the last `*` was, and how much of the name it has taken
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star_pi, star_ni)) = star {
+            pi = star_pi + 1;
+            ni = star_ni + 1;
+            star = Some((star_pi, star_ni + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Compact duration like "3d 4h", "12m 5s" or "800ms"
+#[cfg(any(feature = "units", feature = "network", feature = "host"))]
+pub fn format_duration_usec(usec: u64) -> String {
+    let secs = usec / 1_000_000;
+    if secs >= 86400 {
+        format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
+    } else if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs > 0 {
+        format!("{}s", secs)
+    } else {
+        format!("{}ms", usec / 1_000)
+    }
+}
+
+/// Login name of a UID through NSS, so LDAP and systemd-homed users resolve
+/// too
+#[cfg(feature = "units")]
+pub fn user_name(uid: u32) -> Option<String> {
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; 4096];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let rc = unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return None;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Copy via OSC 52, which most terminals (and tmux with set-clipboard) honor,
+/// so it works over ssh without a clipboard tool on the remote side
+#[cfg(any(feature = "units", feature = "logs"))]
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut out = std::io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    out.flush()
+}
+
+#[cfg(any(feature = "units", feature = "logs"))]
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Popup area covering the given percentages of `r`, centered
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "logs",
+    feature = "coredumps"
+))]
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+const SPINNER: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+
+/// Result of blocking work (D-Bus, /proc, the journal) running on tokio's
+/// blocking pool. Contexts start it from `refresh()` and collect it in
+/// `tick()`, so a slow bus call never stalls key handling.
+pub struct Pending<T> {
+    rx: Option<tokio::sync::oneshot::Receiver<T>>,
+    started: std::time::Instant,
+}
+
+impl<T: Send + 'static> Pending<T> {
+    pub fn idle() -> Self {
+        Self {
+            rx: None,
+            started: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "boot",
+        feature = "logs",
+        feature = "coredumps",
+        feature = "storage",
+        feature = "kernel"
+    ))]
+    pub fn spawn<F>(work: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            // The receiver is gone if the context started over; nothing to do
+            let _ = tx.send(work());
+        });
+        Self {
+            rx: Some(rx),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Same as `spawn`, for async work such as a D-Bus round trip
+    pub fn spawn_async<F>(work: F) -> Self
+    where
+        F: std::future::Future<Output = T> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tx.send(work.await);
+        });
+        Self {
+            rx: Some(rx),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    /// Take the result if the work has finished
+    pub fn poll(&mut self) -> Option<T> {
+        let rx = self.rx.as_mut()?;
+        match rx.try_recv() {
+            Ok(value) => {
+                self.rx = None;
+                Some(value)
+            }
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => None,
+            Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                // The task panicked
+                self.rx = None;
+                None
+            }
+        }
+    }
+
+    /// Spinner frame for block titles, empty when nothing is running
+    pub fn spinner(&self) -> &'static str {
+        if !self.is_pending() {
+            return "";
+        }
+        let frame = self.started.elapsed().as_millis() / 250;
+        SPINNER[frame as usize % SPINNER.len()]
+    }
+
+    /// Block title with the spinner appended while work is running
+    pub fn title(&self, title: &str) -> String {
+        match self.spinner() {
+            "" => format!(" {} ", title),
+            frame => format!(" {} {} ", title, frame),
+        }
+    }
+}
+
+/// Store the outcome of a timed refresh in `error`. Refreshes repeat, so a
+/// failure is only announced when the tab goes from working to failing.
+/// Returns the value when the refresh succeeded.
+#[cfg(any(
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable"
+))]
+pub fn note_refresh_error<T>(
+    error: &mut Option<RootworkError>,
+    messages: &Messages,
+    result: Result<T, RootworkError>,
+) -> Option<T> {
+    match result {
+        Ok(value) => {
+            *error = None;
+            Some(value)
+        }
+        Err(err) => {
+            if error.is_none() {
+                messages.error(err.to_string());
+            }
+            *error = Some(err);
+            None
+        }
+    }
 }
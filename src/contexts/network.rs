@@ -1,18 +1,35 @@
-use crate::contexts::Context;
-use anyhow::Result;
+use crate::config::Config;
+use crate::container;
+use crate::contexts::{
+    Context, Pending, SHORT_HEIGHT, centered_rect, format_bytes, format_duration_usec, glob_match,
+    note_refresh_error,
+};
+use crate::error::RootworkError;
+use crate::firewall::{Rule, Ruleset};
+use crate::messages::Messages;
+use crate::probe;
+use crate::sockdiag::{self, TcpConn};
+use crate::systemd::client::SystemdClient;
+use crate::widgets::picker::fuzzy_match_score;
+use crate::widgets::selectable::{ListState, SelectableTable, render_scrollbar};
+use anyhow::{Context as _, Result};
 use crossterm::event::KeyEvent;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Row},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CStr;
 use std::fs;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 use std::ptr;
+use std::time::{Duration, Instant, SystemTime};
 
 pub struct NetworkInfo {
     interfaces: Vec<Interface>,
@@ -29,6 +46,143 @@ pub struct Interface {
     ipv6: Vec<String>,
     rx_bytes: u64,
     tx_bytes: u64,
+    /// What systemd-networkd got from the DHCPv4 server, if it manages the
+    /// link
+    lease: Option<DhcpLease>,
+}
+
+/// Where systemd-networkd saves each link's DHCPv4 lease, by ifindex
+const LEASES_DIR: &str = "/run/systemd/netif/leases";
+
+#[derive(Clone)]
+pub struct DhcpLease {
+    server: Option<String>,
+    routers: Vec<String>,
+    dns: Vec<String>,
+    /// Classless static routes, "destination/len,gateway" as networkd saves
+    /// them
+    routes: Vec<String>,
+    /// When networkd last saved the lease, i.e. got or renewed it
+    saved: SystemTime,
+    lifetime: Option<Duration>,
+    /// Renewal time; the server may leave it out, then it's half the lifetime
+    t1: Option<Duration>,
+    /// Rebinding time: after it any server may answer
+    t2: Option<Duration>,
+}
+
+/// How a lease is doing, by how much of it has run out
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LeaseHealth {
+    Fine,
+    /// Past T1 without a renewal: the server isn't answering
+    RenewalOverdue,
+    /// Past T2: asking any server now
+    Rebinding,
+    Expired,
+}
+
+impl DhcpLease {
+    /// The lease file of a link. The file format is networkd's private
+    /// data, so anything missing just isn't shown.
+    fn read(ifindex: u32) -> Option<Self> {
+        let path = Path::new(LEASES_DIR).join(ifindex.to_string());
+        let text = fs::read_to_string(&path).ok()?;
+        let saved = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        let mut lease = DhcpLease {
+            server: None,
+            routers: Vec::new(),
+            dns: Vec::new(),
+            routes: Vec::new(),
+            saved,
+            lifetime: None,
+            t1: None,
+            t2: None,
+        };
+        let list = |value: &str| value.split_whitespace().map(str::to_string).collect();
+        let secs = |value: &str| value.parse().ok().map(Duration::from_secs);
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key {
+                "SERVER_ADDRESS" => lease.server = Some(value.to_string()),
+                "ROUTER" => lease.routers = list(value),
+                "DNS" => lease.dns = list(value),
+                "CLASSLESS_ROUTES" | "STATIC_ROUTES" => lease.routes.extend(list(value)),
+                "LIFETIME" => lease.lifetime = secs(value),
+                "T1" => lease.t1 = secs(value),
+                "T2" => lease.t2 = secs(value),
+                _ => {}
+            }
+        }
+        Some(lease)
+    }
+
+    fn age(&self) -> Duration {
+        self.saved.elapsed().unwrap_or_default()
+    }
+
+    /// Time left until the lease runs out; None if it has or there's no
+    /// lifetime (an infinite lease)
+    fn remaining(&self) -> Option<Duration> {
+        self.lifetime?.checked_sub(self.age())
+    }
+
+    fn health(&self) -> LeaseHealth {
+        let Some(lifetime) = self.lifetime else {
+            return LeaseHealth::Fine;
+        };
+        let age = self.age();
+        if age >= lifetime {
+            LeaseHealth::Expired
+        } else if self.t2.is_some_and(|t2| age >= t2) {
+            LeaseHealth::Rebinding
+        } else if age >= self.t1.unwrap_or(lifetime / 2) {
+            LeaseHealth::RenewalOverdue
+        } else {
+            LeaseHealth::Fine
+        }
+    }
+
+    /// "from 10.0.0.1, expires in 20h 3m (renewal overdue)"
+    fn summary(&self) -> String {
+        let mut text = match &self.server {
+            Some(server) => format!("from {}", server),
+            None => "lease".to_string(),
+        };
+        match (self.lifetime, self.remaining()) {
+            (None, _) => text.push_str(", infinite"),
+            (Some(_), Some(left)) => {
+                text.push_str(&format!(
+                    ", expires in {}",
+                    format_duration_usec(left.as_micros() as u64)
+                ));
+            }
+            (Some(_), None) => {}
+        }
+        match self.health() {
+            LeaseHealth::Fine => {}
+            LeaseHealth::RenewalOverdue => text.push_str(" (renewal overdue)"),
+            LeaseHealth::Rebinding => text.push_str(" (rebinding: the server doesn't answer)"),
+            LeaseHealth::Expired => text.push_str(" (expired)"),
+        }
+        text
+    }
+
+    /// "DNS 1.1.1.1 9.9.9.9, router 10.0.0.1, routes 10.1.0.0/16,10.0.0.2"
+    fn options(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            ("DNS", &self.dns),
+            ("router", &self.routers),
+            ("routes", &self.routes),
+        ]
+        .into_iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(label, values)| format!("{} {}", label, values.join(" ")))
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
 }
 
 #[derive(Clone)]
@@ -39,14 +193,465 @@ pub struct Route {
     metric: Option<u32>,
 }
 
+/// A network namespace other than rootwork's own: one named under
+/// /run/netns (`ip netns add`), or one that running processes sit in, such as
+/// a service with PrivateNetwork= or a container
+#[derive(Clone)]
+pub struct Netns {
+    /// The /run/netns name, else the units (or the process) inside
+    label: String,
+    /// What to setns() on: /run/netns/NAME or /proc/PID/ns/net
+    path: PathBuf,
+    /// Units with processes inside, e.g. a NetworkNamespacePath= service
+    units: Vec<String>,
+    processes: usize,
+}
+
+/// Where `ip netns` keeps named namespaces (/var/run links here)
+const NETNS_DIR: &str = "/run/netns";
+
+impl Netns {
+    /// Named namespaces first, then the ones found through /proc. Other
+    /// users' processes can't be looked into unless we're root.
+    fn list() -> Result<Vec<Netns>> {
+        let own = netns_inode(Path::new("/proc/self/ns/net"))
+            .context("Can't read rootwork's own network namespace")?;
+
+        let mut named: Vec<(u64, Netns)> = Vec::new();
+        if let Ok(entries) = fs::read_dir(NETNS_DIR) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(metadata) = fs::metadata(&path) else {
+                    continue;
+                };
+                named.push((
+                    metadata.ino(),
+                    Netns {
+                        label: entry.file_name().to_string_lossy().into_owned(),
+                        path,
+                        units: Vec::new(),
+                        processes: 0,
+                    },
+                ));
+            }
+        }
+        named.sort_by(|a, b| a.1.label.cmp(&b.1.label));
+
+        // Inode -> namespace, for the ones only processes hold
+        let mut unnamed: HashMap<u64, Netns> = HashMap::new();
+        for entry in fs::read_dir("/proc")?.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let path = entry.path().join("ns/net");
+            let Some(inode) = netns_inode(&path).filter(|inode| *inode != own) else {
+                continue;
+            };
+            let netns = match named.iter_mut().find(|(i, _)| *i == inode) {
+                Some((_, netns)) => netns,
+                None => unnamed.entry(inode).or_insert_with(|| Netns {
+                    label: String::new(),
+                    path,
+                    units: Vec::new(),
+                    processes: 0,
+                }),
+            };
+            netns.processes += 1;
+            if let Some(unit) = cgroup_unit(pid)
+                && !netns.units.contains(&unit)
+            {
+                netns.units.push(unit);
+            }
+            if netns.label.is_empty() && netns.units.is_empty() {
+                let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+                netns.label = format!("{} ({})", comm.trim(), pid);
+            }
+        }
+
+        let mut unnamed: Vec<Netns> = unnamed
+            .into_values()
+            .map(|mut netns| {
+                if !netns.units.is_empty() {
+                    netns.label = netns.units.join(", ");
+                }
+                netns
+            })
+            .collect();
+        unnamed.sort_by(|a, b| a.label.cmp(&b.label));
+        Ok(named.into_iter().map(|(_, n)| n).chain(unnamed).collect())
+    }
+}
+
+/// Inode of a namespace link such as /proc/PID/ns/net ("net:[4026531840]")
+fn netns_inode(path: &Path) -> Option<u64> {
+    let target = fs::read_link(path).ok()?;
+    target
+        .to_str()?
+        .strip_prefix("net:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Run `work` on a fresh thread moved into the network namespace at `path`.
+/// setns() only moves the calling thread, and tokio reuses its blocking
+/// threads, so the thread must die with the namespace. Needs root.
+fn in_netns<T: Send + 'static>(
+    path: &Path,
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let file =
+            fs::File::open(&path).with_context(|| format!("Can't open {}", path.display()))?;
+        if unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .context("Can't enter the network namespace (needs root)");
+        }
+        work()
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("Reading the network namespace crashed"))?
+}
+
+/// `in_netns` for the namespace shown, or right here for our own
+fn in_shown_netns<T: Send + 'static>(
+    netns: Option<&Path>,
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    match netns {
+        Some(path) => in_netns(path, work),
+        None => work(),
+    }
+}
+
+/// /proc/net as the current thread sees it; /proc/net itself follows the
+/// main thread, which never leaves rootwork's namespace
+const THREAD_PROC_NET: &str = "/proc/thread-self/net";
+
+/// An inet socket bound on one of an interface's addresses (or on all of
+/// them), with the process holding it
+pub struct Socket {
+    proto: &'static str,
+    local: String,
+    remote: Option<String>,
+    state: &'static str,
+    owner: Option<SocketOwner>,
+}
+
+pub struct SocketOwner {
+    pid: u32,
+    comm: String,
+    /// Innermost unit of the process's cgroup, e.g. "sshd.service"
+    unit: Option<String>,
+}
+
+impl SocketOwner {
+    fn of(pid: u32) -> Option<Self> {
+        Some(Self {
+            pid,
+            comm: fs::read_to_string(format!("/proc/{}/comm", pid))
+                .ok()?
+                .trim()
+                .to_string(),
+            unit: cgroup_unit(pid),
+        })
+    }
+}
+
+/// A TCP connection's traffic, with the rates since the previous sample
+struct ConnRate {
+    conn: TcpConn,
+    owner: Option<SocketOwner>,
+    /// Bytes per second; None on the connection's first sample
+    tx_rate: Option<f64>,
+    rx_rate: Option<f64>,
+}
+
+impl ConnRate {
+    fn rate(&self) -> f64 {
+        self.tx_rate.unwrap_or(0.0) + self.rx_rate.unwrap_or(0.0)
+    }
+}
+
+type ByteCounters = HashMap<u64, (u64, u64)>;
+
+/// TCP connections with the processes holding them
+type ConnSample = Vec<(TcpConn, Option<SocketOwner>)>;
+
+/// TCP connections of the namespace shown with their owners
+fn sample_connections(netns: Option<&Path>) -> Result<ConnSample> {
+    let conns = in_shown_netns(netns, sockdiag::tcp_connections)?;
+    let inodes: HashSet<u64> = conns.iter().map(|c| c.inode).filter(|i| *i != 0).collect();
+    let owners = socket_owners(&inodes);
+    Ok(conns
+        .into_iter()
+        .map(|conn| {
+            let owner = owners
+                .get(&conn.inode)
+                .and_then(|pid| SocketOwner::of(*pid));
+            (conn, owner)
+        })
+        .collect())
+}
+
+impl Socket {
+    /// Port of the local address, e.g. 22 of "*:22"
+    fn local_port(&self) -> Option<u16> {
+        self.local.rsplit_once(':')?.1.parse().ok()
+    }
+
+    /// Sockets on `addresses`, wildcard binds included. Owners come from
+    /// matching socket inodes against every process's fds, so sockets held
+    /// by other users' processes show no owner unless we're root.
+    fn scan(addresses: &[String]) -> Vec<Socket> {
+        Self::scan_in(addresses, "/proc/net")
+    }
+
+    /// Sockets of the namespace at `netns`; none if it can't be entered
+    fn scan_netns(addresses: Vec<String>, netns: &Path) -> Vec<Socket> {
+        in_netns(netns, move || {
+            Ok(Self::scan_in(&addresses, THREAD_PROC_NET))
+        })
+        .unwrap_or_default()
+    }
+
+    /// Socket tables from `proc_net`. Owners are found through /proc/*/fd,
+    /// which works across namespaces since socket inodes are unique.
+    fn scan_in(addresses: &[String], proc_net: &str) -> Vec<Socket> {
+        let wanted: HashSet<&str> = addresses.iter().map(|a| a.as_str()).collect();
+        let mut found: Vec<(Socket, u64)> = Vec::new();
+
+        for (proto, file) in [
+            ("tcp", "tcp"),
+            ("tcp6", "tcp6"),
+            ("udp", "udp"),
+            ("udp6", "udp6"),
+        ] {
+            let Ok(content) = fs::read_to_string(format!("{}/{}", proc_net, file)) else {
+                continue;
+            };
+            for line in content.lines().skip(1) {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() < 10 {
+                    continue;
+                }
+                let (Some((local_ip, local_port)), Some((remote_ip, remote_port))) =
+                    (parse_socket_addr(parts[1]), parse_socket_addr(parts[2]))
+                else {
+                    continue;
+                };
+                let wildcard = local_ip.is_unspecified();
+                if !wildcard && !wanted.contains(local_ip.to_string().as_str()) {
+                    continue;
+                }
+
+                let tcp = proto.starts_with("tcp");
+                let state = socket_state(parts[3], tcp);
+                let local = match local_ip {
+                    _ if wildcard => format!("*:{}", local_port),
+                    std::net::IpAddr::V6(ip) => format!("[{}]:{}", ip, local_port),
+                    std::net::IpAddr::V4(ip) => format!("{}:{}", ip, local_port),
+                };
+                let remote = (!remote_ip.is_unspecified()).then(|| match remote_ip {
+                    std::net::IpAddr::V6(ip) => format!("[{}]:{}", ip, remote_port),
+                    std::net::IpAddr::V4(ip) => format!("{}:{}", ip, remote_port),
+                });
+                let inode = parts[9].parse().unwrap_or(0);
+
+                found.push((
+                    Socket {
+                        proto,
+                        local,
+                        remote,
+                        state,
+                        owner: None,
+                    },
+                    inode,
+                ));
+            }
+        }
+
+        let inodes: HashSet<u64> = found.iter().map(|(_, i)| *i).filter(|i| *i != 0).collect();
+        let owners = socket_owners(&inodes);
+        let mut sockets: Vec<Socket> = found
+            .into_iter()
+            .map(|(mut socket, inode)| {
+                socket.owner = owners.get(&inode).and_then(|pid| SocketOwner::of(*pid));
+                socket
+            })
+            .collect();
+
+        // Listeners first, then by port
+        sockets.sort_by(|a, b| {
+            (a.state != "LISTEN" && a.state != "UNCONN")
+                .cmp(&(b.state != "LISTEN" && b.state != "UNCONN"))
+                .then_with(|| a.proto.cmp(b.proto))
+                .then_with(|| a.local.cmp(&b.local))
+        });
+        sockets
+    }
+}
+
+/// `0100007F:0035` or the 32-digit IPv6 form from /proc/net/*. Addresses
+/// are printed as native-endian 32-bit words.
+fn parse_socket_addr(field: &str) -> Option<(std::net::IpAddr, u16)> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..ip.len()).step_by(8) {
+        let word = u32::from_str_radix(ip.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => std::net::IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            let v6 = Ipv6Addr::from(octets);
+            // v4-mapped addresses on dual-stack sockets
+            match v6.to_ipv4_mapped() {
+                Some(v4) => std::net::IpAddr::V4(v4),
+                None => std::net::IpAddr::V6(v6),
+            }
+        }
+        _ => return None,
+    };
+    Some((ip, port))
+}
+
+fn socket_state(hex: &str, tcp: bool) -> &'static str {
+    match (u8::from_str_radix(hex, 16).unwrap_or(0), tcp) {
+        (0x01, _) => "ESTAB",
+        (0x07, false) => "UNCONN",
+        (0x02, true) => "SYN-SENT",
+        (0x03, true) => "SYN-RECV",
+        (0x04, true) => "FIN-WAIT-1",
+        (0x05, true) => "FIN-WAIT-2",
+        (0x06, true) => "TIME-WAIT",
+        (0x07, true) => "CLOSE",
+        (0x08, true) => "CLOSE-WAIT",
+        (0x09, true) => "LAST-ACK",
+        (0x0A, true) => "LISTEN",
+        (0x0B, true) => "CLOSING",
+        _ => "?",
+    }
+}
+
+/// Which PID holds each socket inode, from /proc/*/fd symlinks
+fn socket_owners(inodes: &HashSet<u64>) -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(procs) = fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in procs.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            if let Some(inode) = inode
+                && inodes.contains(&inode)
+            {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+        if owners.len() == inodes.len() {
+            break;
+        }
+    }
+    owners
+}
+
+/// Innermost non-slice unit in the process's cgroup path
+fn cgroup_unit(pid: u32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    // cgroup v2 is "0::/path"; on v1 the name=systemd hierarchy has the units
+    let path = content
+        .lines()
+        .find_map(|l| l.strip_prefix("0::"))
+        .or_else(|| {
+            content
+                .lines()
+                .find_map(|l| l.split_once(":name=systemd:").map(|(_, p)| p))
+        })?;
+    path.rsplit('/')
+        .find(|c| {
+            [".service", ".scope", ".socket", ".mount", ".swap"]
+                .iter()
+                .any(|suffix| c.ends_with(suffix))
+        })
+        .map(|c| c.to_string())
+}
+
 impl NetworkInfo {
-    fn gather() -> Result<Self> {
+    pub fn gather() -> Result<Self> {
         let interfaces = Self::get_interfaces()?;
-        let routes = Self::get_routes()?;
+        let routes = Self::get_routes("/proc/net")?;
 
         Ok(Self { interfaces, routes })
     }
 
+    /// Interfaces and routes inside another namespace. /sys/class/net shows
+    /// the namespace sysfs was mounted in, so links come from getifaddrs and
+    /// the counters from /proc/net/dev instead.
+    fn gather_netns(netns: &Path) -> Result<Self> {
+        in_netns(netns, || {
+            let interfaces = Self::get_netns_interfaces()?;
+            let routes = Self::get_routes(THREAD_PROC_NET)?;
+            Ok(Self { interfaces, routes })
+        })
+    }
+
+    /// The report's Network section: one table of interfaces with their
+    /// addresses, one of the main routing table
+    pub fn write_report(&self, out: &mut String) {
+        out.push_str("## Network\n\n");
+        out.push_str("| Interface | State | MAC | MTU | Addresses |\n|---|---|---|---|---|\n");
+        for iface in &self.interfaces {
+            let addresses: Vec<&str> = iface
+                .ipv4
+                .iter()
+                .chain(&iface.ipv6)
+                .map(String::as_str)
+                .collect();
+            out.push_str(&crate::report::table_row(&[
+                &iface.name,
+                &iface.state,
+                iface.mac.as_deref().unwrap_or("-"),
+                &iface.mtu.map_or("-".to_string(), |m| m.to_string()),
+                &addresses.join(", "),
+            ]));
+        }
+        out.push_str("\n| Destination | Gateway | Interface | Metric |\n|---|---|---|---|\n");
+        for route in &self.routes {
+            out.push_str(&crate::report::table_row(&[
+                &route.destination,
+                route.gateway.as_deref().unwrap_or("-"),
+                &route.interface,
+                &route.metric.map_or("-".to_string(), |m| m.to_string()),
+            ]));
+        }
+        out.push('\n');
+    }
+
     fn get_interfaces() -> Result<Vec<Interface>> {
         let mut interfaces = Vec::new();
         let addr_map = Self::get_ip_addresses()?;
@@ -74,6 +679,10 @@ impl NetworkInfo {
 
                 let rx_bytes = Self::read_stat(&iface_path, "statistics/rx_bytes");
                 let tx_bytes = Self::read_stat(&iface_path, "statistics/tx_bytes");
+                let lease = fs::read_to_string(iface_path.join("ifindex"))
+                    .ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .and_then(DhcpLease::read);
 
                 let (ipv4, ipv6) = addr_map.get(&name).cloned().unwrap_or_default();
 
@@ -86,6 +695,7 @@ impl NetworkInfo {
                     ipv6,
                     rx_bytes,
                     tx_bytes,
+                    lease,
                 });
             }
         }
@@ -99,6 +709,96 @@ impl NetworkInfo {
         Ok(interfaces)
     }
 
+    /// Interfaces of the current thread's namespace without sysfs. The
+    /// MTU isn't shown; it would take an ioctl per link.
+    fn get_netns_interfaces() -> Result<Vec<Interface>> {
+        let addr_map = Self::get_ip_addresses()?;
+        let links = Self::get_links();
+        let dev = fs::read_to_string(format!("{}/dev", THREAD_PROC_NET))?;
+
+        let mut interfaces: Vec<Interface> = dev
+            .lines()
+            .skip(2)
+            .filter_map(|line| {
+                let (name, counters) = line.split_once(':')?;
+                let name = name.trim().to_string();
+                if name == "lo" {
+                    return None;
+                }
+                // rx bytes is the first counter, tx bytes the ninth
+                let counters: Vec<u64> = counters
+                    .split_whitespace()
+                    .map(|c| c.parse().unwrap_or(0))
+                    .collect();
+                let (flags, mac) = links.get(&name).cloned().unwrap_or((0, None));
+                let state = if flags & libc::IFF_UP as u32 == 0 {
+                    "down"
+                } else if flags & libc::IFF_RUNNING as u32 != 0 {
+                    "up"
+                } else {
+                    "no-carrier"
+                };
+                let (ipv4, ipv6) = addr_map.get(&name).cloned().unwrap_or_default();
+                Some(Interface {
+                    name,
+                    state: state.to_string(),
+                    mac,
+                    mtu: None,
+                    ipv4,
+                    ipv6,
+                    rx_bytes: counters.first().copied().unwrap_or(0),
+                    tx_bytes: counters.get(8).copied().unwrap_or(0),
+                    // networkd's lease files are for the host's links
+                    lease: None,
+                })
+            })
+            .collect();
+
+        interfaces.sort_by(|a, b| {
+            let a_up = a.state == "up";
+            let b_up = b.state == "up";
+            b_up.cmp(&a_up).then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(interfaces)
+    }
+
+    /// Flags and MAC address of each link, from the AF_PACKET entries of
+    /// getifaddrs
+    fn get_links() -> HashMap<String, (u32, Option<String>)> {
+        let mut map = HashMap::new();
+
+        let mut ifap: *mut libc::ifaddrs = ptr::null_mut();
+        if unsafe { libc::getifaddrs(&mut ifap as *mut *mut libc::ifaddrs) } != 0 {
+            return map;
+        }
+
+        let mut cur = ifap;
+        while !cur.is_null() {
+            let ifa = unsafe { &*cur };
+            if !ifa.ifa_name.is_null()
+                && !ifa.ifa_addr.is_null()
+                && unsafe { (*ifa.ifa_addr).sa_family as i32 } == libc::AF_PACKET
+            {
+                let name = unsafe { CStr::from_ptr(ifa.ifa_name) }
+                    .to_string_lossy()
+                    .to_string();
+                let sll = unsafe { *(ifa.ifa_addr as *const libc::sockaddr_ll) };
+                let len = (sll.sll_halen as usize).min(sll.sll_addr.len());
+                let mac = sll.sll_addr[..len]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<Vec<_>>()
+                    .join(":");
+                let mac = Some(mac).filter(|m| !m.is_empty() && m != "00:00:00:00:00:00");
+                map.insert(name, (ifa.ifa_flags, mac));
+            }
+            cur = unsafe { (*cur).ifa_next };
+        }
+
+        unsafe { libc::freeifaddrs(ifap) };
+        map
+    }
+
     fn read_stat(path: &std::path::Path, file: &str) -> u64 {
         fs::read_to_string(path.join(file))
             .ok()
@@ -151,10 +851,10 @@ impl NetworkInfo {
         Ok(map)
     }
 
-    fn get_routes() -> Result<Vec<Route>> {
+    fn get_routes(proc_net: &str) -> Result<Vec<Route>> {
         let mut routes = Vec::new();
 
-        if let Ok(content) = fs::read_to_string("/proc/net/route") {
+        if let Ok(content) = fs::read_to_string(format!("{}/route", proc_net)) {
             for line in content.lines().skip(1) {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 if parts.len() >= 8 {
@@ -225,52 +925,665 @@ impl NetworkInfo {
             "invalid".to_string()
         }
     }
+}
+
+pub struct NetworkContext {
+    info: Option<NetworkInfo>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<NetworkInfo>>,
+    messages: Messages,
+    /// Position in `visible`, not in the interface list
+    selected_interface: usize,
+    /// Indices of the interfaces passing the filter and the virtual toggle
+    visible: Vec<usize>,
+    filter: String,
+    show_filter: bool,
+    /// Hide interfaces matching `[network] virtual_interfaces`
+    hide_virtual: bool,
+    virtual_patterns: Vec<String>,
+    sockets: Vec<Socket>,
+    /// Interface the socket list was built for
+    sockets_for: Option<String>,
+    sockets_pending: Pending<Vec<Socket>>,
+    socket_list: ListState,
+    /// j/k move through the sockets instead of the interfaces
+    sockets_focused: bool,
+    /// Show the routing table even when the terminal is too short for it
+    routes_expanded: bool,
+    jump_to_unit: Option<String>,
+    /// Namespace the interfaces and routes come from; None for our own
+    netns: Option<Netns>,
+    netns_picker: Option<NetnsPicker>,
+    /// The nftables ruleset replaces the interface list
+    firewall_shown: bool,
+    ruleset: Option<Ruleset>,
+    firewall_error: Option<RootworkError>,
+    firewall_pending: Pending<Result<Ruleset>>,
+    firewall_list: ListState,
+    systemd: SystemdClient,
+    /// `[network] check_hostname` and `probe_url`
+    check_hostname: String,
+    probe_url: String,
+    checks: Option<Vec<Check>>,
+    /// Per-connection throughput replaces the interface list
+    bandwidth_shown: bool,
+    connections: Vec<ConnRate>,
+    /// Time and (sent, received) counters by socket cookie of the previous
+    /// sample, for the rates
+    last_sample: Option<(Instant, ByteCounters)>,
+    bandwidth_pending: Pending<Result<ConnSample>>,
+    bandwidth_error: Option<RootworkError>,
+    bandwidth_list: ListState,
+    /// Traceroute target being typed; the popup shows it above the hops
+    trace_input: Option<String>,
+    trace: Option<Trace>,
+}
 
-    fn format_bytes(bytes: u64) -> String {
-        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
-        let mut size = bytes as f64;
-        let mut unit_idx = 0;
+/// Container and VM plumbing, hidden by `v`
+const DEFAULT_VIRTUAL_INTERFACES: &str =
+    "veth*, docker*, br-*, virbr*, vnet*, cni*, flannel*, cali*";
+
+/// Name looked up through resolved by the connectivity checks
+const DEFAULT_CHECK_HOSTNAME: &str = "example.com";
+/// Answers plain HTTP without redirects, as NetworkManager relies on
+const DEFAULT_PROBE_URL: &str = "http://nmcheck.gnome.org/check_network_status.txt";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// One row of the connectivity checks popup
+struct Check {
+    name: &'static str,
+    target: String,
+    pending: Pending<Result<String>>,
+    /// What came back, latency included, or why it failed
+    result: Option<Result<String, String>>,
+}
 
-        while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_idx += 1;
+impl Check {
+    fn spawn(name: &'static str, target: String, pending: Pending<Result<String>>) -> Self {
+        Self {
+            name,
+            target,
+            pending,
+            result: None,
         }
+    }
 
-        format!("{:.1} {}", size, UNITS[unit_idx])
+    /// A check that can't run, e.g. without a default route
+    fn skipped(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            target: "-".to_string(),
+            pending: Pending::idle(),
+            result: Some(Err(reason.to_string())),
+        }
     }
 }
 
-pub struct NetworkContext {
-    info: Option<NetworkInfo>,
+/// Rounds of traceroute to one target, mtr style, until the popup closes
+struct Trace {
+    target: String,
+    addr: Option<Ipv4Addr>,
+    resolve: Pending<Result<Ipv4Addr>>,
+    /// Namespace the probes go out from
+    netns: Option<PathBuf>,
+    round: u16,
+    round_started: Instant,
+    pending: Pending<Result<Vec<probe::HopReply>>>,
+    hops: Vec<HopStats>,
     error: Option<String>,
-    selected_interface: usize,
-    scroll_offset: usize,
+    list: ListState,
 }
 
-impl NetworkContext {
-    pub fn new() -> Self {
-        let (info, error) = match NetworkInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather network info: {}", e))),
+/// Rounds wait for late replies this long, and start at most this often
+const TRACE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Connection counters are sampled this often while the bandwidth view is
+/// shown, independent of the tab's refresh interval
+const BANDWIDTH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct HopStats {
+    /// Last router that answered for this TTL; None while it never did
+    addr: Option<Ipv4Addr>,
+    sent: u32,
+    received: u32,
+    last: Option<Duration>,
+    best: Option<Duration>,
+    worst: Option<Duration>,
+    total: Duration,
+}
+
+impl HopStats {
+    fn record(&mut self, reply: probe::HopReply) {
+        self.sent += 1;
+        let Some((addr, rtt)) = reply else {
+            self.last = None;
+            return;
         };
+        self.addr = Some(addr);
+        self.received += 1;
+        self.last = Some(rtt);
+        self.best = Some(self.best.map_or(rtt, |b| b.min(rtt)));
+        self.worst = Some(self.worst.map_or(rtt, |w| w.max(rtt)));
+        self.total += rtt;
+    }
 
+    fn loss_percent(&self) -> u32 {
+        if self.sent == 0 {
+            return 0;
+        }
+        (self.sent - self.received) * 100 / self.sent
+    }
+}
+
+impl Trace {
+    /// Names go through NSS; addresses are used as typed
+    fn start(target: String, netns: Option<PathBuf>) -> Self {
+        let resolve = match target.parse::<Ipv4Addr>() {
+            Ok(addr) => Pending::spawn(move || Ok(addr)),
+            Err(_) => {
+                let name = target.clone();
+                Pending::spawn(move || {
+                    std::net::ToSocketAddrs::to_socket_addrs(&(name.as_str(), 0))
+                        .with_context(|| format!("Can't resolve {}", name))?
+                        .find_map(|addr| match addr.ip() {
+                            IpAddr::V4(ip) => Some(ip),
+                            IpAddr::V6(_) => None,
+                        })
+                        .with_context(|| format!("{} has no IPv4 address", name))
+                })
+            }
+        };
         Self {
-            info,
-            error,
-            selected_interface: 0,
-            scroll_offset: 0,
+            target,
+            addr: None,
+            resolve,
+            netns,
+            round: 0,
+            round_started: Instant::now(),
+            pending: Pending::idle(),
+            hops: Vec::new(),
+            error: None,
+            list: ListState::new(),
         }
     }
 
+    fn tick(&mut self) {
+        if let Some(result) = self.resolve.poll() {
+            match result {
+                Ok(addr) => self.addr = Some(addr),
+                Err(e) => self.error = Some(format!("{:#}", e)),
+            }
+        }
+
+        if let Some(result) = self.pending.poll() {
+            match result {
+                Ok(replies) => {
+                    self.hops.resize_with(replies.len(), HopStats::default);
+                    for (hop, reply) in self.hops.iter_mut().zip(replies) {
+                        hop.record(reply);
+                    }
+                    self.list.clamp(self.hops.len());
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(format!("{:#}", e)),
+            }
+        }
+
+        let Some(addr) = self.addr else {
+            return;
+        };
+        // A failing round (no route, no ICMP socket) isn't retried
+        if self.pending.is_pending()
+            || (self.round > 0 && self.error.is_some())
+            || self.round_started.elapsed() < TRACE_INTERVAL
+        {
+            return;
+        }
+        self.round = self.round.wrapping_add(1);
+        self.round_started = Instant::now();
+        let (round, netns) = (self.round, self.netns.clone());
+        self.pending = Pending::spawn(move || {
+            in_shown_netns(netns.as_deref(), move || {
+                probe::trace_round(addr, round, TRACE_INTERVAL)
+            })
+        });
+    }
+}
+
+/// Popup choosing the namespace to look into; the first row is our own
+struct NetnsPicker {
+    namespaces: Vec<Netns>,
+    pending: Pending<Result<Vec<Netns>>>,
+    list: ListState,
+}
+
+impl NetworkContext {
+    pub fn new(systemd: &SystemdClient, config: &Config, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            info: None,
+            error: None,
+            pending: Pending::idle(),
+            messages: messages.clone(),
+            selected_interface: 0,
+            visible: Vec::new(),
+            filter: String::new(),
+            show_filter: false,
+            hide_virtual: false,
+            virtual_patterns: config
+                .get("network", "virtual_interfaces")
+                .unwrap_or(DEFAULT_VIRTUAL_INTERFACES)
+                .split([',', ' '])
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect(),
+            sockets: Vec::new(),
+            sockets_for: None,
+            sockets_pending: Pending::idle(),
+            socket_list: ListState::new(),
+            sockets_focused: false,
+            routes_expanded: false,
+            jump_to_unit: None,
+            netns: None,
+            netns_picker: None,
+            firewall_shown: false,
+            ruleset: None,
+            firewall_error: None,
+            firewall_pending: Pending::idle(),
+            firewall_list: ListState::new(),
+            systemd: systemd.clone(),
+            check_hostname: config
+                .get("network", "check_hostname")
+                .unwrap_or(DEFAULT_CHECK_HOSTNAME)
+                .to_string(),
+            probe_url: config
+                .get("network", "probe_url")
+                .unwrap_or(DEFAULT_PROBE_URL)
+                .to_string(),
+            checks: None,
+            bandwidth_shown: false,
+            connections: Vec::new(),
+            last_sample: None,
+            bandwidth_pending: Pending::idle(),
+            bandwidth_error: None,
+            bandwidth_list: ListState::new(),
+            trace_input: None,
+            trace: None,
+        };
+        ctx.refresh();
+        ctx
+    }
+
     fn refresh(&mut self) {
-        let (info, error) = match NetworkInfo::gather() {
-            Ok(info) => (Some(info), None),
-            Err(e) => (None, Some(format!("Failed to gather network info: {}", e))),
+        if !self.pending.is_pending() {
+            self.pending = match &self.netns {
+                Some(netns) => {
+                    let path = netns.path.clone();
+                    Pending::spawn(move || NetworkInfo::gather_netns(&path))
+                }
+                None => Pending::spawn(NetworkInfo::gather),
+            };
+        }
+        if self.firewall_shown && !self.firewall_pending.is_pending() {
+            self.firewall_pending = Pending::spawn(Ruleset::gather);
+        }
+    }
+
+    fn toggle_firewall(&mut self) {
+        self.firewall_shown = !self.firewall_shown;
+        if self.firewall_shown {
+            self.bandwidth_shown = false;
+            self.last_sample = None;
+            self.connections.clear();
+            self.refresh();
+        }
+    }
+
+    fn toggle_bandwidth(&mut self) {
+        self.bandwidth_shown = !self.bandwidth_shown;
+        if self.bandwidth_shown {
+            self.firewall_shown = false;
+        } else {
+            // Rates across a gap would average over it
+            self.last_sample = None;
+            self.connections.clear();
+        }
+    }
+
+    /// Start the next counter sample once the interval has passed
+    fn sample_bandwidth(&mut self) {
+        if !self.bandwidth_shown
+            || self.bandwidth_pending.is_pending()
+            || self
+                .last_sample
+                .as_ref()
+                .is_some_and(|(at, _)| at.elapsed() < BANDWIDTH_INTERVAL)
+        {
+            return;
+        }
+        let netns = self.netns.as_ref().map(|n| n.path.clone());
+        self.bandwidth_pending = Pending::spawn(move || sample_connections(netns.as_deref()));
+    }
+
+    fn apply_bandwidth_sample(&mut self, sample: ConnSample) {
+        let now = Instant::now();
+        let previous = self.last_sample.take();
+        let mut counters = HashMap::new();
+        self.connections = sample
+            .into_iter()
+            .map(|(conn, owner)| {
+                counters.insert(conn.cookie, (conn.bytes_sent, conn.bytes_received));
+                let (tx_rate, rx_rate) = match &previous {
+                    Some((at, last)) => match last.get(&conn.cookie) {
+                        Some((tx, rx)) => {
+                            let secs = now.duration_since(*at).as_secs_f64().max(0.001);
+                            (
+                                Some(conn.bytes_sent.saturating_sub(*tx) as f64 / secs),
+                                Some(conn.bytes_received.saturating_sub(*rx) as f64 / secs),
+                            )
+                        }
+                        None => (None, None),
+                    },
+                    None => (None, None),
+                };
+                ConnRate {
+                    conn,
+                    owner,
+                    tx_rate,
+                    rx_rate,
+                }
+            })
+            .collect();
+        // Busiest first; on the first sample, the biggest talkers overall
+        self.connections.sort_by(|a, b| {
+            b.rate().total_cmp(&a.rate()).then_with(|| {
+                (b.conn.bytes_sent + b.conn.bytes_received)
+                    .cmp(&(a.conn.bytes_sent + a.conn.bytes_received))
+            })
+        });
+        self.bandwidth_list.clamp(self.connections.len());
+        self.last_sample = Some((now, counters));
+    }
+
+    /// Chain headers and rules, as the firewall view lists them
+    fn firewall_rows(&self) -> Vec<(usize, Option<usize>)> {
+        let Some(ruleset) = &self.ruleset else {
+            return Vec::new();
         };
-        self.info = info;
-        self.error = error;
+        ruleset
+            .chains
+            .iter()
+            .enumerate()
+            .flat_map(|(c, chain)| {
+                std::iter::once((c, None)).chain((0..chain.rules.len()).map(move |r| (c, Some(r))))
+            })
+            .collect()
+    }
+
+    /// Owner of a socket listed for the selected interface that the rule
+    /// matches the port of, to highlight it. The selected socket comes
+    /// first while the sockets have the focus.
+    fn rule_socket(&self, rule: &Rule) -> Option<(&Socket, bool)> {
+        if self.sockets_focused
+            && let Some(socket) = self.sockets.get(self.socket_list.selected)
+            && socket
+                .local_port()
+                .is_some_and(|port| rule.matches_port(port))
+        {
+            return Some((socket, true));
+        }
+        self.sockets
+            .iter()
+            .filter(|s| s.state == "LISTEN" || s.state == "UNCONN")
+            .find(|s| s.local_port().is_some_and(|port| rule.matches_port(port)))
+            .map(|s| (s, false))
+    }
+
+    /// Ping the default gateway, look up `check_hostname` and fetch
+    /// `probe_url`, all at once. Ping and HTTP go out from the namespace
+    /// shown; resolved only answers for the host's.
+    fn run_checks(&mut self) {
+        let netns = self.netns.as_ref().map(|n| n.path.clone());
+
+        let gateway = self.info.as_ref().and_then(|info| {
+            info.routes
+                .iter()
+                .filter(|r| r.destination == "default")
+                .min_by_key(|r| r.metric.unwrap_or(0))
+                .and_then(|r| r.gateway.as_deref()?.parse::<Ipv4Addr>().ok())
+        });
+        let ping = match gateway {
+            Some(gateway) => {
+                let netns = netns.clone();
+                Check::spawn(
+                    "Gateway ping",
+                    gateway.to_string(),
+                    Pending::spawn(move || {
+                        in_shown_netns(netns.as_deref(), move || {
+                            let rtt = probe::ping(gateway, CHECK_TIMEOUT)?;
+                            Ok(format!("reply in {}", format_latency(rtt)))
+                        })
+                    }),
+                )
+            }
+            None => Check::skipped("Gateway ping", "no IPv4 default route"),
+        };
+
+        let hostname = self.check_hostname.clone();
+        let dns = Check::spawn(
+            "DNS lookup",
+            hostname.clone(),
+            Pending::spawn_async(resolve_hostname(self.systemd.clone(), hostname)),
+        );
+
+        let url = self.probe_url.clone();
+        let http = Check::spawn(
+            "HTTP probe",
+            url.clone(),
+            Pending::spawn(move || {
+                in_shown_netns(netns.as_deref(), move || {
+                    let (status, elapsed) = probe::http_head(&url, CHECK_TIMEOUT)?;
+                    Ok(format!("{} in {}", status, format_latency(elapsed)))
+                })
+            }),
+        );
+
+        self.checks = Some(vec![ping, dns, http]);
+    }
+
+    fn handle_trace_input_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+        let Some(input) = self.trace_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let target = input.trim().to_string();
+                self.trace_input = None;
+                if target.is_empty() {
+                    return;
+                }
+                let netns = self.netns.as_ref().map(|n| n.path.clone());
+                self.trace = Some(Trace::start(target, netns));
+            }
+            // Back to the running trace, if any
+            KeyCode::Esc => self.trace_input = None,
+            _ => {}
+        }
+    }
+
+    fn handle_trace_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+        let len = trace.hops.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => trace.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => trace.list.up(len),
+            KeyCode::Char('t') => self.trace_input = Some(trace.target.clone()),
+            KeyCode::Char('r') => {
+                let (target, netns) = (trace.target.clone(), trace.netns.clone());
+                self.trace = Some(Trace::start(target, netns));
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.trace = None,
+            _ => {}
+        }
+    }
+
+    fn open_netns_picker(&mut self) {
+        self.netns_picker = Some(NetnsPicker {
+            namespaces: Vec::new(),
+            pending: Pending::spawn(Netns::list),
+            list: ListState::new(),
+        });
+    }
+
+    fn handle_netns_picker_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+        let Some(picker) = self.netns_picker.as_mut() else {
+            return;
+        };
+        // Row 0 is our own namespace
+        let len = picker.namespaces.len() + 1;
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => picker.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => picker.list.up(len),
+            KeyCode::Char('g') => picker.list.top(len),
+            KeyCode::Char('G') => picker.list.bottom(len),
+            KeyCode::Char('r') if !picker.pending.is_pending() => {
+                picker.pending = Pending::spawn(Netns::list)
+            }
+            KeyCode::Enter => {
+                let chosen = match picker.list.selected {
+                    0 => None,
+                    i => picker.namespaces.get(i - 1).cloned(),
+                };
+                self.netns_picker = None;
+                self.set_netns(chosen);
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => self.netns_picker = None,
+            _ => {}
+        }
+    }
+
+    /// Look into another namespace, or back into ours with None
+    fn set_netns(&mut self, netns: Option<Netns>) {
+        self.netns = netns;
+        self.info = None;
+        self.error = None;
+        self.visible.clear();
         self.selected_interface = 0;
-        self.scroll_offset = 0;
+        self.sockets.clear();
+        self.sockets_for = None;
+        self.sockets_focused = false;
+        self.connections.clear();
+        self.last_sample = None;
+        // Whatever is running gathers the old namespace
+        self.pending = Pending::idle();
+        self.sockets_pending = Pending::idle();
+        self.bandwidth_pending = Pending::idle();
+        self.refresh();
+    }
+
+    fn selected(&self) -> Option<&Interface> {
+        let index = *self.visible.get(self.selected_interface)?;
+        self.info.as_ref()?.interfaces.get(index)
+    }
+
+    fn is_virtual(&self, name: &str) -> bool {
+        self.virtual_patterns.iter().any(|p| glob_match(p, name))
+    }
+
+    fn apply_filter(&mut self) {
+        let selected = self.selected().map(|i| i.name.clone());
+        self.update_visible(selected);
+    }
+
+    /// Recompute `visible`, keeping `selected` selected if it still shows
+    fn update_visible(&mut self, selected: Option<String>) {
+        let needle = self.filter.to_lowercase();
+        self.visible = match &self.info {
+            Some(info) => info
+                .interfaces
+                .iter()
+                .enumerate()
+                .filter(|(_, iface)| !(self.hide_virtual && self.is_virtual(&iface.name)))
+                .filter(|(_, iface)| {
+                    fuzzy_match_score(&iface.name.to_lowercase(), &needle).is_some()
+                })
+                .map(|(i, _)| i)
+                .collect(),
+            None => Vec::new(),
+        };
+        let position = selected.and_then(|name| {
+            let info = self.info.as_ref()?;
+            self.visible
+                .iter()
+                .position(|i| info.interfaces[*i].name == name)
+        });
+        self.selected_interface = position
+            .unwrap_or(self.selected_interface)
+            .min(self.visible.len().saturating_sub(1));
+    }
+
+    /// Rescan sockets when the selection moved to another interface
+    fn load_sockets(&mut self, force: bool) {
+        let Some(iface) = self.selected() else {
+            return;
+        };
+        if self.sockets_pending.is_pending()
+            || (!force && self.sockets_for.as_deref() == Some(iface.name.as_str()))
+        {
+            return;
+        }
+        let name = iface.name.clone();
+        let addresses: Vec<String> = iface.ipv4.iter().chain(&iface.ipv6).cloned().collect();
+        self.sockets_pending = match &self.netns {
+            Some(netns) => {
+                let path = netns.path.clone();
+                Pending::spawn(move || Socket::scan_netns(addresses, &path))
+            }
+            None => Pending::spawn(move || Socket::scan(&addresses)),
+        };
+        if self.sockets_for.as_deref() != Some(name.as_str()) {
+            self.socket_list.reset();
+        }
+        self.sockets_for = Some(name);
+    }
+
+    fn jump_to_owner(&mut self) {
+        let Some(socket) = self.sockets.get(self.socket_list.selected) else {
+            return;
+        };
+        self.jump_to_unit_of(socket.owner.as_ref().and_then(|o| o.unit.clone()));
+    }
+
+    fn jump_to_connection_owner(&mut self) {
+        let Some(conn) = self.connections.get(self.bandwidth_list.selected) else {
+            return;
+        };
+        self.jump_to_unit_of(conn.owner.as_ref().and_then(|o| o.unit.clone()));
+    }
+
+    fn jump_to_unit_of(&mut self, unit: Option<String>) {
+        match unit {
+            Some(unit) => self.jump_to_unit = Some(unit),
+            // Without systemd as init nothing here belongs to a unit
+            None if container::in_container() && !Path::new("/run/systemd/system").exists() => self
+                .messages
+                .warn("No units in this container: it doesn't run systemd"),
+            None => self.messages.warn("No unit found for this socket's owner"),
+        }
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
     }
 
     fn move_up(&mut self) {
@@ -280,10 +1593,8 @@ impl NetworkContext {
     }
 
     fn move_down(&mut self) {
-        if let Some(ref info) = self.info {
-            if !info.interfaces.is_empty() && self.selected_interface + 1 < info.interfaces.len() {
-                self.selected_interface += 1;
-            }
+        if self.selected_interface + 1 < self.visible.len() {
+            self.selected_interface += 1;
         }
     }
 
@@ -292,12 +1603,8 @@ impl NetworkContext {
     }
 
     fn page_down(&mut self) {
-        if let Some(ref info) = self.info {
-            if !info.interfaces.is_empty() {
-                self.selected_interface =
-                    (self.selected_interface + 5).min(info.interfaces.len() - 1);
-            }
-        }
+        self.selected_interface =
+            (self.selected_interface + 5).min(self.visible.len().saturating_sub(1));
     }
 
     fn go_top(&mut self) {
@@ -305,11 +1612,7 @@ impl NetworkContext {
     }
 
     fn go_bottom(&mut self) {
-        if let Some(ref info) = self.info {
-            if !info.interfaces.is_empty() {
-                self.selected_interface = info.interfaces.len() - 1;
-            }
-        }
+        self.selected_interface = self.visible.len().saturating_sub(1);
     }
 }
 
@@ -318,21 +1621,185 @@ impl Context for NetworkContext {
         "Network"
     }
 
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
     fn draw(&self, f: &mut Frame, area: Rect) {
+        // Short terminals fold the routes into one line unless asked for
+        let short = area.height < SHORT_HEIGHT;
+        let routes_height = if short && !self.routes_expanded { 1 } else { 6 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(6)])
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if short { 7 } else { 10 }),
+                Constraint::Length(routes_height),
+            ])
             .split(area);
 
-        // Interface list
-        draw_interfaces(self, f, chunks[0]);
+        // Interface list, or the firewall or bandwidth view in its place
+        if self.bandwidth_shown {
+            draw_bandwidth(self, f, chunks[0]);
+        } else if self.firewall_shown {
+            draw_firewall(self, f, chunks[0]);
+        } else {
+            draw_interfaces(self, f, chunks[0]);
+        }
+
+        // Sockets on the selected interface
+        draw_sockets(self, f, chunks[1]);
 
         // Routes
-        draw_routes(self, f, chunks[1]);
+        draw_routes(self, f, chunks[2]);
+
+        if let Some(picker) = &self.netns_picker {
+            draw_netns_picker(self, picker, f, area);
+        }
+        if let Some(checks) = &self.checks {
+            draw_checks(self, checks, f, area);
+        }
+        if self.trace.is_some() || self.trace_input.is_some() {
+            draw_trace(self, f, area);
+        }
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+        if self.netns_picker.is_some() {
+            self.handle_netns_picker_key(key);
+            return;
+        }
+        if self.trace_input.is_some() {
+            self.handle_trace_input_key(key);
+            return;
+        }
+        if self.trace.is_some() {
+            self.handle_trace_key(key);
+            return;
+        }
+        if self.show_filter {
+            match key.code {
+                KeyCode::Esc => {
+                    self.show_filter = false;
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Enter => self.show_filter = false,
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.checks.is_some() {
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Char('r') => self.run_checks(),
+                KeyCode::Esc | KeyCode::Char('q') => self.checks = None,
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Char('c') => {
+                self.run_checks();
+                return;
+            }
+            KeyCode::Char('t') => {
+                self.trace_input = Some(String::new());
+                return;
+            }
+            KeyCode::Char('n') => {
+                self.open_netns_picker();
+                return;
+            }
+            KeyCode::Char('F') => {
+                self.toggle_firewall();
+                return;
+            }
+            KeyCode::Char('B') => {
+                self.toggle_bandwidth();
+                return;
+            }
+            KeyCode::Char('s') => {
+                self.sockets_focused = !self.sockets_focused;
+                return;
+            }
+            KeyCode::Char('R') => {
+                self.routes_expanded = !self.routes_expanded;
+                return;
+            }
+            KeyCode::Esc if self.sockets_focused => {
+                self.sockets_focused = false;
+                return;
+            }
+            _ => {}
+        }
+
+        if self.sockets_focused {
+            let len = self.sockets.len();
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.socket_list.down(len),
+                KeyCode::Char('k') | KeyCode::Up => self.socket_list.up(len),
+                KeyCode::Char(' ') | KeyCode::PageDown => self.socket_list.page_down(len),
+                KeyCode::Char('b') | KeyCode::PageUp => self.socket_list.page_up(len),
+                KeyCode::Char('g') => self.socket_list.top(len),
+                KeyCode::Char('G') => self.socket_list.bottom(len),
+                KeyCode::Enter => self.jump_to_owner(),
+                KeyCode::Char('r') => self.load_sockets(true),
+                _ => {}
+            }
+            return;
+        }
+
+        if self.bandwidth_shown {
+            let len = self.connections.len();
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.bandwidth_list.down(len),
+                KeyCode::Char('k') | KeyCode::Up => self.bandwidth_list.up(len),
+                KeyCode::Char(' ') | KeyCode::PageDown => self.bandwidth_list.page_down(len),
+                KeyCode::Char('b') | KeyCode::PageUp => self.bandwidth_list.page_up(len),
+                KeyCode::Char('g') => self.bandwidth_list.top(len),
+                KeyCode::Char('G') => self.bandwidth_list.bottom(len),
+                KeyCode::Enter => self.jump_to_connection_owner(),
+                KeyCode::Esc => self.toggle_bandwidth(),
+                _ => {}
+            }
+            return;
+        }
+
+        if self.firewall_shown {
+            let len = self.firewall_rows().len();
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.firewall_list.down(len),
+                KeyCode::Char('k') | KeyCode::Up => self.firewall_list.up(len),
+                KeyCode::Char(' ') | KeyCode::PageDown => self.firewall_list.page_down(len),
+                KeyCode::Char('b') | KeyCode::PageUp => self.firewall_list.page_up(len),
+                KeyCode::Char('g') => self.firewall_list.top(len),
+                KeyCode::Char('G') => self.firewall_list.bottom(len),
+                KeyCode::Char('r') => self.refresh(),
+                KeyCode::Esc => self.firewall_shown = false,
+                _ => {}
+            }
+            return;
+        }
+
         match key.code {
+            KeyCode::Char('/') => self.show_filter = true,
+            KeyCode::Char('v') => {
+                self.hide_virtual = !self.hide_virtual;
+                self.apply_filter();
+            }
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
             crossterm::event::KeyCode::Char('r') => self.refresh(),
             crossterm::event::KeyCode::Char('j') | crossterm::event::KeyCode::Down => {
                 self.move_down()
@@ -350,16 +1817,119 @@ impl Context for NetworkContext {
         }
     }
 
-    async fn tick(&mut self) {}
+    fn captures_input(&self) -> bool {
+        self.trace_input.is_some() || self.show_filter
+    }
+
+    async fn tick(&mut self) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.tick();
+        }
+
+        if let Some(picker) = self.netns_picker.as_mut()
+            && let Some(result) = picker.pending.poll()
+        {
+            match result {
+                Ok(namespaces) => {
+                    picker.list.clamp(namespaces.len() + 1);
+                    picker.namespaces = namespaces;
+                }
+                Err(e) => self.messages.error(format!("Network namespaces: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.bandwidth_pending.poll() {
+            match result {
+                Ok(sample) => {
+                    self.apply_bandwidth_sample(sample);
+                    self.bandwidth_error = None;
+                }
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to list TCP connections", &e);
+                    if self.bandwidth_error.is_none() {
+                        self.messages.error(err.to_string());
+                    }
+                    self.bandwidth_error = Some(err);
+                }
+            }
+        }
+        self.sample_bandwidth();
+
+        for check in self.checks.iter_mut().flatten() {
+            if let Some(result) = check.pending.poll() {
+                check.result = Some(result.map_err(|e| format!("{:#}", e)));
+            }
+        }
+
+        if let Some(result) = self.firewall_pending.poll() {
+            match result {
+                Ok(ruleset) => {
+                    self.ruleset = Some(ruleset);
+                    self.firewall_error = None;
+                }
+                Err(e) => {
+                    let err = RootworkError::from_anyhow("Failed to list the nftables ruleset", &e);
+                    if self.firewall_error.is_none() {
+                        self.messages.error(err.to_string());
+                    }
+                    self.ruleset = None;
+                    self.firewall_error = Some(err);
+                }
+            }
+            let len = self.firewall_rows().len();
+            self.firewall_list.clamp(len);
+        }
+
+        if let Some(sockets) = self.sockets_pending.poll() {
+            self.sockets = sockets;
+            self.socket_list.clamp(self.sockets.len());
+        }
+        self.load_sockets(false);
+
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        let result =
+            result.map_err(|e| RootworkError::from_anyhow("Failed to gather network info", &e));
+        // Auto-refresh keeps the same interface selected
+        let selected = self.selected().map(|i| i.name.clone());
+        self.info = note_refresh_error(&mut self.error, &self.messages, result);
+        if self.info.is_some() {
+            self.update_visible(selected);
+        } else {
+            self.visible.clear();
+            self.selected_interface = 0;
+        }
+        self.load_sockets(true);
+    }
 }
 
 fn draw_interfaces(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    // A container only sees its own network namespace
+    let title = match &ctx.netns {
+        Some(netns) => format!("Network Interfaces [netns: {}] (n: switch)", netns.label),
+        None if container::in_container() => "Network Interfaces (container namespace)".to_string(),
+        None => "Network Interfaces".to_string(),
+    };
+    let total = ctx.info.as_ref().map_or(0, |i| i.interfaces.len());
+    let mut title = title;
+    if ctx.visible.len() < total {
+        title.push_str(&format!(" {}/{}", ctx.visible.len(), total));
+    }
+    if ctx.show_filter {
+        title.push_str(&format!(" filter: {}_", ctx.filter));
+    } else if !ctx.filter.is_empty() {
+        title.push_str(&format!(" filter: {} (Esc: clear)", ctx.filter));
+    }
+    if ctx.hide_virtual {
+        title.push_str(" [virtual hidden, v: show]");
+    }
     let block = Block::default()
-        .title(" Network Interfaces ")
+        .title(ctx.pending.title(&title))
         .borders(Borders::ALL);
 
     if let Some(ref error) = ctx.error {
-        let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+        let error_text = Paragraph::new(error.lines()).block(block);
         f.render_widget(error_text, area);
         return;
     }
@@ -370,12 +1940,22 @@ fn draw_interfaces(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
             f.render_widget(empty, area);
             return;
         }
+        if ctx.visible.is_empty() {
+            let empty = Paragraph::new("No interfaces match").block(block);
+            f.render_widget(empty, area);
+            return;
+        }
 
         // Build text lines for multiline display
         let mut lines: Vec<Line> = Vec::new();
+        // Line range of the selected interface, to scroll it into view
+        let mut selected_lines = 0..0;
 
-        for (i, iface) in info.interfaces.iter().enumerate() {
+        for (i, iface) in ctx.visible.iter().map(|i| &info.interfaces[*i]).enumerate() {
             let is_selected = i == ctx.selected_interface;
+            if is_selected {
+                selected_lines.start = lines.len();
+            }
 
             let state_color = match iface.state.as_str() {
                 "up" => crate::palette::green(),
@@ -384,10 +1964,7 @@ fn draw_interfaces(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
             };
 
             let name_style = if is_selected {
-                Style::default()
-                    .fg(crate::palette::black())
-                    .bg(crate::palette::cyan())
-                    .add_modifier(Modifier::BOLD)
+                crate::palette::selected_name()
             } else {
                 Style::default()
                     .fg(crate::palette::cyan())
@@ -402,11 +1979,11 @@ fn draw_interfaces(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
                     Style::default().fg(state_color),
                 ),
                 Span::styled(
-                    format!("RX: {:>10}  ", NetworkInfo::format_bytes(iface.rx_bytes)),
+                    format!("RX: {:>10}  ", format_bytes(iface.rx_bytes)),
                     Style::default().fg(crate::palette::blue()),
                 ),
                 Span::styled(
-                    format!("TX: {:>10}", NetworkInfo::format_bytes(iface.tx_bytes)),
+                    format!("TX: {:>10}", format_bytes(iface.tx_bytes)),
                     Style::default().fg(crate::palette::green()),
                 ),
             ]);
@@ -438,21 +2015,131 @@ fn draw_interfaces(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
                 )]));
             }
 
+            if let Some(lease) = &iface.lease {
+                let color = match lease.health() {
+                    LeaseHealth::Fine => crate::palette::green(),
+                    LeaseHealth::RenewalOverdue => crate::palette::yellow(),
+                    LeaseHealth::Rebinding | LeaseHealth::Expired => crate::palette::red(),
+                };
+                lines.push(Line::from(vec![
+                    Span::raw("             DHCP: "),
+                    Span::styled(lease.summary(), Style::default().fg(color)),
+                ]));
+                if let Some(options) = lease.options() {
+                    lines.push(Line::from(Span::styled(
+                        format!("                   {}", options),
+                        Style::default().fg(crate::palette::gray()),
+                    )));
+                }
+            }
+
+            if is_selected {
+                selected_lines.end = lines.len();
+            }
+
             // Empty line between interfaces (except last)
-            if i < info.interfaces.len() - 1 {
+            if i < ctx.visible.len() - 1 {
                 lines.push(Line::from(""));
             }
         }
 
-        let text = Paragraph::new(lines).block(block);
+        // Show the whole selected interface if it fits, else its header
+        let visible = area.height.saturating_sub(2) as usize;
+        let total_lines = lines.len();
+        let scroll = selected_lines
+            .end
+            .saturating_sub(visible)
+            .min(selected_lines.start);
+
+        let text = Paragraph::new(lines)
+            .scroll((scroll as u16, 0))
+            .block(block);
         f.render_widget(text, area);
+        render_scrollbar(area, total_lines, visible, scroll, f.buffer_mut());
     } else {
         let loading = Paragraph::new("Loading...").block(block);
         f.render_widget(loading, area);
     }
 }
 
+fn draw_sockets(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    let title = format!(
+        "Sockets on {}{}",
+        ctx.sockets_for.as_deref().unwrap_or("-"),
+        if ctx.sockets_focused {
+            " [Enter: jump to unit, s: back]"
+        } else {
+            " [s: select]"
+        }
+    );
+    let mut block = Block::default()
+        .title(ctx.sockets_pending.title(&title))
+        .borders(Borders::ALL);
+    if ctx.sockets_focused {
+        block = block.border_style(Style::default().fg(crate::palette::cyan()));
+    }
+
+    if ctx.sockets.is_empty() {
+        let text = if ctx.sockets_pending.is_pending() {
+            "Scanning..."
+        } else {
+            "No sockets on this interface"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec!["Proto", "State", "Local", "Remote", "Process", "Unit"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.socket_list,
+        ctx.sockets.len(),
+        [
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Length(24),
+            Constraint::Length(24),
+            Constraint::Length(20),
+            Constraint::Min(16),
+        ],
+        |i| {
+            let socket = &ctx.sockets[i];
+            let state_color = match socket.state {
+                "LISTEN" | "UNCONN" => crate::palette::green(),
+                "ESTAB" => crate::palette::blue(),
+                _ => crate::palette::gray(),
+            };
+            let (process, unit) = match &socket.owner {
+                Some(owner) => (
+                    format!("{}/{}", owner.comm, owner.pid),
+                    owner.unit.clone().unwrap_or_else(|| "-".to_string()),
+                ),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            Row::new(vec![
+                Span::raw(socket.proto),
+                Span::styled(socket.state, Style::default().fg(state_color)),
+                Span::raw(socket.local.clone()),
+                Span::styled(
+                    socket.remote.clone().unwrap_or_else(|| "-".to_string()),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                Span::raw(process),
+                Span::styled(unit, Style::default().fg(crate::palette::cyan())),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, area);
+}
+
 fn draw_routes(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    if area.height == 1 {
+        draw_routes_folded(ctx, f, area);
+        return;
+    }
+
     let block = Block::default()
         .title(" Routing Table ")
         .borders(Borders::ALL);
@@ -513,3 +2200,458 @@ fn draw_routes(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
         f.render_widget(loading, area);
     }
 }
+
+/// The default route on one line, for terminals too short for the table
+fn draw_routes_folded(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    let default = ctx
+        .info
+        .as_ref()
+        .and_then(|info| info.routes.iter().find(|r| r.destination == "default"));
+    let summary = match default {
+        Some(route) => format!(
+            "default via {} on {}",
+            route.gateway.as_deref().unwrap_or("-"),
+            route.interface
+        ),
+        None => "no default route".to_string(),
+    };
+    let line = Line::from(vec![
+        Span::styled(" Routes: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(summary),
+        Span::styled(
+            "  (R: full table)",
+            Style::default().fg(crate::palette::gray()),
+        ),
+    ]);
+    f.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_netns_picker(ctx: &NetworkContext, picker: &NetnsPicker, f: &mut Frame, area: Rect) {
+    let area = centered_rect(70, 50, area);
+    let block = Block::default()
+        .title(picker.pending.title(&format!(
+            "Network namespaces ({}) Enter:show r:refresh Esc:close",
+            picker.namespaces.len()
+        )))
+        .borders(Borders::ALL);
+    f.render_widget(Clear, area);
+
+    let current = ctx.netns.as_ref().map(|n| n.path.as_path());
+    let header = Row::new(vec!["Namespace", "Units", "Procs", "Path"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &picker.list,
+        picker.namespaces.len() + 1,
+        [
+            Constraint::Min(24),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(24),
+        ],
+        |i| {
+            let Some(netns) = i.checked_sub(1).and_then(|i| picker.namespaces.get(i)) else {
+                let style = Style::default().fg(if current.is_none() {
+                    crate::palette::green()
+                } else {
+                    crate::palette::cyan()
+                });
+                return Row::new(vec![
+                    Span::styled("rootwork's own (host)", style),
+                    Span::raw(""),
+                    Span::raw(""),
+                    Span::raw(""),
+                ]);
+            };
+            let style = Style::default().fg(if current == Some(netns.path.as_path()) {
+                crate::palette::green()
+            } else {
+                crate::palette::cyan()
+            });
+            Row::new(vec![
+                Span::styled(netns.label.clone(), style),
+                Span::raw(netns.units.len().to_string()),
+                Span::raw(netns.processes.to_string()),
+                Span::styled(
+                    netns.path.display().to_string(),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_checks(ctx: &NetworkContext, checks: &[Check], f: &mut Frame, area: Rect) {
+    let area = centered_rect(80, 30, area);
+    let title = match &ctx.netns {
+        Some(netns) => format!(
+            "Connectivity from {} (DNS from the host) c:rerun Esc:close",
+            netns.label
+        ),
+        None => "Connectivity c:rerun Esc:close".to_string(),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    f.render_widget(Clear, area);
+
+    let mut lines = Vec::new();
+    for check in checks {
+        let (text, color) = match &check.result {
+            _ if check.pending.is_pending() => ("running...".to_string(), crate::palette::gray()),
+            Some(Ok(text)) => (text.clone(), crate::palette::green()),
+            Some(Err(e)) => (e.clone(), crate::palette::red()),
+            None => (String::new(), crate::palette::gray()),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<14}", check.name),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:<32} ", check.target),
+                Style::default().fg(crate::palette::cyan()),
+            ),
+            Span::styled(text, Style::default().fg(color)),
+        ]));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(ratatui::widgets::Wrap { trim: false }),
+        area,
+    );
+}
+
+fn draw_trace(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    let area = centered_rect(80, 70, area);
+    f.render_widget(Clear, area);
+    let trace = ctx.trace.as_ref();
+    let from = match &ctx.netns {
+        Some(netns) => format!(" from {}", netns.label),
+        None => String::new(),
+    };
+    let title = match trace {
+        Some(trace) => {
+            let addr = trace.addr.map(|a| format!(" ({})", a)).unwrap_or_default();
+            trace.pending.title(&format!(
+                "Traceroute to {}{}{}, round {} t:new target r:reset Esc:close",
+                trace.target, addr, from, trace.round
+            ))
+        }
+        None => format!("Traceroute{}", from),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    let status = match (&ctx.trace_input, trace) {
+        (Some(input), _) => Line::from(vec![
+            Span::styled("Target: ", Style::default().fg(crate::palette::yellow())),
+            Span::raw(input.as_str()),
+            Span::raw("_  (host name or IPv4 address, Enter: start, Esc: cancel)"),
+        ]),
+        (None, Some(Trace { error: Some(e), .. })) => Line::from(Span::styled(
+            e.as_str(),
+            Style::default().fg(crate::palette::red()),
+        )),
+        (None, Some(trace)) if trace.addr.is_none() => Line::from("Resolving..."),
+        _ => Line::from(""),
+    };
+    f.render_widget(Paragraph::new(status), chunks[0]);
+
+    let Some(trace) = trace else {
+        return;
+    };
+    let ms = |d: Option<Duration>| d.map(format_latency).unwrap_or_else(|| "-".to_string());
+    let header = Row::new(vec![
+        "#", "Host", "Loss%", "Sent", "Last", "Avg", "Best", "Worst",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &trace.list,
+        trace.hops.len(),
+        [
+            Constraint::Length(3),
+            Constraint::Min(16),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+        ],
+        |i| {
+            let hop = &trace.hops[i];
+            let loss = hop.loss_percent();
+            let loss_color = match loss {
+                0 => crate::palette::green(),
+                100 => crate::palette::red(),
+                _ => crate::palette::yellow(),
+            };
+            let avg = (hop.received > 0).then(|| hop.total / hop.received);
+            Row::new(vec![
+                Span::raw((i + 1).to_string()),
+                match hop.addr {
+                    Some(addr) => Span::styled(
+                        addr.to_string(),
+                        Style::default().fg(crate::palette::cyan()),
+                    ),
+                    None => Span::styled("???", Style::default().fg(crate::palette::gray())),
+                },
+                Span::styled(format!("{}%", loss), Style::default().fg(loss_color)),
+                Span::raw(hop.sent.to_string()),
+                Span::raw(ms(hop.last)),
+                Span::raw(ms(avg)),
+                Span::raw(ms(hop.best)),
+                Span::raw(ms(hop.worst)),
+            ])
+        },
+    )
+    .header(header);
+    f.render_widget(table, chunks[1]);
+}
+
+/// Milliseconds with a decimal below 10ms, where LAN round trips live
+fn format_latency(elapsed: Duration) -> String {
+    let ms = elapsed.as_secs_f64() * 1000.0;
+    if ms < 10.0 {
+        format!("{:.1}ms", ms)
+    } else {
+        format!("{:.0}ms", ms)
+    }
+}
+
+/// (ifindex, family, address bytes) as resolved returns them
+type ResolvedAddress = (i32, i32, Vec<u8>);
+
+/// Addresses resolved has for `name`, through its D-Bus API so the answer
+/// comes from its caches and per-link servers like any NSS lookup
+async fn resolve_hostname(systemd: SystemdClient, name: String) -> Result<String> {
+    let proxy = zbus::Proxy::new(
+        systemd.system_bus().await?,
+        "org.freedesktop.resolve1",
+        "/org/freedesktop/resolve1",
+        "org.freedesktop.resolve1.Manager",
+    )
+    .await?;
+    let start = Instant::now();
+    let (addresses, _canonical, _flags): (Vec<ResolvedAddress>, String, u64) = proxy
+        .call(
+            "ResolveHostname",
+            &(0i32, name.as_str(), libc::AF_UNSPEC, 0u64),
+        )
+        .await
+        .context("resolved")?;
+    let elapsed = start.elapsed();
+    let addresses: Vec<String> = addresses
+        .iter()
+        .filter_map(|(_, family, bytes)| match (*family, bytes.len()) {
+            (libc::AF_INET, 4) => Some(IpAddr::from(<[u8; 4]>::try_from(&bytes[..]).ok()?)),
+            (libc::AF_INET6, 16) => Some(IpAddr::from(<[u8; 16]>::try_from(&bytes[..]).ok()?)),
+            _ => None,
+        })
+        .map(|ip| ip.to_string())
+        .collect();
+    Ok(format!(
+        "{} in {}",
+        addresses.join(", "),
+        format_latency(elapsed)
+    ))
+}
+
+fn draw_bandwidth(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    let (tx, rx) = ctx.connections.iter().fold((0.0, 0.0), |(tx, rx), c| {
+        (tx + c.tx_rate.unwrap_or(0.0), rx + c.rx_rate.unwrap_or(0.0))
+    });
+    let title = format!(
+        "Bandwidth: {} TCP connections, ↓ {}/s ↑ {}/s (Enter: jump to unit, B: back)",
+        ctx.connections.len(),
+        format_bytes(rx as u64),
+        format_bytes(tx as u64)
+    );
+    let block = Block::default()
+        .title(ctx.bandwidth_pending.title(&title))
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.bandwidth_error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    if ctx.connections.is_empty() {
+        let text = if ctx.last_sample.is_none() {
+            "Sampling..."
+        } else {
+            "No TCP connections"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let rate = |r: Option<f64>| match r {
+        Some(r) => format!("{}/s", format_bytes(r as u64)),
+        None => "-".to_string(),
+    };
+    let header = Row::new(vec![
+        "↓ Rate", "↑ Rate", "Total", "Local", "Remote", "Process", "Unit",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.bandwidth_list,
+        ctx.connections.len(),
+        [
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(10),
+            Constraint::Length(22),
+            Constraint::Length(24),
+            Constraint::Length(18),
+            Constraint::Min(16),
+        ],
+        |i| {
+            let c = &ctx.connections[i];
+            let busy = c.rate() >= 1024.0;
+            let (process, unit) = match &c.owner {
+                Some(owner) => (
+                    format!("{}/{}", owner.comm, owner.pid),
+                    owner.unit.clone().unwrap_or_else(|| "-".to_string()),
+                ),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            let rate_style = Style::default().fg(if busy {
+                crate::palette::yellow()
+            } else {
+                crate::palette::gray()
+            });
+            Row::new(vec![
+                Span::styled(rate(c.rx_rate), rate_style),
+                Span::styled(rate(c.tx_rate), rate_style),
+                Span::raw(format_bytes(c.conn.bytes_sent + c.conn.bytes_received)),
+                Span::raw(c.conn.local.to_string()),
+                Span::styled(
+                    c.conn.remote.to_string(),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                Span::raw(process),
+                Span::styled(unit, Style::default().fg(crate::palette::cyan())),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_firewall(ctx: &NetworkContext, f: &mut Frame, area: Rect) {
+    let title = match &ctx.ruleset {
+        Some(ruleset) => format!(
+            "Firewall: {} chains, {} rules (ports of sockets on {} highlighted, F: back)",
+            ruleset.chains.len(),
+            ruleset.rule_count(),
+            ctx.sockets_for.as_deref().unwrap_or("-")
+        ),
+        None => "Firewall (F: back)".to_string(),
+    };
+    let block = Block::default()
+        .title(ctx.firewall_pending.title(&title))
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.firewall_error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    let Some(ruleset) = &ctx.ruleset else {
+        f.render_widget(Paragraph::new("Loading...").block(block), area);
+        return;
+    };
+    let rows = ctx.firewall_rows();
+    if rows.is_empty() {
+        f.render_widget(
+            Paragraph::new("The nftables ruleset is empty: nothing is filtered").block(block),
+            area,
+        );
+        return;
+    }
+
+    let header = Row::new(vec!["Rule", "Packets", "Bytes", "Socket"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.firewall_list,
+        rows.len(),
+        [
+            Constraint::Min(30),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(24),
+        ],
+        |i| {
+            let (c, rule) = rows[i];
+            let chain = &ruleset.chains[c];
+            let Some(rule) = rule.map(|r| &chain.rules[r]) else {
+                let mut text = chain.title();
+                if let Some(hook) = &chain.hook {
+                    text.push_str(&format!(" (hook {}", hook));
+                    if let Some(policy) = &chain.policy {
+                        text.push_str(&format!(", policy {}", policy));
+                    }
+                    text.push(')');
+                }
+                let policy_color = match chain.policy.as_deref() {
+                    Some("drop") => crate::palette::red(),
+                    _ => crate::palette::cyan(),
+                };
+                return Row::new(vec![Span::styled(
+                    text,
+                    Style::default()
+                        .fg(policy_color)
+                        .add_modifier(Modifier::BOLD),
+                )]);
+            };
+
+            let verdict_color = if rule.text.contains("drop") || rule.text.contains("reject") {
+                crate::palette::red()
+            } else if rule.text.contains("accept") {
+                crate::palette::green()
+            } else {
+                crate::palette::white()
+            };
+            let (rule_style, socket) = match ctx.rule_socket(rule) {
+                Some((socket, selected)) => {
+                    let mut style = Style::default().fg(crate::palette::yellow());
+                    if selected {
+                        style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                    }
+                    let owner = socket
+                        .owner
+                        .as_ref()
+                        .map(|o| o.unit.clone().unwrap_or_else(|| o.comm.clone()))
+                        .unwrap_or_else(|| socket.local.clone());
+                    (style, owner)
+                }
+                None => (Style::default().fg(verdict_color), String::new()),
+            };
+            let hits = rule.packets.unwrap_or(0) > 0;
+            Row::new(vec![
+                Span::styled(format!("  {}", rule.text), rule_style),
+                Span::styled(
+                    rule.packets
+                        .map_or_else(|| "-".to_string(), |p| p.to_string()),
+                    Style::default().fg(if hits {
+                        crate::palette::white()
+                    } else {
+                        crate::palette::gray()
+                    }),
+                ),
+                Span::raw(rule.bytes.map_or_else(|| "-".to_string(), format_bytes)),
+                Span::styled(socket, Style::default().fg(crate::palette::cyan())),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,465 @@
+use crate::contexts::{Context, Pending, format_bytes, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::SystemdClient;
+use crate::widgets::selectable::{ListState, SelectableList, SelectableTable};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row},
+};
+use std::collections::HashMap;
+use zbus::Proxy;
+use zbus::zvariant::OwnedObjectPath;
+
+const PORTABLE_SERVICE: &str = "org.freedesktop.portable1";
+const PORTABLE_PATH: &str = "/org/freedesktop/portable1";
+const PORTABLE_MANAGER: &str = "org.freedesktop.portable1.Manager";
+
+/// One row of ListImages: (name, type, read_only, crtime, mtime, usage,
+/// state, object_path)
+type ImageRow = (String, String, bool, u64, u64, u64, String, OwnedObjectPath);
+
+/// A portable service image, attached or not, as `portablectl list` shows it
+pub struct PortableImage {
+    name: String,
+    /// "directory", "subvolume", "raw" or "block"
+    kind: String,
+    read_only: bool,
+    /// "detached", "attached", "attached-runtime", "enabled", "running", ...
+    state: String,
+    usage: Option<u64>,
+}
+
+impl PortableImage {
+    fn is_attached(&self) -> bool {
+        self.state != "detached"
+    }
+
+    /// Attached to /run only, gone at the next boot
+    fn is_runtime(&self) -> bool {
+        self.state.ends_with("-runtime")
+    }
+
+    async fn list(systemd: SystemdClient) -> Result<Vec<PortableImage>> {
+        let manager = portable_manager(&systemd).await?;
+        let raw: Vec<ImageRow> = manager.call("ListImages", &()).await?;
+        let mut images: Vec<PortableImage> = raw
+            .into_iter()
+            .map(
+                |(name, kind, read_only, _crtime, _mtime, usage, state, _)| PortableImage {
+                    name,
+                    kind,
+                    read_only,
+                    state,
+                    usage: Some(usage).filter(|u| *u != u64::MAX),
+                },
+            )
+            .collect();
+        images.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(images)
+    }
+
+    /// Unit files the image ships, which attaching installs
+    async fn units(systemd: SystemdClient, name: String) -> Result<Vec<String>> {
+        let manager = portable_manager(&systemd).await?;
+        let matches: Vec<&str> = Vec::new();
+        let (_, _, units): (String, Vec<u8>, HashMap<String, Vec<u8>>) = manager
+            .call("GetImageMetadata", &(name.as_str(), matches))
+            .await?;
+        let mut units: Vec<String> = units.into_keys().collect();
+        units.sort();
+        Ok(units)
+    }
+}
+
+async fn portable_manager(systemd: &SystemdClient) -> Result<Proxy<'static>> {
+    Ok(Proxy::new(
+        systemd.system_bus().await?,
+        PORTABLE_SERVICE,
+        PORTABLE_PATH,
+        PORTABLE_MANAGER,
+    )
+    .await?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PortableAction {
+    Attach,
+    Detach,
+}
+
+impl PortableAction {
+    fn label(&self) -> &'static str {
+        match self {
+            PortableAction::Attach => "attach",
+            PortableAction::Detach => "detach",
+        }
+    }
+
+    /// Run like `portablectl attach|detach` with the default profile and
+    /// copy mode; returns how many files were changed
+    async fn run(self, systemd: SystemdClient, name: String, runtime: bool) -> Result<usize> {
+        let manager = portable_manager(&systemd).await?;
+        let changes: Vec<(String, String, String)> = match self {
+            PortableAction::Attach => {
+                let matches: Vec<&str> = Vec::new();
+                manager
+                    .call(
+                        "AttachImage",
+                        &(name.as_str(), matches, "default", runtime, ""),
+                    )
+                    .await?
+            }
+            PortableAction::Detach => {
+                manager
+                    .call("DetachImage", &(name.as_str(), runtime))
+                    .await?
+            }
+        };
+        Ok(changes.len())
+    }
+}
+
+pub struct PortableContext {
+    systemd: SystemdClient,
+    images: Vec<PortableImage>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<Vec<PortableImage>>>,
+    list: ListState,
+    /// Unit files of the selected image
+    units: Vec<String>,
+    units_for: Option<String>,
+    /// Tagged with the image it was read for, which the selection may
+    /// have left by the time it's done
+    units_pending: Pending<(String, Result<Vec<String>>)>,
+    unit_list: ListState,
+    /// j/k move through the units instead of the images
+    units_focused: bool,
+    confirm_action: Option<PortableAction>,
+    action: Pending<Result<String>>,
+    jump_to_unit: Option<String>,
+    messages: Messages,
+}
+
+impl PortableContext {
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            systemd: systemd.clone(),
+            images: Vec::new(),
+            error: None,
+            pending: Pending::idle(),
+            list: ListState::new(),
+            units: Vec::new(),
+            units_for: None,
+            units_pending: Pending::idle(),
+            unit_list: ListState::new(),
+            units_focused: false,
+            confirm_action: None,
+            action: Pending::idle(),
+            jump_to_unit: None,
+            messages: messages.clone(),
+        };
+        ctx.refresh();
+        ctx
+    }
+
+    fn refresh(&mut self) {
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn_async(PortableImage::list(self.systemd.clone()));
+        }
+    }
+
+    fn selected(&self) -> Option<&PortableImage> {
+        self.images.get(self.list.selected)
+    }
+
+    /// Read the unit files when the selection moved to another image
+    fn load_units(&mut self) {
+        let Some(name) = self.selected().map(|i| i.name.clone()) else {
+            self.units.clear();
+            self.units_for = None;
+            return;
+        };
+        if self.units_pending.is_pending() || self.units_for.as_deref() == Some(name.as_str()) {
+            return;
+        }
+        self.units.clear();
+        self.unit_list.reset();
+        self.units_for = Some(name.clone());
+        let systemd = self.systemd.clone();
+        self.units_pending = Pending::spawn_async(async move {
+            let units = PortableImage::units(systemd, name.clone()).await;
+            (name, units)
+        });
+    }
+
+    fn request_action(&mut self, action: PortableAction) {
+        let Some(image) = self.selected() else {
+            return;
+        };
+        match action {
+            PortableAction::Attach if image.is_attached() => self
+                .messages
+                .warn(format!("{} is already attached", image.name)),
+            PortableAction::Detach if !image.is_attached() => {
+                self.messages.warn(format!("{} isn't attached", image.name))
+            }
+            _ => self.confirm_action = Some(action),
+        }
+    }
+
+    fn run_action(&mut self, action: PortableAction) {
+        let Some(image) = self.selected() else {
+            return;
+        };
+        let name = image.name.clone();
+        let runtime = image.is_runtime();
+        let systemd = self.systemd.clone();
+        self.action = Pending::spawn_async(async move {
+            let changed = action.run(systemd, name.clone(), runtime).await?;
+            Ok(format!(
+                "{} {}: {} files changed",
+                action.label(),
+                name,
+                changed
+            ))
+        });
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
+    }
+}
+
+impl Context for PortableContext {
+    fn name(&self) -> &'static str {
+        "Portable"
+    }
+
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(10),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        draw_images(self, f, chunks[0]);
+        draw_units(self, f, chunks[1]);
+
+        let status = match (self.confirm_action, self.selected()) {
+            (Some(PortableAction::Attach), Some(image)) => format!(
+                "Attach {} and install its {} unit files? [y/n]",
+                image.name,
+                self.units.len()
+            ),
+            (Some(PortableAction::Detach), Some(image)) => format!(
+                "Detach {}? Its units are removed; running ones must be stopped first [y/n]",
+                image.name
+            ),
+            _ if self.units_focused => "Enter:open unit u/Esc:images r:refresh".to_string(),
+            _ => "a:attach x:detach u:units r:refresh".to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
+            chunks[2],
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if let Some(action) = self.confirm_action.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.run_action(action);
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('u') => {
+                self.units_focused = !self.units_focused;
+                return;
+            }
+            KeyCode::Esc if self.units_focused => {
+                self.units_focused = false;
+                return;
+            }
+            KeyCode::Char('r') => {
+                self.refresh();
+                return;
+            }
+            _ => {}
+        }
+
+        if self.units_focused {
+            let len = self.units.len();
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.unit_list.down(len),
+                KeyCode::Char('k') | KeyCode::Up => self.unit_list.up(len),
+                KeyCode::Char('g') => self.unit_list.top(len),
+                KeyCode::Char('G') => self.unit_list.bottom(len),
+                KeyCode::Enter => {
+                    if let Some(unit) = self.units.get(self.unit_list.selected) {
+                        self.jump_to_unit = Some(unit.clone());
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let len = self.images.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.list.up(len),
+            KeyCode::Char('g') => self.list.top(len),
+            KeyCode::Char('G') => self.list.bottom(len),
+            KeyCode::Char('a') => self.request_action(PortableAction::Attach),
+            KeyCode::Char('x') => self.request_action(PortableAction::Detach),
+            _ => {}
+        }
+        self.load_units();
+    }
+
+    async fn tick(&mut self) {
+        match self.action.poll() {
+            Some(Ok(done)) => {
+                self.messages.info(done);
+                self.refresh();
+            }
+            Some(Err(e)) => self.messages.error(format!("portable: {}", e)),
+            None => {}
+        }
+
+        if let Some((name, result)) = self.units_pending.poll() {
+            if self.selected().is_some_and(|i| i.name == name) {
+                match result {
+                    Ok(units) => self.units = units,
+                    Err(e) => self.messages.warn(format!("Units of {}: {}", name, e)),
+                }
+            } else {
+                // The selection moved on while this was read
+                self.units_for = None;
+                self.load_units();
+            }
+        }
+
+        if let Some(result) = self.pending.poll() {
+            let result = result
+                .map_err(|e| RootworkError::from_anyhow("Failed to list portable images", &e));
+            self.images =
+                note_refresh_error(&mut self.error, &self.messages, result).unwrap_or_default();
+            self.list.clamp(self.images.len());
+            self.load_units();
+        }
+    }
+}
+
+fn draw_images(ctx: &PortableContext, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(
+            ctx.pending
+                .title(&format!("Portable images ({})", ctx.images.len())),
+        )
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+    if ctx.images.is_empty() {
+        let text = if ctx.pending.is_pending() {
+            "Loading..."
+        } else {
+            "No portable images (looked in /etc, /run and /var/lib/portables)"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Type", "RO", "Usage", "State"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.list,
+        ctx.images.len(),
+        [
+            Constraint::Min(20),
+            Constraint::Length(10),
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Length(18),
+        ],
+        |i| {
+            let image = &ctx.images[i];
+            let state_color = match image.state.as_str() {
+                "running" | "running-runtime" => crate::palette::green(),
+                "detached" => crate::palette::gray(),
+                _ => crate::palette::yellow(),
+            };
+            Row::new(vec![
+                Span::styled(
+                    image.name.clone(),
+                    Style::default().fg(crate::palette::cyan()),
+                ),
+                Span::raw(image.kind.clone()),
+                Span::raw(if image.read_only { "ro" } else { "" }),
+                Span::raw(
+                    image
+                        .usage
+                        .map(format_bytes)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Span::styled(image.state.clone(), Style::default().fg(state_color)),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_units(ctx: &PortableContext, f: &mut Frame, area: Rect) {
+    let title = format!(
+        "Units in {}{}",
+        ctx.units_for.as_deref().unwrap_or("-"),
+        if ctx.units_focused {
+            " [Enter: open, u: back]"
+        } else {
+            " [u: select]"
+        }
+    );
+    let mut block = Block::default()
+        .title(ctx.units_pending.title(&title))
+        .borders(Borders::ALL);
+    if ctx.units_focused {
+        block = block.border_style(Style::default().fg(crate::palette::cyan()));
+    }
+
+    if ctx.units.is_empty() {
+        let text = if ctx.units_pending.is_pending() {
+            "Reading image..."
+        } else {
+            "No unit files"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let list = SelectableList::new(&ctx.unit_list, ctx.units.len(), |i| {
+        Line::from(ctx.units[i].as_str())
+    })
+    .block(block);
+    f.render_widget(list, area);
+}
@@ -0,0 +1,787 @@
+use crate::contexts::{Context, NARROW_WIDTH, Pending, format_bytes, note_refresh_error};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::systemd::client::{DiskUsage, SystemdClient};
+use crate::widgets::selectable::{ListState, SelectableTable};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph, Row},
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Cells in the usage bar
+const USAGE_BAR_WIDTH: usize = 10;
+
+pub struct StorageInfo {
+    /// Disks, each followed by its partitions
+    pub devices: Vec<BlockDevice>,
+}
+
+pub struct BlockDevice {
+    /// Kernel name, e.g. "sda1", or "mapper/NAME" for device-mapper
+    name: String,
+    /// Partitions are listed under their disk
+    partition: bool,
+    model: Option<String>,
+    size: u64,
+    removable: bool,
+    rotational: bool,
+    fs_type: Option<String>,
+    label: Option<String>,
+    mountpoints: Vec<String>,
+    /// Of the first mount point
+    usage: Option<DiskUsage>,
+}
+
+/// An active swap area from /proc/swaps, or a swap unit that isn't on
+pub struct SwapEntry {
+    /// Device or file; the unit's description while inactive
+    path: String,
+    /// "partition" or "file"; empty while inactive
+    kind: String,
+    size: u64,
+    used: u64,
+    priority: Option<i32>,
+    /// The .swap unit, when systemd knows the area
+    unit: Option<String>,
+    active: bool,
+    zram: Option<Zram>,
+}
+
+/// Compression stats of a zram device used as swap
+struct Zram {
+    algorithm: String,
+    /// Data stored, before compression
+    original: u64,
+    compressed: u64,
+    /// RAM the device takes, allocator overhead included
+    memory: u64,
+}
+
+impl SwapEntry {
+    /// Active areas from /proc/swaps, then swap units that are loaded but
+    /// not active (e.g. from fstab, or turned off by hand). A device shows up
+    /// under several unit names (by-uuid, by-label, ...), so active ones are
+    /// matched by their path rather than listed from the units.
+    async fn gather(systemd: SystemdClient) -> Result<Vec<SwapEntry>> {
+        let units = systemd.list_units_matching(&["*.swap"]).await?;
+        let text = fs::read_to_string("/proc/swaps")?;
+        let mut entries: Vec<SwapEntry> = text
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [path, kind, size, used, priority] = fields[..] else {
+                    return None;
+                };
+                let path = unescape_octal(path);
+                let unit = path_unit_name(&path, "swap");
+                Some(SwapEntry {
+                    unit: units.iter().any(|u| u.name == unit).then_some(unit),
+                    zram: read_zram(&path),
+                    path,
+                    kind: kind.to_string(),
+                    size: size.parse::<u64>().unwrap_or(0) * 1024,
+                    used: used.parse::<u64>().unwrap_or(0) * 1024,
+                    priority: priority.parse().ok(),
+                    active: true,
+                })
+            })
+            .collect();
+        for unit in units {
+            if matches!(
+                unit.active_state.as_str(),
+                "active" | "activating" | "deactivating"
+            ) {
+                continue;
+            }
+            entries.push(SwapEntry {
+                path: unit.description.clone(),
+                kind: String::new(),
+                size: 0,
+                used: 0,
+                priority: None,
+                unit: Some(unit.name),
+                active: false,
+                zram: None,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Stats of /dev/zramN, None for anything else
+fn read_zram(path: &str) -> Option<Zram> {
+    let name = path.strip_prefix("/dev/")?;
+    if !name.starts_with("zram") {
+        return None;
+    }
+    let sys = Path::new("/sys/block").join(name);
+    // orig_data_size compr_data_size mem_used_total ...
+    let stat = fs::read_to_string(sys.join("mm_stat")).ok()?;
+    let mut fields = stat
+        .split_whitespace()
+        .map(|f| f.parse::<u64>().unwrap_or(0));
+    // The one in use is bracketed: "lzo-rle [zstd] lz4"
+    let algorithm = fs::read_to_string(sys.join("comp_algorithm"))
+        .ok()?
+        .split_whitespace()
+        .find_map(|a| a.strip_prefix('[')?.strip_suffix(']').map(str::to_string))
+        .unwrap_or_default();
+    Some(Zram {
+        algorithm,
+        original: fields.next()?,
+        compressed: fields.next()?,
+        memory: fields.next()?,
+    })
+}
+
+/// A line of /proc/self/mountinfo
+struct Mount {
+    dev: String,
+    /// The device as given to mount, e.g. "/dev/sda1"
+    source: String,
+    mountpoint: String,
+    fs_type: String,
+}
+
+impl StorageInfo {
+    /// Block devices from /sys/block with filesystem details from the udev
+    /// database and mount points from mountinfo
+    pub fn gather() -> Result<Self> {
+        let mounts = read_mounts()?;
+        let mut devices = Vec::new();
+        let mut names: Vec<String> = fs::read_dir("/sys/block")?
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with("ram"))
+            .collect();
+        names.sort();
+
+        for name in names {
+            let sys = Path::new("/sys/block").join(&name);
+            let Some(disk) = read_device(&sys, &name, false, &mounts) else {
+                continue;
+            };
+            if disk.size == 0 {
+                continue;
+            }
+            let mut partitions: Vec<String> = fs::read_dir(&sys)?
+                .flatten()
+                .filter(|e| e.path().join("partition").exists())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect();
+            partitions.sort_by_key(|p| partition_number(p));
+            devices.push(disk);
+            for part in partitions {
+                devices.extend(read_device(&sys.join(&part), &part, true, &mounts));
+            }
+        }
+        Ok(Self { devices })
+    }
+}
+
+fn read_device(sys: &Path, name: &str, partition: bool, mounts: &[Mount]) -> Option<BlockDevice> {
+    let read = |file: &str| {
+        fs::read_to_string(sys.join(file))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let dev = read("dev")?;
+    let udev = read_udev(&dev);
+
+    // Sources are matched too: btrfs mounts carry an anonymous device
+    // number, and /dev/mapper/NAME links to /dev/dm-N
+    let device_node = Path::new("/dev").join(name);
+    let mounted: Vec<&Mount> = mounts
+        .iter()
+        .filter(|m| {
+            m.dev == dev
+                || (m.source.starts_with("/dev/")
+                    && fs::canonicalize(&m.source).is_ok_and(|p| p == device_node))
+        })
+        .collect();
+    let name = match read("dm/name") {
+        Some(dm) => format!("mapper/{}", dm),
+        None => name.to_string(),
+    };
+    let mountpoints: Vec<String> = mounted.iter().map(|m| m.mountpoint.clone()).collect();
+
+    Some(BlockDevice {
+        partition,
+        model: read("device/model").or_else(|| udev.get("ID_MODEL").cloned()),
+        size: read("size")
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0)
+            * 512,
+        removable: read("removable").as_deref() == Some("1"),
+        rotational: read("queue/rotational").as_deref() == Some("1"),
+        fs_type: udev
+            .get("ID_FS_TYPE")
+            .cloned()
+            .or_else(|| mounted.first().map(|m| m.fs_type.clone())),
+        label: udev.get("ID_FS_LABEL").cloned(),
+        usage: mountpoints.first().and_then(|p| DiskUsage::of(p).ok()),
+        mountpoints,
+        name,
+    })
+}
+
+/// "E:KEY=value" properties udev recorded for the device
+fn read_udev(dev: &str) -> HashMap<String, String> {
+    fs::read_to_string(format!("/run/udev/data/b{}", dev))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.strip_prefix("E:")?.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn read_mounts() -> Result<Vec<Mount>> {
+    let text = fs::read_to_string("/proc/self/mountinfo")?;
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let left: Vec<&str> = left.split(' ').collect();
+            let mut right = right.split(' ');
+            Some(Mount {
+                dev: left.get(2)?.to_string(),
+                mountpoint: unescape_octal(left.get(4)?),
+                fs_type: right.next()?.to_string(),
+                source: unescape_octal(right.next()?),
+            })
+        })
+        .collect())
+}
+
+/// mountinfo writes space, tab, newline and backslash as \ooo
+fn unescape_octal(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4);
+        match code.and_then(|c| u8::from_str_radix(c, 8).ok()) {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Trailing digits of a partition name, so sda10 sorts after sda9
+fn partition_number(name: &str) -> u32 {
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    name[name.len() - digits..].parse().unwrap_or(0)
+}
+
+/// Name of the unit systemd generates for a mount point or swap device,
+/// e.g. ("/boot/efi", "mount") gives "boot-efi.mount", escaped like
+/// `systemd-escape --path`
+pub fn path_unit_name(path: &str, suffix: &str) -> String {
+    let trimmed = path.trim_matches('/');
+    if trimmed.is_empty() {
+        return format!("-.{}", suffix);
+    }
+    let mut out = String::new();
+    for (i, component) in trimmed.split('/').filter(|c| !c.is_empty()).enumerate() {
+        if i > 0 {
+            out.push('-');
+        }
+        for (j, byte) in component.bytes().enumerate() {
+            let plain = byte.is_ascii_alphanumeric()
+                || byte == b':'
+                || byte == b'_'
+                || (byte == b'.' && !(i == 0 && j == 0));
+            if plain {
+                out.push(byte as char);
+            } else {
+                out.push_str(&format!("\\x{:02x}", byte));
+            }
+        }
+    }
+    out.push('.');
+    out.push_str(suffix);
+    out
+}
+
+fn usage_bar(percent: u64) -> String {
+    let filled = (percent as usize * USAGE_BAR_WIDTH)
+        .div_ceil(100)
+        .min(USAGE_BAR_WIDTH);
+    format!(
+        "{}{} {:>3}%",
+        "█".repeat(filled),
+        "░".repeat(USAGE_BAR_WIDTH - filled),
+        percent
+    )
+}
+
+pub struct StorageContext {
+    systemd: SystemdClient,
+    info: Option<StorageInfo>,
+    error: Option<RootworkError>,
+    pending: Pending<Result<StorageInfo>>,
+    list: ListState,
+    swaps: Vec<SwapEntry>,
+    swaps_pending: Pending<Result<Vec<SwapEntry>>>,
+    swap_list: ListState,
+    /// j/k move through the swap areas instead of the devices
+    swaps_focused: bool,
+    /// Asking before turning off the selected swap unit
+    confirm_swapoff: bool,
+    /// swapon/swapoff job, reporting what it did
+    swap_action: Pending<Result<String>>,
+    jump_to_unit: Option<String>,
+    messages: Messages,
+}
+
+impl StorageContext {
+    pub fn new(systemd: &SystemdClient, messages: &Messages) -> Self {
+        let mut ctx = Self {
+            systemd: systemd.clone(),
+            info: None,
+            error: None,
+            pending: Pending::idle(),
+            list: ListState::new(),
+            swaps: Vec::new(),
+            swaps_pending: Pending::idle(),
+            swap_list: ListState::new(),
+            swaps_focused: false,
+            confirm_swapoff: false,
+            swap_action: Pending::idle(),
+            jump_to_unit: None,
+            messages: messages.clone(),
+        };
+        ctx.refresh();
+        ctx
+    }
+
+    fn refresh(&mut self) {
+        if !self.pending.is_pending() {
+            self.pending = Pending::spawn(StorageInfo::gather);
+        }
+        if !self.swaps_pending.is_pending() {
+            self.swaps_pending = Pending::spawn_async(SwapEntry::gather(self.systemd.clone()));
+        }
+    }
+
+    fn selected_swap(&self) -> Option<&SwapEntry> {
+        self.swaps.get(self.swap_list.selected)
+    }
+
+    /// Start or stop the selected swap unit
+    fn swap_toggle(&mut self, on: bool) {
+        let Some(swap) = self.selected_swap() else {
+            return;
+        };
+        let Some(unit) = swap.unit.clone() else {
+            self.messages
+                .warn(format!("{} has no swap unit to act on", swap.path));
+            return;
+        };
+        if swap.active == on {
+            self.messages.info(format!(
+                "{} is already {}",
+                unit,
+                if on { "on" } else { "off" }
+            ));
+            return;
+        }
+        let systemd = self.systemd.clone();
+        self.swap_action = Pending::spawn_async(async move {
+            if on {
+                systemd.start_unit(&unit).await?;
+                Ok(format!("Activated {}", unit))
+            } else {
+                systemd.stop_unit(&unit).await?;
+                Ok(format!("Deactivated {}", unit))
+            }
+        });
+    }
+
+    fn handle_swap_key(&mut self, key: KeyEvent) {
+        if self.confirm_swapoff {
+            self.confirm_swapoff = false;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.swap_toggle(false);
+            }
+            return;
+        }
+        let len = self.swaps.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.swap_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.swap_list.up(len),
+            KeyCode::Char('g') => self.swap_list.top(len),
+            KeyCode::Char('G') => self.swap_list.bottom(len),
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('a') => self.swap_toggle(true),
+            KeyCode::Char('x') => match self.selected_swap() {
+                Some(swap) if swap.active && swap.unit.is_some() => self.confirm_swapoff = true,
+                Some(_) => self.swap_toggle(false),
+                None => {}
+            },
+            KeyCode::Enter => match self.selected_swap().map(|s| s.unit.clone()) {
+                Some(Some(unit)) => self.jump_to_unit = Some(unit),
+                Some(None) => self.messages.warn("No swap unit for this area"),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn devices(&self) -> &[BlockDevice] {
+        self.info.as_ref().map_or(&[], |info| &info.devices)
+    }
+
+    fn selected(&self) -> Option<&BlockDevice> {
+        self.devices().get(self.list.selected)
+    }
+
+    /// Unit the user asked to jump to, consumed by the app
+    pub fn take_jump(&mut self) -> Option<String> {
+        self.jump_to_unit.take()
+    }
+}
+
+impl Context for StorageContext {
+    fn name(&self) -> &'static str {
+        "Storage"
+    }
+
+    fn auto_refresh(&mut self) {
+        self.refresh();
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect) {
+        let swap_height = (self.swaps.len().max(1) as u16 + 3).min(8);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(swap_height),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        draw_devices(self, f, chunks[0]);
+        draw_swaps(self, f, chunks[1]);
+
+        let status = if self.swaps_focused {
+            match self.selected_swap() {
+                Some(swap) if self.confirm_swapoff => format!(
+                    "Turn off swap on {}? Its pages move back to RAM. [y/n]",
+                    swap.path
+                ),
+                _ => "a:swapon x:swapoff Enter:swap unit s/Esc:devices r:refresh".to_string(),
+            }
+        } else {
+            match self.selected() {
+                Some(dev) if dev.mountpoints.len() > 1 => format!(
+                    "Also mounted on {} │ Enter:mount unit s:swap r:refresh",
+                    dev.mountpoints[1..].join(" ")
+                ),
+                _ => "Enter:mount unit s:swap r:refresh".to_string(),
+            }
+        };
+        f.render_widget(
+            Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
+            chunks[1],
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('s') if !self.confirm_swapoff => {
+                self.swaps_focused = !self.swaps_focused;
+                return;
+            }
+            KeyCode::Esc if self.swaps_focused && !self.confirm_swapoff => {
+                self.swaps_focused = false;
+                return;
+            }
+            _ => {}
+        }
+        if self.swaps_focused {
+            self.handle_swap_key(key);
+            return;
+        }
+
+        let len = self.devices().len();
+        match key.code {
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('j') | KeyCode::Down => self.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => self.list.page_up(len),
+            KeyCode::Char('g') => self.list.top(len),
+            KeyCode::Char('G') => self.list.bottom(len),
+            KeyCode::Enter => match self.selected().map(|d| d.mountpoints.first()) {
+                Some(Some(mountpoint)) => {
+                    self.jump_to_unit = Some(path_unit_name(mountpoint, "mount"));
+                }
+                Some(None) => self.messages.warn("Not mounted"),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    async fn tick(&mut self) {
+        match self.swap_action.poll() {
+            Some(Ok(done)) => {
+                self.messages.info(done);
+                self.swaps_pending = Pending::spawn_async(SwapEntry::gather(self.systemd.clone()));
+            }
+            Some(Err(e)) => self.messages.error(format!("Swap: {}", e)),
+            None => {}
+        }
+        match self.swaps_pending.poll() {
+            Some(Ok(swaps)) => {
+                self.swap_list.clamp(swaps.len());
+                self.swaps = swaps;
+            }
+            // /proc/swaps and the bus are both needed; the list stays as
+            // it was and the devices table carries on
+            Some(Err(e)) => tracing::debug!("Swap areas: {}", e),
+            None => {}
+        }
+
+        let Some(result) = self.pending.poll() else {
+            return;
+        };
+        let result =
+            result.map_err(|e| RootworkError::from_anyhow("Failed to read block devices", &e));
+        self.info = note_refresh_error(&mut self.error, &self.messages, result);
+        self.list
+            .clamp(self.info.as_ref().map_or(0, |info| info.devices.len()));
+    }
+}
+
+fn draw_devices(ctx: &StorageContext, f: &mut Frame, area: Rect) {
+    let devices = ctx.devices();
+    let disks = devices.iter().filter(|d| !d.partition).count();
+    let block = Block::default()
+        .title(ctx.pending.title(&format!("Storage ({} disks)", disks)))
+        .borders(Borders::ALL);
+
+    if let Some(ref error) = ctx.error {
+        f.render_widget(Paragraph::new(error.lines()).block(block), area);
+        return;
+    }
+
+    if devices.is_empty() {
+        let text = if ctx.pending.is_pending() {
+            "Loading..."
+        } else {
+            "No block devices"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    // Narrow terminals drop the model column
+    let narrow = area.width < NARROW_WIDTH;
+    let mut titles = vec![
+        "Device",
+        "Size",
+        "Type",
+        "FS",
+        "Label",
+        "Mounted on",
+        "Usage",
+    ];
+    let mut widths = vec![
+        Constraint::Length(22),
+        Constraint::Length(10),
+        Constraint::Length(4),
+        Constraint::Length(8),
+        Constraint::Length(14),
+        Constraint::Min(12),
+        Constraint::Length(USAGE_BAR_WIDTH as u16 + 5),
+    ];
+    if !narrow {
+        titles.insert(1, "Model");
+        widths.insert(1, Constraint::Length(24));
+    }
+    let header = Row::new(titles).style(Style::default().add_modifier(Modifier::BOLD));
+
+    let table = SelectableTable::new(&ctx.list, devices.len(), widths, |i| {
+        let dev = &devices[i];
+        let name = if dev.partition {
+            format!("  └─{}", dev.name)
+        } else {
+            dev.name.clone()
+        };
+        let kind = match (dev.partition, dev.removable, dev.rotational) {
+            (true, _, _) => "part",
+            (false, true, _) => "usb",
+            (false, false, true) => "hdd",
+            (false, false, false) => "ssd",
+        };
+        let (usage, usage_color) = match dev.usage {
+            Some(usage) if usage.total > 0 => {
+                let percent = usage.percent();
+                let color = match percent {
+                    90.. => crate::palette::light_red(),
+                    75.. => crate::palette::yellow(),
+                    _ => crate::palette::green(),
+                };
+                (usage_bar(percent), color)
+            }
+            _ => ("-".to_string(), crate::palette::gray()),
+        };
+        let dash = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+
+        let mut cells = vec![
+            Span::styled(
+                name,
+                if dev.partition {
+                    Style::default()
+                } else {
+                    Style::default()
+                        .fg(crate::palette::cyan())
+                        .add_modifier(Modifier::BOLD)
+                },
+            ),
+            Span::raw(format_bytes(dev.size)),
+            Span::styled(kind, Style::default().fg(crate::palette::gray())),
+            Span::raw(dash(&dev.fs_type)),
+            Span::raw(dash(&dev.label)),
+            Span::raw(
+                dev.mountpoints
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Span::styled(usage, Style::default().fg(usage_color)),
+        ];
+        if !narrow {
+            cells.insert(
+                1,
+                Span::styled(
+                    dash(&dev.model),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+            );
+        }
+        Row::new(cells)
+    })
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+fn draw_swaps(ctx: &StorageContext, f: &mut Frame, area: Rect) {
+    let total: u64 = ctx.swaps.iter().map(|s| s.size).sum();
+    let used: u64 = ctx.swaps.iter().map(|s| s.used).sum();
+    let title = format!(
+        "Swap {} of {} used{}",
+        format_bytes(used),
+        format_bytes(total),
+        if ctx.swaps_focused {
+            " [a: on, x: off, s: back]"
+        } else {
+            " [s: select]"
+        }
+    );
+    let mut block = Block::default()
+        .title(ctx.swaps_pending.title(&title))
+        .borders(Borders::ALL);
+    if ctx.swaps_focused {
+        block = block.border_style(Style::default().fg(crate::palette::cyan()));
+    }
+
+    if ctx.swaps.is_empty() {
+        let text = if ctx.swaps_pending.is_pending() {
+            "Loading..."
+        } else {
+            "No swap"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec![
+        "Device", "Type", "Size", "Used", "Prio", "Unit", "zram",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(USAGE_BAR_WIDTH as u16 + 5),
+        Constraint::Length(5),
+        Constraint::Length(28),
+        Constraint::Length(30),
+    ];
+    let table = SelectableTable::new(&ctx.swap_list, ctx.swaps.len(), widths, |i| {
+        let swap = &ctx.swaps[i];
+        if !swap.active {
+            return Row::new(vec![
+                Span::styled(
+                    swap.path.clone(),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                Span::styled("off", Style::default().fg(crate::palette::gray())),
+                Span::raw("-"),
+                Span::raw("-"),
+                Span::raw("-"),
+                Span::raw(swap.unit.clone().unwrap_or_else(|| "-".to_string())),
+                Span::raw("-"),
+            ]);
+        }
+        let percent = if swap.size == 0 {
+            0
+        } else {
+            (swap.used * 100).div_ceil(swap.size)
+        };
+        let zram = match &swap.zram {
+            Some(z) if z.compressed > 0 => format!(
+                "{} {:.1}x, {} RAM",
+                z.algorithm,
+                z.original as f64 / z.compressed as f64,
+                format_bytes(z.memory)
+            ),
+            Some(z) => z.algorithm.clone(),
+            None => "-".to_string(),
+        };
+        Row::new(vec![
+            Span::styled(
+                swap.path.clone(),
+                Style::default().fg(crate::palette::cyan()),
+            ),
+            Span::raw(swap.kind.clone()),
+            Span::raw(format_bytes(swap.size)),
+            Span::raw(usage_bar(percent)),
+            Span::raw(
+                swap.priority
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Span::raw(swap.unit.clone().unwrap_or_else(|| "-".to_string())),
+            Span::raw(zram),
+        ])
+    })
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
@@ -1,5 +1,27 @@
-use crate::contexts::Context;
-use crate::systemd::client::{SystemdClient, UnitInfo};
+use crate::config::Config;
+use crate::contexts::{
+    Context, NARROW_WIDTH, Pending, SHORT_HEIGHT, centered_rect, copy_to_clipboard, format_bytes,
+    format_duration_usec, glob_match, user_name,
+};
+use crate::error::RootworkError;
+use crate::messages::Messages;
+use crate::presets::{Preset, PresetAction, PresetMenu, Presets};
+use crate::systemd::client::{
+    DiskUsage, ManagerStatus, ServiceExec, SystemdClient, UnitConditions, UnitInfo,
+    cgroup_interface, is_root,
+};
+use crate::systemd::dropin::{self, Scope};
+use crate::systemd::history::{self, EventKind, History};
+use crate::systemd::limits::{Limit, ResourceLimits};
+use crate::systemd::logs::{
+    LogRates, UnitActivity, UnitLogEntry, read_recent_unit_logs, read_unit_activity, read_unit_logs,
+};
+use crate::systemd::oom::{OomKill, OomKills, read_oom_kills};
+use crate::systemd::security::{self, Verdict};
+use crate::timestamp::{self, TimestampFormat};
+use crate::watchlist::Watchlist;
+use crate::widgets::picker::fuzzy_match_score;
+use crate::widgets::selectable::{ListState, SelectableList, SelectableTable, render_scrollbar};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -7,20 +29,12 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
 };
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-use std::ffi::CString;
-use std::os::raw::{c_char, c_int, c_void};
-
-/// A log entry with timestamp for display
-#[derive(Clone)]
-pub struct UnitLogEntry {
-    pub timestamp_micros: u64,
-    pub display_time: String,
-    pub message: String,
-}
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Command;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
@@ -32,6 +46,562 @@ pub enum ViewMode {
 pub enum SortBy {
     Name,
     State,
+    Recent,
+    Memory,
+    Cpu,
+    Tasks,
+    Restarts,
+}
+
+impl SortBy {
+    fn label(&self) -> &'static str {
+        match self {
+            SortBy::Name => "name",
+            SortBy::State => "state",
+            SortBy::Recent => "recent",
+            SortBy::Memory => "memory",
+            SortBy::Cpu => "cpu time",
+            SortBy::Tasks => "tasks",
+            SortBy::Restarts => "restarts",
+        }
+    }
+
+    /// The per-unit property this sorts on, largest first; units without
+    /// it go last
+    fn usage(&self, unit: &UnitInfo) -> Option<u64> {
+        match self {
+            SortBy::Memory => unit.memory_bytes,
+            SortBy::Cpu => unit.cpu_nsec,
+            SortBy::Tasks => unit.tasks_current,
+            SortBy::Restarts => unit.n_restarts.map(u64::from),
+            SortBy::Name | SortBy::State | SortBy::Recent => None,
+        }
+    }
+
+    /// Whether the sort needs properties ListUnits doesn't return
+    fn needs_properties(&self) -> bool {
+        !matches!(self, SortBy::Name | SortBy::State)
+    }
+}
+
+/// One-key filter on the unit's state, applied before the text filter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateFilter {
+    All,
+    Failed,
+    Active,
+    Running,
+    Inactive,
+}
+
+impl StateFilter {
+    fn label(&self) -> &'static str {
+        match self {
+            StateFilter::All => "all",
+            StateFilter::Failed => "failed",
+            StateFilter::Active => "active",
+            StateFilter::Running => "running",
+            StateFilter::Inactive => "inactive",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            StateFilter::All => StateFilter::Failed,
+            StateFilter::Failed => StateFilter::Active,
+            StateFilter::Active => StateFilter::Running,
+            StateFilter::Running => StateFilter::Inactive,
+            StateFilter::Inactive => StateFilter::All,
+        }
+    }
+
+    fn matches(&self, unit: &UnitInfo) -> bool {
+        match self {
+            StateFilter::All => true,
+            StateFilter::Failed => unit.active_state == "failed",
+            StateFilter::Active => unit.active_state == "active",
+            StateFilter::Running => unit.sub_state == "running",
+            StateFilter::Inactive => unit.active_state == "inactive",
+        }
+    }
+}
+
+/// A column in list view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    State,
+    Name,
+    Description,
+    Memory,
+    Cpu,
+    Uptime,
+    Enabled,
+    /// Journal messages over the last 5 and 60 minutes
+    Logs,
+    /// Messages at priority err or worse, same windows
+    Errors,
+}
+
+impl Column {
+    const ALL: [Column; 9] = [
+        Column::State,
+        Column::Name,
+        Column::Description,
+        Column::Memory,
+        Column::Cpu,
+        Column::Uptime,
+        Column::Enabled,
+        Column::Logs,
+        Column::Errors,
+    ];
+
+    const DEFAULT: [Column; 3] = [Column::State, Column::Name, Column::Description];
+
+    fn from_name(name: &str) -> Option<Column> {
+        Column::ALL.into_iter().find(|c| c.key() == name)
+    }
+
+    /// Name used in the config file
+    fn key(&self) -> &'static str {
+        match self {
+            Column::State => "state",
+            Column::Name => "name",
+            Column::Description => "description",
+            Column::Memory => "memory",
+            Column::Cpu => "cpu",
+            Column::Uptime => "uptime",
+            Column::Enabled => "enabled",
+            Column::Logs => "logs",
+            Column::Errors => "errors",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::State => "State",
+            Column::Name => "Name",
+            Column::Description => "Description",
+            Column::Memory => "Memory",
+            Column::Cpu => "CPU",
+            Column::Uptime => "Uptime",
+            Column::Enabled => "Enabled",
+            Column::Logs => "Logs 5m/1h",
+            Column::Errors => "Errs 5m/1h",
+        }
+    }
+
+    fn width(&self) -> Constraint {
+        match self {
+            Column::State => Constraint::Length(6),
+            Column::Name => Constraint::Length(35),
+            Column::Description => Constraint::Min(10),
+            Column::Memory => Constraint::Length(10),
+            Column::Cpu => Constraint::Length(10),
+            Column::Uptime => Constraint::Length(10),
+            Column::Enabled => Constraint::Length(10),
+            Column::Logs => Constraint::Length(11),
+            Column::Errors => Constraint::Length(11),
+        }
+    }
+
+    /// Whether the column needs `load_unit_properties`
+    fn needs_properties(&self) -> bool {
+        matches!(
+            self,
+            Column::Memory | Column::Cpu | Column::Uptime | Column::Enabled
+        )
+    }
+
+    /// Whether the column counts journal entries per unit
+    fn needs_log_rates(&self) -> bool {
+        matches!(self, Column::Logs | Column::Errors)
+    }
+
+    /// Parse `columns = "state,name,memory"` from the `[units]` config section
+    fn from_config(config: &Config) -> Vec<Column> {
+        let Some(value) = config.get("units", "columns") else {
+            return Column::DEFAULT.to_vec();
+        };
+        let columns: Vec<Column> = value
+            .split(',')
+            .filter_map(|name| {
+                let name = name.trim();
+                let col = Column::from_name(name);
+                if col.is_none() {
+                    tracing::warn!("Unknown unit column {:?}", name);
+                }
+                col
+            })
+            .collect();
+        if columns.is_empty() {
+            Column::DEFAULT.to_vec()
+        } else {
+            columns
+        }
+    }
+}
+
+/// State of the runtime column picker popup
+struct ColumnPicker {
+    selected: usize,
+    /// All columns in display order, with enabled flag
+    entries: Vec<(Column, bool)>,
+}
+
+impl ColumnPicker {
+    fn new(columns: &[Column]) -> Self {
+        let mut entries: Vec<(Column, bool)> = columns.iter().map(|c| (*c, true)).collect();
+        for col in Column::ALL {
+            if !columns.contains(&col) {
+                entries.push((col, false));
+            }
+        }
+        Self {
+            selected: 0,
+            entries,
+        }
+    }
+
+    fn columns(&self) -> Vec<Column> {
+        self.entries
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(c, _)| *c)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BatchAction {
+    RestartFailed,
+    Restart,
+    Start,
+    Stop,
+}
+
+impl BatchAction {
+    const ALL: [BatchAction; 4] = [
+        BatchAction::RestartFailed,
+        BatchAction::Restart,
+        BatchAction::Start,
+        BatchAction::Stop,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            BatchAction::RestartFailed => "restart failed",
+            BatchAction::Restart => "restart",
+            BatchAction::Start => "start",
+            BatchAction::Stop => "stop",
+        }
+    }
+
+    fn applies_to(&self, unit: &UnitInfo) -> bool {
+        match self {
+            BatchAction::RestartFailed => unit.is_failed(),
+            BatchAction::Restart => true,
+            BatchAction::Start => !unit.is_active(),
+            BatchAction::Stop => unit.is_active(),
+        }
+    }
+
+    fn next(&self) -> BatchAction {
+        let i = Self::ALL.iter().position(|a| a == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Units that require, bind to or are part of a target go down with
+    /// it on a stop, and are restarted along with it on a restart
+    fn propagates(&self) -> bool {
+        !matches!(self, BatchAction::Start)
+    }
+}
+
+/// A unit in a batch, with its outcome once the action ran on it
+struct BatchTarget {
+    name: String,
+    result: Option<Result<(), String>>,
+}
+
+/// Batch action popup over one tree group's filtered units. Shows a preview
+/// first, then runs one unit per tick so results fill in as they come.
+struct BatchPopup {
+    group: String,
+    action: BatchAction,
+    targets: Vec<BatchTarget>,
+    running: bool,
+    /// Running units outside the batch the action takes along
+    impact: Option<ActionImpact>,
+    impact_pending: Pending<(BatchAction, Result<ActionImpact>)>,
+}
+
+impl BatchPopup {
+    fn is_done(&self) -> bool {
+        self.running && self.targets.iter().all(|t| t.result.is_some())
+    }
+}
+
+/// Targets offered by the switcher, as `systemctl isolate` users know them
+const SWITCH_TARGETS: [&str; 4] = [
+    "graphical.target",
+    "multi-user.target",
+    "rescue.target",
+    "emergency.target",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TargetChange {
+    Isolate,
+    SetDefault,
+}
+
+impl TargetChange {
+    fn label(&self) -> &'static str {
+        match self {
+            TargetChange::Isolate => "isolate",
+            TargetChange::SetDefault => "make default",
+        }
+    }
+
+    /// "Switched to rescue.target" once the change went through
+    fn done(&self, target: &str) -> String {
+        match self {
+            TargetChange::Isolate => format!("Switched to {}", target),
+            TargetChange::SetDefault => format!("Default target is now {}", target),
+        }
+    }
+
+    /// "Failed to make rescue.target the default"
+    fn failed(&self, target: &str) -> String {
+        match self {
+            TargetChange::Isolate => format!("Failed to switch to {}", target),
+            TargetChange::SetDefault => format!("Failed to make {} the default", target),
+        }
+    }
+}
+
+/// Target switcher popup. Both changes are confirmed by typing the target's
+/// name: isolating stops everything the target doesn't pull in, possibly
+/// the session rootwork runs in.
+struct TargetPopup {
+    default: Option<String>,
+    default_pending: Pending<Result<String>>,
+    list: ListState,
+    /// Change being confirmed and the name typed so far
+    confirm: Option<(TargetChange, String)>,
+    /// The change in flight, with its target, for the message once done
+    change_pending: Pending<(TargetChange, &'static str, Result<()>)>,
+}
+
+impl TargetPopup {
+    fn selected(&self) -> &'static str {
+        SWITCH_TARGETS[self.list.selected.min(SWITCH_TARGETS.len() - 1)]
+    }
+}
+
+/// How long one user's manager gets to answer before it counts as
+/// unreachable
+const USER_MANAGER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A `user@UID.service` instance and what its manager says about itself
+struct UserManager {
+    uid: u32,
+    user: String,
+    unit: String,
+    active_state: String,
+    sub_state: String,
+    /// None while the instance isn't running, Err when it didn't answer
+    status: Option<Result<ManagerStatus, String>>,
+}
+
+impl UserManager {
+    fn is_broken(&self) -> bool {
+        match &self.status {
+            Some(Ok(status)) => status.is_degraded() || status.n_failed_units > 0,
+            Some(Err(_)) => true,
+            None => self.active_state == "failed",
+        }
+    }
+}
+
+/// Every user@ instance with its manager's state and failed unit count.
+/// Managers are asked one after another through `-M USER@.host`
+/// connections, each spawning a short-lived bridge in the user's session.
+async fn user_managers(systemd: SystemdClient) -> Result<Vec<UserManager>> {
+    let units = systemd.list_units_matching(&["user@*.service"]).await?;
+    let mut managers = Vec::new();
+    for unit in units {
+        let Some(uid) = unit
+            .name
+            .strip_prefix("user@")
+            .and_then(|n| n.strip_suffix(".service"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let user = user_name(uid).unwrap_or_else(|| uid.to_string());
+        let status = if unit.is_active() {
+            let reply = tokio::time::timeout(USER_MANAGER_TIMEOUT, async {
+                SystemdClient::connect_user_manager(&user)
+                    .await?
+                    .manager_status()
+                    .await
+            })
+            .await;
+            Some(match reply {
+                Ok(Ok(status)) => Ok(status),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err("no answer".to_string()),
+            })
+        } else {
+            None
+        };
+        managers.push(UserManager {
+            uid,
+            user,
+            unit: unit.name,
+            active_state: unit.active_state,
+            sub_state: unit.sub_state,
+            status,
+        });
+    }
+    managers.sort_by_key(|m| m.uid);
+    Ok(managers)
+}
+
+/// Popup listing the user@ instances
+struct UserManagersPopup {
+    managers: Vec<UserManager>,
+    pending: Pending<Result<Vec<UserManager>>>,
+    list: ListState,
+}
+
+impl UserManagersPopup {
+    fn load(systemd: &SystemdClient) -> Pending<Result<Vec<UserManager>>> {
+        Pending::spawn_async(user_managers(systemd.clone()))
+    }
+}
+
+/// A man page rendered to text for the built-in pager
+struct ManPager {
+    /// "sshd(8)"
+    title: String,
+    /// Index into the unit's man: links, cycled with Tab
+    doc: usize,
+    lines: Vec<String>,
+    pending: Pending<Result<Vec<String>, String>>,
+    scroll: u16,
+}
+
+impl ManPager {
+    fn open(page: &str, section: Option<&str>, doc: usize) -> Self {
+        let title = match section {
+            Some(section) => format!("{}({})", page, section),
+            None => page.to_string(),
+        };
+        let page = page.to_string();
+        let section = section.map(str::to_string);
+        // Fill the popup, which is 90% of the terminal minus its borders
+        let width = crossterm::terminal::size().map_or(80, |(w, _)| w as usize * 9 / 10 - 2);
+        Self {
+            title,
+            doc,
+            lines: Vec::new(),
+            pending: Pending::spawn(move || render_man_page(&page, section.as_deref(), width)),
+            scroll: 0,
+        }
+    }
+}
+
+/// What the lower pane of the detail popup shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DetailTab {
+    Logs,
+    Properties,
+    Security,
+    History,
+    Environment,
+    Limits,
+    Conditions,
+}
+
+type Property = (String, String, String);
+
+/// `systemctl show` for the detail unit: every D-Bus property as
+/// interface/name/value, with an incremental filter
+struct PropertyInspector {
+    properties: Vec<Property>,
+    pending: Pending<Result<Vec<Property>>>,
+    list: ListState,
+    filter: String,
+    editing: bool,
+}
+
+impl PropertyInspector {
+    fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+            pending: Pending::idle(),
+            list: ListState::new(),
+            filter: String::new(),
+            editing: false,
+        }
+    }
+
+    fn load(&mut self, systemd: &SystemdClient, unit: &str) {
+        let systemd = systemd.clone();
+        let unit = unit.to_string();
+        self.pending = Pending::spawn_async(async move { systemd.all_properties(&unit).await });
+    }
+
+    fn visible(&self) -> Vec<&Property> {
+        let needle = self.filter.to_lowercase();
+        self.properties
+            .iter()
+            .filter(|(_, name, value)| {
+                needle.is_empty()
+                    || name.to_lowercase().contains(&needle)
+                    || value.to_lowercase().contains(&needle)
+            })
+            .collect()
+    }
+
+    /// Navigation and filter keys; anything else falls through to the popup
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.editing {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.filter.clear();
+                }
+                _ => {}
+            }
+            self.list.top(self.visible().len());
+            return true;
+        }
+
+        let len = self.visible().len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => self.list.page_up(len),
+            KeyCode::Char('g') => self.list.top(len),
+            KeyCode::Char('G') => self.list.bottom(len),
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.list.top(self.visible().len());
+            }
+            _ => return false,
+        }
+        true
+    }
 }
 
 /// An item in the tree view - either a group or a unit
@@ -43,16 +613,109 @@ pub enum TreeItem {
         active: usize,
     },
     Unit {
-        unit: UnitInfo,
+        unit: Box<UnitInfo>,
+        /// A path or automount unit shown under the unit it triggers
+        nested: bool,
     },
 }
 
-#[derive(Debug, Clone, Copy)]
+/// What the cursor is on, so it can be found again after the list changes
+enum SelectionAnchor {
+    Unit(String),
+    Group(String),
+}
+
+/// Tree group of a unit: its type suffix, e.g. "service"
+fn unit_group(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or("unknown")
+}
+
+/// Unit types with a chip above the list, in chip order; other types are
+/// always shown
+const CHIP_TYPES: [&str; 8] = [
+    "service", "timer", "socket", "target", "mount", "scope", "slice", "device",
+];
+
+/// `[units] hide_types = "device, slice"`: types left out of list view
+fn hidden_types_from_config(config: &Config) -> HashSet<String> {
+    let Some(value) = config.get("units", "hide_types") else {
+        return HashSet::new();
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter(|name| {
+            let known = CHIP_TYPES.contains(name);
+            if !known {
+                tracing::warn!("Unknown unit type {:?} in hide_types", name);
+            }
+            known
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// systemd's own plumbing, D-Bus and the scopes of sessions and desktop
+/// apps: hidden by default so the list shows the workload. Devices are left
+/// to the type chips.
+const DEFAULT_INTERNAL: [&str; 7] = [
+    "systemd-*",
+    "dbus*",
+    "init.scope",
+    "session-*.scope",
+    "app-*.scope",
+    "run-*.scope",
+    "user-runtime-dir@*",
+];
+
+/// Which units count as internal, and whether they're hidden
+struct InternalUnits {
+    patterns: Vec<String>,
+    hidden: bool,
+}
+
+impl InternalUnits {
+    /// `[units] internal = "systemd-*, dbus*"` and `hide_internal`, on by
+    /// default
+    fn from_config(config: &Config) -> Self {
+        let patterns = match config.get("units", "internal") {
+            Some(value) => value
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            None => DEFAULT_INTERNAL.iter().map(|p| p.to_string()).collect(),
+        };
+        let hidden = match config.get("units", "hide_internal") {
+            None | Some("true") => true,
+            Some("false") => false,
+            Some(other) => {
+                tracing::warn!("Invalid units hide_internal {:?}", other);
+                true
+            }
+        };
+        Self { patterns, hidden }
+    }
+
+    /// Whether the unit is hidden; failed ones always show
+    fn hides(&self, unit: &UnitInfo) -> bool {
+        self.hidden
+            && unit.active_state != "failed"
+            && self.patterns.iter().any(|p| glob_match(p, &unit.name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum UnitAction {
     Start,
     Stop,
     Enable,
     Disable,
+    /// Stop a mount unit
+    Unmount,
+    /// Reload a mount unit, which remounts it with its options
+    Remount,
 }
 
 impl UnitAction {
@@ -62,60 +725,337 @@ impl UnitAction {
             UnitAction::Stop => "stop",
             UnitAction::Enable => "enable",
             UnitAction::Disable => "disable",
+            UnitAction::Unmount => "unmount",
+            UnitAction::Remount => "remount",
+        }
+    }
+
+    /// Actions that can take a box off the network or out of the next boot
+    fn is_disruptive(&self) -> bool {
+        matches!(
+            self,
+            UnitAction::Stop | UnitAction::Disable | UnitAction::Unmount
+        )
+    }
+
+    /// Dependency properties the manager follows when queueing this job:
+    /// stopping a unit stops what requires, binds to or is part of it;
+    /// starting it starts what it requires, wants or binds to
+    fn propagates_through(&self) -> &'static [&'static str] {
+        match self {
+            UnitAction::Start => &["Requires", "Wants", "BindsTo"],
+            UnitAction::Stop | UnitAction::Unmount => &["RequiredBy", "BoundBy", "ConsistsOf"],
+            UnitAction::Enable | UnitAction::Disable | UnitAction::Remount => &[],
+        }
+    }
+}
+
+/// Units visited at most when working out what an action takes along
+const IMPACT_MAX_UNITS: usize = 64;
+
+/// Other units a start or stop would touch, shown while confirming
+#[derive(Debug, Clone, Default)]
+struct ActionImpact {
+    /// Units the job propagates to, nearest first
+    units: Vec<String>,
+    /// Sockets, timers and paths that can start the unit again after a stop
+    triggered_by: Vec<String>,
+    /// The walk hit IMPACT_MAX_UNITS
+    truncated: bool,
+}
+
+/// Walk the dependency graph from `unit` the way the manager would
+/// propagate `action`
+async fn action_impact(
+    systemd: SystemdClient,
+    unit: String,
+    action: UnitAction,
+) -> Result<ActionImpact> {
+    let mut impact = dependency_walk(
+        &systemd,
+        std::slice::from_ref(&unit),
+        action.propagates_through(),
+    )
+    .await?;
+    if matches!(action, UnitAction::Stop | UnitAction::Unmount) {
+        impact.triggered_by = systemd.dependencies(&unit, &["TriggeredBy"]).await?;
+    }
+    Ok(impact)
+}
+
+/// Units reached from `roots` through the dependency `properties`, nearest
+/// first, the roots themselves left out
+async fn dependency_walk(
+    systemd: &SystemdClient,
+    roots: &[String],
+    properties: &[&str],
+) -> Result<ActionImpact> {
+    let mut impact = ActionImpact::default();
+    let mut seen: HashSet<String> = roots.iter().cloned().collect();
+    let mut queue: std::collections::VecDeque<String> = roots.iter().cloned().collect();
+    while let Some(name) = queue.pop_front() {
+        for dep in systemd.dependencies(&name, properties).await? {
+            if !seen.insert(dep.clone()) {
+                continue;
+            }
+            if seen.len() > IMPACT_MAX_UNITS {
+                impact.truncated = true;
+                queue.clear();
+                break;
+            }
+            impact.units.push(dep.clone());
+            queue.push_back(dep);
         }
     }
+    Ok(impact)
 }
 
-unsafe extern "C" {
-    fn sd_journal_open(ret: *mut *mut c_void, flags: c_int) -> c_int;
-    fn sd_journal_close(j: *mut c_void);
-    fn sd_journal_add_match(j: *mut c_void, data: *const c_void, size: usize) -> c_int;
-    fn sd_journal_seek_tail(j: *mut c_void) -> c_int;
-    fn sd_journal_previous(j: *mut c_void) -> c_int;
-    fn sd_journal_get_realtime_usec(j: *mut c_void, ret: *mut u64) -> c_int;
-    fn sd_journal_get_data(
-        j: *mut c_void,
-        field: *const c_char,
-        data: *mut *const u8,
-        length: *mut usize,
-    ) -> c_int;
+/// How often the detail pane re-reads the unit's journal while following
+const DETAIL_FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When a service counts as restart-storming: more than `restarts`
+/// restarts within `window`
+#[derive(Debug, Clone, Copy)]
+struct StormThreshold {
+    restarts: usize,
+    window: Duration,
+}
+
+impl StormThreshold {
+    /// `[units] storm_restarts` (0 turns detection off) and `storm_minutes`
+    fn from_config(config: &Config) -> Option<Self> {
+        let number = |key: &str, default: u64| match config.get("units", key) {
+            Some(value) => value.parse().unwrap_or_else(|_| {
+                tracing::warn!("Invalid units {} {:?}", key, value);
+                default
+            }),
+            None => default,
+        };
+        let restarts = number("storm_restarts", 5) as usize;
+        let minutes = number("storm_minutes", 10).max(1);
+        (restarts > 0).then(|| Self {
+            restarts,
+            window: Duration::from_secs(minutes * 60),
+        })
+    }
 }
 
-const SD_JOURNAL_LOCAL_ONLY: c_int = 1;
+/// Units that need the name typed out before a stop or disable
+const DEFAULT_PROTECTED: [&str; 3] = ["dbus.service", "systemd-journald.service", "sshd.service"];
+
+fn protected_from_config(config: &Config) -> Vec<String> {
+    match config.get("units", "protected") {
+        Some(value) => value
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+        None => DEFAULT_PROTECTED.iter().map(|s| s.to_string()).collect(),
+    }
+}
 
 pub struct UnitsContext {
     units: Vec<UnitInfo>,
     filtered_units: Vec<UnitInfo>,
     tree_items: Vec<TreeItem>,
-    selected: usize,
-    scroll_offset: usize,
+    list: ListState,
     filter: String,
     filter_backup: Option<String>,
     show_filter: bool,
     loading: bool,
-    error: Option<String>,
+    error: Option<RootworkError>,
     view_mode: ViewMode,
     sort_by: SortBy,
     sort_ascending: bool,
+    state_filter: StateFilter,
     collapsed_groups: HashSet<String>, // Set of collapsed group names
+    /// Tree view shows path and automount units under what they trigger
+    nest_triggers: bool,
     systemd: SystemdClient,
     detail_unit: Option<UnitInfo>,
     detail_logs: Vec<UnitLogEntry>,
+    /// Logs plus the InvocationID they were limited to
+    detail_logs_pending: Pending<(Option<String>, Vec<UnitLogEntry>)>,
+    /// Only show the current run's logs (`i`)
+    detail_current_run: bool,
+    /// InvocationID the shown logs are limited to
+    detail_invocation: Option<String>,
+    /// When the detail logs were last read, to pace following
+    detail_logs_read: Instant,
+    timestamps: TimestampFormat,
+    detail_docs: Vec<String>,
+    detail_docs_pending: Pending<Result<Vec<String>>>,
+    /// Condition*=/Assert*= checks, read for every unit to explain an
+    /// inactive one
+    detail_conditions: Option<UnitConditions>,
+    /// statvfs of the detail unit's mount point, for mount units
+    detail_usage: Option<Result<DiskUsage, String>>,
+    detail_usage_pending: Pending<Result<DiskUsage, String>>,
+    /// Memory and CPU of the unit in the popup while it's open
+    usage_history: Option<UsageHistory>,
+    detail_conditions_pending: Pending<Result<UnitConditions>>,
+    conditions_list: ListState,
+    detail_exec: Option<ServiceExec>,
+    detail_exec_pending: Pending<Result<ServiceExec>>,
+    detail_tab: DetailTab,
+    inspector: PropertyInspector,
+    /// Sandboxing report for a service, derived from the inspector's properties
+    detail_security: Option<security::Report>,
+    security_list: ListState,
+    /// State changes seen this session, seeded per unit from the journal
+    history: History,
+    history_pending: Pending<(String, Vec<history::Event>)>,
+    history_list: ListState,
+    storm_threshold: Option<StormThreshold>,
+    /// Services over the threshold, with their restart count in the window
+    storms: HashMap<String, usize>,
+    restart_counts_pending: Pending<Vec<(String, Result<u32>)>>,
+    /// OOM kills per unit since boot, from the journal
+    oom_kills: OomKills,
+    oom_pending: Pending<(Vec<OomKill>, u64)>,
+    oom_read: Option<Instant>,
+    env_list: ListState,
+    /// `KEY=VALUE` being typed for a drop-in of the given scope
+    env_input: Option<(Scope, String)>,
+    /// What rootwork's own drop-ins set, to tell overrides apart
+    env_overrides: Vec<(Scope, String)>,
+    env_pending: Pending<Result<String>>,
+    limits: Option<ResourceLimits>,
+    limits_pending: Pending<Result<ResourceLimits>>,
+    limits_list: ListState,
+    /// New value being typed for a limit, and where it should stick
+    limit_input: Option<(Limit, Scope, String)>,
+    limit_set_pending: Pending<Result<String>>,
     confirm_action: Option<UnitAction>,
+    /// Unit name typed so far when confirming on a protected unit
+    confirm_input: Option<String>,
+    /// Units the action being confirmed would also start or stop
+    confirm_impact: Option<ActionImpact>,
+    confirm_impact_pending: Pending<Result<ActionImpact>>,
+    protected: Vec<String>,
     pending_action: Option<UnitAction>,
-    action_status: Option<String>,
     detail_log_scroll: usize,
     detail_log_follow: bool,
+    shell_requested: bool,
+    external_command: Option<Command>,
+    needs_properties: bool,
+    columns: Vec<Column>,
+    column_picker: Option<ColumnPicker>,
+    /// Unit types list view leaves out
+    hidden_types: HashSet<String>,
+    internal: InternalUnits,
+    /// Units the last filter left out as internal
+    internal_hidden: usize,
+    /// Units per type chip, counted after the internal and state filters
+    type_counts: HashMap<String, usize>,
+    /// Chip under the cursor while toggling types with `y`
+    type_chip: Option<usize>,
+    batch: Option<BatchPopup>,
+    man_pager: Option<ManPager>,
+    targets: Option<TargetPopup>,
+    user_managers: Option<UserManagersPopup>,
+    background: Pending<Result<Vec<UnitInfo>>>,
+    /// Journal entries per unit for the Logs and Errors columns, read
+    /// while one of them is shown
+    log_rates: LogRates,
+    log_rates_pending: Pending<UnitActivity>,
+    log_rates_read: Option<Instant>,
+    /// Unit globs of the applied filter preset, on top of `filter`
+    preset: Option<Preset>,
+    preset_menu: Option<PresetMenu>,
+    presets: Presets,
+    messages: Messages,
+    watchlist: Watchlist,
+}
+
+/// How often the Logs and Errors columns catch up with the journal
+const LOG_RATES_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the detail popup samples memory and CPU
+const USAGE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Samples kept for the popup's sparklines, five minutes' worth
+const USAGE_SAMPLES: usize = 300;
+
+/// MemoryCurrent and CPUUsageNSec, when accounted
+type UsageSample = (Option<u64>, Option<u64>);
+
+/// MemoryCurrent and CPU use of the unit in the detail popup, sampled
+/// while it's open, oldest first
+struct UsageHistory {
+    unit: String,
+    memory: VecDeque<u64>,
+    /// Percent of one CPU between consecutive samples
+    cpu: VecDeque<f64>,
+    last_cpu: Option<(Instant, u64)>,
+    pending: Pending<(Instant, Result<UsageSample>)>,
+    read: Option<Instant>,
+}
+
+impl UsageHistory {
+    fn new(unit: &str) -> Self {
+        Self {
+            unit: unit.to_string(),
+            memory: VecDeque::new(),
+            cpu: VecDeque::new(),
+            last_cpu: None,
+            pending: Pending::idle(),
+            read: None,
+        }
+    }
+
+    fn record(&mut self, at: Instant, memory: Option<u64>, cpu_nsec: Option<u64>) {
+        if let Some(memory) = memory {
+            push_sample(&mut self.memory, memory);
+        }
+        if let Some(cpu_nsec) = cpu_nsec {
+            if let Some((prev_at, prev_nsec)) = self.last_cpu {
+                let wall = at.duration_since(prev_at).as_nanos() as f64;
+                // A restart starts the counter over
+                if wall > 0.0 && cpu_nsec >= prev_nsec {
+                    push_sample(&mut self.cpu, (cpu_nsec - prev_nsec) as f64 * 100.0 / wall);
+                }
+            }
+            self.last_cpu = Some((at, cpu_nsec));
+        }
+    }
+}
+
+fn push_sample<T>(samples: &mut VecDeque<T>, value: T) {
+    if samples.len() == USAGE_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(value);
+}
+
+/// How often OOM kills are read from the journal
+const OOM_INTERVAL: Duration = Duration::from_secs(10);
+
+/// OOM kills this recent stay in the status bar
+const OOM_RECENT_USEC: u64 = 3_600_000_000;
+
+/// Realtime of this boot, from CLOCK_BOOTTIME via /proc/uptime
+fn boot_usec() -> u64 {
+    let uptime = std::fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|s| s.split_whitespace().next()?.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    timestamp::now_usec().saturating_sub((uptime * 1_000_000.0) as u64)
 }
 
 impl UnitsContext {
-    pub async fn new(systemd: &SystemdClient) -> Result<Self> {
+    pub async fn new(
+        systemd: &SystemdClient,
+        config: &Config,
+        messages: &Messages,
+        watchlist: &Watchlist,
+        presets: &Presets,
+    ) -> Result<Self> {
         let mut ctx = Self {
             units: Vec::new(),
             filtered_units: Vec::new(),
             tree_items: Vec::new(),
-            selected: 0,
-            scroll_offset: 0,
+            list: ListState::new(),
             filter: String::new(),
             filter_backup: None,
             show_filter: false,
@@ -124,62 +1064,736 @@ impl UnitsContext {
             view_mode: ViewMode::Tree, // Default to tree view
             sort_by: SortBy::Name,
             sort_ascending: true,
+            state_filter: StateFilter::All,
             collapsed_groups: HashSet::new(), // Start with all collapsed
+            nest_triggers: false,
             systemd: systemd.clone(),
             detail_unit: None,
             detail_logs: Vec::new(),
+            detail_logs_pending: Pending::idle(),
+            detail_current_run: false,
+            detail_invocation: None,
+            detail_logs_read: Instant::now(),
+            timestamps: TimestampFormat::from_config(config),
+            detail_docs: Vec::new(),
+            detail_docs_pending: Pending::idle(),
+            detail_conditions: None,
+            detail_usage: None,
+            detail_usage_pending: Pending::idle(),
+            usage_history: None,
+            detail_conditions_pending: Pending::idle(),
+            conditions_list: ListState::new(),
+            detail_exec: None,
+            detail_exec_pending: Pending::idle(),
+            detail_tab: DetailTab::Logs,
+            inspector: PropertyInspector::new(),
+            detail_security: None,
+            security_list: ListState::new(),
+            history: History::default(),
+            history_pending: Pending::idle(),
+            history_list: ListState::new(),
+            storm_threshold: StormThreshold::from_config(config),
+            storms: HashMap::new(),
+            restart_counts_pending: Pending::idle(),
+            oom_kills: OomKills::default(),
+            oom_pending: Pending::idle(),
+            oom_read: None,
+            env_list: ListState::new(),
+            env_input: None,
+            env_overrides: Vec::new(),
+            env_pending: Pending::idle(),
+            limits: None,
+            limits_pending: Pending::idle(),
+            limits_list: ListState::new(),
+            limit_input: None,
+            limit_set_pending: Pending::idle(),
             confirm_action: None,
+            confirm_input: None,
+            confirm_impact: None,
+            confirm_impact_pending: Pending::idle(),
+            protected: protected_from_config(config),
             pending_action: None,
-            action_status: None,
             detail_log_scroll: 0,
             detail_log_follow: true,
+            shell_requested: false,
+            external_command: None,
+            needs_properties: false,
+            columns: Column::from_config(config),
+            column_picker: None,
+            hidden_types: hidden_types_from_config(config),
+            internal: InternalUnits::from_config(config),
+            internal_hidden: 0,
+            type_counts: HashMap::new(),
+            type_chip: None,
+            batch: None,
+            man_pager: None,
+            targets: None,
+            user_managers: None,
+            background: Pending::idle(),
+            log_rates: LogRates::default(),
+            log_rates_pending: Pending::idle(),
+            log_rates_read: None,
+            preset: None,
+            preset_menu: None,
+            presets: presets.clone(),
+            messages: messages.clone(),
+            watchlist: watchlist.clone(),
+        };
+
+        ctx.refresh(systemd).await;
+        Ok(ctx)
+    }
+
+    pub async fn refresh(&mut self, systemd: &SystemdClient) {
+        self.loading = true;
+        self.error = None;
+        // Anything listed in the background is older than what we're about to get
+        self.background = Pending::idle();
+
+        match systemd.list_units().await {
+            Ok(mut units) => {
+                if self.wants_properties()
+                    && let Err(e) = systemd.load_unit_properties(&mut units).await
+                {
+                    self.messages
+                        .warn(format!("Failed to load unit properties: {}", e));
+                }
+                systemd.load_type_details(&mut units).await;
+                self.history
+                    .observe(&self.units, &units, timestamp::now_usec());
+                self.units = units;
+                self.apply_filter_and_sort();
+                self.check_storms();
+                self.loading = false;
+            }
+            Err(e) => {
+                let err = RootworkError::from_anyhow("Failed to list units", &e);
+                self.messages.error(err.to_string());
+                self.error = Some(err);
+                self.loading = false;
+            }
+        }
+    }
+
+    /// Re-list units on a spawned task so the UI keeps responding
+    fn start_background_refresh(&mut self) {
+        let systemd = self.systemd.clone();
+        let with_properties = self.wants_properties();
+        self.background = Pending::spawn_async(async move {
+            let mut units = systemd.list_units().await?;
+            if with_properties {
+                systemd.load_unit_properties(&mut units).await?;
+            }
+            systemd.load_type_details(&mut units).await;
+            Ok(units)
+        });
+    }
+
+    /// Take a fresh listing without disturbing the view: filter, sort,
+    /// collapsed groups and scroll stay as they are, and an unchanged list
+    /// isn't rebuilt at all.
+    fn merge_units(&mut self, mut units: Vec<UnitInfo>) {
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut current: Vec<&UnitInfo> = self.units.iter().collect();
+        current.sort_by(|a, b| a.name.cmp(&b.name));
+        if current.len() == units.len() && current.iter().zip(&units).all(|(a, b)| *a == b) {
+            return;
+        }
+
+        if let Some(detail) = self.detail_unit.as_mut()
+            && let Some(fresh) = units.iter().find(|u| u.name == detail.name)
+        {
+            *detail = fresh.clone();
+        }
+        self.history
+            .observe(&self.units, &units, timestamp::now_usec());
+        self.units = units;
+        self.apply_filter_and_sort();
+        self.check_storms();
+    }
+
+    /// Fold in NRestarts from the listing, re-evaluate storms, and read
+    /// NRestarts of services that look like they might be cycling
+    fn check_storms(&mut self) {
+        let Some(threshold) = self.storm_threshold else {
+            return;
+        };
+        let now = timestamp::now_usec();
+        for unit in &self.units {
+            if let Some(count) = unit.n_restarts {
+                self.history.record_restart_count(&unit.name, count, now);
+            }
+        }
+        self.update_storms();
+
+        if self.restart_counts_pending.is_pending() {
+            return;
+        }
+        let since = now.saturating_sub(threshold.window.as_micros() as u64);
+        let suspects: Vec<String> = self
+            .units
+            .iter()
+            .filter(|u| u.name.ends_with(".service") && u.n_restarts.is_none())
+            .filter(|u| {
+                u.sub_state == "auto-restart"
+                    || u.active_state == "activating"
+                    || u.is_failed()
+                    || self.storms.contains_key(&u.name)
+                    || self
+                        .history
+                        .events(&u.name)
+                        .last()
+                        .is_some_and(|e| e.at_usec >= since)
+            })
+            .map(|u| u.name.clone())
+            .collect();
+        if suspects.is_empty() {
+            return;
+        }
+        let systemd = self.systemd.clone();
+        self.restart_counts_pending = Pending::spawn_async(async move {
+            let mut counts = Vec::with_capacity(suspects.len());
+            for name in suspects {
+                let count = systemd.n_restarts(&name).await;
+                counts.push((name, count));
+            }
+            counts
+        });
+    }
+
+    /// Recount restarts in the window; services newly over the threshold
+    /// are announced in the status line
+    fn update_storms(&mut self) {
+        let Some(threshold) = self.storm_threshold else {
+            return;
+        };
+        let since = timestamp::now_usec().saturating_sub(threshold.window.as_micros() as u64);
+        let mut storms = HashMap::new();
+        for unit in &self.units {
+            let restarts = self.history.restarts_since(&unit.name, since);
+            if restarts > threshold.restarts {
+                if !self.storms.contains_key(&unit.name) {
+                    self.messages.warn(format!(
+                        "{} restarted {} times in {} min",
+                        unit.name,
+                        restarts,
+                        threshold.window.as_secs() / 60
+                    ));
+                }
+                storms.insert(unit.name.clone(), restarts);
+            }
+        }
+        self.storms = storms;
+    }
+
+    /// Services restarting faster than the configured threshold
+    pub fn storm_count(&self) -> usize {
+        self.storms.len()
+    }
+
+    /// Read the journal entries logged since the last read, starting an
+    /// hour back. The journal is this machine's, so not for remote hosts.
+    fn poll_log_rates(&mut self) {
+        let now_usec = chrono::Utc::now().timestamp_micros().max(0) as u64;
+        if let Some(activity) = self.log_rates_pending.poll() {
+            self.log_rates.record(activity, now_usec);
+        }
+        if !self.columns.iter().any(|c| c.needs_log_rates())
+            || self.systemd.host().is_some()
+            || self.log_rates_pending.is_pending()
+            || self
+                .log_rates_read
+                .is_some_and(|t| t.elapsed() < LOG_RATES_INTERVAL)
+        {
+            return;
+        }
+        self.log_rates_read = Some(Instant::now());
+        let since = match self.log_rates.last_usec {
+            0 => now_usec.saturating_sub(3600 * 1_000_000),
+            last => last,
+        };
+        let user = self.systemd.is_user_mode();
+        self.log_rates_pending = Pending::spawn(move || read_unit_activity(since, user));
+    }
+
+    /// Catch up with OOM kills in the journal; kills after the first read
+    /// are announced
+    fn poll_oom_kills(&mut self) {
+        if let Some(batch) = self.oom_pending.poll() {
+            // The first read goes back to boot: history, not news
+            let first = self.oom_kills.last_usec == 0;
+            for kill in self.oom_kills.record(batch) {
+                if !first {
+                    self.messages.error(format!(
+                        "{} was killed by the {}",
+                        kill.unit,
+                        kill.killer.label()
+                    ));
+                }
+            }
+        }
+        if self.systemd.host().is_some()
+            || self.oom_pending.is_pending()
+            || self.oom_read.is_some_and(|t| t.elapsed() < OOM_INTERVAL)
+        {
+            return;
+        }
+        self.oom_read = Some(Instant::now());
+        let since = match self.oom_kills.last_usec {
+            0 => boot_usec(),
+            last => last,
+        };
+        let user = self.systemd.is_user_mode();
+        self.oom_pending = Pending::spawn(move || read_oom_kills(since, user));
+    }
+
+    /// Sample the popup's unit about once a second
+    fn poll_usage_history(&mut self) {
+        let Some(history) = self.usage_history.as_mut() else {
+            return;
+        };
+        if let Some((at, result)) = history.pending.poll() {
+            match result {
+                Ok((memory, cpu_nsec)) => history.record(at, memory, cpu_nsec),
+                Err(e) => {
+                    tracing::debug!("Usage sample of {} failed: {}", history.unit, e);
+                }
+            }
+        }
+        if history.pending.is_pending()
+            || history
+                .read
+                .is_some_and(|t| t.elapsed() < USAGE_SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        history.read = Some(Instant::now());
+        let systemd = self.systemd.clone();
+        let name = history.unit.clone();
+        history.pending = Pending::spawn_async(async move {
+            let result = systemd.usage_sample(&name).await;
+            (Instant::now(), result)
+        });
+    }
+
+    /// The latest OOM kill of the last hour, for the status bar
+    pub fn recent_oom(&self) -> Option<String> {
+        let since = timestamp::now_usec().saturating_sub(OOM_RECENT_USEC);
+        let (unit, tally) = self.oom_kills.latest_since(since)?;
+        let ago = timestamp::now_usec().saturating_sub(tally.last_usec) / 60_000_000;
+        Some(format!(
+            "OOM: {} killed by the {} {} min ago",
+            unit,
+            tally.killer.label(),
+            ago
+        ))
+    }
+
+    /// Whether the current sort or columns need per-unit properties
+    fn wants_properties(&self) -> bool {
+        self.sort_by.needs_properties() || self.columns.iter().any(|c| c.needs_properties())
+    }
+
+    fn close_column_picker(&mut self) {
+        if let Some(picker) = self.column_picker.take() {
+            let columns = picker.columns();
+            if !columns.is_empty() {
+                self.columns = columns;
+            }
+            if self.wants_properties() {
+                self.needs_properties = true;
+            }
+        }
+    }
+
+    fn handle_column_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.column_picker.as_mut() else {
+            return;
+        };
+        let last = picker.entries.len() - 1;
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                let entry = &mut picker.entries[picker.selected];
+                entry.1 = !entry.1;
+            }
+            KeyCode::Char('J') if picker.selected < last => {
+                picker.entries.swap(picker.selected, picker.selected + 1);
+                picker.selected += 1;
+            }
+            KeyCode::Char('K') if picker.selected > 0 => {
+                picker.entries.swap(picker.selected, picker.selected - 1);
+                picker.selected -= 1;
+            }
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('C') => self.close_column_picker(),
+            _ => {}
+        }
+    }
+
+    fn handle_type_chip_key(&mut self, key: KeyEvent) {
+        let Some(chip) = self.type_chip else {
+            return;
+        };
+        let last = CHIP_TYPES.len() - 1;
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Left => self.type_chip = Some(chip.saturating_sub(1)),
+            KeyCode::Char('l') | KeyCode::Right => self.type_chip = Some((chip + 1).min(last)),
+            KeyCode::Char(' ') => {
+                let name = CHIP_TYPES[chip];
+                if !self.hidden_types.remove(name) {
+                    self.hidden_types.insert(name.to_string());
+                }
+                self.apply_filter_and_sort();
+            }
+            // Only this type
+            KeyCode::Char('o') => {
+                self.hidden_types = CHIP_TYPES
+                    .iter()
+                    .filter(|t| **t != CHIP_TYPES[chip])
+                    .map(|t| t.to_string())
+                    .collect();
+                self.apply_filter_and_sort();
+            }
+            KeyCode::Char('a') => {
+                self.hidden_types.clear();
+                self.apply_filter_and_sort();
+            }
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('y') => self.type_chip = None,
+            _ => {}
+        }
+    }
+
+    /// Open the batch popup for the group under the cursor
+    fn open_batch(&mut self) {
+        if self.view_mode != ViewMode::Tree {
+            return;
+        }
+        let Some(TreeItem::Group { name, .. }) = self.tree_items.get(self.list.selected) else {
+            return;
+        };
+        let mut batch = BatchPopup {
+            group: name.clone(),
+            action: BatchAction::RestartFailed,
+            targets: Vec::new(),
+            running: false,
+            impact: None,
+            impact_pending: Pending::idle(),
+        };
+        self.fill_batch_targets(&mut batch);
+        self.batch = Some(batch);
+    }
+
+    /// Preview: the group's filtered units the action applies to. Protected
+    /// units are never stopped in bulk; they show up as skipped. Units the
+    /// action would take along are looked up in the background.
+    fn fill_batch_targets(&self, batch: &mut BatchPopup) {
+        batch.targets = self
+            .filtered_units
+            .iter()
+            .filter(|u| unit_group(&u.name) == batch.group && batch.action.applies_to(u))
+            .map(|u| BatchTarget {
+                name: u.name.clone(),
+                result: (batch.action == BatchAction::Stop && self.protected.contains(&u.name))
+                    .then(|| Err("protected, skipped".to_string())),
+            })
+            .collect();
+        batch.impact = None;
+        let roots: Vec<String> = batch
+            .targets
+            .iter()
+            .filter(|t| t.result.is_none())
+            .map(|t| t.name.clone())
+            .collect();
+        batch.impact_pending = if batch.action.propagates() && !roots.is_empty() {
+            let systemd = self.systemd.clone();
+            let action = batch.action;
+            Pending::spawn_async(async move {
+                let properties = UnitAction::Stop.propagates_through();
+                (action, dependency_walk(&systemd, &roots, properties).await)
+            })
+        } else {
+            Pending::idle()
+        };
+    }
+
+    fn handle_batch_key(&mut self, key: KeyEvent) {
+        let Some(mut batch) = self.batch.take() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if batch.running && !batch.is_done() {
+                    self.messages.warn(format!(
+                        "{} in {}: cancelled",
+                        batch.action.label(),
+                        batch.group
+                    ));
+                }
+                return;
+            }
+            KeyCode::Tab | KeyCode::Char('l') | KeyCode::Right if !batch.running => {
+                batch.action = batch.action.next();
+                self.fill_batch_targets(&mut batch);
+            }
+            KeyCode::Enter | KeyCode::Char('y') if !batch.running && !batch.targets.is_empty() => {
+                batch.running = true;
+            }
+            _ => {}
+        }
+        self.batch = Some(batch);
+    }
+
+    fn open_targets(&mut self) {
+        let systemd = self.systemd.clone();
+        self.targets = Some(TargetPopup {
+            default: None,
+            default_pending: Pending::spawn_async(async move { systemd.default_target().await }),
+            list: ListState::new(),
+            confirm: None,
+            change_pending: Pending::idle(),
+        });
+    }
+
+    fn handle_targets_key(&mut self, key: KeyEvent) {
+        let Some(popup) = self.targets.as_mut() else {
+            return;
+        };
+        let selected = popup.selected();
+        if let Some((change, input)) = popup.confirm.as_mut() {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter if input == selected => {
+                    let change = *change;
+                    let target = selected;
+                    let systemd = self.systemd.clone();
+                    popup.confirm = None;
+                    popup.change_pending = Pending::spawn_async(async move {
+                        let result = match change {
+                            TargetChange::Isolate => systemd.isolate(target).await,
+                            TargetChange::SetDefault => systemd.set_default_target(target).await,
+                        };
+                        (change, target, result)
+                    });
+                }
+                KeyCode::Esc => popup.confirm = None,
+                _ => {}
+            }
+            return;
+        }
+        let len = SWITCH_TARGETS.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => popup.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => popup.list.up(len),
+            KeyCode::Enter | KeyCode::Char('i') if !popup.change_pending.is_pending() => {
+                popup.confirm = Some((TargetChange::Isolate, String::new()))
+            }
+            KeyCode::Char('d') if !popup.change_pending.is_pending() => {
+                popup.confirm = Some((TargetChange::SetDefault, String::new()))
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.targets = None,
+            _ => {}
+        }
+    }
+
+    fn open_user_managers(&mut self) {
+        if !is_root() {
+            self.messages
+                .warn("Reading other users' service managers needs root");
+            return;
+        }
+        self.user_managers = Some(UserManagersPopup {
+            managers: Vec::new(),
+            pending: UserManagersPopup::load(&self.systemd),
+            list: ListState::new(),
+        });
+    }
+
+    fn handle_user_managers_key(&mut self, key: KeyEvent) {
+        let Some(popup) = self.user_managers.as_mut() else {
+            return;
+        };
+        let len = popup.managers.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => popup.list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => popup.list.up(len),
+            KeyCode::Char('g') => popup.list.top(len),
+            KeyCode::Char('G') => popup.list.bottom(len),
+            KeyCode::Char('r') if !popup.pending.is_pending() => {
+                popup.pending = UserManagersPopup::load(&self.systemd)
+            }
+            KeyCode::Enter => {
+                if let Some(unit) = popup
+                    .managers
+                    .get(popup.list.selected)
+                    .map(|m| m.unit.clone())
+                {
+                    self.user_managers = None;
+                    self.open_unit(&unit);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.user_managers = None,
+            _ => {}
+        }
+    }
+
+    fn poll_targets(&mut self) {
+        let Some(popup) = self.targets.as_mut() else {
+            return;
+        };
+        match popup.default_pending.poll() {
+            Some(Ok(default)) => popup.default = Some(default),
+            Some(Err(e)) => self
+                .messages
+                .warn(format!("Failed to read the default target: {}", e)),
+            None => {}
+        }
+        let Some((change, target, result)) = popup.change_pending.poll() else {
+            return;
+        };
+        match result {
+            Ok(()) => {
+                self.messages.info(change.done(target));
+                let systemd = self.systemd.clone();
+                popup.default_pending =
+                    Pending::spawn_async(async move { systemd.default_target().await });
+                self.start_background_refresh();
+            }
+            Err(e) => {
+                let err = RootworkError::from_anyhow(&change.failed(target), &e);
+                self.messages.error(err.to_string());
+            }
+        }
+    }
+
+    /// Run the next unit of a started batch; refresh once all are through
+    async fn step_batch(&mut self) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+        if !batch.running {
+            return;
+        }
+        let action = batch.action;
+        let Some(target) = batch.targets.iter_mut().find(|t| t.result.is_none()) else {
+            return;
+        };
+        let result = match action {
+            BatchAction::RestartFailed | BatchAction::Restart => {
+                self.systemd.restart_unit(&target.name).await
+            }
+            BatchAction::Start => self.systemd.start_unit(&target.name).await,
+            BatchAction::Stop => self.systemd.stop_unit(&target.name).await,
         };
+        target.result = Some(result.map_err(|e| e.to_string()));
 
-        ctx.refresh(systemd).await;
-        Ok(ctx)
+        if batch.is_done() {
+            let ok = batch
+                .targets
+                .iter()
+                .filter(|t| matches!(t.result, Some(Ok(()))))
+                .count();
+            let failed = batch.targets.len() - ok;
+            let summary = format!(
+                "{} in {}: {} OK, {} not done",
+                action.label(),
+                batch.group,
+                ok,
+                failed
+            );
+            if failed == 0 {
+                self.messages.info(summary);
+            } else {
+                self.messages.warn(summary);
+            }
+            self.refresh(&self.systemd.clone()).await;
+        }
     }
 
-    pub async fn refresh(&mut self, systemd: &SystemdClient) {
-        self.loading = true;
-        self.error = None;
-
-        match systemd.list_units().await {
-            Ok(units) => {
-                self.units = units;
+    fn handle_preset_menu_key(&mut self, key: KeyEvent) {
+        let Some(menu) = self.preset_menu.as_mut() else {
+            return;
+        };
+        match menu.handle_key(key) {
+            PresetAction::None => {}
+            PresetAction::Close => self.preset_menu = None,
+            PresetAction::Apply(preset) => {
+                self.preset_menu = None;
+                self.filter = preset.text.clone().unwrap_or_default();
+                self.messages.info(format!("Preset: {}", preset.name));
+                self.preset = Some(preset);
                 self.apply_filter_and_sort();
-                self.loading = false;
+                self.move_to_first_leaf_after_filter();
             }
-            Err(e) => {
-                self.error = Some(format!("Failed to list units: {}", e));
-                self.loading = false;
+            PresetAction::Save(name) => {
+                self.preset_menu = None;
+                self.save_preset(name);
+            }
+        }
+    }
+
+    /// Save the unit globs of the applied preset and the typed filter
+    fn save_preset(&mut self, name: String) {
+        let mut preset = self.preset.clone().unwrap_or_default();
+        preset.name = name;
+        preset.text = Some(self.filter.trim().to_string()).filter(|t| !t.is_empty());
+        if preset.units.is_empty() && preset.text.is_none() {
+            self.messages
+                .warn("Nothing to save: filter with / or apply a preset first");
+            return;
+        }
+        match self.presets.save(preset.clone()) {
+            Ok(()) => {
+                self.messages.info(format!("Saved preset {}", preset.name));
+                self.preset = Some(preset);
             }
+            Err(e) => self.messages.error(format!("Failed to save preset: {}", e)),
         }
     }
 
+    /// Internal units stay out of the list unless searched for by name or
+    /// pinned to the watchlist
+    fn hides_internal(&self, unit: &UnitInfo) -> bool {
+        self.filter.trim().is_empty()
+            && !self.watchlist.contains(&unit.name)
+            && self.internal.hides(unit)
+    }
+
     fn apply_filter_and_sort(&mut self) {
+        let anchor = self.selection_anchor();
+
+        self.internal_hidden = self.units.iter().filter(|u| self.hides_internal(u)).count();
+        let mut type_counts: HashMap<String, usize> = HashMap::new();
+        for unit in self
+            .units
+            .iter()
+            .filter(|u| !self.hides_internal(u) && self.state_filter.matches(u))
+        {
+            *type_counts
+                .entry(unit_group(&unit.name).to_string())
+                .or_default() += 1;
+        }
+        self.type_counts = type_counts;
+
         // Filter + fuzzy ranking
+        let units = self
+            .units
+            .iter()
+            .filter(|u| !self.hides_internal(u))
+            .filter(|u| self.state_filter.matches(u))
+            .filter(|u| {
+                self.view_mode == ViewMode::Tree || !self.hidden_types.contains(unit_group(&u.name))
+            })
+            .filter(|u| self.preset.as_ref().is_none_or(|p| p.matches_unit(&u.name)));
         let mut ranked_units: Vec<(UnitInfo, Option<usize>)> = if self.filter.is_empty() {
-            self.units.iter().cloned().map(|u| (u, None)).collect()
+            units.cloned().map(|u| (u, None)).collect()
         } else {
             let needle = self.filter.trim().to_lowercase();
-            self.units
-                .iter()
-                .filter_map(|u| {
-                    let name = u.name.to_lowercase();
-                    let desc = u.description.to_lowercase();
-
-                    let name_score = fuzzy_match_score(&name, &needle);
-                    let desc_score = fuzzy_match_score(&desc, &needle).map(|s| s + 200);
-
-                    let best_score = match (name_score, desc_score) {
-                        (Some(a), Some(b)) => Some(a.min(b)),
-                        (Some(a), None) => Some(a),
-                        (None, Some(b)) => Some(b),
-                        (None, None) => None,
-                    };
-
-                    best_score.map(|score| (u.clone(), Some(score)))
-                })
+            units
+                .filter_map(|u| unit_match_score(u, &needle).map(|score| (u.clone(), Some(score))))
                 .collect()
         };
 
@@ -197,6 +1811,17 @@ impl UnitsContext {
                         .active_state
                         .cmp(&b.active_state)
                         .then_with(|| a.name.cmp(&b.name)),
+                    // Most recent first, so "what just changed" is at the top
+                    SortBy::Recent => b
+                        .state_change_usec
+                        .cmp(&a.state_change_usec)
+                        .then_with(|| a.name.cmp(&b.name)),
+                    // Heaviest or flakiest first
+                    SortBy::Memory | SortBy::Cpu | SortBy::Tasks | SortBy::Restarts => self
+                        .sort_by
+                        .usage(b)
+                        .cmp(&self.sort_by.usage(a))
+                        .then_with(|| a.name.cmp(&b.name)),
                 }
             } else {
                 fuzzy_cmp
@@ -214,28 +1839,41 @@ impl UnitsContext {
         // Rebuild tree items
         self.rebuild_tree_items();
 
-        // Clamp selection
-        let total_items = match self.view_mode {
-            ViewMode::List => self.filtered_units.len(),
-            ViewMode::Tree => self.tree_items.len(),
-        };
+        // Follow the selected unit; if it's gone, the clamp below leaves the
+        // cursor on its nearest neighbor
+        self.restore_selection(anchor);
 
-        if total_items > 0 {
-            if self.selected >= total_items {
-                self.selected = total_items - 1;
-            }
-        } else {
-            self.selected = 0;
-        }
+        // Clamp selection
+        self.list.clamp(self.get_total_items());
     }
 
     fn rebuild_tree_items(&mut self) {
         self.tree_items.clear();
 
+        // Path and automount units whose target is listed go under it
+        // instead of into their own group
+        let mut nested: HashMap<&str, Vec<&UnitInfo>> = HashMap::new();
+        if self.nest_triggers {
+            for unit in &self.filtered_units {
+                let Some(target) = unit
+                    .activation()
+                    .and_then(|a| a.triggers.first())
+                    .filter(|t| self.filtered_units.iter().any(|u| u.name == **t))
+                else {
+                    continue;
+                };
+                nested.entry(target.as_str()).or_default().push(unit);
+            }
+        }
+        let is_nested = |unit: &UnitInfo| nested.values().flatten().any(|u| u.name == unit.name);
+
         // Group units by type
         let mut groups: HashMap<String, Vec<UnitInfo>> = HashMap::new();
         for unit in &self.filtered_units {
-            let ext = unit.name.split('.').last().unwrap_or("unknown").to_string();
+            if is_nested(unit) {
+                continue;
+            }
+            let ext = unit_group(&unit.name).to_string();
             groups.entry(ext).or_default().push(unit.clone());
         }
 
@@ -258,175 +1896,900 @@ impl UnitsContext {
             if let Some(units) = groups.get(&group_name) {
                 let active_count = units.iter().filter(|u| u.is_active()).count();
 
-                // Add group header
-                self.tree_items.push(TreeItem::Group {
-                    name: group_name.clone(),
-                    count: units.len(),
-                    active: active_count,
-                });
+                // Add group header
+                self.tree_items.push(TreeItem::Group {
+                    name: group_name.clone(),
+                    count: units.len(),
+                    active: active_count,
+                });
+
+                // Add units if group is not collapsed
+                if !self.collapsed_groups.contains(&group_name) {
+                    for unit in units {
+                        self.tree_items.push(TreeItem::Unit {
+                            unit: Box::new(unit.clone()),
+                            nested: false,
+                        });
+                        for trigger in nested.get(unit.name.as_str()).into_iter().flatten() {
+                            self.tree_items.push(TreeItem::Unit {
+                                unit: Box::new((*trigger).clone()),
+                                nested: true,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn selection_anchor(&self) -> Option<SelectionAnchor> {
+        match self.view_mode {
+            ViewMode::List => self
+                .filtered_units
+                .get(self.list.selected)
+                .map(|u| SelectionAnchor::Unit(u.name.clone())),
+            ViewMode::Tree => self
+                .tree_items
+                .get(self.list.selected)
+                .map(|item| match item {
+                    TreeItem::Group { name, .. } => SelectionAnchor::Group(name.clone()),
+                    TreeItem::Unit { unit, .. } => SelectionAnchor::Unit(unit.name.clone()),
+                }),
+        }
+    }
+
+    fn restore_selection(&mut self, anchor: Option<SelectionAnchor>) {
+        let Some(anchor) = anchor else {
+            return;
+        };
+        let found = match (self.view_mode, &anchor) {
+            (ViewMode::List, SelectionAnchor::Unit(name)) => {
+                self.filtered_units.iter().position(|u| u.name == *name)
+            }
+            (ViewMode::List, SelectionAnchor::Group(_)) => None,
+            (ViewMode::Tree, _) => {
+                let find_group = |group: &str| {
+                    self.tree_items.iter().position(
+                        |item| matches!(item, TreeItem::Group { name, .. } if name == group),
+                    )
+                };
+                match &anchor {
+                    SelectionAnchor::Group(group) => find_group(group),
+                    // A unit hidden in a collapsed group lands on its header
+                    SelectionAnchor::Unit(unit_name) => self
+                        .tree_items
+                        .iter()
+                        .position(|item| matches!(item, TreeItem::Unit { unit, .. } if unit.name == *unit_name))
+                        .or_else(|| find_group(unit_group(unit_name))),
+                }
+            }
+        };
+        if let Some(index) = found {
+            self.list.select(index, self.get_total_items());
+        }
+    }
+
+    pub fn selected_unit(&self) -> Option<&UnitInfo> {
+        match self.view_mode {
+            ViewMode::List => self.filtered_units.get(self.list.selected),
+            ViewMode::Tree => {
+                // Find the selected tree item, if it's a unit return it
+                if let Some(item) = self.tree_items.get(self.list.selected) {
+                    match item {
+                        TreeItem::Unit { unit, .. } => Some(unit),
+                        TreeItem::Group { .. } => None,
+                    }
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Move path and automount units under the unit they trigger, or back
+    /// into their own groups
+    fn toggle_nest_triggers(&mut self) {
+        let anchor = self.selection_anchor();
+        self.nest_triggers = !self.nest_triggers;
+        self.messages.info(if self.nest_triggers {
+            "Path and automount units shown under what they trigger"
+        } else {
+            "Path and automount units shown in their own groups"
+        });
+        if self.view_mode == ViewMode::Tree {
+            self.rebuild_tree_items();
+            self.restore_selection(anchor);
+        }
+    }
+
+    fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::List => ViewMode::Tree,
+            ViewMode::Tree => ViewMode::List,
+        };
+        self.list.reset();
+        self.type_chip = None;
+        // Hidden types only apply to list view
+        self.apply_filter_and_sort();
+    }
+
+    fn toggle_sort(&mut self) {
+        self.sort_by = match self.sort_by {
+            SortBy::Name => SortBy::State,
+            SortBy::State => SortBy::Recent,
+            SortBy::Recent => SortBy::Memory,
+            SortBy::Memory => SortBy::Cpu,
+            SortBy::Cpu => SortBy::Tasks,
+            SortBy::Tasks => SortBy::Restarts,
+            SortBy::Restarts => SortBy::Name,
+        };
+        if self.sort_by.needs_properties() {
+            // Timestamps and usage aren't part of ListUnits; fetch them on
+            // the next tick
+            self.needs_properties = true;
+        }
+        self.apply_filter_and_sort();
+    }
+
+    fn cycle_state_filter(&mut self) {
+        self.state_filter = self.state_filter.next();
+        self.apply_filter_and_sort();
+        self.move_to_first_leaf_after_filter();
+    }
+
+    fn toggle_internal(&mut self) {
+        self.internal.hidden = !self.internal.hidden;
+        self.apply_filter_and_sort();
+        self.messages.info(if self.internal.hidden {
+            "Hiding systemd-internal units"
+        } else {
+            "Showing all units"
+        });
+    }
+
+    fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+        self.apply_filter_and_sort();
+    }
+
+    fn toggle_current_group(&mut self) {
+        if self.view_mode != ViewMode::Tree {
+            return;
+        }
+
+        if let Some(TreeItem::Group { name, .. }) = self.tree_items.get(self.list.selected) {
+            let group_name = name.clone();
+            if self.collapsed_groups.contains(&group_name) {
+                self.collapsed_groups.remove(&group_name);
+            } else {
+                self.collapsed_groups.insert(group_name);
+            }
+            self.rebuild_tree_items();
+        }
+    }
+
+    fn expand_all(&mut self) {
+        let anchor = self.selection_anchor();
+        self.collapsed_groups.clear();
+        self.rebuild_tree_items();
+        self.restore_selection(anchor);
+    }
+
+    fn collapse_all(&mut self) {
+        let anchor = self.selection_anchor();
+        // Add all group names to collapsed set
+        self.collapsed_groups.clear();
+        for item in &self.tree_items {
+            if let TreeItem::Group { name, .. } = item {
+                self.collapsed_groups.insert(name.clone());
+            }
+        }
+        self.rebuild_tree_items();
+        self.restore_selection(anchor);
+    }
+
+    fn move_up(&mut self) {
+        self.list.up(self.get_total_items());
+    }
+
+    fn move_down(&mut self) {
+        self.list.down(self.get_total_items());
+    }
+
+    fn go_top(&mut self) {
+        self.list.top(self.get_total_items());
+    }
+
+    fn go_bottom(&mut self) {
+        self.list.bottom(self.get_total_items());
+    }
+
+    fn page_up(&mut self) {
+        self.list.page_up(self.get_total_items());
+    }
+
+    fn page_down(&mut self) {
+        self.list.page_down(self.get_total_items());
+    }
+
+    fn get_total_items(&self) -> usize {
+        match self.view_mode {
+            ViewMode::List => self.filtered_units.len(),
+            ViewMode::Tree => self.tree_items.len(),
+        }
+    }
+
+    fn move_to_first_leaf_after_filter(&mut self) {
+        let first = match self.view_mode {
+            ViewMode::List => 0,
+            ViewMode::Tree => self
+                .tree_items
+                .iter()
+                .position(|item| matches!(item, TreeItem::Unit { .. }))
+                .unwrap_or(0),
+        };
+        self.list.reset();
+        self.list.select(first, self.get_total_items());
+    }
+
+    /// Pin the unit under the cursor (or in the popup) to the watchlist
+    fn toggle_watch(&mut self) {
+        let Some(name) = self
+            .detail_unit
+            .as_ref()
+            .or_else(|| self.selected_unit())
+            .map(|u| u.name.clone())
+        else {
+            return;
+        };
+        match self.watchlist.toggle(&name) {
+            Ok(true) => self.messages.info(format!("Watching {}", name)),
+            Ok(false) => self.messages.info(format!("Stopped watching {}", name)),
+            Err(e) => self
+                .messages
+                .error(format!("Failed to save watchlist: {}", e)),
+        }
+    }
+
+    fn open_detail(&mut self) {
+        if let Some(unit) = self.selected_unit().cloned() {
+            self.show_detail(unit);
+        }
+    }
+
+    /// Replace the filter and land on the first matching unit
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        self.apply_filter_and_sort();
+        self.move_to_first_leaf_after_filter();
+    }
+
+    /// Loaded units matching `query`, best first, for the go-to-unit picker
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&UnitInfo> {
+        let needle = query.trim().to_lowercase();
+        let mut ranked: Vec<(usize, &UnitInfo)> = self
+            .units
+            .iter()
+            .filter_map(|u| unit_match_score(u, &needle).map(|score| (score, u)))
+            .collect();
+        ranked.sort_by(|(a_score, a), (b_score, b)| {
+            a_score.cmp(b_score).then_with(|| a.name.cmp(&b.name))
+        });
+        ranked.into_iter().take(limit).map(|(_, u)| u).collect()
+    }
+
+    /// Open the detail popup for a unit by name, e.g. when another context
+    /// jumps here. Returns false if the unit isn't loaded.
+    pub fn open_unit(&mut self, name: &str) -> bool {
+        match self.units.iter().find(|u| u.name == name).cloned() {
+            Some(unit) => {
+                self.show_detail(unit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Open the detail popup on the selected unit unless one is already up.
+    /// False when the cursor sits on a tree group.
+    pub fn ensure_detail(&mut self) -> bool {
+        if self.detail_unit.is_some() {
+            return true;
+        }
+        match self.selected_unit().cloned() {
+            Some(unit) => {
+                self.show_detail(unit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn show_detail(&mut self, unit: UnitInfo) {
+        self.detail_logs.clear();
+        self.load_detail_logs(&unit.name);
+        self.detail_docs.clear();
+        self.man_pager = None;
+        let systemd = self.systemd.clone();
+        let name = unit.name.clone();
+        self.detail_docs_pending =
+            Pending::spawn_async(async move { systemd.documentation(&name).await });
+        self.detail_conditions = None;
+        self.conditions_list.reset();
+        self.load_conditions(&unit.name);
+        self.load_usage(&unit);
+        self.usage_history = cgroup_interface(&unit.name).map(|_| UsageHistory::new(&unit.name));
+        self.detail_exec = None;
+        self.detail_exec_pending = Pending::idle();
+        if unit.name.ends_with(".service") {
+            self.load_exec(&unit.name);
+        }
+        self.env_list.reset();
+        self.env_input = None;
+        self.load_env_overrides(&unit.name);
+        self.limits = None;
+        self.limits_list.reset();
+        self.limit_input = None;
+        self.inspector = PropertyInspector::new();
+        self.detail_security = None;
+        self.security_list.reset();
+        match self.detail_tab {
+            DetailTab::Logs | DetailTab::Environment | DetailTab::Conditions => {}
+            DetailTab::Properties | DetailTab::Security => {
+                self.inspector.load(&self.systemd, &unit.name)
+            }
+            DetailTab::History => self.load_history(&unit.name),
+            DetailTab::Limits => self.load_limits(&unit.name),
+        }
+        self.history_list.reset();
+        self.detail_unit = Some(unit);
+        self.confirm_action = None;
+        self.confirm_input = None;
+        self.pending_action = None;
+        self.detail_log_follow = true;
+        self.scroll_to_bottom();
+    }
+
+    /// Switch the lower pane to `tab`, or back to the logs if it's already
+    /// showing. Properties (which the security report is built from) load the
+    /// first time either is shown; the history re-reads the journal each time.
+    fn toggle_detail_tab(&mut self, tab: DetailTab) {
+        self.detail_tab = if self.detail_tab == tab {
+            DetailTab::Logs
+        } else {
+            tab
+        };
+        let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone()) else {
+            return;
+        };
+        match self.detail_tab {
+            DetailTab::Properties | DetailTab::Security
+                if self.inspector.properties.is_empty() && !self.inspector.pending.is_pending() =>
+            {
+                self.inspector.load(&self.systemd, &name)
+            }
+            DetailTab::History => self.load_history(&name),
+            DetailTab::Limits => self.load_limits(&name),
+            DetailTab::Conditions => self.load_conditions(&name),
+            _ => {}
+        }
+    }
+
+    fn load_usage(&mut self, unit: &UnitInfo) {
+        self.detail_usage = None;
+        self.detail_usage_pending = match unit.mount().cloned() {
+            Some(mount) if unit.is_active() => {
+                Pending::spawn(move || mount.usage().map_err(|e| e.to_string()))
+            }
+            _ => Pending::idle(),
+        };
+    }
+
+    fn load_conditions(&mut self, unit: &str) {
+        let systemd = self.systemd.clone();
+        let name = unit.to_string();
+        self.detail_conditions_pending =
+            Pending::spawn_async(async move { systemd.conditions(&name).await });
+    }
+
+    fn handle_conditions_key(&mut self, key: KeyEvent) -> bool {
+        let len = self
+            .detail_conditions
+            .as_ref()
+            .map_or(0, |c| c.conditions.len() + c.asserts.len());
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.conditions_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.conditions_list.up(len),
+            KeyCode::Char('g') => self.conditions_list.top(len),
+            KeyCode::Char('G') => self.conditions_list.bottom(len),
+            _ => return false,
+        }
+        true
+    }
+
+    fn load_limits(&mut self, unit: &str) {
+        let systemd = self.systemd.clone();
+        let name = unit.to_string();
+        self.limits_pending =
+            Pending::spawn_async(async move { systemd.resource_limits(&name).await });
+    }
 
-                // Add units if group is not collapsed
-                if !self.collapsed_groups.contains(&group_name) {
-                    for unit in units {
-                        self.tree_items.push(TreeItem::Unit { unit: unit.clone() });
-                    }
-                }
+    fn handle_limits_key(&mut self, key: KeyEvent) -> bool {
+        let len = Limit::ALL.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.limits_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.limits_list.up(len),
+            KeyCode::Char('g') => self.limits_list.top(len),
+            KeyCode::Char('G') => self.limits_list.bottom(len),
+            KeyCode::Char('A') => self.toggle_ip_accounting(),
+            KeyCode::Enter => {
+                let Some(limits) = self.limits.as_ref() else {
+                    return true;
+                };
+                let limit = Limit::ALL[self.limits_list.selected];
+                let text = limit.edit_text(limits.get(limit));
+                self.limit_input = Some((limit, Scope::Runtime, text));
             }
+            _ => return false,
         }
+        true
     }
 
-    pub fn selected_unit(&self) -> Option<&UnitInfo> {
-        match self.view_mode {
-            ViewMode::List => self.filtered_units.get(self.selected),
-            ViewMode::Tree => {
-                // Find the selected tree item, if it's a unit return it
-                if let Some(item) = self.tree_items.get(self.selected) {
-                    match item {
-                        TreeItem::Unit { unit } => Some(unit),
-                        TreeItem::Group { .. } => None,
-                    }
-                } else {
-                    None
+    fn handle_limit_input_key(&mut self, key: KeyEvent) {
+        let Some((limit, scope, input)) = self.limit_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Tab => {
+                *scope = match scope {
+                    Scope::Runtime => Scope::Persistent,
+                    Scope::Persistent => Scope::Runtime,
                 }
             }
+            KeyCode::Enter => {
+                let (limit, scope, text) = (*limit, *scope, input.clone());
+                self.limit_input = None;
+                self.set_limit(limit, scope, text);
+            }
+            KeyCode::Esc => self.limit_input = None,
+            _ => {}
         }
     }
 
-    fn toggle_view_mode(&mut self) {
-        self.view_mode = match self.view_mode {
-            ViewMode::List => ViewMode::Tree,
-            ViewMode::Tree => ViewMode::List,
+    /// Runtime only: accounting is for measuring on the spot, the unit
+    /// file is where it belongs for good
+    fn toggle_ip_accounting(&mut self) {
+        let (Some(unit), Some(limits)) = (
+            self.detail_unit.as_ref().map(|u| u.name.clone()),
+            self.limits.as_ref(),
+        ) else {
+            return;
         };
-        self.selected = 0;
-        self.scroll_offset = 0;
-        if self.view_mode == ViewMode::Tree {
-            self.rebuild_tree_items();
-        }
+        let enable = !limits.ip_accounting;
+        let systemd = self.systemd.clone();
+        self.limit_set_pending = Pending::spawn_async(async move {
+            systemd
+                .set_ip_accounting(&unit, enable)
+                .await
+                .map_err(|e| anyhow::anyhow!("IPAccounting on {}: {}", unit, e))?;
+            Ok(format!(
+                "{} IPAccounting={} (runtime)",
+                unit,
+                if enable { "yes" } else { "no" }
+            ))
+        });
     }
 
-    fn toggle_sort(&mut self) {
-        self.sort_by = match self.sort_by {
-            SortBy::Name => SortBy::State,
-            SortBy::State => SortBy::Name,
+    fn set_limit(&mut self, limit: Limit, scope: Scope, text: String) {
+        let Some(unit) = self.detail_unit.as_ref().map(|u| u.name.clone()) else {
+            return;
         };
-        self.apply_filter_and_sort();
+        let value = match limit.parse(&text) {
+            Ok(value) => value,
+            Err(e) => {
+                self.messages.error(e);
+                return;
+            }
+        };
+        let systemd = self.systemd.clone();
+        self.limit_set_pending = Pending::spawn_async(async move {
+            systemd
+                .set_limit(&unit, limit, value, scope == Scope::Runtime)
+                .await?;
+            Ok(format!(
+                "{} {}={} ({})",
+                unit,
+                limit.setting(),
+                limit.format(value),
+                scope.label()
+            ))
+        });
     }
 
-    fn toggle_sort_direction(&mut self) {
-        self.sort_ascending = !self.sort_ascending;
-        self.apply_filter_and_sort();
+    fn load_exec(&mut self, unit: &str) {
+        let systemd = self.systemd.clone();
+        let name = unit.to_string();
+        self.detail_exec_pending =
+            Pending::spawn_async(async move { systemd.service_exec(&name).await });
     }
 
-    fn toggle_current_group(&mut self) {
-        if self.view_mode != ViewMode::Tree {
-            return;
+    fn load_env_overrides(&mut self, unit: &str) {
+        let user_mode = self.systemd.is_user_mode();
+        self.env_overrides = [Scope::Runtime, Scope::Persistent]
+            .into_iter()
+            .flat_map(|scope| {
+                dropin::read_environment(unit, scope, user_mode)
+                    .into_iter()
+                    .map(move |a| (scope, a))
+            })
+            .collect();
+    }
+
+    fn env_len(&self) -> usize {
+        self.detail_exec
+            .as_ref()
+            .map_or(0, |e| e.environment.len() + e.environment_files.len())
+    }
+
+    fn handle_env_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.env_len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.env_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.env_list.up(len),
+            KeyCode::Char('g') => self.env_list.top(len),
+            KeyCode::Char('G') => self.env_list.bottom(len),
+            KeyCode::Char('n') => self.env_input = Some((Scope::Runtime, String::new())),
+            KeyCode::Char('N') => self.env_input = Some((Scope::Persistent, String::new())),
+            KeyCode::Char('D') => self.remove_env_overrides(),
+            _ => return false,
         }
+        true
+    }
 
-        if let Some(item) = self.tree_items.get(self.selected) {
-            if let TreeItem::Group { name, .. } = item {
-                let group_name = name.clone();
-                if self.collapsed_groups.contains(&group_name) {
-                    self.collapsed_groups.remove(&group_name);
-                } else {
-                    self.collapsed_groups.insert(group_name);
-                }
-                self.rebuild_tree_items();
+    fn handle_env_input_key(&mut self, key: KeyEvent) {
+        let Some((scope, input)) = self.env_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let (scope, assignment) = (*scope, input.clone());
+                self.env_input = None;
+                self.set_env_override(scope, assignment);
             }
+            KeyCode::Esc => self.env_input = None,
+            _ => {}
         }
     }
 
-    fn expand_all(&mut self) {
-        self.collapsed_groups.clear();
-        self.rebuild_tree_items();
+    /// Drop-ins are written on this machine, so they'd miss a remote host
+    fn env_target(&self) -> Option<String> {
+        if self.systemd.host().is_some() {
+            self.messages
+                .warn("Environment overrides only work on the local machine");
+            return None;
+        }
+        if self.env_pending.is_pending() {
+            return None;
+        }
+        self.detail_unit.as_ref().map(|u| u.name.clone())
     }
 
-    fn collapse_all(&mut self) {
-        // Add all group names to collapsed set
-        self.collapsed_groups.clear();
-        for item in &self.tree_items {
-            if let TreeItem::Group { name, .. } = item {
-                self.collapsed_groups.insert(name.clone());
+    /// Write `KEY=VALUE` into rootwork's drop-in and reload the manager
+    fn set_env_override(&mut self, scope: Scope, assignment: String) {
+        let Some(unit) = self.env_target() else {
+            return;
+        };
+        let systemd = self.systemd.clone();
+        let user_mode = systemd.is_user_mode();
+        self.env_pending = Pending::spawn_async(async move {
+            let path = dropin::set_environment(&unit, scope, user_mode, &assignment)?;
+            systemd.reload_daemon().await?;
+            Ok(format!(
+                "Wrote {}; restart {} to apply",
+                path.display(),
+                unit
+            ))
+        });
+    }
+
+    fn remove_env_overrides(&mut self) {
+        let Some(unit) = self.env_target() else {
+            return;
+        };
+        let systemd = self.systemd.clone();
+        let user_mode = systemd.is_user_mode();
+        self.env_pending = Pending::spawn_async(async move {
+            let mut removed = false;
+            for scope in [Scope::Runtime, Scope::Persistent] {
+                removed |= dropin::remove_environment(&unit, scope, user_mode)?;
+            }
+            if !removed {
+                return Ok(format!("{} has no rootwork environment overrides", unit));
             }
+            systemd.reload_daemon().await?;
+            Ok(format!(
+                "Removed environment overrides; restart {} to apply",
+                unit
+            ))
+        });
+    }
+
+    /// Read PID 1's journal messages about the unit off the UI thread
+    fn load_history(&mut self, unit: &str) {
+        let unit = unit.to_string();
+        let user = self.systemd.is_user_mode();
+        self.history_pending = Pending::spawn(move || {
+            let events = history::read_journal(&unit, user, 200);
+            (unit, events)
+        });
+    }
+
+    fn history_len(&self) -> usize {
+        self.detail_unit
+            .as_ref()
+            .map_or(0, |u| self.history.events(&u.name).len())
+    }
+
+    fn handle_history_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.history_len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.history_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.history_list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.history_list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => self.history_list.page_up(len),
+            KeyCode::Char('g') => self.history_list.top(len),
+            KeyCode::Char('G') => self.history_list.bottom(len),
+            _ => return false,
         }
-        self.rebuild_tree_items();
+        true
     }
 
-    fn move_up(&mut self) {
-        if self.selected > 0 {
-            self.selected -= 1;
+    /// Score the service's sandboxing from freshly loaded properties
+    fn update_security(&mut self) {
+        let is_service = self
+            .detail_unit
+            .as_ref()
+            .is_some_and(|u| u.name.ends_with(".service"));
+        self.detail_security = is_service.then(|| {
+            let props = self
+                .inspector
+                .properties
+                .iter()
+                .filter(|(iface, _, _)| iface == "Service")
+                .map(|(_, key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            security::analyze(&props)
+        });
+        let len = self.security_len();
+        self.security_list.clamp(len);
+    }
+
+    fn security_len(&self) -> usize {
+        self.detail_security.as_ref().map_or(0, |r| r.checks.len())
+    }
+
+    fn handle_security_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.security_len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.security_list.down(len),
+            KeyCode::Char('k') | KeyCode::Up => self.security_list.up(len),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.security_list.page_down(len),
+            KeyCode::Char('b') | KeyCode::PageUp => self.security_list.page_up(len),
+            KeyCode::Char('g') => self.security_list.top(len),
+            KeyCode::Char('G') => self.security_list.bottom(len),
+            _ => return false,
         }
+        true
     }
 
-    fn move_down(&mut self) {
-        let max = match self.view_mode {
-            ViewMode::List => self.filtered_units.len(),
-            ViewMode::Tree => self.tree_items.len(),
+    /// Ask for confirmation; protected units need their name typed instead
+    /// of a single keypress
+    fn request_action(&mut self, action: UnitAction) {
+        let Some(unit) = self.detail_unit.as_ref() else {
+            return;
+        };
+        self.confirm_input = if action.is_disruptive() && self.protected.contains(&unit.name) {
+            Some(String::new())
+        } else {
+            None
+        };
+        self.confirm_action = Some(action);
+        self.confirm_impact = None;
+        self.confirm_impact_pending = if action.propagates_through().is_empty() {
+            Pending::idle()
+        } else {
+            let systemd = self.systemd.clone();
+            let name = unit.name.clone();
+            Pending::spawn_async(action_impact(systemd, name, action))
         };
-        if self.selected + 1 < max {
-            self.selected += 1;
+    }
+
+    /// Unmount and remount only make sense on a mount unit
+    fn request_mount_action(&mut self, action: UnitAction) {
+        match self.detail_unit.as_ref() {
+            Some(unit) if unit.mount().is_some() => self.request_action(action),
+            Some(unit) => self
+                .messages
+                .warn(format!("{}: not a mount unit", unit.name)),
+            None => {}
         }
     }
 
-    fn go_top(&mut self) {
-        self.selected = 0;
+    /// Keep only the units whose state the action would actually change:
+    /// running ones for a stop, stopped (or not loaded) ones for a start
+    fn settle_impact(&self, action: UnitAction, mut impact: ActionImpact) -> ActionImpact {
+        let running = |name: &str| {
+            self.units
+                .iter()
+                .find(|u| u.name == name)
+                .is_some_and(|u| !matches!(u.active_state.as_str(), "inactive" | "failed"))
+        };
+        match action {
+            UnitAction::Start => impact.units.retain(|name| !running(name)),
+            _ => impact.units.retain(|name| running(name)),
+        }
+        impact
     }
 
-    fn go_bottom(&mut self) {
-        let max = match self.view_mode {
-            ViewMode::List => self.filtered_units.len(),
-            ViewMode::Tree => self.tree_items.len(),
+    fn handle_typed_confirm_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.confirm_input.as_mut() else {
+            return;
         };
-        if max > 0 {
-            self.selected = max - 1;
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let matches = self.detail_unit.as_ref().is_some_and(|u| u.name == *input);
+                if matches {
+                    self.pending_action = self.confirm_action.take();
+                    self.confirm_input = None;
+                }
+            }
+            KeyCode::Esc => {
+                self.confirm_action = None;
+                self.confirm_input = None;
+            }
+            _ => {}
         }
     }
 
-    fn page_up(&mut self, page_size: usize) {
-        self.selected = self.selected.saturating_sub(page_size);
+    /// Read the detail unit's `doc`th man: link in the built-in pager
+    fn open_man_pager(&mut self, doc: usize) {
+        let pages: Vec<(&str, Option<&str>)> = self
+            .detail_docs
+            .iter()
+            .filter_map(|d| parse_man_uri(d))
+            .collect();
+        if pages.is_empty() {
+            self.messages.warn("No man page in Documentation=");
+            return;
+        }
+        let doc = doc % pages.len();
+        let (page, section) = pages[doc];
+        self.man_pager = Some(ManPager::open(page, section, doc));
     }
 
-    fn page_down(&mut self, page_size: usize) {
-        let max = match self.view_mode {
-            ViewMode::List => self.filtered_units.len(),
-            ViewMode::Tree => self.tree_items.len(),
+    fn handle_man_pager_key(&mut self, key: KeyEvent) {
+        let Some(pager) = self.man_pager.as_mut() else {
+            return;
         };
-        self.selected = (self.selected + page_size).min(max.saturating_sub(1));
+        let last = pager.lines.len().saturating_sub(1) as u16;
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => pager.scroll = (pager.scroll + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => pager.scroll = pager.scroll.saturating_sub(1),
+            KeyCode::Char(' ') | KeyCode::PageDown => pager.scroll = (pager.scroll + 20).min(last),
+            KeyCode::Char('b') | KeyCode::PageUp => pager.scroll = pager.scroll.saturating_sub(20),
+            KeyCode::Char('g') => pager.scroll = 0,
+            KeyCode::Char('G') => pager.scroll = last,
+            KeyCode::Tab => {
+                let next = pager.doc + 1;
+                self.open_man_pager(next);
+            }
+            KeyCode::Char('M') => {
+                self.man_pager = None;
+                self.open_man_page();
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.man_pager = None,
+            _ => {}
+        }
     }
 
-    fn get_total_items(&self) -> usize {
-        match self.view_mode {
-            ViewMode::List => self.filtered_units.len(),
-            ViewMode::Tree => self.tree_items.len(),
+    /// Open the first man: link of the detail unit in man(1)
+    fn open_man_page(&mut self) {
+        let Some((page, section)) = self.detail_docs.iter().find_map(|d| parse_man_uri(d)) else {
+            self.messages.warn("No man page in Documentation=");
+            return;
+        };
+        let mut cmd = Command::new("man");
+        if let Some(section) = section {
+            cmd.arg(section);
         }
+        cmd.arg(page);
+        self.external_command = Some(cmd);
     }
 
-    fn move_to_first_leaf_after_filter(&mut self) {
-        self.selected = match self.view_mode {
-            ViewMode::List => 0,
-            ViewMode::Tree => self
-                .tree_items
-                .iter()
-                .position(|item| matches!(item, TreeItem::Unit { .. }))
-                .unwrap_or(0),
+    /// Put the web links (or every link when there are none) on the clipboard
+    fn copy_doc_links(&mut self) {
+        let web: Vec<&str> = self
+            .detail_docs
+            .iter()
+            .filter(|d| !d.starts_with("man:"))
+            .map(|d| d.as_str())
+            .collect();
+        let links = if web.is_empty() {
+            self.detail_docs.iter().map(|d| d.as_str()).collect()
+        } else {
+            web
         };
-        self.scroll_offset = 0;
+        if links.is_empty() {
+            self.messages.warn("No documentation links");
+            return;
+        }
+        match copy_to_clipboard(&links.join("\n")) {
+            Ok(()) => self
+                .messages
+                .info(format!("Copied {} documentation link(s)", links.len())),
+            Err(e) => self.messages.error(format!("Copy failed: {}", e)),
+        }
     }
 
-    fn open_detail(&mut self) {
-        if let Some(unit) = self.selected_unit().cloned() {
-            self.detail_logs = read_recent_unit_logs(&unit.name, 120);
-            self.detail_unit = Some(unit);
-            self.confirm_action = None;
-            self.pending_action = None;
-            self.action_status = None;
-            self.detail_log_follow = true;
-            self.scroll_to_bottom();
+    /// Read the unit's journal off the UI thread; `tick()` picks it up.
+    /// Starting over drops any read still in flight for another unit.
+    fn load_detail_logs(&mut self, unit: &str) {
+        self.detail_logs_read = Instant::now();
+        // The journal read is this machine's, not the remote host's
+        if self.systemd.host().is_some() {
+            return;
+        }
+        let unit = unit.to_string();
+        let user = self.systemd.is_user_mode();
+        if !self.detail_current_run {
+            self.detail_logs_pending =
+                Pending::spawn(move || (None, read_recent_unit_logs(&unit, user, 120)));
+            return;
+        }
+        // The ID changes on every start, so look it up with each read
+        let systemd = self.systemd.clone();
+        self.detail_logs_pending = Pending::spawn_async(async move {
+            let id = systemd.invocation_id(&unit).await.ok().flatten();
+            let filter = id.clone();
+            let logs = tokio::task::spawn_blocking(move || {
+                read_unit_logs(&unit, user, filter.as_deref(), 120)
+            })
+            .await
+            .unwrap_or_default();
+            (id, logs)
+        });
+    }
+
+    fn toggle_current_run(&mut self) {
+        self.detail_current_run = !self.detail_current_run;
+        self.messages.info(if self.detail_current_run {
+            "Logs: current run only"
+        } else {
+            "Logs: all runs"
+        });
+        if let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone()) {
+            self.detail_tab = DetailTab::Logs;
+            self.load_detail_logs(&name);
         }
     }
 
-    fn close_detail(&mut self) {
+    pub fn close_detail(&mut self) {
         self.detail_unit = None;
+        self.usage_history = None;
         self.confirm_action = None;
+        self.confirm_input = None;
         self.pending_action = None;
         self.detail_log_scroll = 0;
         self.detail_log_follow = true;
@@ -435,115 +2798,146 @@ impl UnitsContext {
     fn scroll_to_bottom(&mut self) {
         self.detail_log_scroll = usize::MAX;
     }
-}
 
-fn read_recent_unit_logs(unit: &str, max: usize) -> Vec<UnitLogEntry> {
-    let mut out = Vec::new();
-    unsafe {
-        let mut j: *mut c_void = std::ptr::null_mut();
-        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
-            return out;
+    /// Command to run with the TUI suspended, consumed by the main loop
+    pub fn take_external_command(&mut self) -> Option<Command> {
+        self.external_command.take()
+    }
+
+    /// Build a shell command that lands inside the unit's namespaces.
+    /// nspawn containers go through the machine so the shell gets a proper
+    /// session; everything else uses nsenter on the main PID.
+    async fn namespace_shell(&self, unit: &UnitInfo) -> Result<Command, String> {
+        let mut argv: Vec<String> = if let Some(machine) = unit
+            .name
+            .strip_prefix("systemd-nspawn@")
+            .and_then(|n| n.strip_suffix(".service"))
+        {
+            vec![
+                "systemd-run".to_string(),
+                format!("--machine={}", machine),
+                "--pty".to_string(),
+                "--quiet".to_string(),
+                "/bin/sh".to_string(),
+            ]
+        } else {
+            let pid = self
+                .systemd
+                .get_main_pid(&unit.name)
+                .await
+                .map_err(|e| e.to_string())?;
+            if pid == 0 {
+                return Err("no main PID (unit not running?)".to_string());
+            }
+            vec![
+                "nsenter".to_string(),
+                format!("--target={}", pid),
+                "--all".to_string(),
+            ]
+        };
+
+        if !is_root() || self.systemd.host().is_some() {
+            argv.insert(0, "sudo".to_string());
+        }
+        if let Some(host) = self.systemd.host() {
+            argv.splice(
+                0..0,
+                ["ssh".to_string(), "-t".to_string(), host.to_string()],
+            );
         }
 
-        let m = format!("_SYSTEMD_UNIT={unit}");
-        let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
-        let _ = sd_journal_seek_tail(j);
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        Ok(cmd)
+    }
+}
 
-        for _ in 0..max {
-            if sd_journal_previous(j) <= 0 {
-                break;
+/// The page as man(1) renders it for a terminal `width` columns wide,
+/// without the bold/underline overstrikes and SGR codes
+fn render_man_page(page: &str, section: Option<&str>, width: usize) -> Result<Vec<String>, String> {
+    let mut cmd = Command::new("man");
+    cmd.env("MANWIDTH", width.to_string())
+        .env("MANPAGER", "cat")
+        .env("GROFF_NO_SGR", "1")
+        .env_remove("MAN_KEEP_FORMATTING");
+    if let Some(section) = section {
+        cmd.arg(section);
+    }
+    let output = cmd
+        .arg(page)
+        .output()
+        .map_err(|e| format!("man: {} (M opens it outside)", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.lines().next().unwrap_or("man failed").to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(strip_overstrike)
+        .collect())
+}
+
+/// Drop "x\bx" bold and "_\bx" underline pairs and ESC[...m sequences
+fn strip_overstrike(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{8}' => {
+                out.pop();
             }
-            if let Some(entry) = read_journal_entry(j) {
-                out.push(entry);
+            '\u{1b}' if chars.peek() == Some(&'[') => {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
             }
+            c => out.push(c),
         }
-        sd_journal_close(j);
     }
-    out.reverse();
     out
 }
 
-fn get_journal_field(j: *mut c_void, field: &str) -> Option<String> {
-    let field_c = CString::new(field).ok()?;
-    let mut data_ptr: *const u8 = std::ptr::null();
-    let mut len: usize = 0;
-    let rc = unsafe {
-        sd_journal_get_data(
-            j,
-            field_c.as_ptr(),
-            &mut data_ptr as *mut *const u8,
-            &mut len as *mut usize,
-        )
-    };
-    if rc < 0 || data_ptr.is_null() || len == 0 {
-        return None;
+/// Split "man:sshd(8)" into ("sshd", Some("8"))
+fn parse_man_uri(uri: &str) -> Option<(&str, Option<&str>)> {
+    let page = uri.strip_prefix("man:")?;
+    match page.split_once('(') {
+        Some((name, rest)) => Some((name, rest.strip_suffix(')'))),
+        None => Some((page, None)),
     }
-    let text = String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(data_ptr, len) });
-    let prefix = format!("{}=", field);
-    text.strip_prefix(&prefix).map(|s| s.to_string())
 }
 
-fn read_journal_entry(j: *mut c_void) -> Option<UnitLogEntry> {
-    // Get timestamp
-    let mut ts_micros: u64 = 0;
-    let rc = unsafe { sd_journal_get_realtime_usec(j, &mut ts_micros as *mut u64) };
-    if rc < 0 {
-        return None;
+/// Best of the name and description scores, names ranking ahead.
+/// `needle` must already be lowercase.
+fn unit_match_score(unit: &UnitInfo, needle: &str) -> Option<usize> {
+    let name_score = fuzzy_match_score(&unit.name.to_lowercase(), needle);
+    let desc_score = fuzzy_match_score(&unit.description.to_lowercase(), needle).map(|s| s + 200);
+    match (name_score, desc_score) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
     }
-
-    let message = get_journal_field(j, "MESSAGE")?;
-
-    // Format timestamp as YYMMDD HH:MM:SS
-    let ts_secs = (ts_micros / 1_000_000) as i64;
-    let display_time = chrono::DateTime::from_timestamp(ts_secs, 0)
-        .map(|dt| {
-            let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
-            local.format("%y%m%d %H:%M:%S").to_string()
-        })
-        .unwrap_or_else(|| "?".to_string());
-
-    Some(UnitLogEntry {
-        timestamp_micros: ts_micros,
-        display_time,
-        message,
-    })
 }
 
-fn fuzzy_match_score(haystack: &str, needle: &str) -> Option<usize> {
-    if needle.is_empty() {
-        return Some(0);
-    }
-
-    // Fast path: contiguous substring match should rank highest.
-    if let Some(idx) = haystack.find(needle) {
-        return Some(idx);
+impl Context for UnitsContext {
+    fn name(&self) -> &'static str {
+        "Units"
     }
 
-    // Subsequence fuzzy match: all needle chars must appear in order.
-    let mut last_idx = 0usize;
-    let mut first_match: Option<usize> = None;
-    let mut gap_penalty = 0usize;
-
-    for n in needle.chars() {
-        let Some(found_rel) = haystack[last_idx..].find(n) else {
-            return None;
-        };
-
-        let found_abs = last_idx + found_rel;
-        if first_match.is_none() {
-            first_match = Some(found_abs);
+    fn auto_refresh(&mut self) {
+        if !self.background.is_pending() {
+            self.start_background_refresh();
         }
-
-        gap_penalty += found_rel;
-        last_idx = found_abs + n.len_utf8();
     }
 
-    Some(first_match.unwrap_or(0) + gap_penalty * 2 + 100)
-}
-
-impl Context for UnitsContext {
-    fn name(&self) -> &'static str {
-        "Units"
+    fn captures_input(&self) -> bool {
+        self.show_filter
+            || self.confirm_input.is_some()
+            || self.env_input.is_some()
+            || self.limit_input.is_some()
+            || self.inspector.editing
+            || self.targets.as_ref().is_some_and(|t| t.confirm.is_some())
+            || self.preset_menu.is_some()
     }
 
     fn draw(&self, f: &mut Frame, area: Rect) {
@@ -552,25 +2946,91 @@ impl Context for UnitsContext {
             .constraints([Constraint::Min(0), Constraint::Length(4)])
             .split(area);
 
-        // Calculate visible rows
-        let visible_rows = chunks[0].height as usize - 3;
-
         // Unit list
         match self.view_mode {
-            ViewMode::List => draw_unit_list(self, f, chunks[0], visible_rows),
-            ViewMode::Tree => draw_unit_tree(self, f, chunks[0], visible_rows),
+            ViewMode::List => draw_unit_list(self, f, chunks[0]),
+            ViewMode::Tree => draw_unit_tree(self, f, chunks[0]),
         }
 
         // Details/status bar
         draw_details(self, f, chunks[1]);
 
-        if self.detail_unit.is_some() {
-            draw_unit_popup(self, f, area);
+        if self.detail_unit.is_some() {
+            draw_unit_popup(self, f, area);
+            if let Some(ref pager) = self.man_pager {
+                draw_man_pager(pager, f, area);
+            }
+        }
+
+        if let Some(ref picker) = self.column_picker {
+            draw_column_picker(picker, f, area);
+        }
+
+        if let Some(ref menu) = self.preset_menu {
+            menu.draw(f, area);
+        }
+
+        if let Some(ref batch) = self.batch {
+            draw_batch(batch, f, area);
+        }
+
+        if let Some(ref popup) = self.targets {
+            draw_targets(self, popup, f, area);
+        }
+
+        if let Some(ref popup) = self.user_managers {
+            draw_user_managers(popup, f, area);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.column_picker.is_some() {
+            self.handle_column_picker_key(key);
+            return;
+        }
+
+        if self.preset_menu.is_some() {
+            self.handle_preset_menu_key(key);
+            return;
+        }
+
+        if self.type_chip.is_some() {
+            self.handle_type_chip_key(key);
+            return;
+        }
+
+        if self.batch.is_some() {
+            self.handle_batch_key(key);
+            return;
+        }
+
+        if self.targets.is_some() {
+            self.handle_targets_key(key);
+            return;
+        }
+
+        if self.user_managers.is_some() {
+            self.handle_user_managers_key(key);
+            return;
         }
-    }
 
-    fn handle_key(&mut self, key: KeyEvent) {
         if self.detail_unit.is_some() {
+            if self.man_pager.is_some() {
+                self.handle_man_pager_key(key);
+                return;
+            }
+            if self.confirm_input.is_some() {
+                self.handle_typed_confirm_key(key);
+                return;
+            }
+            if self.env_input.is_some() {
+                self.handle_env_input_key(key);
+                return;
+            }
+            if self.limit_input.is_some() {
+                self.handle_limit_input_key(key);
+                return;
+            }
             if self.confirm_action.is_some() {
                 match key.code {
                     KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -584,13 +3044,43 @@ impl Context for UnitsContext {
                 return;
             }
 
+            let consumed = match self.detail_tab {
+                DetailTab::Logs => false,
+                DetailTab::Properties => self.inspector.handle_key(key),
+                DetailTab::Security => self.handle_security_key(key),
+                DetailTab::History => self.handle_history_key(key),
+                DetailTab::Environment => self.handle_env_key(key),
+                DetailTab::Limits => self.handle_limits_key(key),
+                DetailTab::Conditions => self.handle_conditions_key(key),
+            };
+            if consumed {
+                return;
+            }
+
             match key.code {
                 KeyCode::Esc | KeyCode::Char('q') => self.close_detail(),
+                KeyCode::Char('p') => self.toggle_detail_tab(DetailTab::Properties),
+                KeyCode::Char('a') => self.toggle_detail_tab(DetailTab::Security),
+                KeyCode::Char('h') => self.toggle_detail_tab(DetailTab::History),
+                KeyCode::Char('v') => self.toggle_detail_tab(DetailTab::Environment),
+                KeyCode::Char('l') => self.toggle_detail_tab(DetailTab::Limits),
+                KeyCode::Char('c') => self.toggle_detail_tab(DetailTab::Conditions),
                 KeyCode::Char('r') => {
-                    if let Some(unit) = &self.detail_unit {
-                        self.detail_logs = read_recent_unit_logs(&unit.name, 120);
-                        if self.detail_log_follow {
-                            self.scroll_to_bottom();
+                    if let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone()) {
+                        match self.detail_tab {
+                            DetailTab::Logs => self.load_detail_logs(&name),
+                            DetailTab::Properties | DetailTab::Security => {
+                                self.inspector.load(&self.systemd, &name)
+                            }
+                            DetailTab::History => self.load_history(&name),
+                            DetailTab::Limits => self.load_limits(&name),
+                            DetailTab::Conditions => self.load_conditions(&name),
+                            DetailTab::Environment => {
+                                self.load_env_overrides(&name);
+                                if name.ends_with(".service") {
+                                    self.load_exec(&name);
+                                }
+                            }
                         }
                     }
                 }
@@ -624,10 +3114,23 @@ impl Context for UnitsContext {
                     self.detail_log_scroll = 0;
                     self.detail_log_follow = false;
                 }
-                KeyCode::Char('s') => self.confirm_action = Some(UnitAction::Start),
-                KeyCode::Char('x') => self.confirm_action = Some(UnitAction::Stop),
-                KeyCode::Char('e') => self.confirm_action = Some(UnitAction::Enable),
-                KeyCode::Char('d') => self.confirm_action = Some(UnitAction::Disable),
+                KeyCode::Char('s') => self.request_action(UnitAction::Start),
+                KeyCode::Char('x') => self.request_action(UnitAction::Stop),
+                KeyCode::Char('e') => self.request_action(UnitAction::Enable),
+                KeyCode::Char('d') => self.request_action(UnitAction::Disable),
+                KeyCode::Char('U') => self.request_mount_action(UnitAction::Unmount),
+                KeyCode::Char('R') => self.request_mount_action(UnitAction::Remount),
+                KeyCode::Char('o') => self.shell_requested = true,
+                KeyCode::Char('m') => self.open_man_pager(0),
+                KeyCode::Char('M') => self.open_man_page(),
+                KeyCode::Char('y') => self.copy_doc_links(),
+                KeyCode::Char('t') => {
+                    self.timestamps = self.timestamps.next();
+                    self.messages
+                        .info(format!("Timestamps: {}", self.timestamps.label()));
+                }
+                KeyCode::Char('w') => self.toggle_watch(),
+                KeyCode::Char('i') => self.toggle_current_run(),
                 _ => {}
             }
             return;
@@ -660,15 +3163,13 @@ impl Context for UnitsContext {
             return;
         }
 
-        let page_size = 10;
-
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => self.move_down(),
             KeyCode::Char('k') | KeyCode::Up => self.move_up(),
             KeyCode::Char('g') => self.go_top(),
             KeyCode::Char('G') => self.go_bottom(),
-            KeyCode::Char(' ') | KeyCode::PageDown => self.page_down(page_size),
-            KeyCode::Char('b') | KeyCode::PageUp => self.page_up(page_size),
+            KeyCode::Char(' ') | KeyCode::PageDown => self.page_down(),
+            KeyCode::Char('b') | KeyCode::PageUp => self.page_up(),
             KeyCode::Char('/') => {
                 if !self.show_filter {
                     self.filter_backup = Some(self.filter.clone());
@@ -676,8 +3177,14 @@ impl Context for UnitsContext {
                 self.show_filter = true;
             }
             KeyCode::Char('t') => self.toggle_view_mode(),
+            KeyCode::Char('N') => self.toggle_nest_triggers(),
             KeyCode::Char('s') => self.toggle_sort(),
             KeyCode::Char('S') => self.toggle_sort_direction(),
+            KeyCode::Char('f') => self.cycle_state_filter(),
+            KeyCode::Char('I') => self.toggle_internal(),
+            KeyCode::Char('y') if self.view_mode == ViewMode::List => self.type_chip = Some(0),
+            KeyCode::Char('C') => self.column_picker = Some(ColumnPicker::new(&self.columns)),
+            KeyCode::Char('P') => self.preset_menu = Some(PresetMenu::new(&self.presets)),
             KeyCode::Enter => {
                 if self.selected_unit().is_some() {
                     self.open_detail();
@@ -687,17 +3194,81 @@ impl Context for UnitsContext {
             }
             KeyCode::Char('e') => self.expand_all(),
             KeyCode::Char('c') => self.collapse_all(),
-            KeyCode::Esc => {
-                if !self.filter.is_empty() {
-                    self.filter.clear();
-                    self.apply_filter_and_sort();
-                }
+            KeyCode::Char('B') => self.open_batch(),
+            KeyCode::Char('T') => self.open_targets(),
+            KeyCode::Char('U') => self.open_user_managers(),
+            KeyCode::Char('w') => self.toggle_watch(),
+            KeyCode::Esc
+                if !self.filter.is_empty()
+                    || self.preset.is_some()
+                    || self.state_filter != StateFilter::All =>
+            {
+                self.filter.clear();
+                self.preset = None;
+                self.state_filter = StateFilter::All;
+                self.apply_filter_and_sort();
             }
             _ => {}
         }
     }
 
     async fn tick(&mut self) {
+        if std::mem::take(&mut self.needs_properties) {
+            self.refresh(&self.systemd.clone()).await;
+        }
+
+        self.step_batch().await;
+        if let Some(pager) = self.man_pager.as_mut()
+            && let Some(result) = pager.pending.poll()
+        {
+            match result {
+                Ok(lines) => pager.lines = lines,
+                Err(e) => {
+                    self.messages.error(e);
+                    self.man_pager = None;
+                }
+            }
+        }
+        self.poll_targets();
+        self.poll_log_rates();
+        self.poll_oom_kills();
+        self.poll_usage_history();
+        if let Some(popup) = self.user_managers.as_mut()
+            && let Some(result) = popup.pending.poll()
+        {
+            match result {
+                Ok(managers) => {
+                    popup.list.clamp(managers.len());
+                    popup.managers = managers;
+                }
+                Err(e) => self
+                    .messages
+                    .error(format!("Failed to list user managers: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.background.poll() {
+            match result {
+                Ok(units) => {
+                    self.error = None;
+                    self.merge_units(units);
+                }
+                // Keep showing the last good list
+                Err(e) => self
+                    .messages
+                    .warn(format!("Background refresh failed: {}", e)),
+            }
+        }
+
+        if std::mem::take(&mut self.shell_requested)
+            && let Some(unit) = self.detail_unit.clone()
+        {
+            match self.namespace_shell(&unit).await {
+                Ok(cmd) => self.external_command = Some(cmd),
+                Err(e) => self.messages.error(format!("shell {}: {}", unit.name, e)),
+            }
+        }
+
         if let Some(action) = self.pending_action.take() {
             if let Some(unit) = self.detail_unit.clone() {
                 let result = match action {
@@ -705,35 +3276,197 @@ impl Context for UnitsContext {
                     UnitAction::Stop => self.systemd.stop_unit(&unit.name).await,
                     UnitAction::Enable => self.systemd.enable_unit(&unit.name).await,
                     UnitAction::Disable => self.systemd.disable_unit(&unit.name).await,
+                    UnitAction::Unmount => self.systemd.stop_unit(&unit.name).await,
+                    UnitAction::Remount => self.systemd.reload_unit(&unit.name).await,
                 };
 
-                self.action_status = Some(match result {
-                    Ok(_) => format!("{} {}: OK", action.label(), unit.name),
-                    Err(e) => format!("{} {}: {}", action.label(), unit.name, e),
-                });
+                match result {
+                    Ok(_) => self
+                        .messages
+                        .info(format!("{} {}: OK", action.label(), unit.name)),
+                    Err(e) => {
+                        self.messages
+                            .error(format!("{} {}: {}", action.label(), unit.name, e))
+                    }
+                }
 
                 self.refresh(&self.systemd.clone()).await;
-                self.detail_logs = read_recent_unit_logs(&unit.name, 120);
-                if self.detail_log_follow {
-                    self.scroll_to_bottom();
-                } else {
-                    // Clamp scroll to valid range in case log count changed
-                    let visible = 10; // Approximate visible lines
-                    let max_scroll = self.detail_logs.len().saturating_sub(visible);
-                    self.detail_log_scroll = self.detail_log_scroll.min(max_scroll);
+                self.load_detail_logs(&unit.name);
+            }
+        }
+
+        if let Some(mut batch) = self.batch.take() {
+            match batch.impact_pending.poll() {
+                Some((action, Ok(impact))) if action == batch.action => {
+                    batch.impact = Some(self.settle_impact(UnitAction::Stop, impact));
+                }
+                Some((_, Err(e))) => self
+                    .messages
+                    .warn(format!("Failed to work out dependent units: {}", e)),
+                _ => {}
+            }
+            self.batch = Some(batch);
+        }
+
+        if let Some(result) = self.confirm_impact_pending.poll() {
+            match (result, self.confirm_action) {
+                (Ok(impact), Some(action)) => {
+                    self.confirm_impact = Some(self.settle_impact(action, impact))
+                }
+                (Ok(_), None) => {}
+                (Err(e), _) => self
+                    .messages
+                    .warn(format!("Failed to work out dependent units: {}", e)),
+            }
+        }
+
+        if let Some(counts) = self.restart_counts_pending.poll() {
+            let now = timestamp::now_usec();
+            for (name, count) in counts {
+                if let Ok(count) = count {
+                    self.history.record_restart_count(&name, count, now);
+                }
+            }
+            self.update_storms();
+        }
+
+        if let Some((unit, events)) = self.history_pending.poll() {
+            self.history.seed(&unit, events);
+            // Newest at the bottom, which is where a triage starts
+            let len = self.history_len();
+            self.history_list.bottom(len);
+        }
+
+        if let Some(result) = self.inspector.pending.poll() {
+            match result {
+                Ok(properties) => {
+                    self.inspector.properties = properties;
+                    let len = self.inspector.visible().len();
+                    self.inspector.list.clamp(len);
+                    self.update_security();
                 }
+                Err(e) => self
+                    .messages
+                    .error(format!("Failed to read properties: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.limits_pending.poll() {
+            match result {
+                Ok(limits) => self.limits = Some(limits),
+                Err(e) => self
+                    .messages
+                    .warn(format!("Failed to read resource limits: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.limit_set_pending.poll() {
+            match result {
+                Ok(text) => self.messages.info(text),
+                Err(e) => self.messages.error(format!("Set limit: {}", e)),
+            }
+            if let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone()) {
+                self.load_limits(&name);
+            }
+        }
+
+        if let Some(result) = self.env_pending.poll() {
+            match result {
+                Ok(text) => self.messages.info(text),
+                Err(e) => self.messages.error(format!("Environment override: {}", e)),
+            }
+            if let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone()) {
+                self.load_env_overrides(&name);
+                self.load_exec(&name);
+            }
+        }
+
+        if let Some(result) = self.detail_exec_pending.poll() {
+            match result {
+                Ok(exec) => self.detail_exec = Some(exec),
+                Err(e) => self
+                    .messages
+                    .warn(format!("Failed to read service properties: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.detail_usage_pending.poll() {
+            self.detail_usage = Some(result);
+        }
+        if let Some(result) = self.detail_conditions_pending.poll() {
+            match result {
+                Ok(conditions) => {
+                    let len = conditions.conditions.len() + conditions.asserts.len();
+                    self.conditions_list.clamp(len);
+                    self.detail_conditions = Some(conditions);
+                }
+                Err(e) => self
+                    .messages
+                    .warn(format!("Failed to read conditions: {}", e)),
+            }
+        }
+
+        if let Some(result) = self.detail_docs_pending.poll() {
+            match result {
+                Ok(docs) => self.detail_docs = docs,
+                Err(e) => self
+                    .messages
+                    .warn(format!("Failed to read Documentation=: {}", e)),
+            }
+        }
+
+        // Tail the unit while following; a reader scrolled back keeps a
+        // still page until they press f or G
+        if self.detail_log_follow
+            && self.detail_tab == DetailTab::Logs
+            && !self.detail_logs_pending.is_pending()
+            && self.detail_logs_read.elapsed() >= DETAIL_FOLLOW_INTERVAL
+            && let Some(name) = self.detail_unit.as_ref().map(|u| u.name.clone())
+        {
+            self.load_detail_logs(&name);
+        }
+
+        if let Some((invocation, logs)) = self.detail_logs_pending.poll() {
+            self.detail_invocation = invocation;
+            self.detail_logs = logs;
+            if self.detail_log_follow {
+                self.scroll_to_bottom();
+            } else {
+                // Clamp scroll to valid range in case log count changed
+                let visible = 10; // Approximate visible lines
+                let max_scroll = self.detail_logs.len().saturating_sub(visible);
+                self.detail_log_scroll = self.detail_log_scroll.min(max_scroll);
             }
         }
     }
 }
 
-fn draw_unit_list(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: usize) {
-    let sort_indicator = match (ctx.sort_by, ctx.sort_ascending) {
-        (SortBy::Name, true) => " [name ▲]",
-        (SortBy::Name, false) => " [name ▼]",
-        (SortBy::State, true) => " [state ▲]",
-        (SortBy::State, false) => " [state ▼]",
+fn sort_indicator(ctx: &UnitsContext) -> String {
+    let arrow = if ctx.sort_ascending { "▲" } else { "▼" };
+    format!(" [{} {}]", ctx.sort_by.label(), arrow)
+}
+
+/// " [42 internal hidden] [failed] [preset web stack]" for what's filtered
+/// besides the text
+fn filter_indicator(ctx: &UnitsContext) -> String {
+    let internal = match ctx.internal_hidden {
+        0 => String::new(),
+        n => format!(" [{} internal hidden]", n),
     };
+    let state = match ctx.state_filter {
+        StateFilter::All => String::new(),
+        state => format!(" [{}]", state.label()),
+    };
+    let preset = ctx
+        .preset
+        .as_ref()
+        .map(|p| format!(" [preset {}]", p.name))
+        .unwrap_or_default();
+    format!("{}{}{}", internal, state, preset)
+}
+
+fn draw_unit_list(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let sort_indicator = format!("{}{}", filter_indicator(ctx), sort_indicator(ctx));
 
     let title = if ctx.show_filter {
         format!(" Units [filter: {}]{} ", ctx.filter, sort_indicator)
@@ -743,6 +3476,17 @@ fn draw_unit_list(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: u
 
     let block = Block::default().title(title).borders(Borders::ALL);
 
+    let area = if area.height > 6 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        draw_type_chips(ctx, f, chunks[0]);
+        chunks[1]
+    } else {
+        area
+    };
+
     if ctx.loading {
         let loading = Paragraph::new("Loading units...").block(block);
         f.render_widget(loading, area);
@@ -750,85 +3494,420 @@ fn draw_unit_list(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: u
     }
 
     if let Some(ref error) = ctx.error {
-        let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+        let error_text = Paragraph::new(error.lines()).block(block);
         f.render_widget(error_text, area);
         return;
     }
 
-    // Calculate scroll offset
-    let scroll_offset = if ctx.selected < ctx.scroll_offset {
-        ctx.selected
-    } else if ctx.selected >= ctx.scroll_offset + visible_rows {
-        ctx.selected.saturating_sub(visible_rows - 1)
+    // The description is the first thing to go on a narrow terminal
+    let columns: Vec<Column> = ctx
+        .columns
+        .iter()
+        .copied()
+        .filter(|c| area.width >= NARROW_WIDTH || *c != Column::Description)
+        .collect();
+    let header = Row::new(columns.iter().map(|c| c.title()))
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let now_usec = chrono::Utc::now().timestamp_micros().max(0) as u64;
+
+    let table = SelectableTable::new(
+        &ctx.list,
+        ctx.filtered_units.len(),
+        columns.iter().map(|c| c.width()),
+        |i| {
+            let unit = &ctx.filtered_units[i];
+            let state_style = crate::palette::unit_state(&unit.active_state, &unit.sub_state);
+
+            let cells: Vec<Span> = columns
+                .iter()
+                .map(|col| match col {
+                    Column::State => Span::styled(unit.state_indicator(), state_style),
+                    Column::Name => unit_name_span(ctx, unit),
+                    Column::Description => description_span(unit),
+                    Column::Memory => Span::raw(
+                        unit.memory_bytes
+                            .map(format_bytes)
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    Column::Cpu => Span::raw(
+                        unit.cpu_nsec
+                            .map(|ns| format_duration_usec(ns / 1_000))
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    Column::Uptime => {
+                        Span::raw(if unit.is_active() && unit.active_enter_usec > 0 {
+                            format_duration_usec(now_usec.saturating_sub(unit.active_enter_usec))
+                        } else {
+                            "-".to_string()
+                        })
+                    }
+                    Column::Enabled => Span::raw(
+                        unit.unit_file_state
+                            .clone()
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    Column::Logs => {
+                        let rate = ctx.log_rates.rate(&unit.name, now_usec);
+                        Span::raw(format!("{}/{}", rate.messages_5m, rate.messages_1h))
+                    }
+                    Column::Errors => {
+                        let rate = ctx.log_rates.rate(&unit.name, now_usec);
+                        let style = if rate.errors_5m > 0 {
+                            Style::default().fg(crate::palette::red())
+                        } else if rate.errors_1h > 0 {
+                            Style::default().fg(crate::palette::yellow())
+                        } else {
+                            Style::default()
+                        };
+                        Span::styled(format!("{}/{}", rate.errors_5m, rate.errors_1h), style)
+                    }
+                })
+                .collect();
+
+            Row::new(cells)
+        },
+    )
+    .header(header)
+    .block(block);
+
+    f.render_widget(table, area);
+}
+
+/// " service 142  timer 12 ..." above the list: hidden types crossed out,
+/// the chip under the cursor highlighted while toggling
+fn draw_type_chips(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let counts = &ctx.type_counts;
+    let mut spans = Vec::new();
+    for (i, name) in CHIP_TYPES.iter().enumerate() {
+        let style = if ctx.type_chip == Some(i) {
+            crate::palette::selected()
+        } else if ctx.hidden_types.contains(*name) {
+            Style::default()
+                .fg(crate::palette::dark_gray())
+                .add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default().fg(crate::palette::cyan())
+        };
+        spans.push(Span::styled(
+            format!(" {} {} ", name, counts.get(*name).copied().unwrap_or(0)),
+            style,
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if ctx.type_chip.is_some() {
+        spans.push(Span::styled(
+            "h/l:move Space:toggle o:only a:all Enter:done",
+            Style::default().fg(crate::palette::gray()),
+        ));
+    } else {
+        spans.push(Span::styled(
+            "y:types",
+            Style::default().fg(crate::palette::gray()),
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_column_picker(picker: &ColumnPicker, f: &mut Frame, area: Rect) {
+    let popup = centered_rect(40, 50, area);
+    let lines: Vec<Line> = picker
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, (col, on))| {
+            let style = if i == picker.selected {
+                crate::palette::selected()
+            } else {
+                Style::default()
+            };
+            Line::from(Span::styled(
+                format!("[{}] {}", if *on { "x" } else { " " }, col.title()),
+                style,
+            ))
+        })
+        .chain([
+            Line::from(""),
+            Line::from(Span::styled(
+                "Space:toggle J/K:move Enter:apply",
+                Style::default().fg(crate::palette::gray()),
+            )),
+        ])
+        .collect();
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().title(" Columns ").borders(Borders::ALL)),
+        popup,
+    );
+}
+
+fn draw_batch(batch: &BatchPopup, f: &mut Frame, area: Rect) {
+    let popup = centered_rect(60, 60, area);
+    let mut lines: Vec<Line> = if batch.targets.is_empty() {
+        vec![Line::from(format!(
+            "No units in {} to {}",
+            batch.group,
+            batch.action.label()
+        ))]
+    } else {
+        batch
+            .targets
+            .iter()
+            .map(|t| match &t.result {
+                None => Line::from(format!("  {}", t.name)),
+                Some(Ok(())) => Line::from(Span::styled(
+                    format!("✓ {}", t.name),
+                    Style::default().fg(crate::palette::green()),
+                )),
+                Some(Err(e)) => Line::from(Span::styled(
+                    format!("✗ {}: {}", t.name, e),
+                    Style::default().fg(crate::palette::red()),
+                )),
+            })
+            .collect()
+    };
+
+    if !batch.running && batch.action.propagates() && !batch.targets.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(batch_impact_line(batch));
+    }
+
+    let done = batch.targets.iter().filter(|t| t.result.is_some()).count();
+    let footer = if !batch.running {
+        "Tab:next action Enter:run Esc:close".to_string()
+    } else if batch.is_done() {
+        "Done. Esc:close".to_string()
     } else {
-        ctx.scroll_offset
+        format!("Running {}/{}... Esc:cancel", done, batch.targets.len())
     };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        footer,
+        Style::default().fg(crate::palette::gray()),
+    )));
 
-    let header = Row::new(vec!["State", "Name", "Description"])
-        .style(Style::default().add_modifier(Modifier::BOLD));
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!(
+                        " Batch: {} in {} ({} units) ",
+                        batch.action.label(),
+                        batch.group,
+                        batch.targets.len()
+                    ))
+                    .borders(Borders::ALL),
+            )
+            .wrap(Wrap { trim: false }),
+        popup,
+    );
+}
+
+/// The running units outside the batch its action would take along
+fn batch_impact_line(batch: &BatchPopup) -> Line<'static> {
+    let gray = Style::default().fg(crate::palette::gray());
+    let verb = match batch.action {
+        BatchAction::Stop => "Also stops",
+        _ => "Also restarts",
+    };
+    let Some(impact) = batch.impact.as_ref() else {
+        let text = if batch.impact_pending.is_pending() {
+            format!(
+                "{} Checking dependent units...",
+                batch.impact_pending.spinner()
+            )
+        } else {
+            "Dependent units unknown".to_string()
+        };
+        return Line::styled(text, gray);
+    };
+    if impact.units.is_empty() {
+        return Line::styled(format!("{}: no other units", verb), gray);
+    }
+    let more = if impact.truncated { ", ..." } else { "" };
+    Line::from(vec![
+        Span::styled(
+            format!("{} {}: ", verb, impact.units.len()),
+            Style::default()
+                .fg(crate::palette::yellow())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("{}{}", impact.units.join(", "), more)),
+    ])
+}
 
-    let visible_units: Vec<&UnitInfo> = ctx
-        .filtered_units
+fn draw_man_pager(pager: &ManPager, f: &mut Frame, area: Rect) {
+    let area = centered_rect(90, 90, area);
+    let title = format!(
+        "{}─ j/k scroll  Tab next page  M open in man  q close ",
+        pager.pending.title(&pager.title)
+    );
+    let block = Block::default().title(title).borders(Borders::ALL);
+    f.render_widget(Clear, area);
+    if pager.pending.is_pending() {
+        f.render_widget(Paragraph::new("Rendering...").block(block), area);
+        return;
+    }
+    let lines: Vec<Line> = pager
+        .lines
         .iter()
-        .skip(scroll_offset)
-        .take(visible_rows)
+        .map(|line| {
+            // Section headings are the only lines starting in column 0
+            if !line.is_empty() && !line.starts_with(' ') {
+                Line::styled(line.as_str(), Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(line.as_str())
+            }
+        })
         .collect();
+    f.render_widget(
+        Paragraph::new(lines).block(block).scroll((pager.scroll, 0)),
+        area,
+    );
+}
 
-    let rows: Vec<Row> = visible_units
-        .iter()
-        .enumerate()
-        .map(|(i, unit)| {
-            let actual_idx = scroll_offset + i;
-            let style = if actual_idx == ctx.selected {
+fn draw_targets(ctx: &UnitsContext, popup: &TargetPopup, f: &mut Frame, area: Rect) {
+    let area = centered_rect(60, 50, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    let title = match &popup.default {
+        Some(default) => format!(" Targets (default: {}) ", default),
+        None => " Targets ".to_string(),
+    };
+    let list = SelectableList::new(&popup.list, SWITCH_TARGETS.len(), |i| {
+        let name = SWITCH_TARGETS[i];
+        let unit = ctx.units.iter().find(|u| u.name == name);
+        let state = unit.map_or("inactive", |u| u.active_state.as_str());
+        let mut spans = vec![
+            Span::styled(
+                format!("{:10} ", state),
+                crate::palette::unit_state(state, unit.map_or("", |u| u.sub_state.as_str())),
+            ),
+            Span::raw(name),
+        ];
+        if popup.default.as_deref() == Some(name) {
+            spans.push(Span::styled(
+                "  (default)",
+                Style::default().fg(crate::palette::cyan()),
+            ));
+        }
+        Line::from(spans)
+    })
+    .block(Block::default().title(title).borders(Borders::ALL));
+
+    let footer = match &popup.confirm {
+        Some((change, input)) => Line::from(vec![
+            Span::styled(
+                format!(
+                    "Type {} and press Enter to {}: ",
+                    popup.selected(),
+                    change.label()
+                ),
                 Style::default()
-                    .bg(crate::palette::dark_gray())
-                    .add_modifier(Modifier::BOLD)
+                    .fg(crate::palette::red())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(input.as_str()),
+        ]),
+        None if popup.change_pending.is_pending() => Line::from(format!(
+            "{} Switching to {}...",
+            popup.change_pending.spinner(),
+            popup.selected()
+        )),
+        None => Line::styled(
+            "Enter:isolate (stops what the target doesn't need)  d:make default  Esc:close",
+            Style::default().fg(crate::palette::gray()),
+        ),
+    };
+
+    f.render_widget(Clear, area);
+    f.render_widget(list, chunks[0]);
+    f.render_widget(
+        Paragraph::new(footer)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL)),
+        chunks[1],
+    );
+}
+
+fn draw_user_managers(popup: &UserManagersPopup, f: &mut Frame, area: Rect) {
+    let area = centered_rect(80, 60, area);
+    let broken = popup.managers.iter().filter(|m| m.is_broken()).count();
+    let block = Block::default()
+        .title(popup.pending.title(&format!(
+            "User managers ({}, {} with problems) Enter:open r:refresh Esc:close",
+            popup.managers.len(),
+            broken
+        )))
+        .borders(Borders::ALL);
+    f.render_widget(Clear, area);
+
+    if popup.managers.is_empty() {
+        let text = if popup.pending.is_pending() {
+            "Asking each user's manager..."
+        } else {
+            "No user@ instances running"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec![
+        "UID", "User", "Instance", "Manager", "Failed", "Version",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &popup.list,
+        popup.managers.len(),
+        [
+            Constraint::Length(7),
+            Constraint::Length(16),
+            Constraint::Length(18),
+            Constraint::Min(16),
+            Constraint::Length(7),
+            Constraint::Length(10),
+        ],
+        |i| {
+            let m = &popup.managers[i];
+            let (manager, failed, version) = match &m.status {
+                Some(Ok(status)) => (
+                    status.system_state.clone(),
+                    status.n_failed_units.to_string(),
+                    status.version.clone(),
+                ),
+                Some(Err(e)) => (e.clone(), "?".to_string(), "-".to_string()),
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            let style = if m.is_broken() {
+                Style::default().fg(crate::palette::light_red())
             } else {
                 Style::default()
             };
-
-            let state_color = match unit.active_state.as_str() {
-                "active" => crate::palette::green(),
-                "failed" => crate::palette::red(),
-                "inactive" => crate::palette::gray(),
-                "activating" => crate::palette::yellow(),
-                "deactivating" => crate::palette::yellow(),
-                _ => crate::palette::white(),
-            };
-
             Row::new(vec![
-                Span::styled(unit.state_indicator(), Style::default().fg(state_color)),
-                Span::raw(&unit.name),
+                Span::raw(m.uid.to_string()),
+                Span::styled(m.user.clone(), Style::default().fg(crate::palette::cyan())),
                 Span::styled(
-                    &unit.description,
-                    Style::default().fg(crate::palette::gray()),
+                    format!("{} ({})", m.active_state, m.sub_state),
+                    crate::palette::unit_state(&m.active_state, &m.sub_state),
                 ),
+                Span::styled(manager, style),
+                Span::styled(failed, style),
+                Span::styled(version, Style::default().fg(crate::palette::gray())),
             ])
-            .style(style)
-        })
-        .collect();
-
-    let table = Table::new(
-        rows,
-        vec![
-            Constraint::Length(6),
-            Constraint::Length(35),
-            Constraint::Min(10),
-        ],
+        },
     )
     .header(header)
     .block(block);
-
     f.render_widget(table, area);
 }
 
-fn draw_unit_tree(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: usize) {
-    let sort_indicator = match (ctx.sort_by, ctx.sort_ascending) {
-        (SortBy::Name, true) => " [name ▲]",
-        (SortBy::Name, false) => " [name ▼]",
-        (SortBy::State, true) => " [state ▲]",
-        (SortBy::State, false) => " [state ▼]",
-    };
+fn draw_unit_tree(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let sort_indicator = format!("{}{}", filter_indicator(ctx), sort_indicator(ctx));
 
     let expanded_count = ctx.tree_items.len();
     let total_count = ctx.filtered_units.len();
@@ -856,41 +3935,13 @@ fn draw_unit_tree(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: u
     }
 
     if let Some(ref error) = ctx.error {
-        let error_text = Paragraph::new(format!("Error: {}", error)).block(block);
+        let error_text = Paragraph::new(error.lines()).block(block);
         f.render_widget(error_text, area);
         return;
     }
 
-    // Calculate scroll offset
-    let scroll_offset = if ctx.selected < ctx.scroll_offset {
-        ctx.selected
-    } else if ctx.selected >= ctx.scroll_offset + visible_rows {
-        ctx.selected.saturating_sub(visible_rows - 1)
-    } else {
-        ctx.scroll_offset
-    };
-
-    let visible_items: Vec<&TreeItem> = ctx
-        .tree_items
-        .iter()
-        .skip(scroll_offset)
-        .take(visible_rows)
-        .collect();
-
-    let mut text_lines: Vec<Line> = Vec::new();
-
-    for (i, item) in visible_items.iter().enumerate() {
-        let actual_idx = scroll_offset + i;
-        let is_selected = actual_idx == ctx.selected;
-        let style = if is_selected {
-            Style::default()
-                .bg(crate::palette::dark_gray())
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
-
-        match item {
+    let list = SelectableList::new(&ctx.list, ctx.tree_items.len(), |i| {
+        match &ctx.tree_items[i] {
             TreeItem::Group {
                 name,
                 count,
@@ -898,40 +3949,119 @@ fn draw_unit_tree(ctx: &UnitsContext, f: &mut Frame, area: Rect, visible_rows: u
             } => {
                 let is_collapsed = ctx.collapsed_groups.contains(name);
                 let icon = if is_collapsed { "▶" } else { "▼" };
-                text_lines.push(Line::from(vec![Span::styled(
+                Line::from(vec![Span::styled(
                     format!("{} {} ({} / {} active)", icon, name, active, count),
-                    style
+                    Style::default()
                         .fg(crate::palette::cyan())
                         .add_modifier(Modifier::BOLD),
-                )]));
-            }
-            TreeItem::Unit { unit } => {
-                let state_color = match unit.active_state.as_str() {
-                    "active" => crate::palette::green(),
-                    "failed" => crate::palette::red(),
-                    "inactive" => crate::palette::gray(),
-                    "activating" => crate::palette::yellow(),
-                    "deactivating" => crate::palette::yellow(),
-                    _ => crate::palette::white(),
-                };
+                )])
+            }
+            TreeItem::Unit { unit, nested } => {
+                let state_style = crate::palette::unit_state(&unit.active_state, &unit.sub_state);
 
-                text_lines.push(Line::from(vec![
-                    Span::raw("    "),
-                    Span::styled(unit.state_indicator(), Style::default().fg(state_color)),
+                Line::from(vec![
+                    Span::raw(if *nested { "      ↳ " } else { "    " }),
+                    Span::styled(unit.state_indicator(), state_style),
                     Span::raw(" "),
-                    Span::styled(&unit.name, style),
+                    unit_name_span(ctx, unit),
                     Span::raw(" "),
-                    Span::styled(
-                        &unit.description,
-                        Style::default().fg(crate::palette::gray()),
-                    ),
-                ]));
+                    description_span(unit),
+                ])
             }
         }
+    })
+    .block(block);
+
+    f.render_widget(list, area);
+}
+
+/// "Sub:" plus what the unit type adds: the watched paths and triggered
+/// unit of path and automount units, device, options and usage of mounts
+fn sub_line<'a>(ctx: &UnitsContext, unit: &UnitInfo) -> Line<'a> {
+    if let Some(activation) = unit.activation() {
+        return Line::from(format!(
+            "Sub: {} · watches {} · triggers {}",
+            unit.sub_state,
+            activation.watches.join(" "),
+            activation.triggers.join(" ")
+        ));
     }
+    let Some(mount) = unit.mount() else {
+        return Line::from(format!("Sub: {}", unit.sub_state));
+    };
+    let usage = match &ctx.detail_usage {
+        Some(Ok(usage)) => format!(
+            " · {} of {} used ({}%)",
+            format_bytes(usage.used()),
+            format_bytes(usage.total),
+            usage.percent()
+        ),
+        Some(Err(e)) => format!(" · usage: {}", e),
+        None if ctx.detail_usage_pending.is_pending() => " · usage: ...".to_string(),
+        None => String::new(),
+    };
+    let style = match &ctx.detail_usage {
+        Some(Ok(usage)) if usage.percent() >= 90 => {
+            Style::default().fg(crate::palette::light_red())
+        }
+        _ => Style::default(),
+    };
+    Line::from(vec![
+        Span::raw(format!(
+            "Sub: {} · {} on {} ({}, {})",
+            unit.sub_state, mount.what, mount.r#where, mount.fs_type, mount.options
+        )),
+        Span::styled(usage, style),
+    ])
+}
+
+/// Description cell: what path, automount and mount units point at, the
+/// description for everything else
+fn description_span(unit: &UnitInfo) -> Span<'_> {
+    let detail = match (unit.activation(), unit.mount()) {
+        (Some(activation), _) => activation.summary(),
+        (None, Some(mount)) => mount.summary(),
+        (None, None) => {
+            return Span::styled(
+                &unit.description,
+                Style::default().fg(crate::palette::gray()),
+            );
+        }
+    };
+    Span::styled(detail, Style::default().fg(crate::palette::cyan()))
+}
 
-    let text = Paragraph::new(text_lines).block(block);
-    f.render_widget(text, area);
+/// The unit's name with its badges: ⚠ for a restart storm, ★ when watched,
+/// "OOM ×N" once killed for lack of memory
+fn unit_name_span<'a>(ctx: &UnitsContext, unit: &'a UnitInfo) -> Span<'a> {
+    let watched = ctx.watchlist.contains(&unit.name);
+    let oom = ctx
+        .oom_kills
+        .get(&unit.name)
+        .map(|tally| format!(" [OOM ×{}]", tally.count));
+    match (ctx.storms.get(&unit.name), oom) {
+        (Some(restarts), oom) => Span::styled(
+            format!(
+                "{}⚠ {} ({} restarts){}",
+                if watched { "★" } else { "" },
+                unit.name,
+                restarts,
+                oom.unwrap_or_default()
+            ),
+            Style::default()
+                .fg(crate::palette::light_red())
+                .add_modifier(Modifier::BOLD),
+        ),
+        (None, Some(oom)) => Span::styled(
+            format!("{}{}{}", if watched { "★ " } else { "" }, unit.name, oom),
+            Style::default().fg(crate::palette::light_red()),
+        ),
+        (None, None) if watched => Span::styled(
+            format!("★ {}", unit.name),
+            Style::default().fg(crate::palette::yellow()),
+        ),
+        (None, None) => Span::raw(&unit.name),
+    }
 }
 
 fn draw_unit_popup(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
@@ -941,23 +4071,38 @@ fn draw_unit_popup(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
 
     f.render_widget(Clear, area);
     let popup = centered_rect(100, 100, area);
+    let is_service = unit.name.ends_with(".service");
+    let sparklines = ctx.usage_history.is_some() && area.height >= SHORT_HEIGHT;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(if sparklines { 4 } else { 0 }),
+            Constraint::Length(if is_service { 8 } else { 0 }),
             Constraint::Min(6),
-            Constraint::Length(3),
+            Constraint::Length(if ctx.confirm_action.is_some() { 5 } else { 3 }),
         ])
         .split(popup);
 
+    let docs = if ctx.detail_docs.is_empty() {
+        if ctx.detail_docs_pending.is_pending() {
+            "...".to_string()
+        } else {
+            "none".to_string()
+        }
+    } else {
+        ctx.detail_docs.join("  ")
+    };
+
     let meta_lines = vec![
         Line::from(format!("Name: {}", unit.name)),
         Line::from(format!("Description: {}", unit.description)),
         Line::from(format!("Load: {}", unit.load_state)),
-        Line::from(format!("Active: {}", unit.active_state)),
-        Line::from(format!("Sub: {}", unit.sub_state)),
+        active_line(ctx, unit),
+        sub_line(ctx, unit),
+        Line::from(format!("Docs: {}", docs)),
         Line::from(
-            "Actions: s=start x=stop e=enable d=disable r=refresh f=follow g=top G=bottom q=back",
+            "Actions: s=start x=stop e=enable d=disable o=shell m=man M=man outside y=copy links t=time w=watch i=this run p=properties a=security h=history v=env l=limits c=conditions U=unmount R=remount r=refresh f=follow g=top G=bottom q=back",
         ),
     ];
 
@@ -970,15 +4115,306 @@ fn draw_unit_popup(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
         chunks[0],
     );
 
+    if let Some(history) = ctx.usage_history.as_ref().filter(|_| sparklines) {
+        draw_usage_history(history, f, chunks[1]);
+    }
+
+    if is_service {
+        draw_service_exec(ctx, f, chunks[2]);
+    }
+
+    match ctx.detail_tab {
+        DetailTab::Logs => draw_detail_logs(ctx, f, chunks[3]),
+        DetailTab::Properties => draw_properties(&ctx.inspector, f, chunks[3]),
+        DetailTab::Security => draw_security(ctx, f, chunks[3]),
+        DetailTab::History => draw_history(ctx, f, chunks[3]),
+        DetailTab::Environment => draw_environment(ctx, f, chunks[3]),
+        DetailTab::Limits => draw_limits(ctx, f, chunks[3]),
+        DetailTab::Conditions => draw_conditions(ctx, f, chunks[3]),
+    }
+
+    let status = match (ctx.confirm_action, ctx.confirm_input.as_ref()) {
+        (Some(confirm), Some(input)) => Line::from(vec![
+            Span::styled(
+                format!(
+                    "{} is protected. Type its name and press Enter to {}: ",
+                    unit.name,
+                    confirm.label()
+                ),
+                Style::default()
+                    .fg(crate::palette::red())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{}_", input)),
+        ]),
+        (Some(confirm), None) => Line::from(format!(
+            "Confirm {} on {} ? [y/n]",
+            confirm.label(),
+            unit.name
+        )),
+        _ => Line::from("Ready"),
+    };
+    let mut lines = vec![status];
+    if let Some(confirm) = ctx.confirm_action {
+        lines.extend(impact_lines(ctx, confirm));
+    }
+
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().title(" Status ").borders(Borders::ALL)),
+        chunks[4],
+    );
+}
+
+/// One line per resource: label, sparkline of the newest samples that fit,
+/// then the current value and the peak in the window
+fn draw_usage_history(history: &UsageHistory, f: &mut Frame, area: Rect) {
+    const LABEL: usize = 8;
+    const VALUE: usize = 28;
+    let width = usize::from(area.width.saturating_sub(2)).saturating_sub(LABEL + VALUE + 2);
+    let line = |label: &str, values: Vec<f64>, format: &dyn Fn(f64) -> String, color| {
+        let Some(&current) = values.last() else {
+            return Line::styled(
+                format!("{:LABEL$}no data yet", label),
+                Style::default().fg(crate::palette::gray()),
+            );
+        };
+        let shown = &values[values.len().saturating_sub(width)..];
+        let peak = values.iter().copied().fold(0.0, f64::max);
+        Line::from(vec![
+            Span::raw(format!("{:LABEL$}", label)),
+            Span::styled(
+                format!("{:width$}", sparkline(shown, peak)),
+                Style::default().fg(color),
+            ),
+            Span::raw(format!("  {:>10} (peak {})", format(current), format(peak))),
+        ])
+    };
+    let lines = vec![
+        line(
+            "Memory",
+            history.memory.iter().map(|&m| m as f64).collect(),
+            &|v| format_bytes(v as u64),
+            crate::palette::cyan(),
+        ),
+        line(
+            "CPU",
+            history.cpu.iter().copied().collect(),
+            &|v| format!("{:.1}%", v),
+            crate::palette::yellow(),
+        ),
+    ];
+    let minutes = (USAGE_SAMPLES as u64 * USAGE_SAMPLE_INTERVAL.as_secs()) / 60;
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(
+                    history
+                        .pending
+                        .title(&format!("Memory / CPU, last {} min", minutes)),
+                )
+                .borders(Borders::ALL),
+        ),
+        area,
+    );
+}
+
+/// Block characters scaled against `peak`, one per value
+fn sparkline(values: &[f64], peak: f64) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    values
+        .iter()
+        .map(|&v| {
+            if peak <= 0.0 {
+                BARS[0]
+            } else {
+                let level = (v / peak * (BARS.len() - 1) as f64).round() as usize;
+                BARS[level.min(BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+/// What else the action being confirmed would start or stop
+fn impact_lines(ctx: &UnitsContext, action: UnitAction) -> Vec<Line<'static>> {
+    if action.propagates_through().is_empty() {
+        return Vec::new();
+    }
+    let gray = Style::default().fg(crate::palette::gray());
+    let Some(impact) = ctx.confirm_impact.as_ref() else {
+        let text = if ctx.confirm_impact_pending.is_pending() {
+            format!(
+                "{} Checking dependent units...",
+                ctx.confirm_impact_pending.spinner()
+            )
+        } else {
+            "Dependent units unknown".to_string()
+        };
+        return vec![Line::styled(text, gray)];
+    };
+
+    let verb = match action {
+        UnitAction::Start => "Also starts",
+        _ => "Also stops",
+    };
+    let mut lines = Vec::new();
+    if impact.units.is_empty() {
+        lines.push(Line::styled(format!("{}: no other units", verb), gray));
+    } else {
+        let more = if impact.truncated { ", ..." } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} {}: ", verb, impact.units.len()),
+                Style::default()
+                    .fg(crate::palette::yellow())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{}{}", impact.units.join(", "), more)),
+        ]));
+    }
+    if !impact.triggered_by.is_empty() {
+        lines.push(Line::styled(
+            format!(
+                "Can be started again by: {}",
+                impact.triggered_by.join(", ")
+            ),
+            gray,
+        ));
+    }
+    lines
+}
+
+fn draw_service_exec(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(ctx.detail_exec_pending.title("Service"))
+        .borders(Borders::ALL);
+    let Some(exec) = ctx.detail_exec.as_ref() else {
+        let text = if ctx.detail_exec_pending.is_pending() {
+            "Loading..."
+        } else {
+            "Service properties unavailable"
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    };
+
+    let label =
+        |text: &'static str| Span::styled(text, Style::default().fg(crate::palette::gray()));
+    let or_dash = |s: &str| {
+        if s.is_empty() {
+            "-".to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    let now_usec = chrono::Utc::now().timestamp_micros().max(0) as u64;
+    let when = |usec: u64| {
+        if usec == 0 {
+            return "-".to_string();
+        }
+        let local = chrono::DateTime::from_timestamp_micros(usec as i64)
+            .map(|dt| {
+                let local: chrono::DateTime<chrono::Local> = chrono::DateTime::from(dt);
+                local.format("%Y-%m-%d %H:%M:%S").to_string()
+            })
+            .unwrap_or_default();
+        format!(
+            "{} ({} ago)",
+            local,
+            format_duration_usec(now_usec.saturating_sub(usec))
+        )
+    };
+
+    let failed = !exec.result.is_empty() && exec.result != "success";
+    let result_style = if failed {
+        Style::default()
+            .fg(crate::palette::red())
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let last_exit = match (exec.last_exit(), exec.exit_usec) {
+        (Some(status), 0) => status,
+        (Some(status), usec) => format!("{} at {}", status, when(usec)),
+        (None, _) => "-".to_string(),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            label("Main PID: "),
+            Span::raw(if exec.main_pid == 0 {
+                "-".to_string()
+            } else {
+                exec.main_pid.to_string()
+            }),
+            label("   User: "),
+            Span::raw(or_dash(&exec.user)),
+            label("   Group: "),
+            Span::raw(or_dash(&exec.group)),
+        ]),
+        Line::from(vec![label("Started: "), Span::raw(when(exec.start_usec))]),
+        Line::from(vec![
+            label("Restart: "),
+            Span::raw(or_dash(&exec.restart)),
+            label("   Restarts: "),
+            Span::styled(
+                exec.n_restarts.to_string(),
+                if exec.n_restarts > 0 {
+                    Style::default().fg(crate::palette::yellow())
+                } else {
+                    Style::default()
+                },
+            ),
+        ]),
+        Line::from(vec![
+            label("Last exit: "),
+            Span::styled(
+                format!("{} [{}]", last_exit, or_dash(&exec.result)),
+                result_style,
+            ),
+        ]),
+    ];
+    if exec.exec_start.is_empty() {
+        lines.push(Line::from(vec![label("ExecStart: "), Span::raw("-")]));
+    }
+    for command in &exec.exec_start {
+        lines.push(Line::from(vec![
+            label("ExecStart: "),
+            Span::raw(command.as_str()),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_detail_logs(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let now_usec = timestamp::now_usec();
+    let time_width = ctx.timestamps.width();
     let log_lines: Vec<Line> = if ctx.detail_logs.is_empty() {
-        vec![Line::from("No logs for this unit")]
+        if let Some(host) = ctx.systemd.host() {
+            vec![Line::from(format!(
+                "The journal of {} can't be read from here; try journalctl -M or ssh",
+                host
+            ))]
+        } else if ctx.detail_logs_pending.is_pending() {
+            vec![Line::from("Loading...")]
+        } else {
+            vec![Line::from("No logs for this unit")]
+        }
     } else {
         ctx.detail_logs
             .iter()
             .map(|entry| {
                 Line::from(vec![
                     Span::styled(
-                        format!("{:15} ", entry.display_time),
+                        format!(
+                            "{:width$} ",
+                            ctx.timestamps.format(
+                                entry.timestamp_micros,
+                                entry.monotonic_usec,
+                                now_usec
+                            ),
+                            width = time_width
+                        ),
                         Style::default().fg(crate::palette::gray()),
                     ),
                     Span::raw(&entry.message),
@@ -987,17 +4423,25 @@ fn draw_unit_popup(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
             .collect()
     };
 
-    let visible = chunks[1].height.saturating_sub(2) as usize;
-    let max_scroll = log_lines.len().saturating_sub(visible);
+    let visible = area.height.saturating_sub(2) as usize;
+    let total_lines = log_lines.len();
+    let max_scroll = total_lines.saturating_sub(visible);
     let scroll = ctx.detail_log_scroll.min(max_scroll) as u16;
+    let current_run = match (ctx.detail_current_run, ctx.detail_invocation.as_deref()) {
+        (false, _) => String::new(),
+        // Eight digits tell runs apart as well as journalctl's output does
+        (true, Some(id)) => format!("[run {}] ", &id[..8.min(id.len())]),
+        (true, None) => "[run: never started] ".to_string(),
+    };
 
     f.render_widget(
         Paragraph::new(log_lines).scroll((scroll, 0)).block(
             Block::default()
                 .title(format!(
-                    " Recent Logs [{} / {}] {}{} ",
+                    " Recent Logs [{} / {}] {}{}{}{} ",
                     scroll,
                     max_scroll,
+                    current_run,
                     if ctx.detail_log_follow {
                         "[follow] "
                     } else {
@@ -1007,45 +4451,72 @@ fn draw_unit_popup(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
                         "[bottom]"
                     } else {
                         ""
-                    }
+                    },
+                    ctx.detail_logs_pending.spinner()
                 ))
                 .borders(Borders::ALL),
         ),
-        chunks[1],
+        area,
     );
+    render_scrollbar(area, total_lines, visible, scroll as usize, f.buffer_mut());
+}
 
-    let status = if let Some(confirm) = ctx.confirm_action {
-        format!("Confirm {} on {} ? [y/n]", confirm.label(), unit.name)
+fn draw_properties(inspector: &PropertyInspector, f: &mut Frame, area: Rect) {
+    let visible = inspector.visible();
+    let filter = if inspector.editing || !inspector.filter.is_empty() {
+        format!(
+            " /{}{}",
+            inspector.filter,
+            if inspector.editing { "_" } else { "" }
+        )
     } else {
-        ctx.action_status
-            .clone()
-            .unwrap_or_else(|| "Ready".to_string())
+        String::new()
     };
+    let title = inspector.pending.title(&format!(
+        "Properties [{}/{}]{}",
+        visible.len(),
+        inspector.properties.len(),
+        filter
+    ));
 
-    f.render_widget(
-        Paragraph::new(status).block(Block::default().title(" Status ").borders(Borders::ALL)),
-        chunks[2],
-    );
-}
-
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
+    if inspector.properties.is_empty() {
+        let text = if inspector.pending.is_pending() {
+            "Loading..."
+        } else {
+            "No properties"
+        };
+        f.render_widget(
+            Paragraph::new(text).block(Block::default().title(title).borders(Borders::ALL)),
+            area,
+        );
+        return;
+    }
 
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+    let header = Row::new(vec!["Interface", "Property", "Value"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &inspector.list,
+        visible.len(),
+        [
+            Constraint::Length(10),
+            Constraint::Length(32),
+            Constraint::Min(20),
+        ],
+        |i| {
+            let (interface, name, value) = visible[i];
+            Row::new(vec![
+                Span::styled(
+                    interface.as_str(),
+                    Style::default().fg(crate::palette::gray()),
+                ),
+                Span::raw(name.as_str()),
+                Span::raw(value.as_str()),
+            ])
+        },
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(table, area);
 }
 
 fn draw_details(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
@@ -1059,11 +4530,7 @@ fn draw_details(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
         .borders(Borders::ALL);
 
     if let Some(unit) = ctx.selected_unit() {
-        let state_color = match unit.active_state.as_str() {
-            "active" => crate::palette::green(),
-            "failed" => crate::palette::red(),
-            _ => crate::palette::gray(),
-        };
+        let state_style = crate::palette::unit_state(&unit.active_state, &unit.sub_state);
 
         let lines = vec![
             Line::from(vec![
@@ -1079,7 +4546,7 @@ fn draw_details(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
                         unit.active_state,
                         unit.sub_state
                     ),
-                    Style::default().fg(state_color),
+                    state_style,
                 ),
             ]),
             Line::from(vec![Span::raw(format!("Load: {}", unit.load_state))]),
@@ -1094,7 +4561,7 @@ fn draw_details(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
         // Check if we're on a group
         let group_name = if ctx.view_mode == ViewMode::Tree {
             ctx.tree_items
-                .get(ctx.selected)
+                .get(ctx.list.selected)
                 .and_then(|item| match item {
                     TreeItem::Group { name, .. } => Some(name.clone()),
                     _ => None,
@@ -1127,3 +4594,446 @@ fn draw_details(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
         f.render_widget(empty, area);
     }
 }
+
+fn verdict_style(verdict: Verdict) -> Style {
+    Style::default().fg(match verdict {
+        Verdict::Good => crate::palette::green(),
+        Verdict::Partial => crate::palette::yellow(),
+        Verdict::Bad => crate::palette::red(),
+    })
+}
+
+fn draw_security(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let Some(report) = ctx.detail_security.as_ref() else {
+        let is_service = ctx
+            .detail_unit
+            .as_ref()
+            .is_some_and(|u| u.name.ends_with(".service"));
+        let text = if !is_service {
+            "Sandboxing analysis only applies to services"
+        } else if ctx.inspector.pending.is_pending() {
+            "Loading..."
+        } else {
+            "No properties"
+        };
+        f.render_widget(
+            Paragraph::new(text).block(
+                Block::default()
+                    .title(ctx.inspector.pending.title("Security"))
+                    .borders(Borders::ALL),
+            ),
+            area,
+        );
+        return;
+    };
+
+    let title = Line::from(vec![
+        Span::raw(" Security: exposure "),
+        Span::styled(
+            format!("{:.1} {}", report.exposure, report.rating()),
+            verdict_style(report.verdict()).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(" {}", ctx.inspector.pending.spinner())),
+    ]);
+    let header = Row::new(vec!["", "Setting", "Value", "Why it matters"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.security_list,
+        report.checks.len(),
+        [
+            Constraint::Length(1),
+            Constraint::Length(24),
+            Constraint::Length(20),
+            Constraint::Min(20),
+        ],
+        |i| {
+            let check = &report.checks[i];
+            let (mark, explanation) = match check.verdict {
+                Verdict::Good => ("✓", ""),
+                Verdict::Partial => ("~", check.explanation),
+                Verdict::Bad => ("✗", check.explanation),
+            };
+            Row::new(vec![
+                Span::styled(mark, verdict_style(check.verdict)),
+                Span::raw(check.setting),
+                Span::raw(check.value.as_str()),
+                Span::styled(explanation, Style::default().fg(crate::palette::gray())),
+            ])
+        },
+    )
+    .header(header)
+    .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(table, area);
+}
+
+fn event_style(kind: EventKind) -> Style {
+    match kind {
+        EventKind::Started | EventKind::Reloaded => Style::default().fg(crate::palette::green()),
+        EventKind::Failed => Style::default().fg(crate::palette::red()),
+        EventKind::Restarting | EventKind::Exited => Style::default().fg(crate::palette::yellow()),
+        EventKind::Stopped | EventKind::Changed => Style::default().fg(crate::palette::gray()),
+    }
+}
+
+fn draw_history(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let Some(unit) = ctx.detail_unit.as_ref() else {
+        return;
+    };
+    let now = timestamp::now_usec();
+    let events = ctx.history.events(&unit.name);
+    let summary = ctx.history.summary(&unit.name, now);
+    let time = |usec: u64| ctx.timestamps.format(usec, None, now);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+
+    let mut headline = Vec::new();
+    if let Some(at) = summary.last_started {
+        headline.push(Span::raw(format!("started {}", time(at))));
+    }
+    if let Some((at, exit)) = &summary.last_failed {
+        if !headline.is_empty() {
+            headline.push(Span::raw(", "));
+        }
+        let text = match exit {
+            Some(exit) => format!("failed {} ({})", time(*at), exit),
+            None => format!("failed {}", time(*at)),
+        };
+        headline.push(Span::styled(
+            text,
+            Style::default().fg(crate::palette::red()),
+        ));
+    }
+    if !headline.is_empty() {
+        headline.push(Span::raw(", "));
+    }
+    let restarts_style = if summary.restarts_last_hour > 0 {
+        Style::default()
+            .fg(crate::palette::yellow())
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    headline.push(Span::styled(
+        format!(
+            "restarted {} times in the last hour",
+            summary.restarts_last_hour
+        ),
+        restarts_style,
+    ));
+    f.render_widget(Paragraph::new(Line::from(headline)), chunks[0]);
+
+    let block = Block::default()
+        .title(ctx.history_pending.title("History"))
+        .borders(Borders::ALL);
+    if events.is_empty() {
+        let text = if ctx.history_pending.is_pending() {
+            "Loading..."
+        } else {
+            "No state changes seen yet (reading the system journal may need root)"
+        };
+        f.render_widget(Paragraph::new(text).block(block), chunks[1]);
+        return;
+    }
+
+    let width = ctx.timestamps.width();
+    let list = SelectableList::new(&ctx.history_list, events.len(), |i| {
+        let event = &events[i];
+        let mut spans = vec![
+            Span::styled(
+                format!("{:width$} ", time(event.at_usec)),
+                Style::default().fg(crate::palette::gray()),
+            ),
+            Span::styled(
+                format!("{:8} ", event.kind.label()),
+                event_style(event.kind),
+            ),
+            Span::raw(event.text.clone()),
+        ];
+        if event.observed {
+            spans.push(Span::styled(
+                " (seen)",
+                Style::default().fg(crate::palette::dark_gray()),
+            ));
+        }
+        Line::from(spans)
+    })
+    .block(block);
+    f.render_widget(list, chunks[1]);
+}
+
+fn draw_environment(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+
+    let hint = match ctx.env_input.as_ref() {
+        Some((scope, input)) => Line::from(vec![
+            Span::styled(
+                format!("{} override NAME=value: ", scope.label()),
+                Style::default().fg(crate::palette::yellow()),
+            ),
+            Span::raw(input.as_str()),
+            Span::raw("_"),
+        ]),
+        None => Line::from(Span::styled(
+            "n: runtime override  N: persistent override  D: remove overrides",
+            Style::default().fg(crate::palette::gray()),
+        )),
+    };
+    f.render_widget(Paragraph::new(hint), chunks[1]);
+
+    let block = Block::default()
+        .title(ctx.env_pending.title("Environment"))
+        .borders(Borders::ALL);
+    let Some(exec) = ctx.detail_exec.as_ref() else {
+        let text = if ctx.detail_exec_pending.is_pending() {
+            "Loading..."
+        } else {
+            "Environment only applies to services"
+        };
+        f.render_widget(Paragraph::new(text).block(block), chunks[0]);
+        return;
+    };
+    if ctx.env_len() == 0 {
+        f.render_widget(
+            Paragraph::new("No Environment= or EnvironmentFile= set").block(block),
+            chunks[0],
+        );
+        return;
+    }
+
+    let header = Row::new(vec!["Name", "Value", "From"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.env_list,
+        ctx.env_len(),
+        [
+            Constraint::Length(24),
+            Constraint::Min(20),
+            Constraint::Length(20),
+        ],
+        |i| {
+            let Some(assignment) = exec.environment.get(i) else {
+                let path = &exec.environment_files[i - exec.environment.len()];
+                return Row::new(vec![
+                    Span::styled("(file)", Style::default().fg(crate::palette::gray())),
+                    Span::raw(path.as_str()),
+                    Span::raw("EnvironmentFile="),
+                ]);
+            };
+            let (name, value) = assignment.split_once('=').unwrap_or((assignment, ""));
+            let from = ctx
+                .env_overrides
+                .iter()
+                .rev()
+                .find(|(_, a)| a == assignment)
+                .map(|(scope, _)| format!("{} override", scope.label()));
+            Row::new(vec![
+                Span::raw(name),
+                Span::raw(value),
+                match from {
+                    Some(from) => Span::styled(from, Style::default().fg(crate::palette::yellow())),
+                    None => Span::raw("Environment="),
+                },
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, chunks[0]);
+}
+
+/// The active state, with the failed check when a condition or assert is
+/// why the unit isn't running
+fn active_line<'a>(ctx: &UnitsContext, unit: &'a UnitInfo) -> Line<'a> {
+    let mut spans = vec![Span::raw(format!("Active: {}", unit.active_state))];
+    if let Some(conditions) = ctx.detail_conditions.as_ref()
+        && conditions.blocked()
+    {
+        let failed: Vec<String> = conditions.failures().map(|c| c.setting()).collect();
+        let what = if conditions.assert_usec > 0 && !conditions.assert_result {
+            "assert failed"
+        } else {
+            "condition failed"
+        };
+        spans.push(Span::styled(
+            format!(" ({}: {}) c=details", what, failed.join(", ")),
+            Style::default().fg(crate::palette::yellow()),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn draw_conditions(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let now = timestamp::now_usec();
+    let Some(conditions) = ctx.detail_conditions.as_ref() else {
+        let text = if ctx.detail_conditions_pending.is_pending() {
+            "Loading..."
+        } else {
+            "Conditions unavailable"
+        };
+        let block = Block::default()
+            .title(ctx.detail_conditions_pending.title("Conditions"))
+            .borders(Borders::ALL);
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    };
+
+    let outcome = |passed: bool, usec: u64, kind: &str| {
+        if usec == 0 {
+            format!("{} not checked yet", kind)
+        } else {
+            format!(
+                "{} {} at {}",
+                kind,
+                if passed { "passed" } else { "failed" },
+                ctx.timestamps.format(usec, None, now)
+            )
+        }
+    };
+    let title = format!(
+        "{}─ {}; {} ",
+        ctx.detail_conditions_pending.title("Conditions"),
+        outcome(
+            conditions.condition_result,
+            conditions.condition_usec,
+            "conditions"
+        ),
+        outcome(conditions.assert_result, conditions.assert_usec, "asserts")
+    );
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    let checks: Vec<_> = conditions
+        .conditions
+        .iter()
+        .chain(&conditions.asserts)
+        .collect();
+    if checks.is_empty() {
+        f.render_widget(
+            Paragraph::new("No Condition*= or Assert*= settings").block(block),
+            area,
+        );
+        return;
+    }
+
+    let table = SelectableTable::new(
+        &ctx.conditions_list,
+        checks.len(),
+        [Constraint::Length(12), Constraint::Min(20)],
+        |i| {
+            let check = checks[i];
+            let (label, style) = match check.state {
+                0 => ("-", Style::default().fg(crate::palette::gray())),
+                s if s > 0 => ("✓ passed", Style::default().fg(crate::palette::green())),
+                _ => (
+                    "✗ failed",
+                    Style::default()
+                        .fg(crate::palette::red())
+                        .add_modifier(Modifier::BOLD),
+                ),
+            };
+            Row::new(vec![Span::styled(label, style), Span::raw(check.setting())])
+        },
+    )
+    .block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_limits(ctx: &UnitsContext, f: &mut Frame, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let hint = match ctx.limit_input.as_ref() {
+        Some((limit, scope, input)) => Line::from(vec![
+            Span::styled(
+                format!("{}= ({}, Tab to switch): ", limit.setting(), scope.label()),
+                Style::default().fg(crate::palette::yellow()),
+            ),
+            Span::raw(input.as_str()),
+            Span::raw("_"),
+            Span::styled(
+                format!("  {}, empty for none", limit.hint()),
+                Style::default().fg(crate::palette::gray()),
+            ),
+        ]),
+        None => Line::from(Span::styled(
+            "Enter: change the selected limit  A: IPAccounting on/off (runtime)",
+            Style::default().fg(crate::palette::gray()),
+        )),
+    };
+    f.render_widget(Paragraph::new(hint), chunks[2]);
+
+    let traffic = match ctx.limits.as_ref() {
+        Some(limits) => match limits.ip_traffic() {
+            Some(traffic) => Line::from(vec![
+                Span::styled(
+                    "IP traffic: ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(traffic, Style::default().fg(crate::palette::cyan())),
+            ]),
+            None if limits.ip_accounting => Line::from("IP traffic: no counters yet"),
+            None => Line::from(Span::styled(
+                "IP traffic: not counted (IPAccounting=no)",
+                Style::default().fg(crate::palette::gray()),
+            )),
+        },
+        None => Line::from(""),
+    };
+    f.render_widget(Paragraph::new(traffic), chunks[1]);
+
+    let title = if ctx.limit_set_pending.is_pending() {
+        ctx.limit_set_pending.title("Resource limits")
+    } else {
+        ctx.limits_pending.title("Resource limits")
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let Some(limits) = ctx.limits.as_ref() else {
+        let text = if ctx.limits_pending.is_pending() {
+            "Loading..."
+        } else {
+            "No resource limits (only units with a cgroup have them)"
+        };
+        f.render_widget(Paragraph::new(text).block(block), chunks[0]);
+        return;
+    };
+
+    let header = Row::new(vec!["Setting", "Value", "In use"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let table = SelectableTable::new(
+        &ctx.limits_list,
+        Limit::ALL.len(),
+        [
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Min(10),
+        ],
+        |i| {
+            let limit = Limit::ALL[i];
+            let value = limits.get(limit);
+            let value_style = if value == crate::systemd::limits::INFINITY {
+                Style::default().fg(crate::palette::gray())
+            } else {
+                Style::default().fg(crate::palette::yellow())
+            };
+            Row::new(vec![
+                Span::raw(limit.setting()),
+                Span::styled(limit.format(value), value_style),
+                Span::raw(limits.usage(limit).unwrap_or_default()),
+            ])
+        },
+    )
+    .header(header)
+    .block(block);
+    f.render_widget(table, chunks[0]);
+}
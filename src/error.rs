@@ -0,0 +1,188 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use thiserror::Error;
+
+/// Errors surfaced to the UI, categorized so each view can suggest what to do
+/// next instead of just printing a string.
+#[derive(Debug, Clone, Error)]
+pub enum RootworkError {
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "host",
+        feature = "boot",
+        feature = "machines",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    #[error("D-Bus: {0}")]
+    DBus(String),
+    #[cfg(any(feature = "logs", feature = "coredumps"))]
+    #[error("Journal: {0}")]
+    Journal(String),
+    /// libsystemd couldn't be loaded, so there is no journal to read
+    #[cfg(any(feature = "logs", feature = "coredumps"))]
+    #[error("Journal unavailable: {0}")]
+    JournalUnavailable(String),
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "host",
+        feature = "boot",
+        feature = "machines",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    #[error("I/O: {0}")]
+    Io(String),
+    #[error("Permission denied: {0}")]
+    Permission(String),
+}
+
+impl RootworkError {
+    /// Classify an error chain, prefixing the message with what we were doing
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "host",
+        feature = "boot",
+        feature = "machines",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    pub fn from_anyhow(what: &str, err: &anyhow::Error) -> Self {
+        let msg = format!("{}: {}", what, err);
+
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<zbus::Error>() {
+                return if is_dbus_access_denied(e) {
+                    Self::Permission(msg)
+                } else {
+                    Self::DBus(msg)
+                };
+            }
+            if let Some(e) = cause.downcast_ref::<zbus::fdo::Error>() {
+                return match e {
+                    zbus::fdo::Error::AccessDenied(_)
+                    | zbus::fdo::Error::InteractiveAuthorizationRequired(_) => {
+                        Self::Permission(msg)
+                    }
+                    _ => Self::DBus(msg),
+                };
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                return if e.kind() == std::io::ErrorKind::PermissionDenied {
+                    Self::Permission(msg)
+                } else {
+                    Self::Io(msg)
+                };
+            }
+        }
+
+        Self::Io(msg)
+    }
+
+    /// Classify a negative errno returned by sd-journal
+    #[cfg(any(feature = "logs", feature = "coredumps"))]
+    pub fn from_journal_errno(what: &str, rc: i32) -> Self {
+        let errno = -rc;
+        if errno == libc::ENOSYS && !crate::systemd::journal_ffi::available() {
+            return Self::JournalUnavailable(format!("{}: libsystemd not found", what));
+        }
+        let msg = format!("{}: {}", what, std::io::Error::from_raw_os_error(errno));
+        if errno == libc::EACCES || errno == libc::EPERM {
+            Self::Permission(msg)
+        } else {
+            Self::Journal(msg)
+        }
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            #[cfg(any(
+                feature = "units",
+                feature = "network",
+                feature = "dns",
+                feature = "host",
+                feature = "boot",
+                feature = "machines",
+                feature = "storage",
+                feature = "portable",
+                feature = "kernel"
+            ))]
+            Self::DBus(_) => "Press r to retry; check that the service is running",
+            #[cfg(any(feature = "logs", feature = "coredumps"))]
+            Self::Journal(_) => "Press r to retry; journal files may be rotating",
+            #[cfg(any(feature = "logs", feature = "coredumps"))]
+            Self::JournalUnavailable(_) => {
+                "Install libsystemd (libsystemd0) to read logs; the other tabs still work"
+            }
+            #[cfg(any(
+                feature = "units",
+                feature = "network",
+                feature = "dns",
+                feature = "host",
+                feature = "boot",
+                feature = "machines",
+                feature = "storage",
+                feature = "portable",
+                feature = "kernel"
+            ))]
+            Self::Io(_) => "Press r to retry",
+            Self::Permission(_) => {
+                "Run as root (sudo rootwork) or add your user to the systemd-journal group"
+            }
+        }
+    }
+
+    /// Message plus hint, ready to drop into a Paragraph
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from(Span::styled(
+                format!("Error: {}", self),
+                Style::default().fg(crate::palette::red()),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                self.hint(),
+                Style::default().fg(crate::palette::gray()),
+            )),
+        ]
+    }
+}
+
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "boot",
+    feature = "machines",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+fn is_dbus_access_denied(err: &zbus::Error) -> bool {
+    match err {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.AccessDenied"
+                | "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired"
+        ),
+        zbus::Error::FDO(e) => matches!(
+            **e,
+            zbus::fdo::Error::AccessDenied(_)
+                | zbus::fdo::Error::InteractiveAuthorizationRequired(_)
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,362 @@
+//! The active nftables ruleset, from `nft -j list ruleset`, boiled down to
+//! chains of one-line rules with their hit counters and the ports they
+//! match on. firewalld's nftables backend shows up as its own tables.
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use std::process::Command;
+
+pub struct Ruleset {
+    pub chains: Vec<Chain>,
+}
+
+pub struct Chain {
+    /// "inet", "ip", "ip6", "arp", "bridge" or "netdev"
+    pub family: String,
+    pub table: String,
+    pub name: String,
+    /// Base chains hook into the stack, e.g. "input"; regular chains are
+    /// only jumped to
+    pub hook: Option<String>,
+    pub policy: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+pub struct Rule {
+    /// Roughly as `nft list ruleset` prints it, counters left out
+    pub text: String,
+    pub packets: Option<u64>,
+    pub bytes: Option<u64>,
+    /// Port ranges the rule matches as source or destination port
+    pub ports: Vec<(u16, u16)>,
+}
+
+impl Rule {
+    pub fn matches_port(&self, port: u16) -> bool {
+        self.ports
+            .iter()
+            .any(|(lo, hi)| (*lo..=*hi).contains(&port))
+    }
+}
+
+impl Chain {
+    /// "inet filter input"
+    pub fn title(&self) -> String {
+        format!("{} {} {}", self.family, self.table, self.name)
+    }
+}
+
+impl Ruleset {
+    /// Listing the ruleset needs CAP_NET_ADMIN
+    pub fn gather() -> Result<Self> {
+        let output = Command::new("nft")
+            .args(["-j", "list", "ruleset"])
+            .output()
+            .context("Can't run nft (is nftables installed?)")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("nft list ruleset: {}", stderr.trim());
+        }
+        let json: Value =
+            serde_json::from_slice(&output.stdout).context("Unexpected nft output")?;
+        Ok(Self::parse(&json))
+    }
+
+    /// Chains in listing order; rules follow their chain in the listing
+    fn parse(json: &Value) -> Self {
+        let mut chains: Vec<Chain> = Vec::new();
+        let objects = json["nftables"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        for object in objects {
+            if let Some(chain) = object.get("chain") {
+                chains.push(Chain {
+                    family: str_field(chain, "family"),
+                    table: str_field(chain, "table"),
+                    name: str_field(chain, "name"),
+                    hook: chain["hook"].as_str().map(str::to_string),
+                    policy: chain["policy"].as_str().map(str::to_string),
+                    rules: Vec::new(),
+                });
+            } else if let Some(rule) = object.get("rule") {
+                let (family, table, name) = (
+                    str_field(rule, "family"),
+                    str_field(rule, "table"),
+                    str_field(rule, "chain"),
+                );
+                if let Some(chain) = chains
+                    .iter_mut()
+                    .rev()
+                    .find(|c| c.family == family && c.table == table && c.name == name)
+                {
+                    chain.rules.push(parse_rule(rule));
+                }
+            }
+        }
+        Self { chains }
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.chains.iter().map(|c| c.rules.len()).sum()
+    }
+}
+
+fn str_field(object: &Value, key: &str) -> String {
+    object[key].as_str().unwrap_or_default().to_string()
+}
+
+fn parse_rule(rule: &Value) -> Rule {
+    let mut parsed = Rule {
+        text: String::new(),
+        packets: None,
+        bytes: None,
+        ports: Vec::new(),
+    };
+    let mut parts: Vec<String> = Vec::new();
+    for statement in rule["expr"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some((key, value)) = single(statement) else {
+            continue;
+        };
+        match key {
+            "counter" => {
+                parsed.packets = value["packets"].as_u64();
+                parsed.bytes = value["bytes"].as_u64();
+            }
+            "match" => {
+                // "!= 22" matches every port but 22; only equality and
+                // set membership name the ports a rule is about
+                let positive = matches!(value["op"].as_str(), Some("==") | Some("in") | None);
+                if positive && is_port(&value["left"]) {
+                    parsed.ports.extend(port_ranges(&value["right"]));
+                }
+                parts.push(match value["op"].as_str() {
+                    Some("==") | Some("in") | None => {
+                        format!("{} {}", expr(&value["left"]), expr(&value["right"]))
+                    }
+                    Some(op) => {
+                        format!("{} {} {}", expr(&value["left"]), op, expr(&value["right"]))
+                    }
+                });
+            }
+            "jump" | "goto" => parts.push(format!("{} {}", key, expr(&value["target"]))),
+            "log" => parts.push(match value["prefix"].as_str() {
+                Some(prefix) => format!("log prefix {:?}", prefix),
+                None => "log".to_string(),
+            }),
+            "limit" => parts.push(format!(
+                "limit rate {}/{}",
+                expr(&value["rate"]),
+                expr(&value["per"])
+            )),
+            "snat" | "dnat" | "redirect" | "masquerade" => {
+                let mut text = key.to_string();
+                if !value["addr"].is_null() {
+                    text.push_str(&format!(" to {}", expr(&value["addr"])));
+                }
+                if !value["port"].is_null() {
+                    text.push_str(&format!(" :{}", expr(&value["port"])));
+                }
+                parts.push(text);
+            }
+            // Verdicts without arguments, "reject", "notrack", ...
+            _ => parts.push(key.to_string()),
+        }
+    }
+    if let Some(comment) = rule["comment"].as_str() {
+        parts.push(format!("comment {:?}", comment));
+    }
+    parsed.text = parts.join(" ");
+    parsed
+}
+
+/// The only key of a `{"key": value}` object, as nft's JSON uses for
+/// statements and expressions
+fn single(value: &Value) -> Option<(&str, &Value)> {
+    let map: &Map<String, Value> = value.as_object()?;
+    let (key, value) = map.iter().next()?;
+    Some((key.as_str(), value))
+}
+
+/// An expression as nft prints it, near enough
+fn expr(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().map(expr).collect::<Vec<_>>().join(","),
+        Value::Object(_) => {
+            let Some((key, inner)) = single(value) else {
+                return String::new();
+            };
+            match key {
+                "payload" if inner["protocol"].is_string() => {
+                    format!("{} {}", expr(&inner["protocol"]), expr(&inner["field"]))
+                }
+                "payload" => format!(
+                    "@{},{},{}",
+                    expr(&inner["base"]),
+                    expr(&inner["offset"]),
+                    expr(&inner["len"])
+                ),
+                "meta" | "ct" | "rt" | "socket" | "osf" => {
+                    format!("{} {}", key, expr(&inner["key"]))
+                }
+                "set" => match inner {
+                    Value::Array(items) => format!(
+                        "{{ {} }}",
+                        items.iter().map(expr).collect::<Vec<_>>().join(", ")
+                    ),
+                    other => expr(other),
+                },
+                "range" => match inner.as_array().map(Vec::as_slice) {
+                    Some([lo, hi]) => format!("{}-{}", expr(lo), expr(hi)),
+                    _ => "range".to_string(),
+                },
+                "prefix" => format!("{}/{}", expr(&inner["addr"]), expr(&inner["len"])),
+                // Set references are plain strings starting with "@"
+                _ => key.to_string(),
+            }
+        }
+    }
+}
+
+/// Whether the left side of a match is a source or destination port
+fn is_port(left: &Value) -> bool {
+    matches!(
+        single(left),
+        Some(("payload", inner)) if matches!(inner["field"].as_str(), Some("dport" | "sport"))
+    )
+}
+
+/// Ports on the right side of a port match: a number, a range or an
+/// anonymous set of either. Service names and named sets aren't resolved.
+fn port_ranges(right: &Value) -> Vec<(u16, u16)> {
+    let port = |v: &Value| v.as_u64().and_then(|n| u16::try_from(n).ok());
+    match right {
+        Value::Number(_) => port(right).map(|p| vec![(p, p)]).unwrap_or_default(),
+        Value::Object(_) => match single(right) {
+            Some(("range", Value::Array(bounds))) if bounds.len() == 2 => {
+                match (port(&bounds[0]), port(&bounds[1])) {
+                    (Some(lo), Some(hi)) => vec![(lo, hi)],
+                    _ => Vec::new(),
+                }
+            }
+            Some(("set", Value::Array(items))) => items.iter().flat_map(port_ranges).collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dport(right: Value) -> Value {
+        json!({"match": {
+            "op": "==",
+            "left": {"payload": {"protocol": "tcp", "field": "dport"}},
+            "right": right,
+        }})
+    }
+
+    #[test]
+    fn rules_follow_their_chain() {
+        let ruleset = Ruleset::parse(&json!({"nftables": [
+            {"metainfo": {"json_schema_version": 1}},
+            {"table": {"family": "inet", "name": "filter"}},
+            {"chain": {"family": "inet", "table": "filter", "name": "input",
+                       "hook": "input", "policy": "drop"}},
+            {"chain": {"family": "inet", "table": "filter", "name": "allowed"}},
+            {"rule": {"family": "inet", "table": "filter", "chain": "input",
+                      "expr": [{"jump": {"target": "allowed"}}]}},
+            {"rule": {"family": "inet", "table": "filter", "chain": "allowed",
+                      "expr": [{"accept": null}]}},
+            {"rule": {"family": "inet", "table": "nat", "chain": "input",
+                      "expr": [{"accept": null}]}},
+        ]}));
+        assert_eq!(ruleset.chains.len(), 2);
+        assert_eq!(ruleset.rule_count(), 2);
+        let input = &ruleset.chains[0];
+        assert_eq!(input.title(), "inet filter input");
+        assert_eq!(input.hook.as_deref(), Some("input"));
+        assert_eq!(input.policy.as_deref(), Some("drop"));
+        assert_eq!(input.rules[0].text, "jump allowed");
+        assert_eq!(ruleset.chains[1].hook, None);
+        assert_eq!(ruleset.chains[1].rules[0].text, "accept");
+    }
+
+    #[test]
+    fn missing_listing_is_empty() {
+        assert_eq!(Ruleset::parse(&json!({})).chains.len(), 0);
+    }
+
+    #[test]
+    fn rule_text_counters_and_comment() {
+        let rule = parse_rule(&json!({
+            "expr": [
+                {"match": {"op": "==",
+                           "left": {"meta": {"key": "iifname"}}, "right": "lo"}},
+                {"match": {"op": "!=",
+                           "left": {"payload": {"protocol": "ip", "field": "saddr"}},
+                           "right": {"prefix": {"addr": "10.0.0.0", "len": 8}}}},
+                {"counter": {"packets": 12, "bytes": 3400}},
+                {"log": {"prefix": "in: "}},
+                {"accept": null},
+            ],
+            "comment": "loopback",
+        }));
+        assert_eq!(
+            rule.text,
+            r#"meta iifname lo ip saddr != 10.0.0.0/8 log prefix "in: " accept comment "loopback""#
+        );
+        assert_eq!(rule.packets, Some(12));
+        assert_eq!(rule.bytes, Some(3400));
+        assert!(rule.ports.is_empty());
+    }
+
+    #[test]
+    fn port_matches_collect_numbers_ranges_and_sets() {
+        let rule = parse_rule(&json!({"expr": [
+            dport(json!(22)),
+            dport(json!({"range": [8000, 8080]})),
+            dport(json!({"set": [443, {"range": [60000, 60010]}, "@named"]})),
+            {"accept": null},
+        ]}));
+        assert_eq!(
+            rule.ports,
+            vec![(22, 22), (8000, 8080), (443, 443), (60000, 60010)]
+        );
+        assert_eq!(
+            rule.text,
+            "tcp dport 22 tcp dport 8000-8080 tcp dport { 443, 60000-60010, @named } accept"
+        );
+        assert!(rule.matches_port(8042));
+        assert!(rule.matches_port(443));
+        assert!(!rule.matches_port(444));
+    }
+
+    #[test]
+    fn negated_port_matches_name_no_ports() {
+        let rule = parse_rule(&json!({"expr": [
+            {"match": {"op": "!=",
+                       "left": {"payload": {"protocol": "tcp", "field": "dport"}},
+                       "right": 22}},
+            {"drop": null},
+        ]}));
+        assert!(rule.ports.is_empty());
+        assert_eq!(rule.text, "tcp dport != 22 drop");
+    }
+
+    #[test]
+    fn nat_statements() {
+        let rule = parse_rule(&json!({"expr": [
+            {"dnat": {"addr": "192.168.1.10", "port": 8080}},
+            {"masquerade": null},
+        ]}));
+        assert_eq!(rule.text, "dnat to 192.168.1.10 :8080 masquerade");
+    }
+}
@@ -1,7 +1,25 @@
+#[cfg(not(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "boot",
+    feature = "logs",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+)))]
+compile_error!(
+    "enable at least one tab feature: units, network, dns, host, boot, logs, machines, coredumps, storage, portable or kernel"
+);
+
 use anyhow::Result;
+use clap::{Parser, ValueEnum};
 use crossterm::{
     ExecutableCommand,
-    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
@@ -13,26 +31,160 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Tabs, Wrap},
 };
 use std::io::{Stdout, stdout};
+use std::process::{Command, ExitCode};
 
 mod app;
+mod cli;
+mod commands;
+mod config;
+#[cfg(any(feature = "network", feature = "host", feature = "boot"))]
+mod container;
 mod contexts;
+mod error;
+#[cfg(feature = "network")]
+mod firewall;
+mod messages;
 mod palette;
+mod power;
+#[cfg(any(feature = "units", feature = "logs"))]
+mod presets;
+mod pressure;
+#[cfg(feature = "network")]
+mod probe;
+mod report;
+#[cfg(feature = "network")]
+mod sockdiag;
 mod systemd;
+#[cfg(any(feature = "units", feature = "logs"))]
+mod timestamp;
+mod watchlist;
 mod widgets;
 
 use app::App;
-use contexts::Context;
+use cli::CliCommand;
+use commands::Run;
+use config::Config;
+use contexts::{Context, NARROW_WIDTH};
+use messages::Level;
+use systemd::client::{BusPreference, SystemdClient};
+use widgets::picker::{Picker, draw_picker};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
+    /// Suppress diagnostics from subcommands; rely on the exit code
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Manage a remote machine over SSH, like `systemctl --host`.
+    /// Tabs that read local files or the journal are turned off.
+    #[arg(
+        short = 'H',
+        long,
+        value_name = "[USER@]HOST",
+        conflicts_with = "user",
+        global = true
+    )]
+    host: Option<String>,
+
+    /// Tab to start on
+    #[arg(short, long, value_enum)]
+    tab: Option<Tab>,
+
+    /// Open the detail view for this unit
+    #[arg(short, long, value_name = "UNIT")]
+    unit: Option<String>,
+
+    /// Initial filter for the units list
+    #[arg(short, long)]
+    filter: Option<String>,
+
+    /// Color theme: dark, light, solarized or high-contrast.
+    /// Overrides `theme` in the config file.
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Disable colors; also enabled by a non-empty NO_COLOR
+    #[arg(long)]
+    no_color: bool,
+
+    /// Talk to the user service manager
+    #[arg(long, conflicts_with = "system", global = true)]
+    user: bool,
+
+    /// Talk to the system service manager
+    #[arg(long, global = true)]
+    system: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Tab {
+    Units,
+    Network,
+    Dns,
+    Host,
+    Boot,
+    Logs,
+    Machines,
+    Coredumps,
+    Storage,
+    Portable,
+    Kernel,
+}
+
+impl Tab {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
+    let bus = if cli.user {
+        BusPreference::User
+    } else if cli.system {
+        BusPreference::System
+    } else {
+        BusPreference::Auto
+    };
+
+    if let Some(command) = cli.command {
+        let systemd = match SystemdClient::new(cli.host.as_deref(), bus).await {
+            Ok(systemd) => systemd,
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("Failed to connect: {}", e);
+                }
+                return Ok(ExitCode::FAILURE);
+            }
+        };
+        return Ok(cli::run(command, &systemd, cli.quiet).await);
+    }
+
+    let config = Config::load();
+    let theme = match cli.theme.as_deref() {
+        Some(name) => match palette::Theme::by_name(name) {
+            Some(theme) => theme,
+            None => anyhow::bail!("unknown theme: {}", name),
+        },
+        None => palette::Theme::from_config(&config),
+    };
+    palette::set_theme(theme);
+    palette::set_monochrome(cli.no_color || palette::no_color_env());
+
     // Setup terminal
     let mut terminal = setup_terminal()?;
 
     // Create app (async - connects to systemd)
-    let mut app = match App::new().await {
+    let mut app = match App::new(cli.host.as_deref(), bus, &config).await {
         Ok(app) => app,
         Err(e) => {
             eprintln!("Failed to initialize: {}", e);
@@ -40,13 +192,27 @@ async fn main() -> Result<()> {
         }
     };
 
+    if let Some(tab) = cli.tab {
+        app.set_context(tab.index());
+    }
+    if let Some(ref filter) = cli.filter {
+        app.set_unit_filter(filter);
+    }
+    if let Some(ref unit) = cli.unit
+        && !app.open_unit(unit)
+    {
+        // Not loaded: the closest we can get is a filtered list
+        app.set_context(0);
+        app.set_unit_filter(unit);
+    }
+
     // Run app
     let result = run_app(&mut terminal, &mut app).await;
 
     // Restore terminal
     restore_terminal(terminal)?;
 
-    result
+    result.map(|()| ExitCode::SUCCESS)
 }
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
@@ -67,22 +233,40 @@ fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<
 async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     let mut last_tick = std::time::Instant::now();
     let tick_rate = std::time::Duration::from_millis(250);
-    let refresh_interval = std::time::Duration::from_secs(2);
-    let mut last_refresh = std::time::Instant::now();
 
-    loop {
-        terminal.draw(|f| draw(f, app))?;
+    let mut dirty = true;
+
+    'main: loop {
+        if dirty {
+            terminal.draw(|f| draw(f, app))?;
+            dirty = false;
+        }
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| std::time::Duration::from_secs(0));
 
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match handle_key(key, app) {
-                        Action::Continue => {}
-                        Action::Quit => break,
+        // Drain everything already queued before drawing again, so key
+        // repeat costs one frame per batch rather than one per event.
+        let mut wait = timeout;
+        while crossterm::event::poll(wait)? {
+            wait = std::time::Duration::ZERO;
+            dirty = true;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                match handle_key(key, app) {
+                    Action::Continue => {}
+                    Action::Quit => break 'main,
+                    Action::Shell => {
+                        let shell =
+                            std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+                        if let Err(e) = run_suspended(terminal, Command::new(shell)) {
+                            app.messages()
+                                .error(format!("Shell exited with error: {}", e));
+                        }
+                        break;
                     }
                 }
             }
@@ -91,48 +275,121 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
         if last_tick.elapsed() >= tick_rate {
             app.tick().await;
             last_tick = std::time::Instant::now();
+            dirty = true;
         }
 
-        // Periodic refresh every 2 seconds
-        if last_refresh.elapsed() >= refresh_interval {
-            last_refresh = std::time::Instant::now();
+        if let Some(cmd) = app.take_external_command()
+            && let Err(e) = run_suspended(terminal, cmd)
+        {
+            app.messages()
+                .error(format!("External command failed: {}", e));
         }
     }
 
     Ok(())
 }
 
+/// Leave the alternate screen, run `cmd` on the real terminal and restore the
+/// TUI once it exits. App state is untouched, so the UI resumes where it was.
+fn run_suspended<B: Backend>(terminal: &mut Terminal<B>, mut cmd: Command) -> Result<()> {
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+
+    let status = cmd.status();
+
+    stdout().execute(EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+
+    status?;
+    Ok(())
+}
+
 enum Action {
     Continue,
     Quit,
+    Shell,
 }
 
 fn handle_key(key: KeyEvent, app: &mut App) -> Action {
+    if app.palette().is_some() {
+        return match app.handle_palette_key(key) {
+            Some(command) => run_command(app, command),
+            None => Action::Continue,
+        };
+    }
+
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    if key.code == KeyCode::Char('z') && ctrl {
+        return Action::Shell;
+    }
+    if key.code == KeyCode::Char('p') && ctrl && app.unit_search().is_none() {
+        app.open_unit_search();
+        return Action::Continue;
+    }
+    if key.code == KeyCode::Char('e') && ctrl {
+        app.export_report();
+        return Action::Continue;
+    }
+    if key.code == KeyCode::Char('w') && ctrl {
+        app.cycle_focus();
+        return Action::Continue;
+    }
+    // Typed text isn't a shortcut: let the filter or picker have it
+    if app.captures_input() {
+        app.handle_key(key);
+        return Action::Continue;
+    }
+
     match key.code {
         KeyCode::Char('q') | KeyCode::Char('Q') => return Action::Quit,
+        KeyCode::Char(':') => app.open_palette(),
+        KeyCode::Char('!') => return Action::Shell,
         KeyCode::Char('?') => app.toggle_help(),
+        KeyCode::Char('|') => app.toggle_split(),
         KeyCode::Tab => app.next_context(),
         KeyCode::BackTab => app.prev_context(),
-        KeyCode::Char('1') => app.set_context(0),
-        KeyCode::Char('2') => app.set_context(1),
-        KeyCode::Char('3') => app.set_context(2),
-        KeyCode::Char('4') => app.set_context(3),
-        KeyCode::Char('5') => app.set_context(4),
-        KeyCode::Char('6') => app.set_context(5),
+        // Numbered by position, so a build without some tabs has no gaps
+        KeyCode::Char(c @ '1'..='9') => app.select_tab(c as usize - '1' as usize),
+        KeyCode::Char('0') => app.select_tab(9),
         _ => app.handle_key(key),
     }
     Action::Continue
 }
 
+fn run_command(app: &mut App, command: &commands::Command) -> Action {
+    match command.run {
+        Run::Quit => return Action::Quit,
+        Run::Shell => return Action::Shell,
+        Run::Help => app.toggle_help(),
+        Run::GoToUnit => app.open_unit_search(),
+        Run::Report => app.export_report(),
+        Run::Split => app.toggle_split(),
+        Run::SplitFocus => app.cycle_focus(),
+        Run::Tab(ctx) => app.set_context(ctx),
+        Run::Theme(name) => {
+            if let Some(theme) = palette::Theme::by_name(name) {
+                palette::set_theme(theme);
+            }
+        }
+        Run::Monochrome => palette::set_monochrome(!palette::is_monochrome()),
+        Run::Key(..) | Run::UnitsList(_) | Run::UnitDetail(_) => app.run_command(command),
+    }
+    Action::Continue
+}
+
 fn draw(f: &mut Frame, app: &App) {
+    let watching = !app.watchlist().is_empty();
+
     // Main layout
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(0)
         .constraints([
-            Constraint::Length(3), // Header with tabs
-            Constraint::Min(10),   // Main content
-            Constraint::Length(1), // Status line
+            Constraint::Length(3),                   // Header with tabs
+            Constraint::Min(10),                     // Main content
+            Constraint::Length(u16::from(watching)), // Watchlist strip
+            Constraint::Length(1),                   // Status line
         ])
         .split(f.area());
 
@@ -142,8 +399,20 @@ fn draw(f: &mut Frame, app: &App) {
     // Main content area - delegate to current context
     draw_content(f, app, chunks[1]);
 
+    if watching {
+        draw_watchlist(f, app, chunks[2]);
+    }
+
     // Status line
-    draw_status(f, app, chunks[2]);
+    draw_status(f, app, chunks[3]);
+
+    #[cfg(feature = "units")]
+    if let Some(picker) = app.unit_search() {
+        draw_unit_search(f, app, picker);
+    }
+    if let Some(picker) = app.palette() {
+        draw_palette(f, picker);
+    }
 
     // Help overlay if active
     if app.show_help() {
@@ -151,19 +420,67 @@ fn draw(f: &mut Frame, app: &App) {
     }
 }
 
+#[cfg(feature = "units")]
+fn draw_unit_search(f: &mut Frame, app: &App, picker: &Picker) {
+    let matches = app.units().search(&picker.query, app::UNIT_SEARCH_LIMIT);
+    let area = centered_rect(70, 60, f.area());
+    draw_picker(f, area, "Go to unit", picker, matches.len(), |i| {
+        let unit = matches[i];
+        Line::from(vec![
+            Span::styled(
+                unit.state_indicator(),
+                crate::palette::unit_state(&unit.active_state, &unit.sub_state),
+            ),
+            Span::raw(" "),
+            Span::raw(unit.name.clone()),
+            Span::raw("  "),
+            Span::styled(
+                unit.description.clone(),
+                Style::default().fg(crate::palette::gray()),
+            ),
+        ])
+    });
+}
+
+fn draw_palette(f: &mut Frame, picker: &Picker) {
+    let matches = commands::search(&picker.query);
+    let area = centered_rect(60, 60, f.area());
+    draw_picker(f, area, "Commands", picker, matches.len(), |i| {
+        let command = matches[i];
+        Line::from(vec![
+            Span::raw(command.title),
+            Span::raw("  "),
+            Span::styled(command.keys, Style::default().fg(crate::palette::gray())),
+        ])
+    });
+}
+
 fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let status = app.manager_status();
+    let status_text = status.map(|status| {
+        format!(
+            "systemd {} · {}",
+            status.version.split_whitespace().next().unwrap_or(""),
+            status.state_label()
+        )
+    });
+    let pressure = pressure_line(app.pressure());
     let header_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(12), Constraint::Min(20)])
+        .constraints([
+            Constraint::Length(12),
+            Constraint::Min(20),
+            Constraint::Length(pressure.as_ref().map_or(0, |l| l.width() as u16 + 2)),
+            Constraint::Length(
+                status_text
+                    .as_ref()
+                    .map_or(0, |t| t.chars().count() as u16 + 4),
+            ),
+        ])
         .split(area);
 
     // Title block with user mode indicator
-    let mode_indicator = if app.systemd().is_user_mode() {
-        "[user]"
-    } else {
-        "[system]"
-    };
-    let title_text = format!("🐾 rootwork\n{}", mode_indicator);
+    let title_text = format!("🐾 rootwork\n{}", mode_indicator(app));
     let title = Paragraph::new(title_text)
         .style(
             Style::default()
@@ -173,36 +490,160 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, header_layout[0]);
 
-    // Tabs
-    let titles = vec![
-        "[1] Units",
-        "[2] Network",
-        "[3] DNS",
-        "[4] Host",
-        "[5] Boot",
-        "[6] Logs",
-    ];
+    // Tabs; narrow terminals only name the current one
+    let narrow = area.width < NARROW_WIDTH;
+    let titles: Vec<Line> = app::TABS
+        .iter()
+        .enumerate()
+        .map(|(i, (tab, name))| {
+            // The tenth tab is on 0; later ones have no number key
+            let key = match i {
+                0..=9 => ((i + 1) % 10).to_string(),
+                _ => "-".to_string(),
+            };
+            let title = if !narrow {
+                format!("[{}] {}", key, name)
+            } else if i == app.tab_position() || app.other_pane() == Some(*tab) {
+                format!("{} {}", key, name)
+            } else {
+                key
+            };
+            // The unfocused pane of a split
+            if app.other_pane() == Some(*tab) {
+                Line::styled(title, Style::default().fg(crate::palette::cyan()))
+            } else if app.is_local_only(*tab) {
+                Line::styled(title, Style::default().fg(crate::palette::gray()))
+            } else {
+                Line::raw(title)
+            }
+        })
+        .collect();
     let tabs = Tabs::new(titles)
-        .select(app.current_context())
+        .select(app.tab_position())
         .style(Style::default().fg(crate::palette::white()))
         .highlight_style(
             Style::default()
                 .fg(crate::palette::green())
                 .add_modifier(Modifier::BOLD),
         )
-        .divider(" | ")
+        .divider(if narrow { "|" } else { " | " })
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(tabs, header_layout[1]);
+
+    if let Some(line) = pressure {
+        f.render_widget(
+            Paragraph::new(line).block(Block::default().borders(Borders::ALL)),
+            header_layout[2],
+        );
+    }
+
+    // Manager health at a glance
+    if let (Some(status), Some(text)) = (status, status_text) {
+        let style = if status.is_degraded() || status.n_failed_units > 0 {
+            Style::default()
+                .fg(crate::palette::red())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(crate::palette::green())
+        };
+        let block = Paragraph::new(text)
+            .style(style)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, header_layout[3]);
+    }
+}
+
+/// "cpu 2 mem 0 io 14": avg10 stall percentages, red past the
+/// `[pressure]` thresholds
+fn pressure_line(pressure: &pressure::Pressure) -> Option<Line<'static>> {
+    let stalls = pressure.stalls.as_ref()?;
+    let mut spans = Vec::new();
+    for (name, stall) in ["cpu", "mem", "io"].iter().zip(stalls) {
+        if !spans.is_empty() {
+            spans.push(Span::raw(" "));
+        }
+        let style = if pressure.is_alarming(stall) {
+            Style::default()
+                .fg(crate::palette::red())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(crate::palette::gray())
+        };
+        // Full stalls are the worse kind; show them when there are any
+        let value = match stall.full {
+            Some(full) if full >= 1.0 => format!("{:.0}/{:.0}", stall.some, full),
+            _ => format!("{:.0}", stall.some),
+        };
+        spans.push(Span::styled(format!("{} {}", name, value), style));
+    }
+    Some(Line::from(spans))
 }
 
 fn draw_content(f: &mut Frame, app: &App, area: Rect) {
-    match app.current_context() {
+    let Some((left, right)) = app.panes() else {
+        draw_context(f, app, app.current_context(), area);
+        return;
+    };
+    // Side by side when there's room, stacked on narrow terminals
+    let direction = if area.width < NARROW_WIDTH {
+        Direction::Vertical
+    } else {
+        Direction::Horizontal
+    };
+    let panes = Layout::default()
+        .direction(direction)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    draw_context(f, app, left, panes[0]);
+    draw_context(f, app, right, panes[1]);
+}
+
+fn draw_context(f: &mut Frame, app: &App, ctx: usize, area: Rect) {
+    if app.is_local_only(ctx) {
+        let name = app::TABS
+            .iter()
+            .find(|(tab, _)| *tab == ctx)
+            .map_or("This tab", |(_, name)| name);
+        let text = format!(
+            "{} reads this machine, not {}, so it's off while managing a remote host",
+            name,
+            app.systemd().host().unwrap_or_default()
+        );
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", name));
+        f.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(crate::palette::gray()))
+                .wrap(Wrap { trim: true })
+                .block(block),
+            area,
+        );
+        return;
+    }
+    match ctx {
+        #[cfg(feature = "units")]
         0 => app.units().draw(f, area),
+        #[cfg(feature = "network")]
         1 => app.network().draw(f, area),
+        #[cfg(feature = "dns")]
         2 => app.dns().draw(f, area),
+        #[cfg(feature = "host")]
         3 => app.host().draw(f, area),
+        #[cfg(feature = "boot")]
         4 => app.boot().draw(f, area),
+        #[cfg(feature = "logs")]
         5 => app.logs().draw(f, area),
+        #[cfg(feature = "machines")]
+        6 => app.machines().draw(f, area),
+        #[cfg(feature = "coredumps")]
+        7 => app.coredumps().draw(f, area),
+        #[cfg(feature = "storage")]
+        8 => app.storage().draw(f, area),
+        #[cfg(feature = "portable")]
+        9 => app.portable().draw(f, area),
+        #[cfg(feature = "kernel")]
+        10 => app.kernel().draw(f, area),
         _ => {
             let block = Block::default()
                 .borders(Borders::ALL)
@@ -213,16 +654,109 @@ fn draw_content(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+fn mode_indicator(app: &App) -> String {
+    if let Some(host) = app.systemd().host() {
+        format!("[{}]", host)
+    } else if app.systemd().is_user_mode() {
+        "[user]".to_string()
+    } else {
+        "[system]".to_string()
+    }
+}
+
+/// One line of pinned units, each flashing for a while after it changes state
+fn draw_watchlist(f: &mut Frame, app: &App, area: Rect) {
+    let blink_on = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() / 500 % 2 == 0)
+        .unwrap_or(true);
+
+    let mut spans = vec![Span::styled(
+        "Watch: ",
+        Style::default().fg(crate::palette::gray()),
+    )];
+    for watched in app.watchlist().units() {
+        let state = watched.state.as_deref().unwrap_or("…");
+        let mut style = crate::palette::unit_state(state, "");
+        if watched.is_flashing() && blink_on {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        let text = match (&watched.previous, watched.changed) {
+            (Some(previous), Some(changed)) => format!(
+                "{} {} (was {}, {}s ago)",
+                watched.name,
+                state,
+                previous,
+                changed.elapsed().as_secs()
+            ),
+            _ => format!("{} {}", watched.name, state),
+        };
+        spans.push(Span::styled(text, style));
+        spans.push(Span::raw("  "));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
 fn draw_status(f: &mut Frame, app: &App, area: Rect) {
-    let mode_str = if app.systemd().is_user_mode() {
-        "[user]"
+    let mut spans = vec![Span::raw(format!("{} ", mode_indicator(app)))];
+    // Stays up while the storm lasts, unlike the message announcing it
+    #[cfg(feature = "units")]
+    let storms = app.units().storm_count();
+    #[cfg(not(feature = "units"))]
+    let storms = 0;
+    if storms > 0 {
+        spans.push(Span::styled(
+            format!(
+                "⚠ {} restart storm{} ",
+                storms,
+                if storms == 1 { "" } else { "s" }
+            ),
+            Style::default()
+                .fg(crate::palette::light_red())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Stays up for an hour, long after the message announcing it
+    #[cfg(feature = "units")]
+    if let Some(oom) = app.units().recent_oom() {
+        spans.push(Span::styled(
+            format!("💥 {} ", oom),
+            Style::default()
+                .fg(crate::palette::light_red())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some(charge) = app.low_battery() {
+        spans.push(Span::styled(
+            format!("🔋 {}% ", charge),
+            Style::default()
+                .fg(crate::palette::red())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some((level, text)) = app.messages().current() {
+        let style = match level {
+            Level::Error => Style::default()
+                .fg(crate::palette::red())
+                .add_modifier(Modifier::BOLD),
+            Level::Warn => Style::default().fg(crate::palette::yellow()),
+            Level::Info => Style::default().fg(crate::palette::green()),
+        };
+        spans.push(Span::styled(text, style));
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+        return;
+    }
+
+    let hints = if area.width < NARROW_WIDTH {
+        ":cmds ?:help "
     } else {
-        "[system]"
+        "j:down k:up sp:pg t:view s:sort e:xpnd c:clps /:fltr r:ref ?:help "
     };
-
-    let status = Line::from(vec![
-        Span::raw(format!("{} ", mode_str)),
-        Span::raw("j:down k:up sp:pg t:view s:sort e:xpnd c:clps /:fltr r:ref ?:help "),
+    spans.extend([
+        Span::raw(hints),
         Span::styled(
             "q:quit",
             Style::default()
@@ -230,7 +764,7 @@ fn draw_status(f: &mut Frame, app: &App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         ),
     ]);
-    let status_bar = Paragraph::new(status);
+    let status_bar = Paragraph::new(Line::from(spans));
     f.render_widget(status_bar, area);
 }
 
@@ -241,18 +775,65 @@ fn draw_help(f: &mut Frame, app: &App) {
     j, ↓          Down        k, ↑          Up
     g             Top         G             Bottom
     Space, PgDn   Page down   b, PgUp       Page up
-    /             Filter      Esc           Clear filter
-    Enter         Toggle group expand/collapse
+    /             Filter      Esc           Clear filters and preset
+    f             State filter: all/failed/active/running/inactive,
+                  applied before the text filter
+    P             Filter presets: apply one, or type a new name to save
+                  the current filter ([preset.NAME] in the config file)
+    Enter         Toggle group expand/collapse, or open the unit: its
+                  detail samples memory and CPU every second and
+                  sparklines the last 5 minutes while open
     e             Expand all  c             Collapse all
+    B             Batch action on the selected group (tree view)
+    T             Targets: isolate one or make it the default
+    U             User managers: user@ instances and their failed units (root)
+    w             Pin/unpin the unit on the watchlist strip (★)
+                  ⚠ marks a service in a restart storm
+                  [OOM ×N]: killed N times since boot by the kernel OOM
+                  killer or systemd-oomd; the status bar names the last
+                  kill for an hour
+    I             Hide/show systemd-internal units (systemd-*, dbus,
+                  session and app scopes; failed, watched and searched
+                  ones always show): [units] internal, hide_internal
     t             Toggle tree/list view
-    s             Toggle sort (name/state)
-    S             Toggle sort direction"#
+    y             Unit type chips (list view): h/l move, Space shows/hides
+                  a type, o only this type, a all ([units] hide_types)
+    N             Nest path/automount units under what they trigger
+    C             Choose list columns (Logs/Errs: journal entries in the
+                  last 5 minutes / hour, to spot noisy or failing units)
+    s             Cycle sort (name/state/recently changed/memory/CPU
+                  time/tasks/restarts; the last four heaviest first)
+    S             Toggle sort direction
+
+    ● running  ✓ exited  ○ inactive  ✗ failed
+    ◐ starting  ↻ auto-restart  ◑ stopping"#
         }
 
         1 => {
             r#"Network View:
     j, ↓          Down        k, ↑          Up
-    r             Refresh"#
+    /             Filter interfaces by name   Esc  Clear filter
+    v             Hide/show virtual interfaces ([network] virtual_interfaces,
+                  default veth*, docker*, br-*, virbr*, ...)
+    s             Focus the sockets on the selected interface
+    R             Show or fold the routing table (folded on short terminals)
+    Enter         (sockets) Jump to the owning unit
+    n             Look into another network namespace: /run/netns or one
+                  a running unit has (PrivateNetwork=, containers; root)
+    F             Show the nftables ruleset instead of the interfaces, with
+                  hit counters; rules on ports of the listed sockets are
+                  yellow, the selected socket's underlined (root)
+    B             Show TCP connections by throughput instead of the
+                  interfaces, sampled every second, with their units
+    c             Connectivity checks: ping the default gateway, look up
+                  [network] check_hostname through resolved and fetch
+                  [network] probe_url (plain http://)
+    t             Traceroute to a host, repeated every second with loss
+                  and latency per hop like mtr
+    r             Refresh
+
+    networkd's DHCP leases turn yellow once renewal is overdue and red
+    when rebinding or expired"#
         }
 
         2 => {
@@ -263,24 +844,120 @@ fn draw_help(f: &mut Frame, app: &App) {
 
         3 => {
             r#"Host View:
-    r             Refresh host information"#
+    r             Refresh host information
+    s             Sync the clock now by restarting systemd-timesyncd"#
         }
 
         4 => {
             r#"Boot View:
     j, ↓          Down        k, ↑          Up
-    r             Refresh"#
+    r             Refresh
+    l, Tab        Focus the journal of the entry's last boot (Esc back)
+    U             Update systemd-boot on the ESP when the installed one
+                  is older than systemd's (bootctl update, root)
+
+    The journal pane shows warnings and errors from the newest boot
+    whose kernel matches the selected entry's version"#
         }
 
         5 => {
             r#"Logs View:
     j, ↓          Down        k, ↑          Up
-    g             Top         G             Bottom (follow)
+    g             Top         G             Bottom: follow again
     Space, PgDn   Page down   b, PgUp       Page up
-    p             Pause/unpause streaming
+                  Moving up past the first entry loads older ones
+                  ([logs] page_size at a time, default 100)
+                  Moving up stops following: new entries are held back
+                  under an "N new entries" chip until G shows them
+    p             Pause/unpause (new entries are held, not dropped)
     f             Toggle follow mode
     c             Clear logs
-    r             Refresh/reload"#
+    r             Refresh/reload
+    Enter         Show every field of the entry (y copies the message)
+    t             Cycle timestamps: short, ISO-8601, relative, monotonic
+    J             Cycle journals: all readable, system + user, system, user
+    n / N         Jump to the next / previous error (priority err or worse)
+    /             Filter: FIELD=value matches, OR between groups, then
+                  ~ regex on the message, e.g.
+                  SYSLOG_IDENTIFIER=sshd OR _COMM=sudo ~ fail(ed|ure)
+    Esc           Clear the filter
+    P             Filter presets: unit globs, priority and text saved as
+                  [preset.NAME] in the config file; type a new name to
+                  save the current unit and filter
+    d             Collapse repeated messages into one ×N row, or show all
+    e             Expand the selected ×N row
+    H             Show/hide the volume strip: entries per minute of the
+                  loaded window, colored by the worst priority in each
+    [ / ]         Jump to the previous / next bar of the strip
+    A             SELinux AVC and AppArmor denials of the last day per
+                  unit, with the audit2allow-style rules (y copies)
+
+    [clock +37s]  Wall clock was stepped (e.g. by NTP) just before
+                  this entry, so neighbors may look out of order"#
+        }
+
+        6 => {
+            r#"Machines View:
+    j, ↓          Down        k, ↑          Up
+    x             Terminate machine
+    p             Power off machine
+    Enter         Jump to the machine's unit
+    i             Select images (Esc or i to go back)
+      s           Start a container from the image (machinectl start)
+      x           Remove the image, after confirming
+    r             Refresh"#
+        }
+
+        7 => {
+            r#"Coredumps View:
+    j, ↓          Down        k, ↑          Up
+    g             Top         G             Bottom
+    Enter         Show the backtrace
+    u             Jump to the crashed process's unit
+    x             Delete the dump file
+    r             Refresh"#
+        }
+
+        8 => {
+            r#"Storage View:
+    j, ↓          Down        k, ↑          Up
+    g             Top         G             Bottom
+    Enter         Jump to the mount unit of the device
+    s             Select swap areas (Esc or s to go back)
+      a           Activate the swap unit (swapon)
+      x           Deactivate it (swapoff), after confirming
+      Enter       Jump to the swap unit
+    r             Refresh
+
+    Usage bars turn yellow at 75% and red at 90%"#
+        }
+
+        9 => {
+            r#"Portable View:
+    j, ↓          Down        k, ↑          Up
+    g             Top         G             Bottom
+    a             Attach the image (portablectl attach)
+    x             Detach it (portablectl detach)
+    u             Select the image's units (Esc or u to go back)
+      Enter       Open the unit in Units
+    r             Refresh"#
+        }
+
+        10 => {
+            r#"Kernel View:
+    j, ↓          Down        k, ↑          Up
+    g             Top         G             Bottom
+    /             Filter parameters by name (fuzzy)
+    d             Only parameters that differ from /etc/sysctl.d and co.
+    e, Enter      Change the value until reboot (root)
+    c             Put back the value the sysctl.d files set (root)
+    m             Switch between sysctl parameters and kernel modules
+      d           Only modules asked for (modules-load.d, modprobe@) but
+                  not loaded
+      Enter       Open the modprobe@ or systemd-modules-load unit
+    r             Refresh
+
+    Configured values in yellow differ from the running value"#
         }
 
         _ => "Unknown context",
@@ -291,13 +968,35 @@ fn draw_help(f: &mut Frame, app: &App) {
 Global:
     q, Q          Quit
     ?             Toggle this help
+    !, Ctrl+Z     Suspend to shell (exit shell to resume)
+    :             Command palette (every action, searchable)
+    Ctrl+P        Go to unit (fuzzy search from any tab)
+    Ctrl+E        Export a markdown report of every tab
     Tab           Next context
     Shift+Tab     Previous context
-    1-6           Jump to context
+    1-9, 0        Jump to context (numbered as in the header; tabs
+                  after the tenth through Tab or the palette)
+    |             Split: show Logs (or Units) beside this tab, or unsplit
+    Ctrl+W        Move the focus to the other pane of the split
+                  (Logs beside Units follow the selected unit)
+
+    cpu mem io    In the header: % of the last 10s tasks stalled on
+                  the resource (some/full when all tasks stalled); red
+                  past [pressure] some_alarm / full_alarm in the config
+    🔋 N%         In the status bar: on battery at or under [power]
+                  low_battery percent (default 10, 0 turns it off)
 
 Press any key to close this help"#;
 
-    let full_help = format!("{}{}", help_text, global_help);
+    let recipes: String = commands::recipes(app.current_context())
+        .iter()
+        .map(|(task, keys)| format!("\n    {:34}{}", task, keys))
+        .collect();
+    let full_help = if recipes.is_empty() {
+        format!("{}{}", help_text, global_help)
+    } else {
+        format!("{}\n\nRecipes:{}{}", help_text, recipes, global_help)
+    };
 
     let block = Block::default()
         .title(format!(" Help - {} ", app.context_name()))
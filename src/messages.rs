@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// How long a message stays in the status line
+    fn timeout(&self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(3),
+            Level::Warn => Duration::from_secs(5),
+            Level::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+struct Message {
+    level: Level,
+    text: String,
+    expires: Instant,
+}
+
+/// Transient messages shown in the status line. Cheap to clone; every
+/// context holds a handle and the app renders whatever is newest.
+#[derive(Clone, Default)]
+pub struct Messages {
+    queue: Arc<Mutex<VecDeque<Message>>>,
+}
+
+impl Messages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn info(&self, text: impl Into<String>) {
+        self.push(Level::Info, text.into());
+    }
+
+    pub fn warn(&self, text: impl Into<String>) {
+        self.push(Level::Warn, text.into());
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.push(Level::Error, text.into());
+    }
+
+    fn push(&self, level: Level, text: String) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.push_back(Message {
+            level,
+            expires: Instant::now() + level.timeout(),
+            text,
+        });
+        // Nobody reads further back than the newest few
+        while queue.len() > 16 {
+            queue.pop_front();
+        }
+    }
+
+    /// Newest message that hasn't expired yet
+    pub fn current(&self) -> Option<(Level, String)> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        queue.retain(|m| m.expires > now);
+        queue.back().map(|m| (m.level, m.text.clone()))
+    }
+}
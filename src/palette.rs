@@ -0,0 +1,324 @@
+use crate::config::Config;
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The color slots the UI draws with. Slots are named after the colors the
+/// default dark theme uses, so a light theme maps e.g. `white` (regular text)
+/// to something dark.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub black: Color,
+    pub blue: Color,
+    pub cyan: Color,
+    pub dark_gray: Color,
+    pub gray: Color,
+    pub green: Color,
+    pub light_red: Color,
+    pub red: Color,
+    pub white: Color,
+    pub yellow: Color,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        black: Color::Black,
+        blue: Color::Blue,
+        cyan: Color::Cyan,
+        dark_gray: Color::DarkGray,
+        gray: Color::Gray,
+        green: Color::Green,
+        light_red: Color::LightRed,
+        red: Color::Red,
+        white: Color::White,
+        yellow: Color::Yellow,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        black: Color::Rgb(0xff, 0xff, 0xff),
+        blue: Color::Rgb(0x1f, 0x4f, 0xb0),
+        cyan: Color::Rgb(0x00, 0x6b, 0x7a),
+        dark_gray: Color::Rgb(0xd0, 0xd0, 0xd0),
+        gray: Color::Rgb(0x60, 0x60, 0x60),
+        green: Color::Rgb(0x1a, 0x7f, 0x1a),
+        light_red: Color::Rgb(0xc0, 0x39, 0x2b),
+        red: Color::Rgb(0xa0, 0x00, 0x00),
+        white: Color::Rgb(0x10, 0x10, 0x10),
+        yellow: Color::Rgb(0x9a, 0x6a, 0x00),
+    };
+
+    pub const SOLARIZED: Theme = Theme {
+        black: Color::Rgb(0x00, 0x2b, 0x36),
+        blue: Color::Rgb(0x26, 0x8b, 0xd2),
+        cyan: Color::Rgb(0x2a, 0xa1, 0x98),
+        dark_gray: Color::Rgb(0x07, 0x36, 0x42),
+        gray: Color::Rgb(0x83, 0x94, 0x96),
+        green: Color::Rgb(0x85, 0x99, 0x00),
+        light_red: Color::Rgb(0xcb, 0x4b, 0x16),
+        red: Color::Rgb(0xdc, 0x32, 0x2f),
+        white: Color::Rgb(0xee, 0xe8, 0xd5),
+        yellow: Color::Rgb(0xb5, 0x89, 0x00),
+    };
+
+    pub const HIGH_CONTRAST: Theme = Theme {
+        black: Color::Black,
+        blue: Color::LightBlue,
+        cyan: Color::LightCyan,
+        dark_gray: Color::Blue,
+        gray: Color::White,
+        green: Color::LightGreen,
+        light_red: Color::LightRed,
+        red: Color::LightRed,
+        white: Color::White,
+        yellow: Color::LightYellow,
+    };
+
+    const MONOCHROME: Theme = Theme {
+        black: Color::Reset,
+        blue: Color::Reset,
+        cyan: Color::Reset,
+        dark_gray: Color::Reset,
+        gray: Color::Reset,
+        green: Color::Reset,
+        light_red: Color::Reset,
+        red: Color::Reset,
+        white: Color::Reset,
+        yellow: Color::Reset,
+    };
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            "solarized" => Some(Self::SOLARIZED),
+            "high-contrast" => Some(Self::HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    /// Build the theme from config: `theme = "<name>"` picks a base, and a
+    /// `[colors]` section overrides individual slots by name or `#rrggbb`.
+    pub fn from_config(config: &Config) -> Theme {
+        let mut theme = match config.get("", "theme") {
+            Some(name) => Self::by_name(name).unwrap_or_else(|| {
+                tracing::warn!("Unknown theme {:?}, using dark", name);
+                Self::DARK
+            }),
+            None => Self::DARK,
+        };
+
+        if let Some(colors) = config.section("colors") {
+            for (slot, value) in colors {
+                let Ok(color) = Color::from_str(value) else {
+                    tracing::warn!("Invalid color {:?} for {}", value, slot);
+                    continue;
+                };
+                match slot.as_str() {
+                    "black" => theme.black = color,
+                    "blue" => theme.blue = color,
+                    "cyan" => theme.cyan = color,
+                    "dark_gray" => theme.dark_gray = color,
+                    "gray" => theme.gray = color,
+                    "green" => theme.green = color,
+                    "light_red" => theme.light_red = color,
+                    "red" => theme.red = color,
+                    "white" => theme.white = color,
+                    "yellow" => theme.yellow = color,
+                    _ => tracing::warn!("Unknown color slot {}", slot),
+                }
+            }
+        }
+
+        theme
+    }
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::DARK);
+static MONOCHROME: AtomicBool = AtomicBool::new(false);
+
+/// Install the theme used by every palette lookup
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Drop all colors; state is then carried by symbols and text attributes
+pub fn set_monochrome(on: bool) {
+    MONOCHROME.store(on, Ordering::Relaxed);
+}
+
+pub fn is_monochrome() -> bool {
+    MONOCHROME.load(Ordering::Relaxed)
+}
+
+/// https://no-color.org: any non-empty NO_COLOR disables color
+pub fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+fn theme() -> Theme {
+    if is_monochrome() {
+        return Theme::MONOCHROME;
+    }
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+// Semantic styles. Anything that conveys meaning through color goes through
+// these so monochrome mode can substitute attributes.
+
+/// Selected row in a list or table
+pub fn selected() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default()
+            .bg(dark_gray())
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Selected name cell in the Network interface list
+#[cfg(feature = "network")]
+pub fn selected_name() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(black())
+            .bg(cyan())
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// A unit's ActiveState, refined by SubState where it matters: oneshots that
+/// ran and exited look different from running daemons, and restart loops
+/// stand out from a normal start.
+pub fn unit_state(active_state: &str, sub_state: &str) -> Style {
+    let transient = is_transient(active_state, sub_state);
+    if is_monochrome() {
+        return match (active_state, sub_state) {
+            ("failed", _) => Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            (_, "auto-restart") => Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            _ if transient => Style::default().add_modifier(Modifier::ITALIC),
+            ("inactive", _) => Style::default().add_modifier(Modifier::DIM),
+            _ => Style::default(),
+        };
+    }
+    let color = match (active_state, sub_state) {
+        ("failed", _) => red(),
+        (_, "auto-restart") => light_red(),
+        _ if transient => yellow(),
+        ("active", "exited") => blue(),
+        ("active", _) => green(),
+        ("inactive", _) => gray(),
+        _ => white(),
+    };
+    Style::default().fg(color)
+}
+
+/// A unit on its way between states: starting, stopping or reloading,
+/// including the ExecStartPre=/ExecStopPost= style sub-states and the
+/// kill phases
+fn is_transient(active_state: &str, sub_state: &str) -> bool {
+    matches!(
+        active_state,
+        "activating" | "deactivating" | "reloading" | "refreshing"
+    ) || matches!(
+        sub_state,
+        "condition"
+            | "start-pre"
+            | "start"
+            | "start-post"
+            | "start-chown"
+            | "reload"
+            | "reload-signal"
+            | "reload-notify"
+            | "stop"
+            | "stop-pre"
+            | "stop-pre-sigterm"
+            | "stop-pre-sigkill"
+            | "stop-post"
+            | "stop-watchdog"
+            | "stop-sigterm"
+            | "stop-sigkill"
+            | "final-watchdog"
+            | "final-sigterm"
+            | "final-sigkill"
+            | "mounting"
+            | "mounting-done"
+            | "remounting"
+            | "remounting-sigterm"
+            | "remounting-sigkill"
+            | "unmounting"
+            | "unmounting-sigterm"
+            | "unmounting-sigkill"
+            | "activating"
+            | "activating-done"
+            | "deactivating"
+            | "deactivating-sigterm"
+            | "deactivating-sigkill"
+            | "cleaning"
+    )
+}
+
+/// A journal message by syslog priority
+#[cfg(any(feature = "boot", feature = "logs"))]
+pub fn log_priority(priority: u8) -> Style {
+    if is_monochrome() {
+        return match priority {
+            0..=3 => Style::default().add_modifier(Modifier::BOLD),
+            4 => Style::default().add_modifier(Modifier::UNDERLINED),
+            7 => Style::default().add_modifier(Modifier::DIM),
+            _ => Style::default(),
+        };
+    }
+    let color = match priority {
+        0..=2 => red(),
+        3 => light_red(),
+        4 => yellow(),
+        5 => green(),
+        6 => blue(),
+        _ => gray(),
+    };
+    Style::default().fg(color)
+}
+
+pub fn black() -> Color {
+    theme().black
+}
+
+pub fn blue() -> Color {
+    theme().blue
+}
+
+pub fn cyan() -> Color {
+    theme().cyan
+}
+
+pub fn dark_gray() -> Color {
+    theme().dark_gray
+}
+
+pub fn gray() -> Color {
+    theme().gray
+}
+
+pub fn green() -> Color {
+    theme().green
+}
+
+pub fn light_red() -> Color {
+    theme().light_red
+}
+
+pub fn red() -> Color {
+    theme().red
+}
+
+pub fn white() -> Color {
+    theme().white
+}
+
+pub fn yellow() -> Color {
+    theme().yellow
+}
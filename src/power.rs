@@ -0,0 +1,203 @@
+use crate::config::Config;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// AC adapters and batteries from /sys/class/power_supply
+#[derive(Default)]
+pub struct PowerSupplies {
+    /// None on machines without a Mains supply, e.g. desktops that don't
+    /// report one
+    pub ac_online: Option<bool>,
+    pub batteries: Vec<Battery>,
+}
+
+#[cfg_attr(not(feature = "host"), allow(dead_code))]
+pub struct Battery {
+    /// "BAT0"
+    pub name: String,
+    /// "Charging", "Discharging", "Full", "Not charging", ...
+    pub status: String,
+    pub capacity: Option<u8>,
+    /// Full capacity against the design capacity, in percent
+    pub health: Option<f64>,
+    /// Until empty while discharging, until full while charging
+    pub time_left: Option<Duration>,
+}
+
+impl Battery {
+    fn read(path: &Path) -> Self {
+        let number = |name: &str| read_number(path, name);
+        let status = read_attr(path, "status").unwrap_or_else(|| "Unknown".to_string());
+        // Drivers report energy (µWh, with power in µW) or charge (µAh,
+        // with current in µA); the ratios come out the same
+        let (now, full, design, rate) = match number("energy_now") {
+            Some(now) => (
+                Some(now),
+                number("energy_full"),
+                number("energy_full_design"),
+                number("power_now"),
+            ),
+            None => (
+                number("charge_now"),
+                number("charge_full"),
+                number("charge_full_design"),
+                number("current_now"),
+            ),
+        };
+        let capacity = number("capacity")
+            .map(|c| c.min(100) as u8)
+            .or_else(|| match (now, full) {
+                (Some(now), Some(full)) if full > 0 => Some((now * 100 / full).min(100) as u8),
+                _ => None,
+            });
+        let health = match (full, design) {
+            (Some(full), Some(design)) if design > 0 => Some(full as f64 * 100.0 / design as f64),
+            _ => None,
+        };
+        let remaining = match status.as_str() {
+            "Discharging" => now,
+            "Charging" => full.zip(now).map(|(full, now)| full.saturating_sub(now)),
+            _ => None,
+        };
+        let time_left = match (remaining, rate) {
+            (Some(remaining), Some(rate)) if rate > 0 => {
+                Some(Duration::from_secs(remaining * 3600 / rate))
+            }
+            _ => None,
+        };
+        Self {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            status,
+            capacity,
+            health,
+            time_left,
+        }
+    }
+
+    pub fn is_discharging(&self) -> bool {
+        self.status == "Discharging"
+    }
+
+    /// "87%, discharging, 2h 10m left, health 91%"
+    #[cfg(feature = "host")]
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            self.capacity
+                .map_or_else(|| "?%".to_string(), |c| format!("{}%", c)),
+            self.status.to_lowercase(),
+        ];
+        if let Some(left) = self.time_left {
+            let minutes = left.as_secs() / 60;
+            let until = if self.is_discharging() {
+                "left"
+            } else {
+                "to full"
+            };
+            parts.push(format!("{}h {:02}m {}", minutes / 60, minutes % 60, until));
+        }
+        if let Some(health) = self.health {
+            parts.push(format!("health {:.0}%", health));
+        }
+        parts.join(", ")
+    }
+}
+
+impl PowerSupplies {
+    pub fn read() -> Self {
+        let mut supplies = Self {
+            ac_online: None,
+            batteries: Vec::new(),
+        };
+        let Ok(dir) = fs::read_dir("/sys/class/power_supply") else {
+            return supplies;
+        };
+        let mut paths: Vec<_> = dir.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        for path in paths {
+            match read_attr(&path, "type").as_deref() {
+                Some("Mains") => {
+                    let online = read_number(&path, "online") == Some(1);
+                    supplies.ac_online = Some(supplies.ac_online.unwrap_or(false) || online);
+                }
+                // Peripherals such as mice report a scope of "Device"
+                Some("Battery") if read_attr(&path, "scope").as_deref() != Some("Device") => {
+                    supplies.batteries.push(Battery::read(&path));
+                }
+                _ => {}
+            }
+        }
+        supplies
+    }
+}
+
+fn read_attr(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Some drivers report negative currents while discharging
+fn read_number(dir: &Path, name: &str) -> Option<u64> {
+    read_attr(dir, name)?
+        .parse::<i64>()
+        .ok()
+        .map(i64::unsigned_abs)
+}
+
+/// How often the status bar re-reads the batteries
+const ALARM_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default `[power] low_battery`, in percent; 0 turns the warning off
+const DEFAULT_LOW_BATTERY: u8 = 10;
+
+/// Low-battery warning for the status bar: the lowest charge of the
+/// batteries while running on them, when it's at or under the threshold
+pub struct BatteryAlarm {
+    threshold: u8,
+    last_read: Option<Instant>,
+    low: Option<u8>,
+}
+
+impl BatteryAlarm {
+    pub fn from_config(config: &Config) -> Self {
+        let threshold = match config.get("power", "low_battery") {
+            Some(value) => value.parse().unwrap_or_else(|_| {
+                tracing::warn!("Invalid power low_battery {:?}", value);
+                DEFAULT_LOW_BATTERY
+            }),
+            None => DEFAULT_LOW_BATTERY,
+        };
+        Self {
+            threshold,
+            last_read: None,
+            low: None,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.threshold == 0 || self.last_read.is_some_and(|t| t.elapsed() < ALARM_INTERVAL) {
+            return;
+        }
+        self.last_read = Some(Instant::now());
+        let supplies = PowerSupplies::read();
+        if supplies.ac_online == Some(true) {
+            self.low = None;
+            return;
+        }
+        self.low = supplies
+            .batteries
+            .iter()
+            .filter(|b| b.is_discharging())
+            .filter_map(|b| b.capacity)
+            .min()
+            .filter(|&capacity| capacity <= self.threshold);
+    }
+
+    pub fn low(&self) -> Option<u8> {
+        self.low
+    }
+}
@@ -0,0 +1,282 @@
+use crate::config::Config;
+#[cfg(feature = "units")]
+use crate::contexts::glob_match;
+use crate::widgets::picker::{Picker, PickerEvent, draw_picker, fuzzy_match_score};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A named filter from a `[preset.<name>]` section of the config file:
+///
+/// ```toml
+/// [preset.web stack]
+/// units = "nginx*, php-fpm*, redis*"
+/// priority = "warning"
+/// text = "timeout"
+/// ```
+///
+/// Units uses the unit globs and the text; Logs all of it, the text as a
+/// case-insensitive search of the message and `journal` as a `/` filter
+/// expression.
+#[derive(Debug, Clone, Default)]
+pub struct Preset {
+    pub name: String,
+    /// Unit name globs; empty for every unit
+    pub units: Vec<String>,
+    /// Most verbose syslog priority shown, 0 (emerg) to 7 (debug)
+    pub priority: Option<u8>,
+    pub text: Option<String>,
+    /// Field matches and `~ regex` as typed after `/` in Logs
+    pub journal: Option<String>,
+}
+
+const SECTION_PREFIX: &str = "preset.";
+
+const PRIORITY_NAMES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// "err" or "3"
+fn parse_priority(value: &str) -> Option<u8> {
+    let value = value.trim();
+    match value.parse::<u8>() {
+        Ok(n) if n <= 7 => Some(n),
+        Ok(_) => None,
+        Err(_) => PRIORITY_NAMES
+            .iter()
+            .position(|name| *name == value)
+            .map(|n| n as u8),
+    }
+}
+
+impl Preset {
+    #[cfg(feature = "units")]
+    pub fn matches_unit(&self, unit: &str) -> bool {
+        self.units.is_empty() || self.units.iter().any(|p| glob_match(p, unit))
+    }
+
+    /// "nginx*, php-fpm* · ≤ warning · "timeout""
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.units.is_empty() {
+            parts.push(self.units.join(", "));
+        }
+        if let Some(priority) = self.priority {
+            parts.push(format!("≤ {}", PRIORITY_NAMES[usize::from(priority)]));
+        }
+        if let Some(text) = &self.text {
+            parts.push(format!("{:?}", text));
+        }
+        if let Some(journal) = &self.journal {
+            parts.push(journal.clone());
+        }
+        parts.join(" · ")
+    }
+
+    fn from_section(name: &str, keys: &std::collections::HashMap<String, String>) -> Self {
+        let text = |key: &str| {
+            keys.get(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let priority = keys.get("priority").and_then(|value| {
+            let priority = parse_priority(value);
+            if priority.is_none() {
+                tracing::warn!("Invalid priority {:?} in preset {:?}", value, name);
+            }
+            priority
+        });
+        Self {
+            name: name.to_string(),
+            units: keys
+                .get("units")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            priority,
+            text: text("text"),
+            journal: text("journal"),
+        }
+    }
+
+    /// The preset as a config section. Values are written unescaped since
+    /// `Config` takes everything between the outer quotes literally.
+    fn to_section(&self) -> String {
+        let mut out = format!("\n[{}{}]\n", SECTION_PREFIX, self.name);
+        if !self.units.is_empty() {
+            out.push_str(&format!("units = \"{}\"\n", self.units.join(", ")));
+        }
+        if let Some(priority) = self.priority {
+            out.push_str(&format!(
+                "priority = \"{}\"\n",
+                PRIORITY_NAMES[usize::from(priority)]
+            ));
+        }
+        if let Some(text) = &self.text {
+            out.push_str(&format!("text = \"{}\"\n", text));
+        }
+        if let Some(journal) = &self.journal {
+            out.push_str(&format!("journal = \"{}\"\n", journal));
+        }
+        out
+    }
+}
+
+/// The presets of the config file plus those saved since, shared by Units
+/// and Logs. Cheap to clone like `Messages`.
+#[derive(Clone, Default)]
+pub struct Presets {
+    inner: Arc<Mutex<Vec<Preset>>>,
+}
+
+impl Presets {
+    pub fn from_config(config: &Config) -> Self {
+        let mut presets: Vec<Preset> = config
+            .sections()
+            .filter_map(|(section, keys)| {
+                let name = section.strip_prefix(SECTION_PREFIX)?;
+                Some(Preset::from_section(name.trim(), keys))
+            })
+            .collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            inner: Arc::new(Mutex::new(presets)),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Preset> {
+        self.inner.lock().unwrap().clone()
+    }
+
+    /// Append `preset` to the config file and offer it from now on. The
+    /// file keeps whatever else it had; a preset of the same name is
+    /// replaced here, and in the file by the later section.
+    pub fn save(&self, preset: Preset) -> std::io::Result<()> {
+        let path = Config::path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "No config directory")
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        file.write_all(preset.to_section().as_bytes())?;
+
+        let mut presets = self.inner.lock().unwrap();
+        presets.retain(|p| p.name != preset.name);
+        presets.push(preset);
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+}
+
+/// What a key did in the preset menu
+pub enum PresetAction {
+    None,
+    Close,
+    Apply(Preset),
+    /// Save the current filter under this name
+    Save(String),
+}
+
+/// `P` popup of Units and Logs: type to narrow the presets, Enter applies
+/// one, or saves the current filter when the typed name is new
+pub struct PresetMenu {
+    picker: Picker,
+    presets: Vec<Preset>,
+}
+
+impl PresetMenu {
+    pub fn new(presets: &Presets) -> Self {
+        Self {
+            picker: Picker::new(),
+            presets: presets.list(),
+        }
+    }
+
+    /// Indices of the presets matching the query, best first
+    fn matches(&self) -> Vec<usize> {
+        let needle = self.picker.query.trim().to_lowercase();
+        let mut ranked: Vec<(usize, usize)> = self
+            .presets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| {
+                fuzzy_match_score(&p.name.to_lowercase(), &needle).map(|score| (score, i))
+            })
+            .collect();
+        ranked.sort();
+        ranked.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// The typed name, when saving under it would add a preset
+    fn new_name(&self) -> Option<&str> {
+        let name = self.picker.query.trim();
+        (!name.is_empty() && !self.presets.iter().any(|p| p.name == name)).then_some(name)
+    }
+
+    fn len(&self) -> usize {
+        self.matches().len() + usize::from(self.new_name().is_some())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PresetAction {
+        let len = self.len();
+        match self.picker.handle_key(key, len) {
+            PickerEvent::Close => PresetAction::Close,
+            PickerEvent::Accept => {
+                let matches = self.matches();
+                match matches.get(self.picker.list.selected) {
+                    Some(&i) => PresetAction::Apply(self.presets[i].clone()),
+                    None => match self.new_name() {
+                        Some(name) => PresetAction::Save(name.to_string()),
+                        None => PresetAction::None,
+                    },
+                }
+            }
+            PickerEvent::Changed | PickerEvent::None => PresetAction::None,
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let matches = self.matches();
+        let new_name = self.new_name();
+        let area = crate::contexts::centered_rect(60, 50, area);
+        draw_picker(
+            f,
+            area,
+            "Filter presets (type a new name to save the current filter)",
+            &self.picker,
+            self.len(),
+            |i| match matches.get(i) {
+                Some(&p) => {
+                    let preset = &self.presets[p];
+                    Line::from(vec![
+                        Span::raw(preset.name.clone()),
+                        Span::raw("  "),
+                        Span::styled(
+                            preset.describe(),
+                            Style::default().fg(crate::palette::gray()),
+                        ),
+                    ])
+                }
+                None => Line::styled(
+                    format!("+ Save the current filter as {:?}", new_name.unwrap_or("")),
+                    Style::default().fg(crate::palette::green()),
+                ),
+            },
+        );
+    }
+}
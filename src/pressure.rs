@@ -0,0 +1,96 @@
+use crate::config::Config;
+use std::time::{Duration, Instant};
+
+/// How often the header re-reads /proc/pressure
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default `[pressure] some_alarm`: share of time in percent (avg10) at
+/// least one task stalled on the resource
+const DEFAULT_SOME_ALARM: f64 = 20.0;
+/// Default `[pressure] full_alarm`: share of time every non-idle task
+/// stalled at once
+const DEFAULT_FULL_ALARM: f64 = 5.0;
+
+/// avg10 of one /proc/pressure file
+#[derive(Debug, Clone, Copy)]
+pub struct Stall {
+    pub some: f64,
+    /// Absent for cpu on kernels before 5.13
+    pub full: Option<f64>,
+}
+
+impl Stall {
+    fn read(resource: &str) -> Option<Self> {
+        let text = std::fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+        let mut stall = Stall {
+            some: 0.0,
+            full: None,
+        };
+        // "some avg10=1.30 avg60=1.71 avg300=1.91 total=168424394"
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            let kind = fields.next();
+            let avg10 = fields
+                .find_map(|f| f.strip_prefix("avg10="))
+                .and_then(|v| v.parse().ok());
+            match (kind, avg10) {
+                (Some("some"), Some(avg10)) => stall.some = avg10,
+                (Some("full"), Some(avg10)) => stall.full = Some(avg10),
+                _ => {}
+            }
+        }
+        Some(stall)
+    }
+}
+
+/// Pressure stall information for the header: how much of the last ten
+/// seconds tasks spent waiting for CPU, memory and IO. Only sampled for
+/// the local machine; a kernel without CONFIG_PSI has no /proc/pressure.
+pub struct Pressure {
+    /// cpu, memory, io; None when PSI is unavailable
+    pub stalls: Option<[Stall; 3]>,
+    some_alarm: f64,
+    full_alarm: f64,
+    last_read: Option<Instant>,
+}
+
+const RESOURCES: [&str; 3] = ["cpu", "memory", "io"];
+
+impl Pressure {
+    /// `[pressure] some_alarm` and `full_alarm`, percentages of avg10
+    pub fn from_config(config: &Config) -> Self {
+        let threshold = |key: &str, default: f64| match config.get("pressure", key) {
+            Some(value) => value.parse().unwrap_or_else(|_| {
+                tracing::warn!("Invalid pressure {} {:?}", key, value);
+                default
+            }),
+            None => default,
+        };
+        Self {
+            stalls: None,
+            some_alarm: threshold("some_alarm", DEFAULT_SOME_ALARM),
+            full_alarm: threshold("full_alarm", DEFAULT_FULL_ALARM),
+            last_read: None,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self
+            .last_read
+            .is_some_and(|t| t.elapsed() < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        self.last_read = Some(Instant::now());
+        let [cpu, memory, io] = RESOURCES.map(Stall::read);
+        self.stalls = match (cpu, memory, io) {
+            (Some(cpu), Some(memory), Some(io)) => Some([cpu, memory, io]),
+            _ => None,
+        };
+    }
+
+    /// Whether a resource is past either threshold
+    pub fn is_alarming(&self, stall: &Stall) -> bool {
+        stall.some >= self.some_alarm || stall.full.is_some_and(|full| full >= self.full_alarm)
+    }
+}
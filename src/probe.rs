@@ -0,0 +1,405 @@
+//! Active network probes for the Network tab: ICMP echo, a traceroute
+//! round and a plain HTTP HEAD request. ICMP goes through an unprivileged
+//! ping socket where net.ipv4.ping_group_range allows it, else through a
+//! raw one (root).
+
+use anyhow::{Context, Result, bail};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpStream, ToSocketAddrs};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::time::{Duration, Instant};
+
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMP_DEST_UNREACH: u8 = 3;
+const ICMP_ECHO: u8 = 8;
+const ICMP_TIME_EXCEEDED: u8 = 11;
+
+/// Bytes of payload after the echo header
+const PAYLOAD_LEN: usize = 16;
+
+/// TTLs a traceroute round tries
+pub const MAX_HOPS: u8 = 30;
+
+/// What came back for the echo request with sequence number `seq`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IcmpReply {
+    Echo { seq: u16 },
+    Unreachable { seq: u16 },
+    TimeExceeded { seq: u16 },
+}
+
+pub struct Icmp {
+    fd: OwnedFd,
+    /// Raw sockets see every ICMP packet of the host, IP header included
+    raw: bool,
+    /// Echo identifier; ping sockets pick their own
+    id: u16,
+}
+
+impl Icmp {
+    pub fn open() -> io::Result<Self> {
+        let (fd, raw) = match open_socket(libc::SOCK_DGRAM) {
+            Ok(fd) => (fd, false),
+            Err(e) if matches!(e.raw_os_error(), Some(libc::EACCES | libc::EPERM)) => {
+                (open_socket(libc::SOCK_RAW)?, true)
+            }
+            Err(e) => return Err(e),
+        };
+        if !raw {
+            // Ping sockets only see ICMP errors through the error queue
+            setsockopt_int(&fd, libc::SOL_IP, libc::IP_RECVERR, 1)?;
+        }
+        Ok(Self {
+            fd,
+            raw,
+            id: std::process::id() as u16,
+        })
+    }
+
+    pub fn set_ttl(&self, ttl: u8) -> io::Result<()> {
+        setsockopt_int(&self.fd, libc::IPPROTO_IP, libc::IP_TTL, ttl.into())
+    }
+
+    pub fn send_echo(&self, dst: Ipv4Addr, seq: u16) -> io::Result<()> {
+        let mut packet = [0u8; 8 + PAYLOAD_LEN];
+        packet[0] = ICMP_ECHO;
+        packet[4..6].copy_from_slice(&self.id.to_be_bytes());
+        packet[6..8].copy_from_slice(&seq.to_be_bytes());
+        packet[8..].copy_from_slice(b"rootwork-probe..");
+        let sum = checksum(&packet);
+        packet[2..4].copy_from_slice(&sum.to_be_bytes());
+
+        let addr = sockaddr(dst);
+        let send = || {
+            let rc = unsafe {
+                libc::sendto(
+                    self.fd.as_raw_fd(),
+                    packet.as_ptr() as *const libc::c_void,
+                    packet.len(),
+                    0,
+                    &addr as *const libc::sockaddr_in as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                )
+            };
+            if rc < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        };
+        // An ICMP error for an earlier request fails the next send once; a
+        // missing route fails it again
+        send().or_else(|e| match e.raw_os_error() {
+            Some(libc::EHOSTUNREACH | libc::ENETUNREACH | libc::ECONNREFUSED) => send(),
+            _ => Err(e),
+        })
+    }
+
+    /// The next reply to one of our echo requests, or None once `deadline`
+    /// passes
+    pub fn recv(&self, deadline: Instant) -> io::Result<Option<(Ipv4Addr, IcmpReply)>> {
+        let mut buf = [0u8; 1500];
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(None);
+            }
+            let mut pollfd = libc::pollfd {
+                fd: self.fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let rc = unsafe { libc::poll(&mut pollfd, 1, left.as_millis().max(1) as i32) };
+            if rc < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if rc == 0 {
+                return Ok(None);
+            }
+            if !self.raw && pollfd.revents & libc::POLLERR != 0 {
+                if let Some(reply) = self.recv_error()? {
+                    return Ok(Some(reply));
+                }
+                continue;
+            }
+
+            let mut from: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+            let mut len = std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+            let n = unsafe {
+                libc::recvfrom(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    0,
+                    &mut from as *mut libc::sockaddr_in as *mut libc::sockaddr,
+                    &mut len,
+                )
+            };
+            if n < 0 {
+                // A ping socket also reports the last ICMP error here; the
+                // error queue has the details
+                if !self.raw {
+                    continue;
+                }
+                return Err(io::Error::last_os_error());
+            }
+            let from = Ipv4Addr::from(u32::from_be(from.sin_addr.s_addr));
+            if let Some(reply) = self.parse(&buf[..n as usize]) {
+                return Ok(Some((from, reply)));
+            }
+        }
+    }
+
+    /// An ICMP error queued on a ping socket: the router that sent it and
+    /// our request it refers to
+    fn recv_error(&self) -> io::Result<Option<(Ipv4Addr, IcmpReply)>> {
+        let mut data = [0u8; 576];
+        // u64 keeps the control buffer aligned for cmsghdr
+        let mut control = [0u64; 64];
+        let mut iov = libc::iovec {
+            iov_base: data.as_mut_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+        let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = std::mem::size_of_val(&control) as _;
+        let n = unsafe { libc::recvmsg(self.fd.as_raw_fd(), &mut msg, libc::MSG_ERRQUEUE) };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::WouldBlock => Ok(None),
+                _ => Err(err),
+            };
+        }
+        // The queued packet is our request as sent
+        let Some(seq) = data.get(6..8).filter(|_| n >= 8) else {
+            return Ok(None);
+        };
+        let seq = u16::from_be_bytes([seq[0], seq[1]]);
+
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_IP && (*cmsg).cmsg_type == libc::IP_RECVERR {
+                    let err = libc::CMSG_DATA(cmsg) as *const libc::sock_extended_err;
+                    let ee = std::ptr::read_unaligned(err);
+                    if ee.ee_origin != libc::SO_EE_ORIGIN_ICMP {
+                        return Ok(None);
+                    }
+                    let offender = std::ptr::read_unaligned(
+                        libc::SO_EE_OFFENDER(err) as *const libc::sockaddr_in
+                    );
+                    let from = Ipv4Addr::from(u32::from_be(offender.sin_addr.s_addr));
+                    let reply = match ee.ee_type {
+                        ICMP_TIME_EXCEEDED => IcmpReply::TimeExceeded { seq },
+                        ICMP_DEST_UNREACH => IcmpReply::Unreachable { seq },
+                        _ => return Ok(None),
+                    };
+                    return Ok(Some((from, reply)));
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Our reply in a received packet; raw sockets get everyone's ICMP
+    fn parse(&self, packet: &[u8]) -> Option<IcmpReply> {
+        let icmp = if self.raw {
+            let header_len = (*packet.first()? as usize & 0x0f) * 4;
+            packet.get(header_len..)?
+        } else {
+            packet
+        };
+        let (kind, id, seq) = (
+            *icmp.first()?,
+            u16::from_be_bytes([*icmp.get(4)?, *icmp.get(5)?]),
+            u16::from_be_bytes([*icmp.get(6)?, *icmp.get(7)?]),
+        );
+        match kind {
+            // Ping sockets only deliver their own replies, with the id the
+            // kernel picked
+            ICMP_ECHO_REPLY if !self.raw || id == self.id => Some(IcmpReply::Echo { seq }),
+            ICMP_DEST_UNREACH | ICMP_TIME_EXCEEDED => {
+                // The error quotes the IP header and first bytes of our probe
+                let quoted = icmp.get(8..)?;
+                let quoted_icmp = quoted.get((*quoted.first()? as usize & 0x0f) * 4..)?;
+                let quoted_id = u16::from_be_bytes([*quoted_icmp.get(4)?, *quoted_icmp.get(5)?]);
+                let seq = u16::from_be_bytes([*quoted_icmp.get(6)?, *quoted_icmp.get(7)?]);
+                if *quoted_icmp.first()? != ICMP_ECHO || (self.raw && quoted_id != self.id) {
+                    return None;
+                }
+                Some(if kind == ICMP_DEST_UNREACH {
+                    IcmpReply::Unreachable { seq }
+                } else {
+                    IcmpReply::TimeExceeded { seq }
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn open_socket(kind: libc::c_int) -> io::Result<OwnedFd> {
+    let fd = unsafe { libc::socket(libc::AF_INET, kind | libc::SOCK_CLOEXEC, libc::IPPROTO_ICMP) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn setsockopt_int(
+    fd: &OwnedFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: libc::c_int,
+) -> io::Result<()> {
+    let rc = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            level,
+            name,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if rc < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn sockaddr(ip: Ipv4Addr) -> libc::sockaddr_in {
+    let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_addr.s_addr = u32::from(ip).to_be();
+    addr
+}
+
+/// RFC 1071 internet checksum
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Round trip of one echo request
+pub fn ping(dst: Ipv4Addr, timeout: Duration) -> Result<Duration> {
+    let icmp = Icmp::open().context("Can't open an ICMP socket")?;
+    let start = Instant::now();
+    icmp.send_echo(dst, 1)?;
+    loop {
+        match icmp.recv(start + timeout)? {
+            Some((from, IcmpReply::Echo { seq: 1 })) if from == dst => return Ok(start.elapsed()),
+            Some((from, IcmpReply::Unreachable { seq: 1 })) => {
+                bail!("{} reports it unreachable", from)
+            }
+            Some(_) => {}
+            None => bail!("no reply within {}s", timeout.as_secs()),
+        }
+    }
+}
+
+/// What one hop answered in a traceroute round, None for silence
+pub type HopReply = Option<(Ipv4Addr, Duration)>;
+
+/// One echo request per TTL from 1 to MAX_HOPS, all sent at once, and the
+/// answers to them within `timeout`. Hops beyond the destination are cut
+/// off. `round` keeps sequence numbers apart from earlier rounds' late
+/// replies.
+pub fn trace_round(dst: Ipv4Addr, round: u16, timeout: Duration) -> Result<Vec<HopReply>> {
+    let icmp = Icmp::open().context("Can't open an ICMP socket")?;
+    let base = round.wrapping_mul(MAX_HOPS.into());
+    let mut sent = Vec::with_capacity(MAX_HOPS.into());
+    for ttl in 1..=MAX_HOPS {
+        icmp.set_ttl(ttl)?;
+        icmp.send_echo(dst, base.wrapping_add(ttl.into()))?;
+        sent.push(Instant::now());
+    }
+
+    let mut hops: Vec<HopReply> = vec![None; MAX_HOPS.into()];
+    // TTL the destination itself answered at
+    let mut reached: Option<usize> = None;
+    let deadline = Instant::now() + timeout;
+    while let Some((from, reply)) = icmp.recv(deadline)? {
+        let (IcmpReply::Echo { seq }
+        | IcmpReply::Unreachable { seq }
+        | IcmpReply::TimeExceeded { seq }) = reply;
+        let index = usize::from(seq.wrapping_sub(base)).wrapping_sub(1);
+        let Some(hop) = hops.get_mut(index) else {
+            continue;
+        };
+        if hop.is_none() {
+            *hop = Some((from, sent[index].elapsed()));
+        }
+        if !matches!(reply, IcmpReply::TimeExceeded { .. }) {
+            reached = Some(reached.map_or(index, |r| r.min(index)));
+        }
+        if let Some(last) = reached
+            && hops[..=last].iter().all(Option::is_some)
+        {
+            break;
+        }
+    }
+    if let Some(last) = reached {
+        hops.truncate(last + 1);
+    }
+    Ok(hops)
+}
+
+/// Status line of a HEAD request to an http:// URL and how long it took to
+/// arrive, connecting included. The host is resolved through NSS, so
+/// resolved answers it like any other program's lookup.
+pub fn http_head(url: &str, timeout: Duration) -> Result<(String, Duration)> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        bail!("only http:// probe URLs are supported");
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(']') || authority.starts_with('[') => {
+            (host, port.parse().context("bad port in the probe URL")?)
+        }
+        _ => (authority, 80),
+    };
+
+    let start = Instant::now();
+    let addr = (host.trim_matches(['[', ']']), port)
+        .to_socket_addrs()
+        .with_context(|| format!("can't resolve {}", host))?
+        .next()
+        .with_context(|| format!("{} has no addresses", host))?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)
+        .with_context(|| format!("can't connect to {}", addr))?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    write!(
+        stream,
+        "HEAD {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: rootwork\r\nConnection: close\r\n\r\n",
+        path, authority
+    )?;
+
+    let mut status = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status)
+        .context("no response")?;
+    let elapsed = start.elapsed();
+    let status = status.trim();
+    if !status.starts_with("HTTP/") {
+        bail!("not an HTTP response: {:?}", status);
+    }
+    // "HTTP/1.1 204 No Content" -> "204 No Content"
+    let status = status
+        .split_once(' ')
+        .map_or(status, |(_, rest)| rest)
+        .to_string();
+    Ok((status, elapsed))
+}
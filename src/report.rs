@@ -0,0 +1,154 @@
+//! A markdown snapshot of what the tabs show, for pasting into an incident
+//! ticket: failed units, recent errors, network, DNS, host and boot.
+//! Sections for tabs left out of the build are skipped.
+
+#[cfg(feature = "boot")]
+use crate::contexts::boot::BootInfo;
+#[cfg(feature = "dns")]
+use crate::contexts::dns::DnsInfo;
+#[cfg(feature = "host")]
+use crate::contexts::host::HostInfo;
+#[cfg(feature = "logs")]
+use crate::contexts::logs;
+#[cfg(feature = "network")]
+use crate::contexts::network::NetworkInfo;
+use crate::systemd::client::SystemdClient;
+use std::path::PathBuf;
+
+/// Error lines from the journal to include
+#[cfg(feature = "logs")]
+const MAX_ERRORS: usize = 50;
+
+pub async fn generate(systemd: &SystemdClient) -> String {
+    let now = chrono::Local::now();
+    let manager = match (systemd.host(), systemd.is_user_mode()) {
+        (Some(host), _) => format!("{} (remote)", host),
+        (None, true) => "user manager".to_string(),
+        (None, false) => "system manager".to_string(),
+    };
+    let mut out = format!(
+        "# rootwork report\n\n- Generated: {}\n- Manager: {}\n",
+        now.format("%Y-%m-%d %H:%M:%S %:z"),
+        manager
+    );
+    if let Ok(status) = systemd.manager_status().await {
+        out.push_str(&format!(
+            "- systemd: {}\n- State: {}\n- Features: {}\n",
+            status.version,
+            status.state_label(),
+            status.features
+        ));
+    }
+    out.push('\n');
+
+    out.push_str("## Failed units\n\n");
+    match systemd.list_units().await {
+        Ok(units) => {
+            let failed: Vec<_> = units.iter().filter(|u| u.is_failed()).collect();
+            if failed.is_empty() {
+                out.push_str("None.\n");
+            } else {
+                out.push_str(
+                    "| Unit | Load | Active | Sub | Description |\n|---|---|---|---|---|\n",
+                );
+                for unit in failed {
+                    out.push_str(&table_row(&[
+                        &unit.name,
+                        &unit.load_state,
+                        &unit.active_state,
+                        &unit.sub_state,
+                        &unit.description,
+                    ]));
+                }
+            }
+        }
+        Err(e) => out.push_str(&format!("Unavailable: {}\n", e)),
+    }
+    out.push('\n');
+
+    // Errors, Network and Boot read local files and the journal; DNS and
+    // Host ask the managed machine over its system bus
+    if systemd.host().is_some() {
+        out.push_str(
+            "_The Recent errors, Network and Boot sections describe the local machine, not the remote host._\n\n",
+        );
+    }
+    let local = tokio::task::spawn_blocking(|| {
+        #[cfg_attr(not(any(feature = "logs", feature = "network")), allow(unused_mut))]
+        let mut out = String::new();
+        #[cfg(feature = "logs")]
+        logs::write_error_report(&mut out, MAX_ERRORS);
+        #[cfg(feature = "network")]
+        match NetworkInfo::gather() {
+            Ok(info) => info.write_report(&mut out),
+            Err(e) => out.push_str(&format!("## Network\n\nUnavailable: {}\n\n", e)),
+        }
+        out
+    })
+    .await;
+    match local {
+        Ok(text) => out.push_str(&text),
+        Err(e) => out.push_str(&format!("(local sections failed: {})\n", e)),
+    }
+    #[cfg(feature = "dns")]
+    match DnsInfo::gather(systemd).await {
+        Ok(info) => info.write_report(&mut out),
+        Err(e) => out.push_str(&format!("## DNS\n\nUnavailable: {}\n\n", e)),
+    }
+    #[cfg(feature = "host")]
+    match HostInfo::gather(systemd).await {
+        Ok(info) => info.write_report(&mut out),
+        Err(e) => out.push_str(&format!("## Host\n\nUnavailable: {}\n\n", e)),
+    }
+    let local = tokio::task::spawn_blocking(|| {
+        #[cfg_attr(not(feature = "boot"), allow(unused_mut))]
+        let mut out = String::new();
+        #[cfg(feature = "boot")]
+        if let Some(container) = crate::container::detect() {
+            out.push_str(&format!(
+                "## Boot\n\nNot applicable inside a container ({}).\n\n",
+                container.kind
+            ));
+        } else {
+            match BootInfo::gather() {
+                Ok(info) => info.write_report(&mut out),
+                Err(e) => out.push_str(&format!("## Boot\n\nUnavailable: {}\n\n", e)),
+            }
+        }
+        out
+    })
+    .await;
+    match local {
+        Ok(text) => out.push_str(&text),
+        Err(e) => out.push_str(&format!("(local sections failed: {})\n", e)),
+    }
+    out
+}
+
+/// A markdown table row. Pipes are escaped and line breaks become `<br>` so
+/// a description or message can't split the row or shift its columns.
+pub fn table_row(cells: &[&str]) -> String {
+    let mut row = String::from("|");
+    for cell in cells {
+        let cell = cell
+            .trim_end_matches('\n')
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>");
+        row.push_str(&format!(" {} |", cell));
+    }
+    row.push('\n');
+    row
+}
+
+/// Write the report to `rootwork-report-<timestamp>.md` in the current
+/// directory
+pub fn write(report: &str) -> std::io::Result<PathBuf> {
+    let name = format!(
+        "rootwork-report-{}.md",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let path = std::env::current_dir()?.join(name);
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
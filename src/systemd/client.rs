@@ -1,4 +1,33 @@
+#[cfg(feature = "units")]
+use crate::systemd::limits::{INFINITY, Limit, ResourceLimits};
 use anyhow::Result;
+#[cfg(any(feature = "units", feature = "dns", feature = "host"))]
+use std::collections::HashMap;
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "machines",
+    feature = "portable"
+))]
+use std::sync::Arc;
+#[cfg(any(feature = "units", feature = "dns", feature = "host"))]
+use std::sync::Mutex;
+#[cfg(any(
+    feature = "network",
+    feature = "dns",
+    feature = "host",
+    feature = "machines",
+    feature = "portable"
+))]
+use tokio::sync::OnceCell;
+#[cfg(any(feature = "dns", feature = "host"))]
+use zbus::Proxy;
+#[cfg(any(feature = "dns", feature = "host"))]
+use zbus::proxy::CacheProperties;
+#[cfg(any(feature = "dns", feature = "host"))]
+use zbus::zvariant::OwnedValue;
 use zbus::{Connection, proxy};
 
 /// Detect if running as root
@@ -6,6 +35,21 @@ pub fn is_root() -> bool {
     unsafe { libc::getuid() == 0 }
 }
 
+/// One row of ListUnits: (name, description, load_state, active_state,
+/// sub_state, follower, object_path, job_id, job_type, job_object_path)
+type UnitRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    zbus::zvariant::OwnedObjectPath,
+    u32,
+    String,
+    zbus::zvariant::OwnedObjectPath,
+);
+
 /// Systemd Manager D-Bus proxy
 #[proxy(
     interface = "org.freedesktop.systemd1.Manager",
@@ -14,34 +58,30 @@ pub fn is_root() -> bool {
 )]
 trait SystemdManager {
     /// List all units
-    /// Returns: [(name, description, load_state, active_state, sub_state,
-    ///           follower, object_path, job_id, job_type, job_object_path)]
-    fn list_units(
+    fn list_units(&self) -> zbus::Result<Vec<UnitRow>>;
+
+    /// List loaded units filtered by state and name globs
+    fn list_units_by_patterns(
         &self,
-    ) -> zbus::Result<
-        Vec<(
-            String,
-            String,
-            String,
-            String,
-            String,
-            String,
-            zbus::zvariant::OwnedObjectPath,
-            u32,
-            String,
-            zbus::zvariant::OwnedObjectPath,
-        )>,
-    >;
+        states: &[&str],
+        patterns: &[&str],
+    ) -> zbus::Result<Vec<UnitRow>>;
 
     /// Get unit by name
     fn get_unit(&self, name: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
+    /// Load unit by name, even if nothing references it yet
+    fn load_unit(&self, name: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
     /// Start a unit
     fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
     /// Stop a unit
     fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
+    /// Reload a unit
+    fn reload_unit(&self, name: &str, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
     /// Restart a unit
     fn restart_unit(&self, name: &str, mode: &str)
     -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
@@ -49,6 +89,39 @@ trait SystemdManager {
     /// Reload daemon
     fn reload(&self) -> zbus::Result<()>;
 
+    /// Target booted into by default, e.g. "graphical.target"
+    fn get_default_target(&self) -> zbus::Result<String>;
+
+    /// Point default.target at another target
+    fn set_default_target(
+        &self,
+        name: &str,
+        force: bool,
+    ) -> zbus::Result<Vec<(String, String, String)>>;
+
+    #[zbus(property)]
+    fn version(&self) -> zbus::Result<String>;
+
+    /// Compile-time options, e.g. "+PAM +AUDIT -APPARMOR ..."
+    #[zbus(property)]
+    fn features(&self) -> zbus::Result<String>;
+
+    /// "running", "degraded", "starting", "stopping", ...
+    #[zbus(property)]
+    fn system_state(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "NFailedUnits")]
+    fn n_failed_units(&self) -> zbus::Result<u32>;
+
+    /// Change properties of a loaded unit, like `systemctl set-property`.
+    /// `runtime` keeps the change until reboot instead of writing a drop-in.
+    fn set_unit_properties(
+        &self,
+        name: &str,
+        runtime: bool,
+        properties: &[(&str, zbus::zvariant::Value<'_>)],
+    ) -> zbus::Result<()>;
+
     /// Enable unit files
     fn enable_unit_files(
         &self,
@@ -65,15 +138,56 @@ trait SystemdManager {
     ) -> zbus::Result<Vec<(String, String, String)>>;
 }
 
+/// Which bus to manage when not connecting to a remote host
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusPreference {
+    /// System bus as root, user session otherwise
+    Auto,
+    User,
+    System,
+}
+
+/// (service, path, interface) of a proxy on the local system bus
+#[cfg(any(feature = "dns", feature = "host"))]
+type ProxyKey = (&'static str, &'static str, &'static str);
+
+/// Type details by unit, with the StateChangeTimestamp they were read at
+#[cfg(feature = "units")]
+type DetailsCache = HashMap<String, (u64, Box<TypeDetails>)>;
+
 #[derive(Clone)]
 pub struct SystemdClient {
     connection: Connection,
     user_mode: bool,
+    host: Option<String>,
+    /// The system bus for the tabs that talk to other services; the manager
+    /// connection itself when that is one, which --host's always is
+    #[cfg(any(
+        feature = "network",
+        feature = "dns",
+        feature = "host",
+        feature = "machines",
+        feature = "portable"
+    ))]
+    system_bus: Arc<OnceCell<Connection>>,
+    #[cfg(any(feature = "dns", feature = "host"))]
+    proxies: Arc<Mutex<HashMap<ProxyKey, Proxy<'static>>>>,
+    /// What `load_type_details` read last time
+    #[cfg(feature = "units")]
+    type_details: Arc<Mutex<DetailsCache>>,
 }
 
 impl SystemdClient {
-    pub async fn new() -> Result<Self> {
-        let (connection, user_mode) = if is_root() {
+    pub async fn new(host: Option<&str>, bus: BusPreference) -> Result<Self> {
+        if let Some(host) = host {
+            return Self::connect_remote(host).await;
+        }
+
+        let (connection, user_mode) = if bus == BusPreference::User {
+            let conn = Connection::session().await?;
+            tracing::info!("Connected to user D-Bus session");
+            (conn, true)
+        } else if bus == BusPreference::System || is_root() {
             // Running as root - connect to system bus
             let conn = Connection::system().await?;
             tracing::info!("Connected to system D-Bus as root");
@@ -97,16 +211,159 @@ impl SystemdClient {
             }
         };
 
+        #[cfg(any(
+            feature = "network",
+            feature = "dns",
+            feature = "host",
+            feature = "machines",
+            feature = "portable"
+        ))]
+        let system_bus = if user_mode {
+            OnceCell::new()
+        } else {
+            OnceCell::new_with(Some(connection.clone()))
+        };
         Ok(Self {
             connection,
             user_mode,
+            host: None,
+            #[cfg(any(
+                feature = "network",
+                feature = "dns",
+                feature = "host",
+                feature = "machines",
+                feature = "portable"
+            ))]
+            system_bus: Arc::new(system_bus),
+            #[cfg(any(feature = "dns", feature = "host"))]
+            proxies: Arc::default(),
+            #[cfg(feature = "units")]
+            type_details: Arc::default(),
+        })
+    }
+
+    /// Connect to the system bus of a remote machine the same way
+    /// `systemctl --host` does: spawn `systemd-stdio-bridge` over SSH and
+    /// speak D-Bus through its stdio.
+    async fn connect_remote(host: &str) -> Result<Self> {
+        let address = format!(
+            "unixexec:path=ssh,argv1=-xT,argv2=--,argv3={},argv4=systemd-stdio-bridge",
+            dbus_address_escape(host)
+        );
+        let connection = zbus::connection::Builder::address(address.as_str())?
+            .build()
+            .await?;
+        tracing::info!("Connected to system D-Bus on {}", host);
+
+        Ok(Self {
+            user_mode: false,
+            host: Some(host.to_string()),
+            // systemd-stdio-bridge speaks for the remote system bus, so the
+            // other services are reached through it too
+            #[cfg(any(
+                feature = "network",
+                feature = "dns",
+                feature = "host",
+                feature = "machines",
+                feature = "portable"
+            ))]
+            system_bus: Arc::new(OnceCell::new_with(Some(connection.clone()))),
+            connection,
+            #[cfg(any(feature = "dns", feature = "host"))]
+            proxies: Arc::default(),
+            #[cfg(feature = "units")]
+            type_details: Arc::default(),
         })
     }
 
+    /// Connect to another user's service manager the way `systemctl --user
+    /// -M USER@.host` does: systemd-run starts systemd-stdio-bridge in the
+    /// user's session and D-Bus goes through its stdio. Needs root.
+    #[cfg(feature = "units")]
+    pub async fn connect_user_manager(user: &str) -> Result<Self> {
+        let address = format!(
+            "unixexec:path=systemd-run,argv1={},argv2=--user,argv3=-PGq,argv4=--wait,argv5=--,argv6=systemd-stdio-bridge,argv7=--user",
+            dbus_address_escape(&format!("-M{}@.host", user))
+        );
+        let connection = zbus::connection::Builder::address(address.as_str())?
+            .build()
+            .await?;
+
+        Ok(Self {
+            connection,
+            user_mode: true,
+            host: None,
+            #[cfg(any(
+                feature = "network",
+                feature = "dns",
+                feature = "host",
+                feature = "machines",
+                feature = "portable"
+            ))]
+            system_bus: Arc::default(),
+            #[cfg(any(feature = "dns", feature = "host"))]
+            proxies: Arc::default(),
+            #[cfg(feature = "units")]
+            type_details: Arc::default(),
+        })
+    }
+
+    /// The system bus of the managed machine; the local one is connected on
+    /// first use when the manager connection is a user session
+    #[cfg(any(
+        feature = "network",
+        feature = "dns",
+        feature = "host",
+        feature = "machines",
+        feature = "portable"
+    ))]
+    pub async fn system_bus(&self) -> Result<&Connection> {
+        Ok(self.system_bus.get_or_try_init(Connection::system).await?)
+    }
+
+    /// A property of a service on the local system bus. Proxies are kept
+    /// across calls; their property cache is off since not every property
+    /// announces its changes.
+    #[cfg(any(feature = "dns", feature = "host"))]
+    pub async fn system_property<T>(
+        &self,
+        service: &'static str,
+        path: &'static str,
+        interface: &'static str,
+        property: &str,
+    ) -> Result<T>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<zbus::Error>,
+    {
+        let key = (service, path, interface);
+        let cached = self.proxies.lock().unwrap().get(&key).cloned();
+        let proxy = match cached {
+            Some(proxy) => proxy,
+            None => {
+                let proxy = zbus::proxy::Builder::<Proxy>::new(self.system_bus().await?)
+                    .destination(service)?
+                    .path(path)?
+                    .interface(interface)?
+                    .cache_properties(CacheProperties::No)
+                    .build()
+                    .await?;
+                self.proxies.lock().unwrap().insert(key, proxy.clone());
+                proxy
+            }
+        };
+        Ok(proxy.get_property(property).await?)
+    }
+
     pub fn is_user_mode(&self) -> bool {
         self.user_mode
     }
 
+    /// Remote host this client talks to, if any
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
     /// Get the manager proxy for making calls
     async fn manager(&self) -> Result<SystemdManagerProxy<'_>> {
         let proxy = SystemdManagerProxy::new(&self.connection).await?;
@@ -117,23 +374,277 @@ impl SystemdClient {
     pub async fn list_units(&self) -> Result<Vec<UnitInfo>> {
         let manager = self.manager().await?;
         let units = manager.list_units().await?;
+        Ok(units.into_iter().map(unit_info).collect())
+    }
+
+    /// Loaded units whose names match one of the globs, e.g. "*.swap"
+    #[cfg(any(feature = "units", feature = "storage", feature = "kernel"))]
+    pub async fn list_units_matching(&self, patterns: &[&str]) -> Result<Vec<UnitInfo>> {
+        let manager = self.manager().await?;
+        let units = manager.list_units_by_patterns(&[], patterns).await?;
+        Ok(units.into_iter().map(unit_info).collect())
+    }
+
+    /// Fill in the runtime properties ListUnits doesn't return (timestamps,
+    /// enablement, resource usage). A couple of D-Bus calls per unit, so only
+    /// worth doing when something actually shows or sorts on them.
+    #[cfg(feature = "units")]
+    pub async fn load_unit_properties(&self, units: &mut [UnitInfo]) -> Result<()> {
+        for unit in units.iter_mut() {
+            let props = zbus::fdo::PropertiesProxy::builder(&self.connection)
+                .destination("org.freedesktop.systemd1")?
+                .path(&unit.object_path)?
+                .build()
+                .await?;
+
+            let common = props
+                .get_all("org.freedesktop.systemd1.Unit".try_into()?)
+                .await
+                .unwrap_or_default();
+            let get_u64 = |map: &std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+                           key: &str| {
+                map.get(key).and_then(|v| v.downcast_ref::<u64>().ok())
+            };
+
+            unit.state_change_usec = get_u64(&common, "StateChangeTimestamp").unwrap_or(0);
+            unit.active_enter_usec = get_u64(&common, "ActiveEnterTimestamp").unwrap_or(0);
+            unit.unit_file_state = common
+                .get("UnitFileState")
+                .and_then(|v| v.downcast_ref::<&str>().ok())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
 
-        let unit_info: Vec<UnitInfo> = units
-            .into_iter()
-            .map(
-                |(name, description, load_state, active_state, sub_state, _, _, _, _, _)| {
-                    UnitInfo {
-                        name,
-                        description,
-                        load_state,
-                        active_state,
-                        sub_state,
-                    }
-                },
-            )
-            .collect();
+            // Resource accounting lives on the type-specific interface and
+            // only exists for unit types that own a cgroup
+            if let Some(iface) = cgroup_interface(&unit.name) {
+                let typed = props
+                    .get_all(iface.as_str().try_into()?)
+                    .await
+                    .unwrap_or_default();
+                unit.memory_bytes = get_u64(&typed, "MemoryCurrent").filter(|v| *v != u64::MAX);
+                unit.cpu_nsec = get_u64(&typed, "CPUUsageNSec").filter(|v| *v != u64::MAX);
+                unit.tasks_current = get_u64(&typed, "TasksCurrent").filter(|v| *v != u64::MAX);
+                unit.n_restarts = typed
+                    .get("NRestarts")
+                    .and_then(|v| v.downcast_ref::<u32>().ok());
+            }
+        }
+        Ok(())
+    }
 
-        Ok(unit_info)
+    /// Read what `.path` units watch and where `.automount` units mount, plus
+    /// the unit each one triggers, and what `.mount` units mount. Other unit
+    /// types are left alone, and a unit whose state hasn't changed since the
+    /// last listing keeps what was read then, so this stays cheap enough to
+    /// run on every listing. A unit that can't be read is logged and left
+    /// without details.
+    #[cfg(feature = "units")]
+    pub async fn load_type_details(&self, units: &mut [UnitInfo]) {
+        let mut seen = HashMap::new();
+        for unit in units.iter_mut() {
+            let iface = match unit.name.rsplit('.').next() {
+                Some("path") => "org.freedesktop.systemd1.Path",
+                Some("automount") => "org.freedesktop.systemd1.Automount",
+                Some("mount") => "org.freedesktop.systemd1.Mount",
+                _ => continue,
+            };
+            match self.type_details(unit, iface).await {
+                Ok((stamp, details)) => {
+                    unit.details = Some(details.clone());
+                    seen.insert(unit.name.clone(), (stamp, details));
+                }
+                Err(e) => tracing::warn!("Failed to read {}: {}", unit.name, e),
+            }
+        }
+        // Units gone from the listing drop out of the cache
+        *self.type_details.lock().unwrap() = seen;
+    }
+
+    /// One unit's details for `load_type_details`, from the cache while
+    /// its StateChangeTimestamp is the one they were read at
+    #[cfg(feature = "units")]
+    async fn type_details(
+        &self,
+        unit: &UnitInfo,
+        iface: &'static str,
+    ) -> Result<(u64, Box<TypeDetails>)> {
+        let common = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            &unit.object_path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        // Only filled in when the listing loaded properties
+        let stamp = match unit.state_change_usec {
+            0 => common.get_property("StateChangeTimestamp").await?,
+            stamp => stamp,
+        };
+        let cached = self
+            .type_details
+            .lock()
+            .unwrap()
+            .get(&unit.name)
+            .filter(|(read_at, _)| stamp != 0 && *read_at == stamp)
+            .map(|(_, details)| details.clone());
+        if let Some(details) = cached {
+            return Ok((stamp, details));
+        }
+
+        let typed = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            &unit.object_path,
+            iface,
+        )
+        .await?;
+        let details = if iface.ends_with("Mount") {
+            TypeDetails::Mount(MountInfo {
+                what: typed.get_property("What").await?,
+                r#where: typed.get_property("Where").await?,
+                fs_type: typed.get_property("Type").await?,
+                options: typed.get_property("Options").await?,
+            })
+        } else {
+            let watches = if iface.ends_with("Path") {
+                typed
+                    .get_property::<Vec<(String, String)>>("Paths")
+                    .await?
+                    .into_iter()
+                    .map(|(kind, path)| format!("{}={}", kind, path))
+                    .collect()
+            } else {
+                vec![typed.get_property::<String>("Where").await?]
+            };
+            TypeDetails::Activation(Activation {
+                watches,
+                triggers: common.get_property("Triggers").await?,
+            })
+        };
+        Ok((stamp, Box::new(details)))
+    }
+
+    /// Every property of a unit as (interface, name, value) — the generic Unit
+    /// interface first, then the type-specific one — like `systemctl show`
+    #[cfg(feature = "units")]
+    pub async fn all_properties(&self, name: &str) -> Result<Vec<(String, String, String)>> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let props = zbus::fdo::PropertiesProxy::builder(&self.connection)
+            .destination("org.freedesktop.systemd1")?
+            .path(path)?
+            .build()
+            .await?;
+
+        let mut interfaces = vec!["org.freedesktop.systemd1.Unit".to_string()];
+        interfaces.extend(type_interface(name));
+
+        let mut out = Vec::new();
+        for iface in interfaces {
+            let mut map: Vec<_> = props
+                .get_all(iface.as_str().try_into()?)
+                .await?
+                .into_iter()
+                .collect();
+            map.sort_by(|a, b| a.0.cmp(&b.0));
+            let short = iface.rsplit('.').next().unwrap_or(&iface).to_string();
+            out.extend(
+                map.into_iter()
+                    .map(|(key, value)| (short.clone(), key, format_value(&value))),
+            );
+        }
+        Ok(out)
+    }
+
+    /// The Service properties needed to triage a failure without
+    /// `systemctl status`: what runs, as whom, and how the last run ended
+    #[cfg(feature = "units")]
+    pub async fn service_exec(&self, name: &str) -> Result<ServiceExec> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let props = zbus::fdo::PropertiesProxy::builder(&self.connection)
+            .destination("org.freedesktop.systemd1")?
+            .path(path)?
+            .build()
+            .await?;
+        let map = props
+            .get_all("org.freedesktop.systemd1.Service".try_into()?)
+            .await?;
+
+        let get_str = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.downcast_ref::<&str>().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let get_u64 = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.downcast_ref::<u64>().ok())
+                .unwrap_or(0)
+        };
+        let get_u32 = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.downcast_ref::<u32>().ok())
+                .unwrap_or(0)
+        };
+        let get_i32 = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.downcast_ref::<i32>().ok())
+                .unwrap_or(0)
+        };
+
+        // ExecStart is a(sasbttttuii); only the argv is interesting here
+        let exec_start = match map.get("ExecStart").map(|v| &**v) {
+            Some(zbus::zvariant::Value::Array(commands)) => commands
+                .inner()
+                .iter()
+                .filter_map(|command| match command {
+                    zbus::zvariant::Value::Structure(st) => st.fields().get(1).map(format_value),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let environment = map
+            .get("Environment")
+            .and_then(|v| Vec::<String>::try_from(v.try_clone().ok()?).ok())
+            .unwrap_or_default();
+        // EnvironmentFiles is a(sb): path, and whether a missing file is fine
+        let environment_files = match map.get("EnvironmentFiles").map(|v| &**v) {
+            Some(zbus::zvariant::Value::Array(files)) => files
+                .inner()
+                .iter()
+                .filter_map(|file| match file {
+                    zbus::zvariant::Value::Structure(st) => match st.fields() {
+                        [path, zbus::zvariant::Value::Bool(optional)] => Some(format!(
+                            "{}{}",
+                            if *optional { "-" } else { "" },
+                            format_value(path)
+                        )),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(ServiceExec {
+            exec_start,
+            environment,
+            environment_files,
+            main_pid: get_u32("MainPID"),
+            start_usec: get_u64("ExecMainStartTimestamp"),
+            exit_usec: get_u64("ExecMainExitTimestamp"),
+            restart: get_str("Restart"),
+            n_restarts: get_u32("NRestarts"),
+            user: get_str("User"),
+            group: get_str("Group"),
+            result: get_str("Result"),
+            exit_code: get_i32("ExecMainCode"),
+            exit_status: get_i32("ExecMainStatus"),
+        })
     }
 
     /// Start a unit
@@ -143,6 +654,31 @@ impl SystemdClient {
         Ok(())
     }
 
+    /// Target the system boots into
+    #[cfg(feature = "units")]
+    pub async fn default_target(&self) -> Result<String> {
+        let manager = self.manager().await?;
+        Ok(manager.get_default_target().await?)
+    }
+
+    /// Boot into `name` from now on, like `systemctl set-default`
+    #[cfg(feature = "units")]
+    pub async fn set_default_target(&self, name: &str) -> Result<()> {
+        let manager = self.manager().await?;
+        manager.set_default_target(name, true).await?;
+        manager.reload().await?;
+        Ok(())
+    }
+
+    /// Start `name` and stop everything it doesn't pull in, like
+    /// `systemctl isolate`
+    #[cfg(feature = "units")]
+    pub async fn isolate(&self, name: &str) -> Result<()> {
+        let manager = self.manager().await?;
+        let _job = manager.start_unit(name, "isolate").await?;
+        Ok(())
+    }
+
     /// Stop a unit
     pub async fn stop_unit(&self, name: &str) -> Result<()> {
         let manager = self.manager().await?;
@@ -150,6 +686,14 @@ impl SystemdClient {
         Ok(())
     }
 
+    /// Reload a unit; for a mount this remounts it with its current options
+    #[cfg(feature = "units")]
+    pub async fn reload_unit(&self, name: &str) -> Result<()> {
+        let manager = self.manager().await?;
+        let _job = manager.reload_unit(name, "replace").await?;
+        Ok(())
+    }
+
     /// Restart a unit
     pub async fn restart_unit(&self, name: &str) -> Result<()> {
         let manager = self.manager().await?;
@@ -157,7 +701,240 @@ impl SystemdClient {
         Ok(())
     }
 
+    /// Version and overall state of the manager
+    pub async fn manager_status(&self) -> Result<ManagerStatus> {
+        let manager = self.manager().await?;
+        Ok(ManagerStatus {
+            version: manager.version().await?,
+            features: manager.features().await.unwrap_or_default(),
+            system_state: manager.system_state().await?,
+            n_failed_units: manager.n_failed_units().await?,
+        })
+    }
+
+    /// Current ActiveState of a unit, loading it if necessary
+    pub async fn active_state(&self, name: &str) -> Result<String> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        Ok(proxy.get_property::<String>("ActiveState").await?)
+    }
+
+    /// InvocationID of the unit's current (or last) run as journald prints
+    /// it, 32 hex digits; None if it never ran
+    #[cfg(feature = "units")]
+    pub async fn invocation_id(&self, name: &str) -> Result<Option<String>> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        let id = proxy.get_property::<Vec<u8>>("InvocationID").await?;
+        if id.iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        Ok(Some(id.iter().map(|b| format!("{:02x}", b)).collect()))
+    }
+
+    /// Unit names listed in dependency properties such as RequiredBy or
+    /// BoundBy, in property order without duplicates
+    #[cfg(feature = "units")]
+    pub async fn dependencies(&self, name: &str, properties: &[&str]) -> Result<Vec<String>> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        let mut names: Vec<String> = Vec::new();
+        for property in properties {
+            for dep in proxy.get_property::<Vec<String>>(property).await? {
+                if !names.contains(&dep) {
+                    names.push(dep);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// How many times the service was restarted automatically (NRestarts)
+    #[cfg(feature = "units")]
+    pub async fn n_restarts(&self, name: &str) -> Result<u32> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Service",
+        )
+        .await?;
+        Ok(proxy.get_property::<u32>("NRestarts").await?)
+    }
+
+    /// Condition*= and Assert*= checks of the unit and how they came out
+    /// the last time it was started
+    #[cfg(feature = "units")]
+    pub async fn conditions(&self, name: &str) -> Result<UnitConditions> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        let list = |raw: Vec<(String, bool, bool, String, i32)>| {
+            raw.into_iter()
+                .map(|(kind, trigger, negate, parameter, state)| UnitCondition {
+                    kind,
+                    trigger,
+                    negate,
+                    parameter,
+                    state,
+                })
+                .collect()
+        };
+        Ok(UnitConditions {
+            condition_result: proxy.get_property("ConditionResult").await?,
+            condition_usec: proxy.get_property("ConditionTimestamp").await?,
+            assert_result: proxy.get_property("AssertResult").await?,
+            assert_usec: proxy.get_property("AssertTimestamp").await?,
+            conditions: list(proxy.get_property("Conditions").await?),
+            asserts: list(proxy.get_property("Asserts").await?),
+        })
+    }
+
+    /// CPUQuota, MemoryMax, TasksMax and IOWeight, with memory and task usage
+    /// and the IP traffic counted by IPAccounting=
+    #[cfg(feature = "units")]
+    pub async fn resource_limits(&self, name: &str) -> Result<ResourceLimits> {
+        let iface = cgroup_interface(name)
+            .ok_or_else(|| anyhow::anyhow!("{} has no cgroup to limit", name))?;
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let props = zbus::fdo::PropertiesProxy::builder(&self.connection)
+            .destination("org.freedesktop.systemd1")?
+            .path(path)?
+            .build()
+            .await?;
+        let map = props.get_all(iface.as_str().try_into()?).await?;
+        let get_u64 = |key: &str| map.get(key).and_then(|v| v.downcast_ref::<u64>().ok());
+
+        Ok(ResourceLimits {
+            cpu_quota_per_sec_usec: get_u64("CPUQuotaPerSecUSec").unwrap_or(INFINITY),
+            memory_max: get_u64("MemoryMax").unwrap_or(INFINITY),
+            tasks_max: get_u64("TasksMax").unwrap_or(INFINITY),
+            io_weight: get_u64("IOWeight").unwrap_or(INFINITY),
+            memory_current: get_u64("MemoryCurrent").filter(|v| *v != INFINITY),
+            tasks_current: get_u64("TasksCurrent").filter(|v| *v != INFINITY),
+            ip_accounting: map
+                .get("IPAccounting")
+                .and_then(|v| v.downcast_ref::<bool>().ok())
+                .unwrap_or(false),
+            ip_ingress_bytes: get_u64("IPIngressBytes").filter(|v| *v != INFINITY),
+            ip_egress_bytes: get_u64("IPEgressBytes").filter(|v| *v != INFINITY),
+            ip_ingress_packets: get_u64("IPIngressPackets").filter(|v| *v != INFINITY),
+            ip_egress_packets: get_u64("IPEgressPackets").filter(|v| *v != INFINITY),
+        })
+    }
+
+    /// MemoryCurrent and CPUUsageNSec right now, either None when the unit
+    /// doesn't account for it
+    #[cfg(feature = "units")]
+    pub async fn usage_sample(&self, name: &str) -> Result<(Option<u64>, Option<u64>)> {
+        let iface =
+            cgroup_interface(name).ok_or_else(|| anyhow::anyhow!("{} has no cgroup", name))?;
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let props = zbus::fdo::PropertiesProxy::builder(&self.connection)
+            .destination("org.freedesktop.systemd1")?
+            .path(path)?
+            .build()
+            .await?;
+        let map = props.get_all(iface.as_str().try_into()?).await?;
+        let get_u64 = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.downcast_ref::<u64>().ok())
+                .filter(|v| *v != INFINITY)
+        };
+        Ok((get_u64("MemoryCurrent"), get_u64("CPUUsageNSec")))
+    }
+
+    /// Turn IPAccounting= on or off until reboot; counting starts from zero
+    /// when it's turned on
+    #[cfg(feature = "units")]
+    pub async fn set_ip_accounting(&self, name: &str, enabled: bool) -> Result<()> {
+        let manager = self.manager().await?;
+        manager
+            .set_unit_properties(name, true, &[("IPAccounting", enabled.into())])
+            .await?;
+        Ok(())
+    }
+
+    /// Set one cgroup limit; persistent changes land in a drop-in under
+    /// /etc/systemd/system.control
+    #[cfg(feature = "units")]
+    pub async fn set_limit(
+        &self,
+        name: &str,
+        limit: Limit,
+        value: u64,
+        runtime: bool,
+    ) -> Result<()> {
+        let manager = self.manager().await?;
+        manager
+            .set_unit_properties(name, runtime, &[(limit.property(), value.into())])
+            .await?;
+        Ok(())
+    }
+
+    /// The unit's Documentation= URIs, e.g. "man:sshd(8)" or "https://..."
+    #[cfg(feature = "units")]
+    pub async fn documentation(&self, name: &str) -> Result<Vec<String>> {
+        let manager = self.manager().await?;
+        let path = manager.load_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        Ok(proxy.get_property::<Vec<String>>("Documentation").await?)
+    }
+
+    /// Get the main PID of a service unit, 0 if it isn't running
+    #[cfg(feature = "units")]
+    pub async fn get_main_pid(&self, name: &str) -> Result<u32> {
+        let manager = self.manager().await?;
+        let path = manager.get_unit(name).await?;
+        let proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            path,
+            "org.freedesktop.systemd1.Service",
+        )
+        .await?;
+        Ok(proxy.get_property::<u32>("MainPID").await?)
+    }
+
     /// Reload daemon
+    #[cfg(feature = "units")]
     pub async fn reload_daemon(&self) -> Result<()> {
         let manager = self.manager().await?;
         manager.reload().await?;
@@ -179,16 +956,395 @@ impl SystemdClient {
     }
 }
 
+/// A ListUnits row, before `load_unit_properties` fills in the rest
+fn unit_info(row: UnitRow) -> UnitInfo {
+    let (name, description, load_state, active_state, sub_state, _, path, _, _, _) = row;
+    UnitInfo {
+        name,
+        description,
+        load_state,
+        active_state,
+        sub_state,
+        object_path: path,
+        state_change_usec: 0,
+        active_enter_usec: 0,
+        unit_file_state: None,
+        memory_bytes: None,
+        cpu_nsec: None,
+        tasks_current: None,
+        n_restarts: None,
+        #[cfg(feature = "units")]
+        details: None,
+    }
+}
+
+/// The type-specific D-Bus interface carrying cgroup accounting properties
+#[cfg(feature = "units")]
+pub fn cgroup_interface(unit_name: &str) -> Option<String> {
+    let iface = match unit_name.rsplit('.').next()? {
+        "service" => "Service",
+        "scope" => "Scope",
+        "slice" => "Slice",
+        "socket" => "Socket",
+        "mount" => "Mount",
+        "swap" => "Swap",
+        _ => return None,
+    };
+    Some(format!("org.freedesktop.systemd1.{}", iface))
+}
+
+/// D-Bus interface with the properties specific to a unit's type
+#[cfg(feature = "units")]
+fn type_interface(unit_name: &str) -> Option<String> {
+    let iface = match unit_name.rsplit('.').next()? {
+        "service" => "Service",
+        "socket" => "Socket",
+        "target" => "Target",
+        "device" => "Device",
+        "mount" => "Mount",
+        "automount" => "Automount",
+        "swap" => "Swap",
+        "timer" => "Timer",
+        "path" => "Path",
+        "slice" => "Slice",
+        "scope" => "Scope",
+        _ => return None,
+    };
+    Some(format!("org.freedesktop.systemd1.{}", iface))
+}
+
+/// Render a property value roughly the way `systemctl show` does: strings
+/// bare, lists space-separated, byte arrays (IDs) as hex
+#[cfg(feature = "units")]
+fn format_value(value: &zbus::zvariant::Value) -> String {
+    use zbus::zvariant::Value;
+    match value {
+        Value::Str(s) => s.to_string(),
+        Value::ObjectPath(p) => p.to_string(),
+        Value::Bool(b) => if *b { "yes" } else { "no" }.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Value(inner) => format_value(inner),
+        Value::Array(array) => {
+            let items = array.inner();
+            if items.iter().all(|v| matches!(v, Value::U8(_))) && !items.is_empty() {
+                items
+                    .iter()
+                    .map(|v| match v {
+                        Value::U8(b) => format!("{:02x}", b),
+                        _ => String::new(),
+                    })
+                    .collect()
+            } else {
+                items.iter().map(format_value).collect::<Vec<_>>().join(" ")
+            }
+        }
+        Value::Structure(st) => format!(
+            "{{ {} }}",
+            st.fields()
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(" ; ")
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// Escape a value for use inside a D-Bus address: everything outside the
+/// optionally-escaped set is written as %XX.
+fn dbus_address_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'/' | b'.' | b'\\' | b'*') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02x}", b));
+        }
+    }
+    out
+}
+
+/// What `systemctl status` prints at the top, see `manager_status`
+#[derive(Debug, Clone)]
+pub struct ManagerStatus {
+    pub version: String,
+    pub features: String,
+    pub system_state: String,
+    pub n_failed_units: u32,
+}
+
+impl ManagerStatus {
+    pub fn is_degraded(&self) -> bool {
+        self.system_state == "degraded"
+    }
+
+    /// "running", or "degraded (3 failed)"
+    pub fn state_label(&self) -> String {
+        if self.n_failed_units > 0 {
+            format!("{} ({} failed)", self.system_state, self.n_failed_units)
+        } else {
+            self.system_state.clone()
+        }
+    }
+}
+
+/// One Condition*= or Assert*= line of a unit
 #[derive(Debug, Clone)]
+#[cfg(feature = "units")]
+pub struct UnitCondition {
+    /// "ConditionPathExists", "AssertVirtualization", ...
+    pub kind: String,
+    /// `|`: any one triggering condition passing is enough
+    pub trigger: bool,
+    /// `!`: passes when the check doesn't
+    pub negate: bool,
+    pub parameter: String,
+    /// Positive when it passed, negative when it failed, 0 if not checked
+    pub state: i32,
+}
+
+#[cfg(feature = "units")]
+impl UnitCondition {
+    /// As written in the unit file, e.g. "ConditionPathExists=!/etc/foo"
+    pub fn setting(&self) -> String {
+        format!(
+            "{}={}{}{}",
+            self.kind,
+            if self.trigger { "|" } else { "" },
+            if self.negate { "!" } else { "" },
+            self.parameter
+        )
+    }
+
+    pub fn failed(&self) -> bool {
+        self.state < 0
+    }
+}
+
+/// See `conditions`
+#[derive(Debug, Clone)]
+#[cfg(feature = "units")]
+pub struct UnitConditions {
+    pub condition_result: bool,
+    /// Realtime µs of the last check, 0 if never checked
+    pub condition_usec: u64,
+    pub assert_result: bool,
+    pub assert_usec: u64,
+    pub conditions: Vec<UnitCondition>,
+    pub asserts: Vec<UnitCondition>,
+}
+
+#[cfg(feature = "units")]
+impl UnitConditions {
+    /// Whether the last start was skipped (conditions) or failed (asserts)
+    /// because of a check
+    pub fn blocked(&self) -> bool {
+        (self.condition_usec > 0 && !self.condition_result)
+            || (self.assert_usec > 0 && !self.assert_result)
+    }
+
+    /// The checks that failed last time, conditions first
+    pub fn failures(&self) -> impl Iterator<Item = &UnitCondition> {
+        self.conditions
+            .iter()
+            .chain(&self.asserts)
+            .filter(|c| c.failed())
+    }
+}
+
+/// Exec-related state of a service unit, see `service_exec`
+#[derive(Debug, Clone, Default)]
+#[cfg(feature = "units")]
+pub struct ServiceExec {
+    /// One command line per ExecStart= entry
+    pub exec_start: Vec<String>,
+    /// Environment= assignments, `KEY=VALUE`
+    pub environment: Vec<String>,
+    /// EnvironmentFile= paths, `-` prefixed when allowed to be missing
+    pub environment_files: Vec<String>,
+    /// 0 if the service isn't running
+    pub main_pid: u32,
+    /// Realtime µs the main process was started, 0 if never
+    pub start_usec: u64,
+    /// Realtime µs the main process exited, 0 if it hasn't
+    pub exit_usec: u64,
+    pub restart: String,
+    pub n_restarts: u32,
+    pub user: String,
+    pub group: String,
+    /// "success", "exit-code", "signal", "timeout", ...
+    pub result: String,
+    /// siginfo code of the last exit: CLD_EXITED, CLD_KILLED or CLD_DUMPED
+    pub exit_code: i32,
+    /// Exit status or signal number, depending on `exit_code`
+    pub exit_status: i32,
+}
+
+#[cfg(feature = "units")]
+impl ServiceExec {
+    /// How the last run ended, e.g. "exited, status=1" or "killed, signal=9"
+    pub fn last_exit(&self) -> Option<String> {
+        let how = match self.exit_code {
+            libc::CLD_EXITED => "exited, status",
+            libc::CLD_KILLED => "killed, signal",
+            libc::CLD_DUMPED => "dumped core, signal",
+            _ => return None,
+        };
+        Some(format!("{}={}", how, self.exit_status))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnitInfo {
     pub name: String,
     pub description: String,
     pub load_state: String,
     pub active_state: String,
     pub sub_state: String,
+    pub object_path: zbus::zvariant::OwnedObjectPath,
+    // The fields below are only filled by `load_unit_properties`
+    /// Realtime µs of the last active state change, 0 if unknown
+    pub state_change_usec: u64,
+    /// Realtime µs the unit last entered the active state, 0 if unknown
+    pub active_enter_usec: u64,
+    pub unit_file_state: Option<String>,
+    pub memory_bytes: Option<u64>,
+    pub cpu_nsec: Option<u64>,
+    pub tasks_current: Option<u64>,
+    /// Automatic restarts since the service was last started by hand
+    pub n_restarts: Option<u32>,
+    /// Path, automount and mount specifics, filled by `load_type_details`
+    #[cfg(feature = "units")]
+    pub details: Option<Box<TypeDetails>>,
+}
+
+/// What some unit types add on top of the common Unit properties
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "units")]
+pub enum TypeDetails {
+    Activation(Activation),
+    Mount(MountInfo),
+}
+
+/// What a `.path` or `.automount` unit watches and what it starts
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "units")]
+pub struct Activation {
+    /// Watched paths as "PathChanged=/etc/foo", or the mount point
+    pub watches: Vec<String>,
+    /// Units started when it fires, usually one service or mount
+    pub triggers: Vec<String>,
+}
+
+/// What a `.mount` unit mounts, where, and how
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "units")]
+pub struct MountInfo {
+    pub what: String,
+    pub r#where: String,
+    pub fs_type: String,
+    pub options: String,
+}
+
+#[cfg(feature = "units")]
+impl MountInfo {
+    /// One-line form for lists: "/dev/sda1 ext4"
+    pub fn summary(&self) -> String {
+        format!("{} {}", self.what, self.fs_type)
+    }
+
+    /// Current space on the mount point. Can block for a long time on a
+    /// dead network filesystem, so keep it off the UI thread.
+    pub fn usage(&self) -> std::io::Result<DiskUsage> {
+        DiskUsage::of(&self.r#where)
+    }
+}
+
+/// Space on a filesystem, in bytes, as statvfs reports it
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg(any(feature = "units", feature = "boot", feature = "storage"))]
+pub struct DiskUsage {
+    pub total: u64,
+    pub free: u64,
+    /// What unprivileged users can still write; less than `free` by the
+    /// reserved blocks
+    pub available: u64,
+}
+
+#[cfg(any(feature = "units", feature = "boot", feature = "storage"))]
+impl DiskUsage {
+    /// statvfs of the filesystem holding `path`
+    pub fn of(path: &str) -> std::io::Result<DiskUsage> {
+        let path = std::ffi::CString::new(path)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let block = stat.f_frsize as u64;
+        Ok(DiskUsage {
+            total: stat.f_blocks as u64 * block,
+            free: stat.f_bfree as u64 * block,
+            available: stat.f_bavail as u64 * block,
+        })
+    }
+
+    #[cfg(any(feature = "units", feature = "storage"))]
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    /// Used share the way df computes it: against what users can reach
+    #[cfg(any(feature = "units", feature = "storage"))]
+    pub fn percent(&self) -> u64 {
+        let reachable = self.used() + self.available;
+        if reachable == 0 {
+            return 0;
+        }
+        (self.used() * 100).div_ceil(reachable)
+    }
+}
+
+#[cfg(feature = "units")]
+impl Activation {
+    /// One-line form for lists: "/etc/foo → foo.service"
+    pub fn summary(&self) -> String {
+        let watches = self
+            .watches
+            .iter()
+            .map(|w| w.split_once('=').map_or(w.as_str(), |(_, path)| path))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{} → {}", watches, self.triggers.join(" "))
+    }
 }
 
 impl UnitInfo {
+    /// What a path or automount unit watches and triggers
+    #[cfg(feature = "units")]
+    pub fn activation(&self) -> Option<&Activation> {
+        match self.details.as_deref() {
+            Some(TypeDetails::Activation(activation)) => Some(activation),
+            _ => None,
+        }
+    }
+
+    /// What a mount unit mounts
+    #[cfg(feature = "units")]
+    pub fn mount(&self) -> Option<&MountInfo> {
+        match self.details.as_deref() {
+            Some(TypeDetails::Mount(mount)) => Some(mount),
+            _ => None,
+        }
+    }
+
     /// Check if unit is active
     pub fn is_active(&self) -> bool {
         self.active_state == "active"
@@ -201,12 +1357,15 @@ impl UnitInfo {
 
     /// Get state icon/color indicator
     pub fn state_indicator(&self) -> &'static str {
-        match self.active_state.as_str() {
-            "active" => "●",
-            "inactive" => "○",
-            "failed" => "✗",
-            "activating" => "◐",
-            "deactivating" => "◑",
+        match (self.active_state.as_str(), self.sub_state.as_str()) {
+            // Oneshot that ran to completion, as opposed to a running daemon
+            ("active", "exited") => "✓",
+            ("active", _) => "●",
+            ("inactive", _) => "○",
+            ("failed", _) => "✗",
+            ("activating", "auto-restart") => "↻",
+            ("activating" | "reloading" | "refreshing", _) => "◐",
+            ("deactivating", _) => "◑",
             _ => "?",
         }
     }
@@ -0,0 +1,393 @@
+//! SELinux AVC and AppArmor denials from the journal, summarized per
+//! source so a policy problem can be triaged without grepping audit logs.
+//!
+//! Both arrive as kernel audit records: through journald's audit socket
+//! (`_TRANSPORT=audit`) or, without auditd and that socket, as kernel
+//! messages.
+
+use crate::systemd::journal_ffi::{
+    SD_JOURNAL_LOCAL_ONLY, sd_journal_add_match, sd_journal_close, sd_journal_get_realtime_usec,
+    sd_journal_open, sd_journal_previous, sd_journal_seek_tail,
+};
+use crate::systemd::logs::get_journal_field;
+use std::collections::{BTreeSet, HashMap};
+use std::os::raw::c_void;
+
+/// Audit and kernel records looked through per read, newest first
+const SCAN_MAX: usize = 200_000;
+
+/// What a denial would take to allow, merged over every denial of the
+/// same subject, target and class
+#[derive(Debug, Clone)]
+pub struct DenialRule {
+    /// `allow httpd_t user_home_t:file { open read };` or
+    /// `/usr/sbin/cupsd: /etc/foo r,`
+    pub rule: String,
+    /// scontext/tcontext/tclass or profile/operation, as logged
+    pub context: String,
+    pub count: usize,
+    pub last_usec: u64,
+    /// SELinux in permissive mode logs denials without enforcing them
+    pub permissive: bool,
+}
+
+/// Denials of one unit, or of one command when its unit is unknown
+#[derive(Debug, Clone)]
+pub struct DenialSource {
+    /// "nginx.service", or "comm nginx" once the process is gone
+    pub name: String,
+    pub count: usize,
+    pub last_usec: u64,
+    /// Most frequent first
+    pub rules: Vec<DenialRule>,
+}
+
+/// One parsed AVC or AppArmor record
+#[derive(Debug)]
+struct Denial {
+    pid: Option<u32>,
+    comm: Option<String>,
+    /// Merging key of the rule, without permissions
+    key: String,
+    perms: Vec<String>,
+    context: String,
+    permissive: bool,
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    /// Source type, target type, class
+    Selinux(String, String, String),
+    /// Profile and the denied path, if any
+    AppArmor(String, Option<String>),
+}
+
+/// `key=value` and `key="quoted value"` pairs of an audit record
+fn audit_fields(message: &str) -> HashMap<&str, &str> {
+    let mut fields = HashMap::new();
+    let mut rest = message;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].rsplit([' ', ':', '(']).next().unwrap_or("");
+        let after = &rest[eq + 1..];
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            },
+            None => {
+                let end = after.find(' ').unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if !key.is_empty() {
+            fields.entry(key).or_insert(value);
+        }
+        rest = next;
+    }
+    fields
+}
+
+/// The type of an SELinux context, `httpd_t` of `system_u:system_r:httpd_t:s0`
+fn context_type(context: &str) -> &str {
+    context.split(':').nth(2).unwrap_or(context)
+}
+
+fn parse_denial(message: &str) -> Option<Denial> {
+    if let Some(avc) = message.find("avc:") {
+        let record = &message[avc..];
+        // "avc:  denied  { read write } for  pid=..."
+        if !record["avc:".len()..].trim_start().starts_with("denied") {
+            return None;
+        }
+        let open = record.find('{')?;
+        let close = record[open..].find('}')? + open;
+        let perms = record[open + 1..close]
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let fields = audit_fields(&record[close + 1..]);
+        let scontext = fields.get("scontext")?;
+        let tcontext = fields.get("tcontext")?;
+        let tclass = fields.get("tclass")?;
+        let kind = Kind::Selinux(
+            context_type(scontext).to_string(),
+            context_type(tcontext).to_string(),
+            tclass.to_string(),
+        );
+        return Some(Denial {
+            pid: fields.get("pid").and_then(|p| p.parse().ok()),
+            comm: fields.get("comm").map(|c| c.to_string()),
+            key: format!("{}|{}|{}", scontext, tcontext, tclass),
+            perms,
+            context: format!(
+                "scontext={} tcontext={} tclass={}",
+                scontext, tcontext, tclass
+            ),
+            permissive: fields.get("permissive") == Some(&"1"),
+            kind,
+        });
+    }
+
+    let fields = audit_fields(message);
+    if fields.get("apparmor") != Some(&"DENIED") {
+        return None;
+    }
+    let profile = fields.get("profile")?;
+    let operation = fields.get("operation").copied().unwrap_or("?");
+    let name = fields.get("name").map(|n| n.to_string());
+    let mask = fields
+        .get("denied_mask")
+        .or_else(|| fields.get("requested_mask"))
+        .copied()
+        .unwrap_or("");
+    Some(Denial {
+        pid: fields.get("pid").and_then(|p| p.parse().ok()),
+        comm: fields.get("comm").map(|c| c.to_string()),
+        key: format!(
+            "{}|{}|{}",
+            profile,
+            operation,
+            name.as_deref().unwrap_or("")
+        ),
+        perms: mask.chars().map(String::from).collect(),
+        context: format!("profile={} operation={}", profile, operation),
+        permissive: false,
+        kind: Kind::AppArmor(profile.to_string(), name),
+    })
+}
+
+/// The innermost service or scope of a cgroup path
+fn unit_of_cgroup(path: &str) -> Option<String> {
+    path.split('/')
+        .rfind(|part| part.ends_with(".service") || part.ends_with(".scope"))
+        .map(str::to_string)
+}
+
+/// The unit whose cgroup a process is in, while it's still running. The
+/// PID may have been reused since the denial, so the process must still
+/// be the `comm` the record names.
+fn unit_of_pid(pid: u32, comm: &str) -> Option<String> {
+    let current = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    if current.trim_end() != comm {
+        return None;
+    }
+    let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    // "0::/system.slice/nginx.service", innermost unit last
+    cgroup
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .rev()
+        .find_map(unit_of_cgroup)
+}
+
+/// Rules being merged for one source
+struct Merging {
+    count: usize,
+    last_usec: u64,
+    rules: HashMap<String, (Denial, BTreeSet<String>, usize, u64)>,
+}
+
+/// Denials logged after `since_usec`, grouped by source, most frequent
+/// first
+pub fn read_denials(since_usec: u64) -> Vec<DenialSource> {
+    let mut sources: HashMap<String, Merging> = HashMap::new();
+    // Processes resolved through /proc, by PID and comm
+    let mut units: HashMap<(u32, String), Option<String>> = HashMap::new();
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return Vec::new();
+        }
+        // Matches on one field are ORed
+        for m in ["_TRANSPORT=audit", "_TRANSPORT=kernel"] {
+            let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        }
+        let _ = sd_journal_seek_tail(j);
+        for _ in 0..SCAN_MAX {
+            if sd_journal_previous(j) <= 0 {
+                break;
+            }
+            let mut usec = 0;
+            if sd_journal_get_realtime_usec(j, &mut usec as *mut u64) < 0 {
+                continue;
+            }
+            if usec <= since_usec {
+                break;
+            }
+            let Some(denial) =
+                get_journal_field(j, "MESSAGE").and_then(|message| parse_denial(&message))
+            else {
+                continue;
+            };
+            // journald resolves the unit when the record arrives; only
+            // kernel messages leave it to /proc, where the process may be
+            // gone or its PID reused
+            let unit = get_journal_field(j, "_SYSTEMD_UNIT")
+                .or_else(|| {
+                    get_journal_field(j, "_SYSTEMD_CGROUP").and_then(|path| unit_of_cgroup(&path))
+                })
+                .or_else(|| {
+                    let (pid, comm) = (denial.pid?, denial.comm.clone()?);
+                    units
+                        .entry((pid, comm.clone()))
+                        .or_insert_with(|| unit_of_pid(pid, &comm))
+                        .clone()
+                });
+            let name = match (unit, &denial.comm) {
+                (Some(unit), _) => unit,
+                (None, Some(comm)) => format!("comm {}", comm),
+                (None, None) => "unknown".to_string(),
+            };
+            let source = sources.entry(name).or_insert_with(|| Merging {
+                count: 0,
+                last_usec: 0,
+                rules: HashMap::new(),
+            });
+            source.count += 1;
+            source.last_usec = source.last_usec.max(usec);
+            let perms = denial.perms.clone();
+            let rule = source
+                .rules
+                .entry(denial.key.clone())
+                .or_insert_with(|| (denial, BTreeSet::new(), 0, usec));
+            rule.1.extend(perms);
+            rule.2 += 1;
+            rule.3 = rule.3.max(usec);
+        }
+        sd_journal_close(j);
+    }
+
+    let mut sources: Vec<DenialSource> = sources
+        .into_iter()
+        .map(|(name, pending)| {
+            let mut rules: Vec<DenialRule> = pending
+                .rules
+                .into_values()
+                .map(|(denial, perms, count, last_usec)| DenialRule {
+                    rule: rule_text(&denial.kind, &perms),
+                    context: denial.context,
+                    count,
+                    last_usec,
+                    permissive: denial.permissive,
+                })
+                .collect();
+            rules.sort_by_key(|r| std::cmp::Reverse((r.count, r.last_usec)));
+            DenialSource {
+                name,
+                count: pending.count,
+                last_usec: pending.last_usec,
+                rules,
+            }
+        })
+        .collect();
+    sources.sort_by_key(|s| std::cmp::Reverse((s.count, s.last_usec)));
+    sources
+}
+
+/// An `audit2allow` rule for SELinux, an `aa-logprof` style profile line
+/// for AppArmor
+fn rule_text(kind: &Kind, perms: &BTreeSet<String>) -> String {
+    let perms: Vec<&str> = perms.iter().map(String::as_str).collect();
+    match kind {
+        Kind::Selinux(source, target, class) => format!(
+            "allow {} {}:{} {{ {} }};",
+            source,
+            target,
+            class,
+            perms.join(" ")
+        ),
+        Kind::AppArmor(profile, Some(name)) => {
+            format!("{}: {} {},", profile, name, perms.concat())
+        }
+        Kind::AppArmor(profile, None) => format!("{}: {},", profile, perms.concat()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVC: &str = r#"audit: type=1400 audit(1700000000.123:42): avc:  denied  { read open } for  pid=1234 comm="nginx" path="/home/web/index.html" dev="sda1" ino=5678 scontext=system_u:system_r:httpd_t:s0 tcontext=unconfined_u:object_r:user_home_t:s0 tclass=file permissive=0"#;
+
+    const APPARMOR: &str = r#"audit: type=1400 audit(1700000000.456:43): apparmor="DENIED" operation="open" profile="/usr/sbin/cupsd" name="/etc/foo" pid=99 comm="cupsd" requested_mask="r" denied_mask="rw" fsuid=0 ouid=0"#;
+
+    #[test]
+    fn audit_fields_handle_quoted_values() {
+        let fields = audit_fields(r#"a=1 b="two words" c=3 a=4 d="unterminated"#);
+        assert_eq!(fields["a"], "1");
+        assert_eq!(fields["b"], "two words");
+        assert_eq!(fields["c"], "3");
+        assert_eq!(fields["d"], "unterminated");
+    }
+
+    #[test]
+    fn selinux_avc_denial() {
+        let denial = parse_denial(AVC).unwrap();
+        assert_eq!(denial.pid, Some(1234));
+        assert_eq!(denial.comm.as_deref(), Some("nginx"));
+        assert_eq!(denial.perms, ["read", "open"]);
+        assert!(!denial.permissive);
+        assert_eq!(
+            denial.context,
+            "scontext=system_u:system_r:httpd_t:s0 \
+             tcontext=unconfined_u:object_r:user_home_t:s0 tclass=file"
+        );
+        let perms = denial.perms.iter().cloned().collect();
+        assert_eq!(
+            rule_text(&denial.kind, &perms),
+            "allow httpd_t user_home_t:file { open read };"
+        );
+    }
+
+    #[test]
+    fn permissive_avc_and_granted_records() {
+        let permissive = AVC.replace("permissive=0", "permissive=1");
+        assert!(parse_denial(&permissive).unwrap().permissive);
+        let granted = AVC.replace("avc:  denied", "avc:  granted");
+        assert!(parse_denial(&granted).is_none());
+        let truncated = AVC.split(" tclass=").next().unwrap();
+        assert!(parse_denial(truncated).is_none());
+    }
+
+    #[test]
+    fn apparmor_denial() {
+        let denial = parse_denial(APPARMOR).unwrap();
+        assert_eq!(denial.pid, Some(99));
+        assert_eq!(denial.comm.as_deref(), Some("cupsd"));
+        assert_eq!(denial.perms, ["r", "w"]);
+        assert_eq!(denial.key, "/usr/sbin/cupsd|open|/etc/foo");
+        assert_eq!(denial.context, "profile=/usr/sbin/cupsd operation=open");
+        let perms = denial.perms.iter().cloned().collect();
+        assert_eq!(
+            rule_text(&denial.kind, &perms),
+            "/usr/sbin/cupsd: /etc/foo rw,"
+        );
+        let allowed = APPARMOR.replace("\"DENIED\"", "\"ALLOWED\"");
+        assert!(parse_denial(&allowed).is_none());
+    }
+
+    #[test]
+    fn unrelated_messages_are_skipped() {
+        assert!(parse_denial("usb 1-1: new high-speed USB device number 2").is_none());
+        assert!(parse_denial("").is_none());
+    }
+
+    #[test]
+    fn innermost_unit_of_a_cgroup() {
+        assert_eq!(
+            unit_of_cgroup("/system.slice/nginx.service").as_deref(),
+            Some("nginx.service")
+        );
+        assert_eq!(
+            unit_of_cgroup("/user.slice/user-1000.slice/user@1000.service/app.slice/app-foo.scope")
+                .as_deref(),
+            Some("app-foo.scope")
+        );
+        assert_eq!(
+            unit_of_cgroup("/init.scope/"),
+            Some("init.scope".to_string())
+        );
+        assert_eq!(unit_of_cgroup("/system.slice"), None);
+    }
+}
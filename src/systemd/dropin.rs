@@ -0,0 +1,213 @@
+//! Drop-in files rootwork writes for a unit. Environment= can't be changed
+//! on a loaded unit over D-Bus (SetUnitProperties only takes it for transient
+//! units), so overrides go into `<unit>.d/` and take effect after a daemon
+//! reload and a restart.
+
+use std::io;
+use std::path::PathBuf;
+
+/// Name of the drop-in holding environment overrides
+const ENVIRONMENT_FILE: &str = "90-rootwork-environment.conf";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    /// Under /run: gone after a reboot
+    Runtime,
+    /// Under /etc (or ~/.config for the user manager)
+    Persistent,
+}
+
+impl Scope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Runtime => "runtime",
+            Scope::Persistent => "persistent",
+        }
+    }
+
+    /// `<unit>.d` directory of this scope, None when the base directory
+    /// can't be worked out (user manager without XDG dirs)
+    fn dir(self, unit: &str, user_mode: bool) -> Option<PathBuf> {
+        let base = match (self, user_mode) {
+            (Scope::Runtime, false) => PathBuf::from("/run/systemd/system"),
+            (Scope::Persistent, false) => PathBuf::from("/etc/systemd/system"),
+            (Scope::Runtime, true) => {
+                PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?).join("systemd/user")
+            }
+            (Scope::Persistent, true) => {
+                let config = std::env::var_os("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .or_else(|| {
+                        std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config"))
+                    })?;
+                config.join("systemd/user")
+            }
+        };
+        Some(base.join(format!("{}.d", unit)))
+    }
+
+    pub fn environment_path(self, unit: &str, user_mode: bool) -> Option<PathBuf> {
+        Some(self.dir(unit, user_mode)?.join(ENVIRONMENT_FILE))
+    }
+}
+
+/// `KEY=VALUE` assignments in rootwork's environment drop-in, in file order
+pub fn read_environment(unit: &str, scope: Scope, user_mode: bool) -> Vec<String> {
+    let Some(path) = scope.environment_path(unit, user_mode) else {
+        return Vec::new();
+    };
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.strip_prefix("Environment="))
+        .map(unquote)
+        .collect()
+}
+
+/// Add or replace one `KEY=VALUE` in the drop-in. Returns the file written.
+pub fn set_environment(
+    unit: &str,
+    scope: Scope,
+    user_mode: bool,
+    assignment: &str,
+) -> io::Result<PathBuf> {
+    let key = match assignment.split_once('=') {
+        Some((key, _)) if is_valid_key(key) => key,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected NAME=value, got {:?}", assignment),
+            ));
+        }
+    };
+    let path = scope
+        .environment_path(unit, user_mode)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no drop-in directory"))?;
+
+    let mut assignments = read_environment(unit, scope, user_mode);
+    assignments.retain(|a| a.split_once('=').map(|(k, _)| k) != Some(key));
+    assignments.push(assignment.to_string());
+
+    let mut content = String::from("# Written by rootwork\n[Service]\n");
+    for assignment in &assignments {
+        content.push_str(&format!("Environment={}\n", quote(assignment)));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Delete the environment drop-in; false if there was none
+pub fn remove_environment(unit: &str, scope: Scope, user_mode: bool) -> io::Result<bool> {
+    let Some(path) = scope.environment_path(unit, user_mode) else {
+        return Ok(false);
+    };
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Quote for a unit file: backslashes and quotes escaped, `%` doubled so
+/// specifiers aren't expanded
+fn quote(assignment: &str) -> String {
+    let escaped = assignment
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+/// Reverse of `quote` for the lines this module wrote
+fn unquote(value: &str) -> String {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            '%' => {
+                out.push('%');
+                // "%%" is one literal percent
+                let mut peek = chars.clone();
+                if peek.next() == Some('%') {
+                    chars = peek;
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_escapes_for_a_unit_file() {
+        assert_eq!(quote("A=b"), r#""A=b""#);
+        assert_eq!(quote("A=50%"), r#""A=50%%""#);
+        assert_eq!(quote(r#"A="x" \y"#), r#""A=\"x\" \\y""#);
+    }
+
+    #[test]
+    fn unquote_reverses_quote() {
+        for assignment in [
+            "PLAIN=value",
+            "EMPTY=",
+            "SPACES=a b  c",
+            "PERCENT=100%",
+            "SPECIFIER=%n and %%i",
+            r#"QUOTES="double" 'single'"#,
+            r"BACKSLASH=C:\dir\ and \\share",
+            r#"MIXED=\"%\"\%"#,
+        ] {
+            assert_eq!(unquote(&quote(assignment)), assignment);
+        }
+    }
+
+    #[test]
+    fn unquote_takes_hand_written_values() {
+        assert_eq!(unquote("A=b"), "A=b");
+        assert_eq!(unquote("A=50%"), "A=50%");
+    }
+
+    #[test]
+    fn environment_keys() {
+        assert!(is_valid_key("PATH"));
+        assert!(is_valid_key("_private2"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("2FAST"));
+        assert!(!is_valid_key("WITH-DASH"));
+        assert!(!is_valid_key("A B"));
+    }
+
+    #[test]
+    fn system_drop_in_paths() {
+        assert_eq!(
+            Scope::Runtime.environment_path("nginx.service", false),
+            Some(PathBuf::from(
+                "/run/systemd/system/nginx.service.d/90-rootwork-environment.conf"
+            ))
+        );
+        assert_eq!(
+            Scope::Persistent.environment_path("nginx.service", false),
+            Some(PathBuf::from(
+                "/etc/systemd/system/nginx.service.d/90-rootwork-environment.conf"
+            ))
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! What happened to a unit over time: state changes seen while rootwork
+//! runs, plus the manager's own journal messages about the unit (started,
+//! failed, scheduled restart, ...) from before that.
+
+use crate::systemd::client::UnitInfo;
+use std::collections::HashMap;
+use std::os::raw::c_void;
+
+use crate::systemd::journal_ffi::{
+    SD_JOURNAL_LOCAL_ONLY, sd_journal_add_match, sd_journal_close, sd_journal_get_realtime_usec,
+    sd_journal_open, sd_journal_previous, sd_journal_seek_tail,
+};
+use crate::systemd::logs::get_journal_field;
+
+/// Events kept per unit
+const MAX_EVENTS: usize = 200;
+
+/// An observed change this close to a journal event is the same change
+const DEDUP_USEC: u64 = 5_000_000;
+
+const HOUR_USEC: u64 = 3_600_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    Started,
+    Stopped,
+    Failed,
+    /// Restart scheduled, e.g. by Restart=on-failure
+    Restarting,
+    /// A process of the unit exited, with code and status
+    Exited,
+    Reloaded,
+    /// Any other ActiveState change
+    Changed,
+}
+
+impl EventKind {
+    /// The systemd catalog message IDs PID 1 logs for a unit, and what they mean
+    const MESSAGE_IDS: [(&'static str, EventKind); 7] = [
+        ("39f53479d3a045ac8e11786248231fbf", EventKind::Started),
+        ("9d1aaa27d60140bd96365438aad20286", EventKind::Stopped),
+        ("7ad2d189f7e94e70a38c781354912448", EventKind::Stopped),
+        ("be02cf6855d2428ba40df7e9d022f03d", EventKind::Failed),
+        ("d9b373ed55a64feb8242e02dbe79a49c", EventKind::Failed),
+        ("5eb03494b6584870a536b337290809b3", EventKind::Restarting),
+        ("98e322203f7a4ed290d09fe03c09fe15", EventKind::Exited),
+    ];
+
+    fn from_message_id(id: &str) -> Option<Self> {
+        Self::MESSAGE_IDS
+            .iter()
+            .find(|(known, _)| *known == id)
+            .map(|(_, kind)| *kind)
+    }
+
+    fn from_active_state(state: &str) -> Self {
+        match state {
+            "active" => EventKind::Started,
+            "inactive" => EventKind::Stopped,
+            "failed" => EventKind::Failed,
+            "reloading" => EventKind::Reloaded,
+            _ => EventKind::Changed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Started => "started",
+            EventKind::Stopped => "stopped",
+            EventKind::Failed => "failed",
+            EventKind::Restarting => "restart",
+            EventKind::Exited => "exited",
+            EventKind::Reloaded => "reloaded",
+            EventKind::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub at_usec: u64,
+    pub kind: EventKind,
+    pub text: String,
+    /// Seen by comparing unit listings rather than read from the journal
+    pub observed: bool,
+}
+
+/// The headline numbers above the timeline
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub last_started: Option<u64>,
+    /// When it last failed, and the process exit that led to it if logged
+    pub last_failed: Option<(u64, Option<String>)>,
+    pub restarts_last_hour: usize,
+}
+
+/// Per-unit event lists for the session, oldest first
+#[derive(Default)]
+pub struct History {
+    units: HashMap<String, Vec<Event>>,
+    /// NRestarts readings as (realtime µs, count), oldest first
+    restart_counts: HashMap<String, Vec<(u64, u32)>>,
+}
+
+impl History {
+    /// Record ActiveState changes between two listings of the same units
+    pub fn observe(&mut self, old: &[UnitInfo], new: &[UnitInfo], now_usec: u64) {
+        let before: HashMap<&str, &UnitInfo> = old.iter().map(|u| (u.name.as_str(), u)).collect();
+        for unit in new {
+            let Some(prev) = before.get(unit.name.as_str()) else {
+                continue;
+            };
+            if prev.active_state == unit.active_state {
+                continue;
+            }
+            let events = self.units.entry(unit.name.clone()).or_default();
+            events.push(Event {
+                at_usec: now_usec,
+                kind: EventKind::from_active_state(&unit.active_state),
+                text: format!(
+                    "{} → {} ({})",
+                    prev.active_state, unit.active_state, unit.sub_state
+                ),
+                observed: true,
+            });
+            truncate(events);
+        }
+    }
+
+    /// Take a fresh journal read for `unit`. It replaces the previous one;
+    /// observed changes the journal also logged are dropped as duplicates.
+    pub fn seed(&mut self, unit: &str, journal: Vec<Event>) {
+        let events = self.units.entry(unit.to_string()).or_default();
+        let observed: Vec<Event> = events
+            .drain(..)
+            .filter(|e| e.observed)
+            .filter(|e| {
+                !journal
+                    .iter()
+                    .any(|j| j.at_usec.abs_diff(e.at_usec) < DEDUP_USEC)
+            })
+            .collect();
+        events.extend(journal);
+        events.extend(observed);
+        events.sort_by_key(|e| e.at_usec);
+        truncate(events);
+    }
+
+    pub fn events(&self, unit: &str) -> &[Event] {
+        self.units.get(unit).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn summary(&self, unit: &str, now_usec: u64) -> Summary {
+        summarize(self.events(unit), now_usec)
+    }
+
+    /// Remember a service's NRestarts; readings older than an hour are dropped
+    pub fn record_restart_count(&mut self, unit: &str, count: u32, now_usec: u64) {
+        let samples = self.restart_counts.entry(unit.to_string()).or_default();
+        samples.push((now_usec, count));
+        let hour_ago = now_usec.saturating_sub(HOUR_USEC);
+        samples.retain(|(at, _)| *at >= hour_ago);
+    }
+
+    /// Restarts since `since_usec`: whichever is higher of the starts seen
+    /// and how far NRestarts grew. NRestarts catches restarts too quick to
+    /// show up between two listings; it resets on a manual start.
+    pub fn restarts_since(&self, unit: &str, since_usec: u64) -> usize {
+        let seen = count_restarts(self.events(unit), since_usec);
+        let counted = self
+            .restart_counts
+            .get(unit)
+            .and_then(|samples| {
+                let first = samples.iter().find(|(at, _)| *at >= since_usec)?;
+                let last = samples.last()?;
+                Some(last.1.saturating_sub(first.1) as usize)
+            })
+            .unwrap_or(0);
+        seen.max(counted)
+    }
+}
+
+fn truncate(events: &mut Vec<Event>) {
+    if events.len() > MAX_EVENTS {
+        events.drain(..events.len() - MAX_EVENTS);
+    }
+}
+
+/// A start counts as a restart when something (a stop, a failure, an
+/// earlier start) happened to the unit before it
+fn count_restarts(events: &[Event], since_usec: u64) -> usize {
+    events
+        .iter()
+        .enumerate()
+        .filter(|(i, e)| *i > 0 && e.kind == EventKind::Started && e.at_usec >= since_usec)
+        .count()
+}
+
+fn summarize(events: &[Event], now_usec: u64) -> Summary {
+    let mut summary = Summary {
+        restarts_last_hour: count_restarts(events, now_usec.saturating_sub(HOUR_USEC)),
+        ..Summary::default()
+    };
+    let mut last_exit: Option<&Event> = None;
+    for event in events {
+        match event.kind {
+            EventKind::Started => summary.last_started = Some(event.at_usec),
+            EventKind::Failed => {
+                let exit = last_exit
+                    .filter(|e| event.at_usec.saturating_sub(e.at_usec) < DEDUP_USEC)
+                    .map(|e| e.text.clone());
+                summary.last_failed = Some((event.at_usec, exit));
+            }
+            EventKind::Exited => last_exit = Some(event),
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// The manager's messages about `unit`, oldest first. Reading the system journal
+/// needs root or the systemd-journal group; without it this comes back empty.
+pub fn read_journal(unit: &str, user: bool, max: usize) -> Vec<Event> {
+    let mut out = Vec::new();
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return out;
+        }
+
+        // A user manager logs about its units under USER_UNIT=
+        let field = if user { "USER_UNIT" } else { "UNIT" };
+        let m = format!("{field}={unit}");
+        let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        // Matches on the same field are OR'ed, different fields AND'ed
+        for (id, _) in EventKind::MESSAGE_IDS {
+            let m = format!("MESSAGE_ID={id}");
+            let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        }
+
+        let _ = sd_journal_seek_tail(j);
+        while out.len() < max && sd_journal_previous(j) > 0 {
+            if let Some(event) = read_event(j, unit) {
+                out.push(event);
+            }
+        }
+        sd_journal_close(j);
+    }
+    out.reverse();
+    out
+}
+
+fn read_event(j: *mut c_void, unit: &str) -> Option<Event> {
+    let mut at_usec = 0u64;
+    if unsafe { sd_journal_get_realtime_usec(j, &mut at_usec) } < 0 {
+        return None;
+    }
+    let kind = EventKind::from_message_id(&get_journal_field(j, "MESSAGE_ID")?)?;
+    let message = get_journal_field(j, "MESSAGE").unwrap_or_default();
+    // "foo.service: Main process exited, ..." reads fine without the name
+    let text = message
+        .strip_prefix(unit)
+        .and_then(|rest| rest.strip_prefix(": "))
+        .unwrap_or(&message)
+        .to_string();
+    Some(Event {
+        at_usec,
+        kind,
+        text,
+        observed: false,
+    })
+}
@@ -0,0 +1,130 @@
+//! The sd-journal API, loaded from libsystemd with dlopen on first use
+//! instead of linked in, so rootwork still starts where libsystemd is
+//! missing (musl builds, hosts without journald) and the other tabs work.
+//! The wrappers keep the C signatures; without the library they return
+//! -ENOSYS, which the journal readers already treat as a failed open.
+
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::OnceLock;
+
+/// `sd_journal_open` flags
+pub const SD_JOURNAL_LOCAL_ONLY: c_int = 1;
+#[cfg(feature = "logs")]
+pub const SD_JOURNAL_SYSTEM: c_int = 4;
+#[cfg(feature = "logs")]
+pub const SD_JOURNAL_CURRENT_USER: c_int = 8;
+
+/// Sonames tried in order; the unversioned one only exists with -dev packages
+const LIBRARIES: [&[u8]; 2] = [b"libsystemd.so.0\0", b"libsystemd.so\0"];
+
+macro_rules! journal_api {
+    ($($(#[$attr:meta])* fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        #[allow(non_snake_case)]
+        struct Api {
+            $($(#[$attr])* $name: unsafe extern "C" fn($($ty),*) $(-> $ret)?,)*
+        }
+
+        unsafe fn load() -> Option<Api> {
+            let handle = LIBRARIES.iter().find_map(|name| {
+                let handle = unsafe {
+                    libc::dlopen(name.as_ptr() as *const c_char, libc::RTLD_NOW | libc::RTLD_LOCAL)
+                };
+                (!handle.is_null()).then_some(handle)
+            })?;
+            Some(Api {
+                $($(#[$attr])* $name: unsafe {
+                    symbol(handle, concat!(stringify!($name), "\0").as_bytes())?
+                },)*
+            })
+        }
+
+        $(
+            $(#[$attr])*
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                match api() {
+                    Some(api) => unsafe { (api.$name)($($arg),*) },
+                    None => missing(),
+                }
+            }
+        )*
+    };
+}
+
+journal_api! {
+    fn sd_journal_open(ret: *mut *mut c_void, flags: c_int) -> c_int;
+    fn sd_journal_close(j: *mut c_void);
+    fn sd_journal_add_match(j: *mut c_void, data: *const c_void, size: usize) -> c_int;
+    fn sd_journal_seek_tail(j: *mut c_void) -> c_int;
+    #[cfg(any(feature = "units", feature = "logs"))]
+    fn sd_journal_seek_realtime_usec(j: *mut c_void, usec: u64) -> c_int;
+    fn sd_journal_previous(j: *mut c_void) -> c_int;
+    #[cfg(any(feature = "units", feature = "boot", feature = "logs"))]
+    fn sd_journal_next(j: *mut c_void) -> c_int;
+    fn sd_journal_get_realtime_usec(j: *mut c_void, ret: *mut u64) -> c_int;
+    fn sd_journal_get_monotonic_usec(j: *mut c_void, ret: *mut u64, boot_id: *mut [u8; 16]) -> c_int;
+    fn sd_journal_get_data(j: *mut c_void, field: *const c_char, data: *mut *const u8, length: *mut usize) -> c_int;
+    #[cfg(feature = "logs")]
+    fn sd_journal_get_cursor(j: *mut c_void, cursor: *mut *mut c_char) -> c_int;
+    #[cfg(feature = "logs")]
+    fn sd_journal_seek_cursor(j: *mut c_void, cursor: *const c_char) -> c_int;
+    #[cfg(feature = "logs")]
+    fn sd_journal_test_cursor(j: *mut c_void, cursor: *const c_char) -> c_int;
+    #[cfg(feature = "logs")]
+    fn sd_journal_restart_data(j: *mut c_void);
+    #[cfg(feature = "logs")]
+    fn sd_journal_enumerate_data(j: *mut c_void, data: *mut *const u8, length: *mut usize) -> c_int;
+    #[cfg(feature = "boot")]
+    fn sd_journal_seek_head(j: *mut c_void) -> c_int;
+    #[cfg(feature = "boot")]
+    fn sd_journal_flush_matches(j: *mut c_void);
+    #[cfg(feature = "boot")]
+    fn sd_journal_query_unique(j: *mut c_void, field: *const c_char) -> c_int;
+    #[cfg(feature = "boot")]
+    fn sd_journal_enumerate_unique(j: *mut c_void, data: *mut *const u8, length: *mut usize) -> c_int;
+    fn sd_journal_add_disjunction(j: *mut c_void) -> c_int;
+}
+
+static API: OnceLock<Option<Api>> = OnceLock::new();
+
+fn api() -> Option<&'static Api> {
+    API.get_or_init(|| {
+        let api = unsafe { load() };
+        if api.is_none() {
+            tracing::warn!("libsystemd not found, the journal is unavailable");
+        }
+        api
+    })
+    .as_ref()
+}
+
+/// Whether libsystemd could be loaded
+#[cfg(any(feature = "logs", feature = "coredumps"))]
+pub fn available() -> bool {
+    api().is_some()
+}
+
+unsafe fn symbol<T: Copy>(handle: *mut c_void, name: &[u8]) -> Option<T> {
+    let ptr = unsafe { libc::dlsym(handle, name.as_ptr() as *const c_char) };
+    // Function pointers and data pointers have the same size on every
+    // platform dlopen exists on
+    (!ptr.is_null()).then(|| unsafe { std::mem::transmute_copy::<*mut c_void, T>(&ptr) })
+}
+
+/// What a wrapper returns without the library: -ENOSYS, or nothing
+trait Missing {
+    fn missing() -> Self;
+}
+
+impl Missing for c_int {
+    fn missing() -> Self {
+        -libc::ENOSYS
+    }
+}
+
+impl Missing for () {
+    fn missing() -> Self {}
+}
+
+fn missing<T: Missing>() -> T {
+    T::missing()
+}
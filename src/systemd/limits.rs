@@ -0,0 +1,278 @@
+//! The handful of cgroup settings worth changing on a running unit, in the
+//! units `systemctl set-property` takes and the raw form D-Bus wants.
+
+use crate::contexts::format_bytes;
+
+/// systemd's "unset" for all of these
+pub const INFINITY: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    CpuQuota,
+    MemoryMax,
+    TasksMax,
+    IoWeight,
+}
+
+impl Limit {
+    pub const ALL: [Limit; 4] = [
+        Limit::CpuQuota,
+        Limit::MemoryMax,
+        Limit::TasksMax,
+        Limit::IoWeight,
+    ];
+
+    /// The unit file setting
+    pub fn setting(self) -> &'static str {
+        match self {
+            Limit::CpuQuota => "CPUQuota",
+            Limit::MemoryMax => "MemoryMax",
+            Limit::TasksMax => "TasksMax",
+            Limit::IoWeight => "IOWeight",
+        }
+    }
+
+    /// The D-Bus property behind the setting, always a `t`
+    pub fn property(self) -> &'static str {
+        match self {
+            Limit::CpuQuota => "CPUQuotaPerSecUSec",
+            Limit::MemoryMax => "MemoryMax",
+            Limit::TasksMax => "TasksMax",
+            Limit::IoWeight => "IOWeight",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Limit::CpuQuota => "percent of one CPU, e.g. 50%, 12.5% or 200%",
+            Limit::MemoryMax => "bytes with K/M/G/T, e.g. 512M",
+            Limit::TasksMax => "number of tasks",
+            Limit::IoWeight => "1-10000, default 100",
+        }
+    }
+
+    /// Parse what a user would write after `Setting=`; empty or
+    /// "infinity" removes the limit
+    pub fn parse(self, text: &str) -> Result<u64, String> {
+        let text = text.trim();
+        if text.is_empty() || text == "infinity" {
+            return Ok(INFINITY);
+        }
+        let invalid = || format!("invalid {} {:?}: {}", self.setting(), text, self.hint());
+        match self {
+            Limit::CpuQuota => {
+                let percent = parse_percent(text.strip_suffix('%').unwrap_or(text))
+                    .filter(|hundredths| *hundredths > 0)
+                    .ok_or_else(invalid)?;
+                // 100% is one full CPU second per second
+                Ok(percent * 100)
+            }
+            Limit::MemoryMax => parse_bytes(text).ok_or_else(invalid),
+            Limit::TasksMax => text.parse().map_err(|_| invalid()),
+            Limit::IoWeight => match text.parse() {
+                Ok(weight @ 1..=10_000) => Ok(weight),
+                _ => Err(invalid()),
+            },
+        }
+    }
+
+    pub fn format(self, value: u64) -> String {
+        if value == INFINITY {
+            return match self {
+                Limit::IoWeight => "default".to_string(),
+                _ => "infinity".to_string(),
+            };
+        }
+        match self {
+            Limit::CpuQuota => format_percent(value),
+            Limit::MemoryMax => format_bytes(value),
+            Limit::TasksMax | Limit::IoWeight => value.to_string(),
+        }
+    }
+
+    /// The value as `parse` reads it back, to start editing from; empty
+    /// when unset
+    pub fn edit_text(self, value: u64) -> String {
+        if value == INFINITY {
+            return String::new();
+        }
+        match self {
+            Limit::CpuQuota => format_percent(value),
+            Limit::MemoryMax => [(40, 'T'), (30, 'G'), (20, 'M'), (10, 'K')]
+                .iter()
+                .find(|(shift, _)| value != 0 && value.is_multiple_of(1 << shift))
+                .map(|(shift, suffix)| format!("{}{}", value >> shift, suffix))
+                .unwrap_or_else(|| value.to_string()),
+            Limit::TasksMax | Limit::IoWeight => value.to_string(),
+        }
+    }
+}
+
+/// "50" or "12.5" (percent, at most two decimals) in hundredths of a
+/// percent, the precision systemd keeps
+fn parse_percent(text: &str) -> Option<u64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if fraction.len() > 2 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let fraction: u64 = format!("{:0<2}", fraction).parse().ok()?;
+    whole
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(100)?
+        .checked_add(fraction)
+}
+
+/// CPUQuotaPerSecUSec as "50%" or "12.5%"
+fn format_percent(usec_per_sec: u64) -> String {
+    let hundredths = usec_per_sec / 100;
+    match hundredths % 100 {
+        0 => format!("{}%", hundredths / 100),
+        fraction => {
+            let fraction = format!("{:02}", fraction);
+            format!("{}.{}%", hundredths / 100, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+/// "512M", "2G", "1048576"; suffixes are powers of 1024 like systemd's
+fn parse_bytes(text: &str) -> Option<u64> {
+    let (number, shift) = match text.char_indices().last()? {
+        (i, 'K') => (&text[..i], 10),
+        (i, 'M') => (&text[..i], 20),
+        (i, 'G') => (&text[..i], 30),
+        (i, 'T') => (&text[..i], 40),
+        _ => (text, 0),
+    };
+    number.parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// Current limits of a unit plus the usage they're compared against
+#[derive(Debug, Clone)]
+pub struct ResourceLimits {
+    pub cpu_quota_per_sec_usec: u64,
+    pub memory_max: u64,
+    pub tasks_max: u64,
+    pub io_weight: u64,
+    pub memory_current: Option<u64>,
+    pub tasks_current: Option<u64>,
+    /// IPAccounting= and the traffic counted since it was turned on
+    pub ip_accounting: bool,
+    pub ip_ingress_bytes: Option<u64>,
+    pub ip_egress_bytes: Option<u64>,
+    pub ip_ingress_packets: Option<u64>,
+    pub ip_egress_packets: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn get(&self, limit: Limit) -> u64 {
+        match limit {
+            Limit::CpuQuota => self.cpu_quota_per_sec_usec,
+            Limit::MemoryMax => self.memory_max,
+            Limit::TasksMax => self.tasks_max,
+            Limit::IoWeight => self.io_weight,
+        }
+    }
+
+    /// What the limit is measured against, where the unit reports it
+    pub fn usage(&self, limit: Limit) -> Option<String> {
+        match limit {
+            Limit::MemoryMax => self.memory_current.map(format_bytes),
+            Limit::TasksMax => self.tasks_current.map(|n| n.to_string()),
+            Limit::CpuQuota | Limit::IoWeight => None,
+        }
+    }
+
+    /// "↓ 1.2 MiB (830 packets)  ↑ 96.0 KiB (412 packets)"; None while
+    /// nothing is counted
+    pub fn ip_traffic(&self) -> Option<String> {
+        if !self.ip_accounting {
+            return None;
+        }
+        let side = |arrow: &str, bytes: u64, packets: Option<u64>| match packets {
+            Some(packets) => format!("{} {} ({} packets)", arrow, format_bytes(bytes), packets),
+            None => format!("{} {}", arrow, format_bytes(bytes)),
+        };
+        Some(format!(
+            "{}  {}",
+            side("↓", self.ip_ingress_bytes?, self.ip_ingress_packets),
+            side("↑", self.ip_egress_bytes?, self.ip_egress_packets)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_infinity_remove_the_limit() {
+        for limit in Limit::ALL {
+            assert_eq!(limit.parse(""), Ok(INFINITY));
+            assert_eq!(limit.parse("  infinity "), Ok(INFINITY));
+            assert_eq!(limit.edit_text(INFINITY), "");
+        }
+        assert_eq!(Limit::MemoryMax.format(INFINITY), "infinity");
+        assert_eq!(Limit::IoWeight.format(INFINITY), "default");
+    }
+
+    #[test]
+    fn cpu_quota_in_percent() {
+        assert_eq!(Limit::CpuQuota.parse("50%"), Ok(500_000));
+        assert_eq!(Limit::CpuQuota.parse("12.5"), Ok(125_000));
+        assert_eq!(Limit::CpuQuota.parse("200%"), Ok(2_000_000));
+        assert_eq!(Limit::CpuQuota.parse("0.01%"), Ok(100));
+        assert!(Limit::CpuQuota.parse("0%").is_err());
+        assert!(Limit::CpuQuota.parse("12.345%").is_err());
+        assert!(Limit::CpuQuota.parse("1.-5%").is_err());
+        assert!(Limit::CpuQuota.parse("half").is_err());
+        assert_eq!(Limit::CpuQuota.format(125_000), "12.5%");
+        assert_eq!(Limit::CpuQuota.edit_text(2_000_000), "200%");
+    }
+
+    #[test]
+    fn memory_suffixes_are_powers_of_1024() {
+        assert_eq!(Limit::MemoryMax.parse("1048576"), Ok(1 << 20));
+        assert_eq!(Limit::MemoryMax.parse("4K"), Ok(4 << 10));
+        assert_eq!(Limit::MemoryMax.parse("512M"), Ok(512 << 20));
+        assert_eq!(Limit::MemoryMax.parse("2G"), Ok(2 << 30));
+        assert_eq!(Limit::MemoryMax.parse("1T"), Ok(1 << 40));
+        assert!(Limit::MemoryMax.parse("512MB").is_err());
+        assert!(Limit::MemoryMax.parse("M").is_err());
+        assert!(Limit::MemoryMax.parse("99999999T").is_err());
+    }
+
+    #[test]
+    fn memory_edit_text_uses_the_largest_exact_suffix() {
+        assert_eq!(Limit::MemoryMax.edit_text(512 << 20), "512M");
+        assert_eq!(Limit::MemoryMax.edit_text(3 << 30), "3G");
+        assert_eq!(Limit::MemoryMax.edit_text(1536 << 20), "1536M");
+        assert_eq!(Limit::MemoryMax.edit_text(1000), "1000");
+        assert_eq!(Limit::MemoryMax.edit_text(0), "0");
+    }
+
+    #[test]
+    fn edit_text_reads_back_to_the_same_value() {
+        let values = [
+            (Limit::CpuQuota, 125_000),
+            (Limit::CpuQuota, 1_000_000),
+            (Limit::CpuQuota, 100),
+            (Limit::MemoryMax, 768 << 20),
+            (Limit::MemoryMax, 12345),
+            (Limit::TasksMax, 4915),
+            (Limit::IoWeight, 100),
+        ];
+        for (limit, value) in values {
+            assert_eq!(limit.parse(&limit.edit_text(value)), Ok(value), "{limit:?}");
+        }
+    }
+
+    #[test]
+    fn io_weight_range() {
+        assert_eq!(Limit::IoWeight.parse("1"), Ok(1));
+        assert_eq!(Limit::IoWeight.parse("10000"), Ok(10_000));
+        assert!(Limit::IoWeight.parse("0").is_err());
+        assert!(Limit::IoWeight.parse("10001").is_err());
+        assert!(Limit::TasksMax.parse("-1").is_err());
+    }
+}
@@ -1,4 +1,105 @@
+#[cfg(feature = "logs")]
+use crate::config::Config;
+#[cfg(any(feature = "units", feature = "boot"))]
+use crate::systemd::journal_ffi::sd_journal_next;
+#[cfg(feature = "units")]
+use crate::systemd::journal_ffi::sd_journal_seek_realtime_usec;
+#[cfg(feature = "logs")]
+use crate::systemd::journal_ffi::{SD_JOURNAL_CURRENT_USER, SD_JOURNAL_SYSTEM};
+use crate::systemd::journal_ffi::{
+    SD_JOURNAL_LOCAL_ONLY, sd_journal_add_disjunction, sd_journal_add_match, sd_journal_close,
+    sd_journal_get_data, sd_journal_get_monotonic_usec, sd_journal_get_realtime_usec,
+    sd_journal_open, sd_journal_previous, sd_journal_seek_tail,
+};
+#[cfg(feature = "boot")]
+use crate::systemd::journal_ffi::{
+    sd_journal_enumerate_unique, sd_journal_flush_matches, sd_journal_query_unique,
+    sd_journal_seek_head,
+};
 use anyhow::Result;
+#[cfg(feature = "boot")]
+use anyhow::bail;
+#[cfg(feature = "units")]
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+#[cfg(feature = "logs")]
+use std::os::raw::c_int;
+use std::os::raw::c_void;
+
+/// Which journal files the Logs tab opens. sd-journal's default is every
+/// file the caller may read, which for a non-root user outside the
+/// systemd-journal group is only their own user journal.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg(feature = "logs")]
+pub enum JournalSource {
+    #[default]
+    All,
+    /// The system journal plus the current user's, interleaved
+    Merged,
+    System,
+    User,
+}
+
+#[cfg(feature = "logs")]
+impl JournalSource {
+    /// `[logs] journal`: all, merged, system or user
+    pub fn from_config(config: &Config) -> Self {
+        match config.get("logs", "journal") {
+            None | Some("all") => Self::All,
+            Some("merged") => Self::Merged,
+            Some("system") => Self::System,
+            Some("user") => Self::User,
+            Some(other) => {
+                tracing::warn!("Unknown logs journal source {:?}", other);
+                Self::All
+            }
+        }
+    }
+
+    /// Flags for sd_journal_open
+    pub fn flags(self) -> c_int {
+        SD_JOURNAL_LOCAL_ONLY
+            | match self {
+                Self::All => 0,
+                Self::Merged => SD_JOURNAL_SYSTEM | SD_JOURNAL_CURRENT_USER,
+                Self::System => SD_JOURNAL_SYSTEM,
+                Self::User => SD_JOURNAL_CURRENT_USER,
+            }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "all readable",
+            Self::Merged => "system + user",
+            Self::System => "system",
+            Self::User => "user",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Merged,
+            Self::Merged => Self::System,
+            Self::System => Self::User,
+            Self::User => Self::All,
+        }
+    }
+}
+
+/// Journal fields naming a unit and one run of it; a user manager's units
+/// are logged under their own fields. A user unit's run is tagged either
+/// way: `USER_INVOCATION_ID` on what the manager logs for it, and
+/// `_SYSTEMD_INVOCATION_ID` on its own stdout and stderr.
+pub fn unit_fields(user: bool) -> (&'static str, &'static [&'static str]) {
+    if user {
+        (
+            "_SYSTEMD_USER_UNIT",
+            &["USER_INVOCATION_ID", "_SYSTEMD_INVOCATION_ID"],
+        )
+    } else {
+        ("_SYSTEMD_UNIT", &["_SYSTEMD_INVOCATION_ID"])
+    }
+}
 
 pub struct Journal;
 
@@ -19,3 +120,363 @@ pub struct LogEntry {
     pub message: String,
     pub priority: u8,
 }
+
+/// A log entry with timestamp for display
+#[derive(Clone)]
+#[cfg_attr(not(feature = "units"), allow(dead_code))]
+pub struct UnitLogEntry {
+    pub timestamp_micros: u64,
+    pub monotonic_usec: Option<u64>,
+    pub message: String,
+}
+
+/// The last `max` lines a unit logged, oldest first. `user` says the unit
+/// belongs to a user manager.
+pub fn read_recent_unit_logs(unit: &str, user: bool, max: usize) -> Vec<UnitLogEntry> {
+    read_unit_logs(unit, user, None, max)
+}
+
+/// Like `read_recent_unit_logs`, limited to one run of the unit when
+/// `invocation` is its InvocationID
+pub fn read_unit_logs(
+    unit: &str,
+    user: bool,
+    invocation: Option<&str>,
+    max: usize,
+) -> Vec<UnitLogEntry> {
+    let (unit_field, invocation_fields) = unit_fields(user);
+    let mut out = Vec::new();
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return out;
+        }
+
+        let unit_match = format!("{unit_field}={unit}");
+        match invocation {
+            // (unit AND one invocation field) OR (unit AND the other)
+            Some(id) => {
+                for (i, field) in invocation_fields.iter().enumerate() {
+                    if i > 0 {
+                        let _ = sd_journal_add_disjunction(j);
+                    }
+                    let m = format!("{field}={id}");
+                    for m in [&unit_match, &m] {
+                        let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+                    }
+                }
+            }
+            None => {
+                let _ =
+                    sd_journal_add_match(j, unit_match.as_ptr() as *const c_void, unit_match.len());
+            }
+        }
+        let _ = sd_journal_seek_tail(j);
+
+        for _ in 0..max {
+            if sd_journal_previous(j) <= 0 {
+                break;
+            }
+            if let Some(entry) = read_journal_entry(j) {
+                out.push(entry);
+            }
+        }
+        sd_journal_close(j);
+    }
+    out.reverse();
+    out
+}
+
+pub(crate) fn get_journal_field(j: *mut c_void, field: &str) -> Option<String> {
+    let field_c = CString::new(field).ok()?;
+    let mut data_ptr: *const u8 = std::ptr::null();
+    let mut len: usize = 0;
+    let rc = unsafe {
+        sd_journal_get_data(
+            j,
+            field_c.as_ptr(),
+            &mut data_ptr as *mut *const u8,
+            &mut len as *mut usize,
+        )
+    };
+    if rc < 0 || data_ptr.is_null() || len == 0 {
+        return None;
+    }
+    let text = String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(data_ptr, len) });
+    let prefix = format!("{}=", field);
+    text.strip_prefix(&prefix).map(|s| s.to_string())
+}
+
+fn read_journal_entry(j: *mut c_void) -> Option<UnitLogEntry> {
+    // Get timestamp
+    let mut ts_micros: u64 = 0;
+    let rc = unsafe { sd_journal_get_realtime_usec(j, &mut ts_micros as *mut u64) };
+    if rc < 0 {
+        return None;
+    }
+
+    let message = get_journal_field(j, "MESSAGE")?;
+
+    let mut mono_micros: u64 = 0;
+    let mut boot_id = [0u8; 16];
+    let rc = unsafe { sd_journal_get_monotonic_usec(j, &mut mono_micros, &mut boot_id) };
+
+    Some(UnitLogEntry {
+        timestamp_micros: ts_micros,
+        monotonic_usec: (rc >= 0).then_some(mono_micros),
+        message,
+    })
+}
+
+/// Realtime µs as YYMMDD HH:MM:SS, local time
+pub fn display_time(usec: u64) -> String {
+    chrono::DateTime::from_timestamp_micros(usec as i64)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%y%m%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// A boot the journal has records of
+#[cfg(feature = "boot")]
+pub struct JournalBoot {
+    /// 32 hex digits, as `journalctl -b` takes it
+    pub id: String,
+    pub first_usec: u64,
+    /// Kernel release from the boot's "Linux version" message, e.g.
+    /// "6.5.6-300.fc39.x86_64"
+    pub kernel: Option<String>,
+}
+
+/// Kernel messages looked at for the version banner; it's the first one
+/// unless the ring buffer overflowed before journald started
+#[cfg(feature = "boot")]
+const BANNER_SEARCH: usize = 20;
+
+/// Every boot in the local journal, newest first
+#[cfg(feature = "boot")]
+pub fn list_boots() -> Result<Vec<JournalBoot>> {
+    let mut boots = Vec::new();
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        let rc = sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY);
+        if rc < 0 || j.is_null() {
+            bail!(
+                "Can't open the journal: {}",
+                std::io::Error::from_raw_os_error(-rc)
+            );
+        }
+
+        let mut ids = Vec::new();
+        let field = CString::new("_BOOT_ID")?;
+        if sd_journal_query_unique(j, field.as_ptr()) >= 0 {
+            let mut data: *const u8 = std::ptr::null();
+            let mut len: usize = 0;
+            while sd_journal_enumerate_unique(j, &mut data, &mut len) > 0 {
+                let text = String::from_utf8_lossy(std::slice::from_raw_parts(data, len));
+                if let Some(id) = text.strip_prefix("_BOOT_ID=") {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+
+        for id in ids {
+            let boot_match = format!("_BOOT_ID={}", id);
+            sd_journal_flush_matches(j);
+            let _ = sd_journal_add_match(j, boot_match.as_ptr() as *const c_void, boot_match.len());
+            let _ = sd_journal_seek_head(j);
+            let mut first_usec = 0;
+            if sd_journal_next(j) <= 0
+                || sd_journal_get_realtime_usec(j, &mut first_usec as *mut u64) < 0
+            {
+                continue;
+            }
+
+            let kernel_match = "_TRANSPORT=kernel";
+            let _ = sd_journal_add_match(
+                j,
+                kernel_match.as_ptr() as *const c_void,
+                kernel_match.len(),
+            );
+            let _ = sd_journal_seek_head(j);
+            let mut kernel = None;
+            for _ in 0..BANNER_SEARCH {
+                if sd_journal_next(j) <= 0 {
+                    break;
+                }
+                // "Linux version 6.5.6-300.fc39.x86_64 (mockbuild@...) ..."
+                if let Some(banner) = get_journal_field(j, "MESSAGE")
+                    .as_deref()
+                    .and_then(|m| m.strip_prefix("Linux version "))
+                {
+                    kernel = banner.split_whitespace().next().map(str::to_string);
+                    break;
+                }
+            }
+            boots.push(JournalBoot {
+                id,
+                first_usec,
+                kernel,
+            });
+        }
+        sd_journal_close(j);
+    }
+    boots.sort_by_key(|boot| std::cmp::Reverse(boot.first_usec));
+    Ok(boots)
+}
+
+/// One line of a boot's log
+#[cfg(feature = "boot")]
+pub struct BootLogEntry {
+    pub display_time: String,
+    pub identifier: String,
+    pub priority: u8,
+    pub message: String,
+}
+
+/// The first `max` entries of boot `id` at `max_priority` or worse
+#[cfg(feature = "boot")]
+pub fn read_boot_logs(id: &str, max_priority: u8, max: usize) -> Vec<BootLogEntry> {
+    let mut out = Vec::new();
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return out;
+        }
+        // Matches on one field are ORed, on different fields ANDed
+        for priority in 0..=max_priority.min(7) {
+            let m = format!("PRIORITY={}", priority);
+            let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        }
+        let m = format!("_BOOT_ID={}", id);
+        let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        let _ = sd_journal_seek_head(j);
+
+        while out.len() < max && sd_journal_next(j) > 0 {
+            let Some(entry) = read_journal_entry(j) else {
+                continue;
+            };
+            out.push(BootLogEntry {
+                display_time: display_time(entry.timestamp_micros),
+                identifier: get_journal_field(j, "SYSLOG_IDENTIFIER")
+                    .or_else(|| get_journal_field(j, "_COMM"))
+                    .unwrap_or_default(),
+                priority: get_journal_field(j, "PRIORITY")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(6),
+                message: entry.message,
+            });
+        }
+        sd_journal_close(j);
+    }
+    out
+}
+
+/// Entries a single activity read goes through; the rest waits for the
+/// next read so a flood can't stall the blocking pool
+#[cfg(feature = "units")]
+const ACTIVITY_BATCH: usize = 100_000;
+
+/// Units' journal entries since some time: (unit, realtime, whether its
+/// priority is err or worse), plus the realtime to continue from
+#[cfg(feature = "units")]
+pub type UnitActivity = (Vec<(String, u64, bool)>, u64);
+
+/// Entries logged under a unit after `since_usec`, oldest first
+#[cfg(feature = "units")]
+pub fn read_unit_activity(since_usec: u64, user: bool) -> UnitActivity {
+    let (unit_field, _) = unit_fields(user);
+    let mut events = Vec::new();
+    let mut last_usec = since_usec;
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return (events, last_usec);
+        }
+        let _ = sd_journal_seek_realtime_usec(j, since_usec + 1);
+        while events.len() < ACTIVITY_BATCH && sd_journal_next(j) > 0 {
+            let mut usec = 0;
+            if sd_journal_get_realtime_usec(j, &mut usec as *mut u64) < 0 || usec <= since_usec {
+                continue;
+            }
+            last_usec = last_usec.max(usec);
+            let Some(unit) = get_journal_field(j, unit_field) else {
+                continue;
+            };
+            let error = get_journal_field(j, "PRIORITY")
+                .and_then(|p| p.parse::<u8>().ok())
+                .is_some_and(|p| p <= 3);
+            events.push((unit, usec, error));
+        }
+        sd_journal_close(j);
+    }
+    (events, last_usec)
+}
+
+/// Messages and errors a unit logged over the last 5 and 60 minutes
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg(feature = "units")]
+pub struct LogRate {
+    pub messages_5m: u32,
+    pub messages_1h: u32,
+    pub errors_5m: u32,
+    pub errors_1h: u32,
+}
+
+/// Per-unit journal counts, bucketed by minute and kept for an hour
+#[derive(Default)]
+#[cfg(feature = "units")]
+pub struct LogRates {
+    /// unit → minute since the epoch → (messages, errors)
+    minutes: HashMap<String, BTreeMap<u64, (u32, u32)>>,
+    /// Where the next read picks up; 0 before the first one
+    pub last_usec: u64,
+}
+
+#[cfg(feature = "units")]
+const RATE_WINDOW_MINUTES: u64 = 60;
+
+#[cfg(feature = "units")]
+impl LogRates {
+    /// Add what a read found and forget minutes older than the window
+    pub fn record(&mut self, (events, last_usec): UnitActivity, now_usec: u64) {
+        for (unit, usec, error) in events {
+            let bucket = self
+                .minutes
+                .entry(unit)
+                .or_default()
+                .entry(usec / 60_000_000)
+                .or_default();
+            bucket.0 += 1;
+            bucket.1 += u32::from(error);
+        }
+        self.last_usec = self.last_usec.max(last_usec);
+        let oldest = (now_usec / 60_000_000).saturating_sub(RATE_WINDOW_MINUTES);
+        self.minutes.retain(|_, buckets| {
+            buckets.retain(|minute, _| *minute >= oldest);
+            !buckets.is_empty()
+        });
+    }
+
+    pub fn rate(&self, unit: &str, now_usec: u64) -> LogRate {
+        let Some(buckets) = self.minutes.get(unit) else {
+            return LogRate::default();
+        };
+        let now = now_usec / 60_000_000;
+        let mut rate = LogRate::default();
+        for (minute, (messages, errors)) in buckets {
+            let age = now.saturating_sub(*minute);
+            if age < RATE_WINDOW_MINUTES {
+                rate.messages_1h += messages;
+                rate.errors_1h += errors;
+            }
+            if age < 5 {
+                rate.messages_5m += messages;
+                rate.errors_5m += errors;
+            }
+        }
+        rate
+    }
+}
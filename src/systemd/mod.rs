@@ -1,3 +1,16 @@
 pub mod client;
+#[cfg(feature = "logs")]
+pub mod denials;
+#[cfg(feature = "units")]
+pub mod dropin;
+#[cfg(feature = "units")]
+pub mod history;
+pub mod journal_ffi;
+#[cfg(feature = "units")]
+pub mod limits;
 pub mod logs;
+#[cfg(feature = "units")]
+pub mod oom;
+#[cfg(feature = "units")]
+pub mod security;
 pub mod units;
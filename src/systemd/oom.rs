@@ -0,0 +1,173 @@
+//! OOM kills from the journal, attributed to the victim's unit: the kernel
+//! OOM killer, as PID 1 reports it for the unit and as the kernel logs it,
+//! and systemd-oomd's kills under memory pressure.
+
+use crate::systemd::journal_ffi::{
+    SD_JOURNAL_LOCAL_ONLY, sd_journal_add_disjunction, sd_journal_add_match, sd_journal_close,
+    sd_journal_get_realtime_usec, sd_journal_next, sd_journal_open, sd_journal_seek_realtime_usec,
+};
+use crate::systemd::logs::get_journal_field;
+use std::collections::HashMap;
+use std::os::raw::c_void;
+
+/// PID 1's "A process of this unit has been killed by the OOM killer."
+const UNIT_OOM_MESSAGE_ID: &str = "fe6faa94e7774663a0da52717891d8ef";
+
+/// Entries a single read goes through, like `read_unit_activity`
+const BATCH: usize = 100_000;
+
+/// The kernel's line and PID 1's message about the same kill are this
+/// close together
+const SAME_KILL_USEC: u64 = 5_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OomKiller {
+    Kernel,
+    Oomd,
+}
+
+impl OomKiller {
+    pub fn label(self) -> &'static str {
+        match self {
+            OomKiller::Kernel => "kernel OOM killer",
+            OomKiller::Oomd => "systemd-oomd",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OomKill {
+    pub unit: String,
+    pub usec: u64,
+    pub killer: OomKiller,
+}
+
+/// The innermost unit of a cgroup path such as
+/// `/user.slice/user-1000.slice/user@1000.service/app.slice/foo.service`
+fn unit_of_cgroup(path: &str) -> Option<String> {
+    path.split('/')
+        .rfind(|part| {
+            [".service", ".scope", ".socket", ".mount", ".swap"]
+                .iter()
+                .any(|suffix| part.ends_with(suffix))
+        })
+        .map(str::to_string)
+}
+
+/// Kills logged after `since_usec`, oldest first, plus the realtime to
+/// continue from
+pub fn read_oom_kills(since_usec: u64, user: bool) -> (Vec<OomKill>, u64) {
+    let unit_field = if user { "USER_UNIT" } else { "UNIT" };
+    let mut kills = Vec::new();
+    let mut last_usec = since_usec;
+    unsafe {
+        let mut j: *mut c_void = std::ptr::null_mut();
+        if sd_journal_open(&mut j as *mut *mut c_void, SD_JOURNAL_LOCAL_ONLY) < 0 || j.is_null() {
+            return (kills, last_usec);
+        }
+        let matches = [
+            format!("MESSAGE_ID={}", UNIT_OOM_MESSAGE_ID),
+            "SYSLOG_IDENTIFIER=systemd-oomd".to_string(),
+            "_TRANSPORT=kernel".to_string(),
+        ];
+        for (i, m) in matches.iter().enumerate() {
+            if i > 0 {
+                let _ = sd_journal_add_disjunction(j);
+            }
+            let _ = sd_journal_add_match(j, m.as_ptr() as *const c_void, m.len());
+        }
+        let _ = sd_journal_seek_realtime_usec(j, since_usec + 1);
+        for _ in 0..BATCH {
+            if sd_journal_next(j) <= 0 {
+                break;
+            }
+            let mut usec = 0;
+            if sd_journal_get_realtime_usec(j, &mut usec as *mut u64) < 0 || usec <= since_usec {
+                continue;
+            }
+            last_usec = last_usec.max(usec);
+            let kill = if get_journal_field(j, "MESSAGE_ID").as_deref() == Some(UNIT_OOM_MESSAGE_ID)
+            {
+                get_journal_field(j, unit_field).map(|unit| (unit, OomKiller::Kernel))
+            } else {
+                get_journal_field(j, "MESSAGE").and_then(|message| parse_message(&message))
+            };
+            if let Some((unit, killer)) = kill {
+                kills.push(OomKill { unit, usec, killer });
+            }
+        }
+        sd_journal_close(j);
+    }
+    (kills, last_usec)
+}
+
+/// The victim of systemd-oomd's "Killed /system.slice/foo.service due to
+/// memory pressure ..." or of the kernel's
+/// "oom-kill:constraint=...,task_memcg=/system.slice/foo.service,task=..."
+fn parse_message(message: &str) -> Option<(String, OomKiller)> {
+    if let Some(rest) = message.strip_prefix("Killed ") {
+        let path = rest.split_whitespace().next()?;
+        return Some((unit_of_cgroup(path)?, OomKiller::Oomd));
+    }
+    let fields = message.strip_prefix("oom-kill:")?;
+    let memcg = fields
+        .split(',')
+        .find_map(|field| field.strip_prefix("task_memcg="))?;
+    Some((unit_of_cgroup(memcg)?, OomKiller::Kernel))
+}
+
+/// Kills of one unit
+#[derive(Debug, Clone, Copy)]
+pub struct OomTally {
+    pub count: usize,
+    pub last_usec: u64,
+    pub killer: OomKiller,
+}
+
+/// OOM kills per unit, kept up to date from the journal
+#[derive(Default)]
+pub struct OomKills {
+    by_unit: HashMap<String, OomTally>,
+    /// Realtime to continue reading from
+    pub last_usec: u64,
+}
+
+impl OomKills {
+    /// Count a batch of kills, returning the ones that are new
+    pub fn record(&mut self, (kills, last_usec): (Vec<OomKill>, u64)) -> Vec<OomKill> {
+        self.last_usec = self.last_usec.max(last_usec);
+        let mut new = Vec::new();
+        for kill in kills {
+            let tally = self.by_unit.entry(kill.unit.clone()).or_insert(OomTally {
+                count: 0,
+                last_usec: 0,
+                killer: kill.killer,
+            });
+            // PID 1's report of a kill the kernel logged too
+            if tally.count > 0
+                && tally.killer == kill.killer
+                && kill.usec.saturating_sub(tally.last_usec) < SAME_KILL_USEC
+            {
+                continue;
+            }
+            tally.count += 1;
+            tally.last_usec = kill.usec;
+            tally.killer = kill.killer;
+            new.push(kill);
+        }
+        new
+    }
+
+    pub fn get(&self, unit: &str) -> Option<&OomTally> {
+        self.by_unit.get(unit)
+    }
+
+    /// The latest kill at or after `since_usec`, with its unit
+    pub fn latest_since(&self, since_usec: u64) -> Option<(&str, &OomTally)> {
+        self.by_unit
+            .iter()
+            .filter(|(_, tally)| tally.last_usec >= since_usec)
+            .max_by_key(|(_, tally)| tally.last_usec)
+            .map(|(unit, tally)| (unit.as_str(), tally))
+    }
+}
@@ -0,0 +1,321 @@
+//! A rough take on `systemd-analyze security`: score a service's sandboxing
+//! from its D-Bus properties. The weights follow systemd's own table for the
+//! settings covered here, so scores land close to (not exactly on) what
+//! `systemd-analyze security` prints.
+
+use std::collections::HashMap;
+
+/// How well one setting is locked down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Good,
+    Partial,
+    Bad,
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub setting: &'static str,
+    /// The current value, as `systemctl show` would print it
+    pub value: String,
+    pub verdict: Verdict,
+    pub explanation: &'static str,
+    weight: u32,
+    range: u32,
+    badness: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub checks: Vec<Check>,
+    /// 0.0 (locked down) to 10.0 (fully exposed)
+    pub exposure: f64,
+}
+
+impl Report {
+    /// systemd-analyze's wording for the overall exposure
+    pub fn rating(&self) -> &'static str {
+        match self.exposure {
+            e if e < 1.0 => "PERFECT",
+            e if e < 2.0 => "SAFE",
+            e if e < 5.0 => "OK",
+            e if e < 7.0 => "MEDIUM",
+            e if e < 9.0 => "EXPOSED",
+            _ => "UNSAFE",
+        }
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match self.exposure {
+            e if e < 5.0 => Verdict::Good,
+            e if e < 7.0 => Verdict::Partial,
+            _ => Verdict::Bad,
+        }
+    }
+}
+
+const CAP_SYS_ADMIN: u64 = 21;
+
+/// Evaluate the Service-interface properties of one unit, keyed by
+/// property name with values formatted like the property inspector shows
+pub fn analyze(props: &HashMap<&str, &str>) -> Report {
+    let get = |key: &str| props.get(key).copied().unwrap_or("");
+    let yes = |key: &str| get(key) == "yes";
+
+    let mut checks = Vec::new();
+    let mut push = |setting: &'static str,
+                    value: String,
+                    weight: u32,
+                    range: u32,
+                    badness: u32,
+                    explanation: &'static str| {
+        let verdict = match badness {
+            0 => Verdict::Good,
+            b if b < range => Verdict::Partial,
+            _ => Verdict::Bad,
+        };
+        checks.push(Check {
+            setting,
+            value,
+            verdict,
+            explanation,
+            weight,
+            range,
+            badness,
+        });
+    };
+
+    let flag = |key: &'static str| (key, get(key).to_string(), u32::from(!yes(key)));
+
+    let user = get("User");
+    let root = user.is_empty() || user == "root" || user == "0";
+    let dynamic = yes("DynamicUser");
+    push(
+        "User/DynamicUser",
+        match (dynamic, user) {
+            (true, _) => "dynamic".to_string(),
+            (false, "") => "root".to_string(),
+            (false, u) => u.to_string(),
+        },
+        2000,
+        1,
+        u32::from(root && !dynamic),
+        "Service runs as root and can modify the whole system",
+    );
+
+    let (key, value, badness) = flag("PrivateNetwork");
+    push(
+        key,
+        value,
+        2500,
+        1,
+        badness,
+        "Service has access to the host's network",
+    );
+
+    let caps = get("CapabilityBoundingSet")
+        .parse::<u64>()
+        .unwrap_or(u64::MAX);
+    push(
+        "CapabilityBoundingSet",
+        format!("{:#x}", caps),
+        1500,
+        1,
+        u32::from(caps & (1 << CAP_SYS_ADMIN) != 0),
+        "Service may acquire CAP_SYS_ADMIN (mounts, namespaces, most of root)",
+    );
+
+    for (key, weight, explanation) in [
+        (
+            "NoNewPrivileges",
+            1000,
+            "Service processes may gain privileges through setuid binaries",
+        ),
+        (
+            "PrivateDevices",
+            1000,
+            "Service has access to hardware devices in /dev",
+        ),
+        (
+            "PrivateTmp",
+            1000,
+            "Service shares /tmp with the rest of the system",
+        ),
+        (
+            "PrivateUsers",
+            1000,
+            "Service has access to other users' UIDs/GIDs",
+        ),
+        (
+            "ProtectKernelTunables",
+            1000,
+            "Service may change kernel tunables in /proc/sys and /sys",
+        ),
+        (
+            "ProtectKernelModules",
+            1000,
+            "Service may load or unload kernel modules",
+        ),
+        (
+            "ProtectKernelLogs",
+            1000,
+            "Service may read from or write to the kernel log ring buffer",
+        ),
+        (
+            "ProtectControlGroups",
+            1000,
+            "Service may modify the control group file system",
+        ),
+        ("ProtectClock", 1000, "Service may change the system clock"),
+        (
+            "RestrictSUIDSGID",
+            1000,
+            "Service may create setuid/setgid files",
+        ),
+        (
+            "RestrictRealtime",
+            500,
+            "Service may acquire realtime scheduling and starve the system",
+        ),
+        (
+            "LockPersonality",
+            100,
+            "Service may change the ABI personality",
+        ),
+        (
+            "MemoryDenyWriteExecute",
+            100,
+            "Service may create writable and executable memory mappings",
+        ),
+        (
+            "ProtectHostname",
+            50,
+            "Service may change the system host name",
+        ),
+    ] {
+        let (key, value, badness) = flag(key);
+        push(key, value, weight, 1, badness, explanation);
+    }
+
+    let protect_system = get("ProtectSystem");
+    push(
+        "ProtectSystem",
+        protect_system.to_string(),
+        1000,
+        2,
+        match protect_system {
+            "strict" => 0,
+            "full" | "yes" => 1,
+            _ => 2,
+        },
+        "Service may write to /usr, /boot or /etc",
+    );
+
+    let protect_home = get("ProtectHome");
+    push(
+        "ProtectHome",
+        protect_home.to_string(),
+        1000,
+        2,
+        match protect_home {
+            "yes" => 0,
+            "read-only" | "tmpfs" => 1,
+            _ => 2,
+        },
+        "Service has access to home directories",
+    );
+
+    let protect_proc = get("ProtectProc");
+    push(
+        "ProtectProc",
+        protect_proc.to_string(),
+        1000,
+        1,
+        u32::from(!matches!(
+            protect_proc,
+            "invisible" | "noaccess" | "ptraceable"
+        )),
+        "Service may see other users' processes in /proc",
+    );
+
+    for (key, weight, explanation) in [
+        (
+            "SystemCallFilter",
+            1000,
+            "Service may issue any system call",
+        ),
+        (
+            "RestrictAddressFamilies",
+            1000,
+            "Service may allocate sockets of any address family",
+        ),
+    ] {
+        let value = get(key);
+        push(
+            key,
+            value.to_string(),
+            weight,
+            1,
+            u32::from(list_is_empty(value)),
+            explanation,
+        );
+    }
+
+    let arches = get("SystemCallArchitectures");
+    push(
+        "SystemCallArchitectures",
+        arches.to_string(),
+        200,
+        1,
+        u32::from(arches.is_empty()),
+        "Service may execute system calls for foreign architectures",
+    );
+
+    let ip_deny = get("IPAddressDeny");
+    push(
+        "IPAddressDeny",
+        ip_deny.to_string(),
+        1000,
+        1,
+        u32::from(ip_deny.trim().is_empty()),
+        "Service may talk to any IP address",
+    );
+
+    let umask = get("UMask").parse::<u32>().unwrap_or(0o022);
+    push(
+        "UMask",
+        format!("{:04o}", umask),
+        100,
+        1,
+        u32::from(umask & 0o007 != 0o007),
+        "Files created by the service are world-readable by default",
+    );
+
+    let total: u32 = checks.iter().map(|c| c.weight * c.range).sum();
+    let bad: u32 = checks.iter().map(|c| c.weight * c.badness).sum();
+    let exposure = if total == 0 {
+        0.0
+    } else {
+        10.0 * f64::from(bad) / f64::from(total)
+    };
+
+    // Worst offenders first, heaviest within each verdict
+    checks.sort_by_key(|c| {
+        let order = match c.verdict {
+            Verdict::Bad => 0,
+            Verdict::Partial => 1,
+            Verdict::Good => 2,
+        };
+        (order, std::cmp::Reverse(c.weight))
+    });
+
+    Report { checks, exposure }
+}
+
+/// `(bas)` allow/deny lists print as `{ yes ; a b }`; empty means no filter
+fn list_is_empty(value: &str) -> bool {
+    value
+        .split_once(';')
+        .map(|(_, items)| items.trim().trim_end_matches('}').trim().is_empty())
+        .unwrap_or(true)
+}
@@ -0,0 +1,99 @@
+use crate::config::Config;
+
+/// How log views print an entry's time. Cycled with `t`; the starting
+/// value comes from `[logs] timestamp` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimestampFormat {
+    /// `%H:%M:%S`, local time
+    #[default]
+    Short,
+    /// Full ISO-8601 with offset
+    Iso,
+    /// "3m ago"
+    Relative,
+    /// Seconds since the entry's boot, like `journalctl -o short-monotonic`
+    Monotonic,
+}
+
+impl TimestampFormat {
+    pub fn from_config(config: &Config) -> Self {
+        match config.get("logs", "timestamp") {
+            None | Some("short") => Self::Short,
+            Some("iso") => Self::Iso,
+            Some("relative") => Self::Relative,
+            Some("monotonic") => Self::Monotonic,
+            Some(other) => {
+                tracing::warn!("Unknown logs timestamp format {:?}", other);
+                Self::Short
+            }
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Short => Self::Iso,
+            Self::Iso => Self::Relative,
+            Self::Relative => Self::Monotonic,
+            Self::Monotonic => Self::Short,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Iso => "iso",
+            Self::Relative => "relative",
+            Self::Monotonic => "monotonic",
+        }
+    }
+
+    /// Column width that fits every value of this format
+    pub fn width(self) -> usize {
+        match self {
+            Self::Short => 8,
+            Self::Iso => 25,
+            Self::Relative => 9,
+            Self::Monotonic => 14,
+        }
+    }
+
+    /// Format realtime µs (and monotonic µs, when the entry has it).
+    /// `now_usec` is taken once per frame so relative times line up.
+    pub fn format(self, realtime_usec: u64, monotonic_usec: Option<u64>, now_usec: u64) -> String {
+        let local = || {
+            chrono::DateTime::from_timestamp_micros(realtime_usec as i64)
+                .map(|dt| dt.with_timezone(&chrono::Local))
+        };
+        match self {
+            Self::Short => local()
+                .map(|dt| dt.format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| "?".to_string()),
+            Self::Iso => local()
+                .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string())
+                .unwrap_or_else(|| "?".to_string()),
+            Self::Relative => relative(now_usec.saturating_sub(realtime_usec)),
+            Self::Monotonic => match monotonic_usec {
+                Some(usec) => format!("[{:>5}.{:06}]", usec / 1_000_000, usec % 1_000_000),
+                None => "[?]".to_string(),
+            },
+        }
+    }
+}
+
+fn relative(age_usec: u64) -> String {
+    let secs = age_usec / 1_000_000;
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86400)
+    }
+}
+
+/// Current realtime in µs, for `TimestampFormat::format`
+pub fn now_usec() -> u64 {
+    chrono::Utc::now().timestamp_micros().max(0) as u64
+}
@@ -0,0 +1,175 @@
+use crate::config::Config;
+use crate::contexts::Pending;
+use crate::messages::Messages;
+use crate::systemd::client::SystemdClient;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often watched units are polled, whichever tab is showing
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long a state change keeps flashing in the strip
+pub const FLASH: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone)]
+pub struct Watched {
+    pub name: String,
+    /// ActiveState at the last poll, None until the first one lands
+    pub state: Option<String>,
+    /// State before the most recent transition
+    pub previous: Option<String>,
+    pub changed: Option<Instant>,
+}
+
+impl Watched {
+    pub fn is_flashing(&self) -> bool {
+        self.changed.is_some_and(|t| t.elapsed() < FLASH)
+    }
+}
+
+struct Inner {
+    units: Vec<Watched>,
+    pending: Pending<Vec<(String, anyhow::Result<String>)>>,
+    last_poll: Option<Instant>,
+}
+
+/// Units pinned with `w`, kept in `$XDG_CONFIG_HOME/rootwork/watchlist`
+/// (one name per line). Cheap to clone like `Messages`: Units toggles
+/// entries, the app polls them and draws the strip.
+#[derive(Clone)]
+pub struct Watchlist {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Watchlist {
+    pub fn load() -> Self {
+        let units = Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|name| Watched {
+                name: name.to_string(),
+                state: None,
+                previous: None,
+                changed: None,
+            })
+            .collect();
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                units,
+                pending: Pending::idle(),
+                last_poll: None,
+            })),
+        }
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(Config::path()?.with_file_name("watchlist"))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(feature = "units")]
+    pub fn contains(&self, name: &str) -> bool {
+        self.lock().units.iter().any(|w| w.name == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().units.is_empty()
+    }
+
+    pub fn units(&self) -> Vec<Watched> {
+        self.lock().units.clone()
+    }
+
+    /// Pin or unpin a unit and save the list. Returns true if it's now watched.
+    #[cfg(feature = "units")]
+    pub fn toggle(&self, name: &str) -> std::io::Result<bool> {
+        let mut inner = self.lock();
+        let watched = match inner.units.iter().position(|w| w.name == name) {
+            Some(index) => {
+                inner.units.remove(index);
+                false
+            }
+            None => {
+                inner.units.push(Watched {
+                    name: name.to_string(),
+                    state: None,
+                    previous: None,
+                    changed: None,
+                });
+                // Poll the newcomer right away
+                inner.last_poll = None;
+                true
+            }
+        };
+        let names: Vec<&str> = inner.units.iter().map(|w| w.name.as_str()).collect();
+        save(&names)?;
+        Ok(watched)
+    }
+
+    /// Apply the last poll and start the next one when due. Transitions are
+    /// announced in the status line; a unit failing is an error.
+    pub fn tick(&self, systemd: &SystemdClient, messages: &Messages) {
+        let mut inner = self.lock();
+
+        if let Some(states) = inner.pending.poll() {
+            for (name, state) in states {
+                let Some(watched) = inner.units.iter_mut().find(|w| w.name == name) else {
+                    continue;
+                };
+                let state = match state {
+                    Ok(state) => state,
+                    Err(_) => "unknown".to_string(),
+                };
+                match watched.state.as_deref() {
+                    None => watched.state = Some(state),
+                    Some(old) if old != state => {
+                        let text = format!("{}: {} → {}", name, old, state);
+                        if state == "failed" {
+                            messages.error(text);
+                        } else {
+                            messages.info(text);
+                        }
+                        watched.previous = watched.state.replace(state);
+                        watched.changed = Some(Instant::now());
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let due = inner.last_poll.is_none_or(|t| t.elapsed() >= POLL_INTERVAL);
+        if due && !inner.pending.is_pending() && !inner.units.is_empty() {
+            let names: Vec<String> = inner.units.iter().map(|w| w.name.clone()).collect();
+            let systemd = systemd.clone();
+            inner.pending = Pending::spawn_async(async move {
+                let mut states = Vec::with_capacity(names.len());
+                for name in names {
+                    let state = systemd.active_state(&name).await;
+                    states.push((name, state));
+                }
+                states
+            });
+            inner.last_poll = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(feature = "units")]
+fn save(names: &[&str]) -> std::io::Result<()> {
+    let Some(path) = Watchlist::path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut content = names.join("\n");
+    content.push('\n');
+    std::fs::write(path, content)
+}
@@ -1,4 +1,6 @@
 pub mod help;
 pub mod log_view;
+pub mod picker;
+pub mod selectable;
 pub mod status_bar;
 pub mod unit_list;
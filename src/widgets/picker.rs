@@ -0,0 +1,153 @@
+use crate::widgets::selectable::{ListState, SelectableList};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// What a key did to a picker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickerEvent {
+    /// Cursor moved or nothing happened
+    None,
+    /// Query changed; the caller re-ranks its matches
+    Changed,
+    Accept,
+    Close,
+}
+
+/// Query and cursor of a type-to-filter popup. The caller owns the items and
+/// ranks them against `query`; the picker only edits and navigates.
+pub struct Picker {
+    pub query: String,
+    pub list: ListState,
+}
+
+impl Default for Picker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Picker {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            list: ListState::new(),
+        }
+    }
+
+    /// Every printable key goes into the query, so j/k can't navigate here;
+    /// arrows and Ctrl+N/Ctrl+P do
+    pub fn handle_key(&mut self, key: KeyEvent, len: usize) -> PickerEvent {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return PickerEvent::Close,
+            KeyCode::Enter => return PickerEvent::Accept,
+            KeyCode::Up => self.list.up(len),
+            KeyCode::Down => self.list.down(len),
+            KeyCode::Char('p') if ctrl => self.list.up(len),
+            KeyCode::Char('n') if ctrl => self.list.down(len),
+            KeyCode::PageUp => self.list.page_up(len),
+            KeyCode::PageDown => self.list.page_down(len),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.list.reset();
+                return PickerEvent::Changed;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.list.reset();
+                return PickerEvent::Changed;
+            }
+            _ => {}
+        }
+        PickerEvent::None
+    }
+}
+
+/// Popup with the query on top and `len` ranked matches below it
+pub fn draw_picker<'a>(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    picker: &'a Picker,
+    len: usize,
+    line: impl Fn(usize) -> Line<'a>,
+) {
+    f.render_widget(Clear, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let input = Line::from(vec![
+        Span::styled(
+            "> ",
+            Style::default()
+                .fg(crate::palette::cyan())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(picker.query.as_str()),
+        Span::raw("_"),
+    ]);
+    f.render_widget(
+        Paragraph::new(input).block(
+            Block::default()
+                .title(format!(" {} ", title))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(crate::palette::cyan())),
+        ),
+        chunks[0],
+    );
+
+    let block = Block::default()
+        .title(format!(
+            " {} matches  ↑/↓ select  Enter go  Esc close ",
+            len
+        ))
+        .borders(Borders::ALL);
+    if len == 0 {
+        f.render_widget(Paragraph::new("No matches").block(block), chunks[1]);
+        return;
+    }
+    f.render_widget(
+        SelectableList::new(&picker.list, len, line).block(block),
+        chunks[1],
+    );
+}
+
+/// Rank `needle` in `haystack`, lower is better: substring hits score by
+/// position, in-order subsequences after them by spread. Both lowercase.
+pub fn fuzzy_match_score(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    // Fast path: contiguous substring match should rank highest.
+    if let Some(idx) = haystack.find(needle) {
+        return Some(idx);
+    }
+
+    // Subsequence fuzzy match: all needle chars must appear in order.
+    let mut last_idx = 0usize;
+    let mut first_match: Option<usize> = None;
+    let mut gap_penalty = 0usize;
+
+    for n in needle.chars() {
+        let found_rel = haystack[last_idx..].find(n)?;
+
+        let found_abs = last_idx + found_rel;
+        if first_match.is_none() {
+            first_match = Some(found_abs);
+        }
+
+        gap_penalty += found_rel;
+        last_idx = found_abs + n.len_utf8();
+    }
+
+    Some(first_match.unwrap_or(0) + gap_penalty * 2 + 100)
+}
@@ -0,0 +1,359 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Margin, Rect},
+    style::Style,
+    text::Line,
+    widgets::{Block, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget},
+};
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "boot",
+    feature = "logs",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+use ratatui::{
+    layout::Constraint,
+    widgets::{Row, Table},
+};
+use std::cell::Cell;
+
+/// Cursor and scroll position of a selectable list or table.
+///
+/// The number of visible rows is only known once the widget is laid out, so
+/// rendering records it here; the next PgUp/PgDn then moves by exactly one
+/// screenful.
+#[derive(Debug)]
+pub struct ListState {
+    pub selected: usize,
+    offset: usize,
+    rows: Cell<usize>,
+}
+
+impl Default for ListState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            rows: Cell::new(10),
+        }
+    }
+
+    /// Rows visible at the last render
+    pub fn rows(&self) -> usize {
+        self.rows.get().max(1)
+    }
+
+    /// Back to the first row, e.g. after switching views
+    pub fn reset(&mut self) {
+        self.selected = 0;
+        self.offset = 0;
+    }
+
+    pub fn select(&mut self, index: usize, len: usize) {
+        self.selected = index.min(len.saturating_sub(1));
+        self.follow(len);
+    }
+
+    /// Keep the cursor inside a list that may have shrunk
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "boot",
+        feature = "logs",
+        feature = "machines",
+        feature = "coredumps",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    pub fn clamp(&mut self, len: usize) {
+        self.select(self.selected, len);
+    }
+
+    pub fn up(&mut self, len: usize) {
+        self.select(self.selected.saturating_sub(1), len);
+    }
+
+    pub fn down(&mut self, len: usize) {
+        self.select(self.selected + 1, len);
+    }
+
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "boot",
+        feature = "logs",
+        feature = "machines",
+        feature = "coredumps",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    pub fn top(&mut self, len: usize) {
+        self.select(0, len);
+    }
+
+    #[cfg(any(
+        feature = "units",
+        feature = "network",
+        feature = "dns",
+        feature = "boot",
+        feature = "logs",
+        feature = "machines",
+        feature = "coredumps",
+        feature = "storage",
+        feature = "portable",
+        feature = "kernel"
+    ))]
+    pub fn bottom(&mut self, len: usize) {
+        self.select(len.saturating_sub(1), len);
+    }
+
+    /// Move a screenful, scrolling the view along with the cursor
+    pub fn page_up(&mut self, len: usize) {
+        let rows = self.rows();
+        self.offset = self.offset.saturating_sub(rows);
+        self.select(self.selected.saturating_sub(rows), len);
+    }
+
+    pub fn page_down(&mut self, len: usize) {
+        let rows = self.rows();
+        self.offset += rows;
+        self.select(self.selected + rows, len);
+    }
+
+    /// Indices of the rows shown at the last render
+    #[cfg(feature = "logs")]
+    pub fn visible(&self, len: usize) -> std::ops::Range<usize> {
+        let offset = self.offset_for(len, self.rows());
+        offset..(offset + self.rows()).min(len)
+    }
+
+    #[cfg(feature = "logs")]
+    pub fn is_at_bottom(&self, len: usize) -> bool {
+        self.selected + 1 >= len
+    }
+
+    /// Scroll just enough to keep the cursor on screen
+    fn follow(&mut self, len: usize) {
+        self.offset = self.offset_for(len, self.rows());
+    }
+
+    /// First row to draw when `rows` fit. Also covers a resize since the
+    /// last move, which rendering can't write back.
+    pub fn offset_for(&self, len: usize, rows: usize) -> usize {
+        let rows = rows.max(1);
+        let mut offset = self.offset.min(len.saturating_sub(rows));
+        if self.selected < offset {
+            offset = self.selected;
+        } else if self.selected >= offset + rows {
+            offset = self.selected + 1 - rows;
+        }
+        offset
+    }
+}
+
+/// A line-per-item list that only builds the visible lines, highlights the
+/// cursor row and shows a scrollbar when it doesn't fit.
+pub struct SelectableList<'a, F> {
+    state: &'a ListState,
+    len: usize,
+    line: F,
+    block: Option<Block<'a>>,
+    highlight: Style,
+}
+
+impl<'a, F> SelectableList<'a, F>
+where
+    F: Fn(usize) -> Line<'a>,
+{
+    pub fn new(state: &'a ListState, len: usize, line: F) -> Self {
+        Self {
+            state,
+            len,
+            line,
+            block: None,
+            highlight: crate::palette::selected(),
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl<'a, F> Widget for SelectableList<'a, F>
+where
+    F: Fn(usize) -> Line<'a>,
+{
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let inner = render_block(self.block, area, buf);
+        let rows = inner.height as usize;
+        self.state.rows.set(rows);
+        let offset = self.state.offset_for(self.len, rows);
+
+        for (y, index) in (offset..self.len.min(offset + rows)).enumerate() {
+            let mut line = (self.line)(index);
+            if index == self.state.selected {
+                line = line.patch_style(self.highlight);
+            }
+            let row = Rect::new(inner.x, inner.y + y as u16, inner.width, 1);
+            buf.set_style(row, line.style);
+            line.render(row, buf);
+        }
+
+        render_scrollbar(area, self.len, rows, offset, buf);
+    }
+}
+
+/// Table counterpart of `SelectableList`, with an optional header row
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "boot",
+    feature = "logs",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+pub struct SelectableTable<'a, F> {
+    state: &'a ListState,
+    len: usize,
+    row: F,
+    widths: Vec<Constraint>,
+    header: Option<Row<'a>>,
+    block: Option<Block<'a>>,
+    highlight: Style,
+}
+
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "boot",
+    feature = "logs",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+impl<'a, F> SelectableTable<'a, F>
+where
+    F: Fn(usize) -> Row<'a>,
+{
+    pub fn new(
+        state: &'a ListState,
+        len: usize,
+        widths: impl IntoIterator<Item = Constraint>,
+        row: F,
+    ) -> Self {
+        Self {
+            state,
+            len,
+            row,
+            widths: widths.into_iter().collect(),
+            header: None,
+            block: None,
+            highlight: crate::palette::selected(),
+        }
+    }
+
+    pub fn header(mut self, header: Row<'a>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+#[cfg(any(
+    feature = "units",
+    feature = "network",
+    feature = "dns",
+    feature = "boot",
+    feature = "logs",
+    feature = "machines",
+    feature = "coredumps",
+    feature = "storage",
+    feature = "portable",
+    feature = "kernel"
+))]
+impl<'a, F> Widget for SelectableTable<'a, F>
+where
+    F: Fn(usize) -> Row<'a>,
+{
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let inner = render_block(self.block, area, buf);
+        let header_rows = usize::from(self.header.is_some());
+        let rows = (inner.height as usize).saturating_sub(header_rows);
+        self.state.rows.set(rows);
+        let offset = self.state.offset_for(self.len, rows);
+
+        let visible: Vec<Row> = (offset..self.len.min(offset + rows))
+            .map(|index| {
+                let row = (self.row)(index);
+                if index == self.state.selected {
+                    row.style(self.highlight)
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let mut table = Table::new(visible, self.widths);
+        if let Some(header) = self.header {
+            table = table.header(header);
+        }
+        Widget::render(table, inner, buf);
+
+        render_scrollbar(area, self.len, rows, offset, buf);
+    }
+}
+
+fn render_block(block: Option<Block>, area: Rect, buf: &mut Buffer) -> Rect {
+    match block {
+        Some(block) => {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        }
+        None => area,
+    }
+}
+
+/// Vertical scrollbar over the right border of `area`, only when `len`
+/// items don't fit in `rows`
+pub fn render_scrollbar(area: Rect, len: usize, rows: usize, offset: usize, buf: &mut Buffer) {
+    if len <= rows {
+        return;
+    }
+    let mut state = ScrollbarState::new(len.saturating_sub(rows))
+        .position(offset)
+        .viewport_content_length(rows);
+    Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .render(area.inner(Margin::new(0, 1)), buf, &mut state);
+}